@@ -0,0 +1,67 @@
+//! # Arduino Uno example
+//!
+//! This example drives a DC motor connected to the outputs `1Y` and `2Y` of an L293D chip. The
+//! ATmega328p only has six hardware PWM channels, which are often needed for other purposes.
+//! Thus, the inputs of the chip are driven by the software PWM of the `l293x` crate instead.
+//!
+//! The speed of the motor is controlled by a potentiometer: In the middle position the motor
+//! stops, while turning it left or right drives the motor in reverse or forward.
+//!
+//! | Arduino pin | Connected to                       |
+//! |-------------|------------------------------------|
+//! | D2          | `1A` (input 1) of the L293D        |
+//! | D4          | `2A` (input 2) of the L293D        |
+//! | D7          | `1,2EN` (enable 1 & 2) of the L293D |
+//! | A0          | Wiper of the potentiometer         |
+#![no_std]
+#![no_main]
+
+use l293x::soft_pwm::SoftPwm;
+use l293x::L293x;
+use panic_halt as _;
+
+/// The number of ticks per PWM period.
+const PWM_PERIOD: u16 = 100;
+
+/// The time between two ticks of the PWM in microseconds (i.e. a 100 Hz PWM).
+const TICK_US: u32 = 100;
+
+/// The center value of the 10-bit ADC.
+const ADC_CENTER: i32 = 512;
+
+#[arduino_hal::entry]
+fn main() -> ! {
+    let dp = arduino_hal::Peripherals::take().unwrap();
+    let pins = arduino_hal::pins!(dp);
+
+    let mut adc = arduino_hal::Adc::new(dp.ADC, Default::default());
+    let potentiometer = pins.a0.into_analog_input(&mut adc);
+
+    let input1 = SoftPwm::new(pins.d2.into_output(), PWM_PERIOD);
+    let input2 = SoftPwm::new(pins.d4.into_output(), PWM_PERIOD);
+    let l293x = L293x::new(
+        input1.channel(),
+        input2.channel(),
+        (),
+        (),
+        pins.d7.into_output(),
+        (),
+    );
+    let mut motor = l293x.full_h12();
+
+    loop {
+        // Generate one full PWM period
+        for _ in 0..PWM_PERIOD {
+            input1.tick().unwrap();
+            input2.tick().unwrap();
+            arduino_hal::delay_us(TICK_US);
+        }
+
+        // Afterwards, update the speed from the potentiometer
+        let reading = i32::from(potentiometer.analog_read(&mut adc));
+        let speed = (reading - ADC_CENTER) * i32::from(i16::MAX) / ADC_CENTER;
+        motor
+            .set_speed(speed.clamp(-i32::from(i16::MAX), i32::from(i16::MAX)) as i16)
+            .unwrap();
+    }
+}
@@ -0,0 +1,75 @@
+//! # Raspberry Pi example
+//!
+//! This example drives a DC motor connected to the outputs `1Y` and `2Y` of an L293D chip from a
+//! Raspberry Pi running Linux. The GPIOs are accessed using the character device API of
+//! `linux-embedded-hal`. Linux does not offer a PWM on arbitrary GPIOs, so the inputs of the chip
+//! are driven by the software PWM of the `l293x` crate, which is ticked from the main thread.
+//!
+//! The motor runs a short program: forward with half and full speed, reverse, and braking.
+//! Afterwards, the outputs are disabled.
+//!
+//! | Raspberry Pi GPIO | Connected to                        |
+//! |-------------------|-------------------------------------|
+//! | GPIO17 (pin 11)   | `1A` (input 1) of the L293D         |
+//! | GPIO27 (pin 13)   | `2A` (input 2) of the L293D         |
+//! | GPIO22 (pin 15)   | `1,2EN` (enable 1 & 2) of the L293D |
+use std::error::Error;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use l293x::soft_pwm::SoftPwm;
+use l293x::L293x;
+use linux_embedded_hal::gpio_cdev::{Chip, LineRequestFlags};
+use linux_embedded_hal::CdevPin;
+
+/// The number of ticks per PWM period.
+const PWM_PERIOD: u16 = 20;
+
+/// The time between two ticks of the PWM (i.e. a 100 Hz PWM).
+const TICK: Duration = Duration::from_micros(500);
+
+/// The speeds of the motor and how long they are kept.
+const PROGRAM: [(i16, Duration); 4] = [
+    (i16::MAX / 2, Duration::from_secs(2)),
+    (i16::MAX, Duration::from_secs(2)),
+    (-i16::MAX / 2, Duration::from_secs(2)),
+    (0, Duration::from_secs(1)),
+];
+
+/// Request the GPIO with the given `offset` as an output, which is low initially.
+fn output(chip: &mut Chip, offset: u32) -> Result<CdevPin, Box<dyn Error>> {
+    let handle = chip
+        .get_line(offset)?
+        .request(LineRequestFlags::OUTPUT, 0, "l293x")?;
+    Ok(CdevPin::new(handle)?)
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut chip = Chip::new("/dev/gpiochip0")?;
+    let input1 = SoftPwm::new(output(&mut chip, 17)?, PWM_PERIOD);
+    let input2 = SoftPwm::new(output(&mut chip, 27)?, PWM_PERIOD);
+    let mut l293x = L293x::new(
+        input1.channel(),
+        input2.channel(),
+        (),
+        (),
+        output(&mut chip, 22)?,
+        (),
+    );
+
+    let mut motor = l293x.full_h12();
+    for (speed, duration) in PROGRAM {
+        println!("Driving the motor with speed {speed} for {duration:?}");
+        // With the `std` feature, the errors of the driver can be propagated using `?`
+        motor.set_speed(speed)?;
+        let end = Instant::now() + duration;
+        while Instant::now() < end {
+            input1.tick()?;
+            input2.tick()?;
+            thread::sleep(TICK);
+        }
+    }
+
+    l293x.disable_y1_and_y2()?;
+    Ok(())
+}
@@ -0,0 +1,123 @@
+//! # Raspberry Pi Pico (RP2040) example
+//!
+//! This example drives the two DC motors of a small robot connected to the outputs `1Y` & `2Y`
+//! and `3Y` & `4Y` of an L293D chip. The inputs of the chip are driven by two hardware PWM
+//! slices, while the async API of the `l293x` crate ramps the motors to new speeds without
+//! blocking the executor.
+//!
+//! The speeds are sent by a remote control task, which simulates the radio link of a real robot.
+//! After a short demo, it falls silent: The dead-man switch notices the missing commands and
+//! disables all outputs.
+//!
+//! | Pico pin | Connected to                        |
+//! |----------|-------------------------------------|
+//! | GP0      | `1A` (input 1) of the L293D         |
+//! | GP1      | `2A` (input 2) of the L293D         |
+//! | GP2      | `3A` (input 3) of the L293D         |
+//! | GP3      | `4A` (input 4) of the L293D         |
+//! | GP4      | `1,2EN` (enable 1 & 2) of the L293D |
+//! | GP5      | `3,4EN` (enable 3 & 4) of the L293D |
+#![no_std]
+#![no_main]
+
+use defmt::{info, warn};
+use embassy_executor::Spawner;
+use embassy_futures::join::join;
+use embassy_rp::gpio::{Level, Output};
+use embassy_rp::pwm::{self, Pwm};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::signal::Signal;
+use embassy_time::{with_timeout, Delay, Duration, Timer};
+use l293x::asynch::ramp_speed;
+use l293x::{DeadmanSwitch, L293x};
+use {defmt_rtt as _, panic_probe as _};
+
+/// The top value of the PWM counters, i.e. a 20 kHz PWM at the 125 MHz system clock.
+const PWM_TOP: u16 = 6_249;
+
+/// The time in milliseconds, after which the motors are stopped without a new command.
+const DEADMAN_WINDOW_MS: u32 = 500;
+
+/// The interval in milliseconds, in which the dead-man switch is checked.
+const DEADMAN_TICK_MS: u32 = 50;
+
+/// The time in milliseconds to ramp the motors to a new speed.
+const RAMP_MS: u32 = 200;
+
+/// The speeds of the left and the right motor sent by the remote control.
+static COMMAND: Signal<CriticalSectionRawMutex, (i16, i16)> = Signal::new();
+
+/// Simulate a remote control, which sends a few commands and falls silent afterwards.
+#[embassy_executor::task]
+async fn remote_control() {
+    let commands = [
+        // Forward
+        (i16::MAX / 2, i16::MAX / 2),
+        // Turn in place
+        (i16::MAX / 2, -i16::MAX / 2),
+        // Reverse at full speed
+        (-i16::MAX, -i16::MAX),
+    ];
+    for command in commands {
+        // Like a radio link, the command is repeated to keep the dead-man switch fed
+        for _ in 0..10 {
+            COMMAND.signal(command);
+            Timer::after_millis(200).await;
+        }
+    }
+    info!("The remote control fell silent");
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    let p = embassy_rp::init(Default::default());
+
+    let mut config = pwm::Config::default();
+    config.top = PWM_TOP;
+    let (a1, a2) = Pwm::new_output_ab(p.PWM_SLICE0, p.PIN_0, p.PIN_1, config.clone()).split();
+    let (a3, a4) = Pwm::new_output_ab(p.PWM_SLICE1, p.PIN_2, p.PIN_3, config).split();
+    let l293x = L293x::new(
+        a1.unwrap(),
+        a2.unwrap(),
+        a3.unwrap(),
+        a4.unwrap(),
+        Output::new(p.PIN_4, Level::Low),
+        Output::new(p.PIN_5, Level::Low),
+    );
+    let mut switch = DeadmanSwitch::new(l293x, DEADMAN_WINDOW_MS);
+    let mut speeds = (0, 0);
+
+    spawner.spawn(remote_control()).unwrap();
+
+    loop {
+        let timeout = Duration::from_millis(DEADMAN_TICK_MS.into());
+        let (left, right) = match with_timeout(timeout, COMMAND.wait()).await {
+            Ok(command) => command,
+            Err(_) => {
+                if switch.tick(DEADMAN_TICK_MS, &mut Delay).unwrap() && speeds != (0, 0) {
+                    warn!("No command received, the motors are stopped");
+                    speeds = (0, 0);
+                }
+                continue;
+            }
+        };
+        switch.feed();
+
+        // The bridges are borrowed from the switch for every command. Thus, they are set to the
+        // last speeds first, so that the ramps start from there. If the switch tripped before,
+        // setting the speeds enables the outputs again.
+        let chip = switch.chip();
+        let mut left_motor = chip.full_h12();
+        let mut right_motor = chip.full_h34();
+        left_motor.set_speed(speeds.0).unwrap();
+        right_motor.set_speed(speeds.1).unwrap();
+        let (left_result, right_result) = join(
+            ramp_speed(&mut left_motor, left, RAMP_MS, &mut Delay),
+            ramp_speed(&mut right_motor, right, RAMP_MS, &mut Delay),
+        )
+        .await;
+        left_result.unwrap();
+        right_result.unwrap();
+        speeds = (left, right);
+    }
+}
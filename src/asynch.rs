@@ -0,0 +1,247 @@
+//! # Async helpers
+//!
+//! This module provides non-blocking variants of the time-based operations of this crate, which
+//! wait using the [DelayNs] trait of [embedded-hal-async](https://docs.rs/embedded-hal-async).
+//! This allows using the drivers from async executors (e.g. embassy) without blocking other
+//! tasks while a motor ramps up.
+//!
+//! Setting the state or the duty cycle of a pin does not block, which is why the embedded-hal
+//! does not define async output pins. Thus, the [L293x](crate::L293x) chip, its
+//! [HalfH](crate::HalfH) bridges and the [FullH] bridges are used as they are. Only waiting is
+//! done asynchronously.
+//!
+//! This module is only available, if the `async` feature is enabled.
+//!
+//! # Examples
+//!
+//! ```
+//! use l293x::asynch::{pulse, ramp_speed, ramp_to};
+//! use l293x::{FullH, L293x};
+//!
+//! let l293x = L293x::new(pwm1, pwm2, (), (), enable12, ());
+//!
+//! // Soft-start the output 1 within 500ms
+//! let mut y1 = l293x.y1();
+//! y1.enable()?;
+//! ramp_to(&mut y1, 0, y1.max_duty_cycle(), 500, &mut delay).await?;
+//!
+//! // Pulse the output 1 for 10ms
+//! pulse(&mut y1, 10_000, &mut delay).await?;
+//!
+//! // Reverse a motor within one second
+//! let mut motor = l293x.full_h12();
+//! ramp_speed(&mut motor, -i16::MAX, 1000, &mut delay).await?;
+//! ```
+use core::convert::Infallible;
+
+use embedded_hal::digital::OutputPin;
+use embedded_hal::pwm::SetDutyCycle;
+use embedded_hal_async::delay::DelayNs;
+
+use crate::ramp::ramp_value;
+use crate::soft_pwm::SoftPwm;
+use crate::FullH;
+
+/// Ramp the duty cycle of a PWM pin linearly from `from` to `to`.
+///
+/// This is the async variant of [`RampExt::ramp_to()`](crate::RampExt::ramp_to). The ramp
+/// consists of the given number of steps, each taking one millisecond. If the number of steps is
+/// zero, the final duty cycle is set immediately.
+///
+/// # Errors
+///
+/// The error of the pin will be returned, if a duty cycle could not be set. The ramp is aborted
+/// in this case.
+pub async fn ramp_to<P, D>(
+    pin: &mut P,
+    from: u16,
+    to: u16,
+    steps: u32,
+    delay: &mut D,
+) -> Result<(), P::Error>
+where
+    P: SetDutyCycle,
+    D: DelayNs,
+{
+    for step in 1..=steps {
+        delay.delay_ms(1).await;
+        pin.set_duty_cycle(ramp_value(from.into(), to.into(), step, steps) as u16)?;
+    }
+    match steps {
+        0 => pin.set_duty_cycle(to),
+        _ => Ok(()),
+    }
+}
+
+/// Ramp the speed of a Full-H bridge linearly to the given speed.
+///
+/// The ramp starts at the last commanded [speed](FullH::speed) of the bridge and consists of the
+/// given number of steps, each taking one millisecond. If the number of steps is zero, the final
+/// speed is set immediately.
+///
+/// # Errors
+///
+/// The error of the first half-bridge, which could not be set, will be returned. The ramp is
+/// aborted in this case.
+pub async fn ramp_speed<A, B, D>(
+    full_h: &mut FullH<A, B>,
+    to: i16,
+    steps: u32,
+    delay: &mut D,
+) -> Result<(), A::Error>
+where
+    A: SetDutyCycle,
+    B: SetDutyCycle<Error = A::Error>,
+    D: DelayNs,
+{
+    let from = full_h.speed();
+    for step in 1..=steps {
+        delay.delay_ms(1).await;
+        full_h.set_speed(ramp_value(from.into(), to.into(), step, steps) as i16)?;
+    }
+    match steps {
+        0 => full_h.set_speed(to),
+        _ => Ok(()),
+    }
+}
+
+/// Drive a pin high for the given duration in microseconds and low afterwards.
+///
+/// # Errors
+///
+/// The error of the pin will be returned, if it could not be set. If the pin could not be set
+/// high, it is not set low afterwards.
+pub async fn pulse<P, D>(pin: &mut P, duration_us: u32, delay: &mut D) -> Result<(), P::Error>
+where
+    P: OutputPin,
+    D: DelayNs,
+{
+    pin.set_high()?;
+    delay.delay_us(duration_us).await;
+    pin.set_low()
+}
+
+/// Generate the signal of a [SoftPwm] by ticking it every `tick_us` microseconds.
+///
+/// This is meant to be run as a separate task of the executor, instead of ticking the PWM from a
+/// timer interrupt. The function only returns, if the PWM could not be ticked. Please note, that
+/// the accuracy of the PWM depends on the latency of the executor.
+///
+/// # Errors
+///
+/// The error of the pin will be returned, if its level could not be changed.
+///
+/// # Examples
+///
+/// ```
+/// use l293x::asynch::run_soft_pwm;
+///
+/// #[embassy_executor::task]
+/// async fn soft_pwm_task(pwm: &'static SoftPwm<Output<'static>>) {
+///     // 100 ticks per period, i.e. 100 Hz with a tick every 100µs
+///     run_soft_pwm(pwm, 100, &mut Delay).await.unwrap();
+/// }
+/// ```
+pub async fn run_soft_pwm<P, D>(
+    pwm: &SoftPwm<P>,
+    tick_us: u32,
+    delay: &mut D,
+) -> Result<Infallible, P::Error>
+where
+    P: OutputPin,
+    D: DelayNs,
+{
+    loop {
+        pwm.tick()?;
+        delay.delay_us(tick_us).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{block_on, Delay, DigitalPin, PwmPin};
+    use coverage_helper::test;
+    use embedded_hal::digital::StatefulOutputPin;
+
+    #[test]
+    fn test_ramp_to() {
+        let mut pin = PwmPin::new();
+        let mut delay = Delay::new();
+
+        block_on(ramp_to(&mut pin, 0, 1000, 10, &mut delay)).unwrap();
+        assert_eq!(pin.get_duty_cycle(), 1000);
+        assert_eq!(delay.elapsed_ns(), 10_000_000);
+
+        block_on(ramp_to(&mut pin, 1000, 500, 0, &mut delay)).unwrap();
+        assert_eq!(pin.get_duty_cycle(), 500);
+
+        pin.fail();
+        assert!(block_on(ramp_to(&mut pin, 0, 1000, 10, &mut delay)).is_err());
+        assert!(block_on(ramp_to(&mut pin, 0, 1000, 0, &mut delay)).is_err());
+    }
+
+    #[test]
+    fn test_ramp_speed() {
+        let mut full_h = FullH::new(PwmPin::new(), PwmPin::new());
+        let mut delay = Delay::new();
+
+        block_on(ramp_speed(&mut full_h, i16::MAX, 4, &mut delay)).unwrap();
+        assert_eq!(full_h.speed(), i16::MAX);
+        assert_eq!(delay.elapsed_ns(), 4_000_000);
+
+        block_on(ramp_speed(&mut full_h, -100, 0, &mut delay)).unwrap();
+        assert_eq!(full_h.speed(), -100);
+
+        let (mut a, b) = full_h.release();
+        a.fail();
+        let mut full_h = FullH::new(a, b);
+        assert!(block_on(ramp_speed(&mut full_h, 100, 2, &mut delay)).is_err());
+        assert!(block_on(ramp_speed(&mut full_h, 100, 0, &mut delay)).is_err());
+    }
+
+    #[test]
+    fn test_pulse() {
+        let mut pin = DigitalPin::new();
+        let mut delay = Delay::new();
+
+        block_on(pulse(&mut pin, 10, &mut delay)).unwrap();
+        assert!(pin.is_set_low().unwrap());
+        assert_eq!(delay.elapsed_ns(), 10_000);
+
+        pin.fail();
+        assert!(block_on(pulse(&mut pin, 10, &mut delay)).is_err());
+    }
+
+    #[test]
+    fn test_run_soft_pwm() {
+        /// A pin failing after the given number of changes
+        struct CountdownPin(u8);
+
+        impl embedded_hal::digital::ErrorType for CountdownPin {
+            type Error = crate::test_util::DigitalError;
+        }
+
+        impl OutputPin for CountdownPin {
+            fn set_low(&mut self) -> Result<(), Self::Error> {
+                self.set_high()
+            }
+
+            fn set_high(&mut self) -> Result<(), Self::Error> {
+                self.0 = self
+                    .0
+                    .checked_sub(1)
+                    .ok_or(crate::test_util::DigitalError())?;
+                Ok(())
+            }
+        }
+
+        let pwm = SoftPwm::new(CountdownPin(3), 4);
+        pwm.channel().set_duty_cycle(2).unwrap();
+        let mut delay = Delay::new();
+
+        assert!(block_on(run_soft_pwm(&pwm, 100, &mut delay)).is_err());
+        // The fourth change of the level (after six ticks) fails
+        assert_eq!(delay.elapsed_ns(), 6 * 100_000);
+    }
+}
@@ -0,0 +1,43 @@
+/// Whether setting the state of a [HalfH](crate::HalfH) bridge enables its output
+///
+/// By default, the [OutputPin](embedded_hal::digital::OutputPin) and
+/// [SetDutyCycle](embedded_hal::pwm::SetDutyCycle) implementations of the bridges only change the
+/// input, like the setters of the chip (e.g. [`set_y1_high()`](crate::L293x::set_y1_high)). The
+/// output must be enabled explicitly. Because two outputs of the [L293](crate::L293x) chip share an
+/// enable pin, enabling an output enables the sibling output as well. Use the
+/// [Always](AutoEnable::Always) policy to opt into enabling the output, whenever it is set, e.g.
+/// when passing a bridge to a driver expecting a plain pin. The Full-H bridges returned by the
+/// chip (e.g. [`full_h12()`](crate::L293x::full_h12)) use this policy for their half-bridges.
+///
+/// Up to version 0.3, the bridges enabled their outputs by default. Code relying on this must
+/// either enable the outputs explicitly or opt into the [Always](AutoEnable::Always) policy.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::digital::OutputPin;
+/// use l293x::AutoEnable;
+///
+/// let mut y1 = l293x.y1();
+/// // Only sets the input 1, the outputs 1 & 2 stay disabled
+/// y1.set_high()?;
+///
+/// let mut y2 = l293x.y2().with_auto_enable(AutoEnable::Always);
+/// // Sets the input 2 and enables the outputs 1 & 2
+/// y2.set_high()?;
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum AutoEnable {
+    /// Enable the output, whenever its state or duty cycle is set.
+    Always,
+    /// Never enable the output implicitly. It must be enabled explicitly. This is the default.
+    Never,
+}
+
+impl Default for AutoEnable {
+    #[inline]
+    fn default() -> Self {
+        AutoEnable::Never
+    }
+}
@@ -0,0 +1,328 @@
+use core::cell::RefCell;
+
+use embedded_hal::digital::{OutputPin, StatefulOutputPin};
+use embedded_hal::pwm::SetDutyCycle;
+
+use crate::pins::Polarized;
+use crate::{AutoEnable, FullH, FullHBridge, HalfH, OutputStateError, Polarity};
+
+/// Two Half-H bridges sharing a common enable pin
+///
+/// A bridge pair owns the two input pins and the common enable pin of either the channels 1 & 2
+/// or the channels 3 & 4 of an [L293](crate::L293x) chip. Both pairs of a chip are created
+/// together using the [`split_pairs()`](L293x::split_pairs) method. Because they own their pins,
+/// the two pairs can be moved into different tasks independently of each other, e.g. one task
+/// per motor.
+///
+/// The bridges of the pair are accessed using the [`first()`](BridgePair::first) and
+/// [`second()`](BridgePair::second) methods. Both bridges still share the enable pin of the pair.
+///
+/// <div class="warning">
+/// Enabling or disabling one of the bridges enables or disables the other bridge as well!
+/// </div>
+///
+/// # Examples
+///
+/// ```
+/// use l293x::L293x;
+///
+/// let l293x = L293x::new(input1, input2, input3, input4, enable12, enable34);
+/// let (mut left, mut right) = l293x.split_pairs();
+///
+/// left.first().set_high()?;
+/// // This does not affect the left pair
+/// right.disable()?;
+/// ```
+#[derive(Debug)]
+pub struct BridgePair<A, B, EN> {
+    a: RefCell<A>,
+    b: RefCell<B>,
+    en: RefCell<EN>,
+    polarity: [Polarity; 2],
+    enable_polarity: Polarity,
+}
+
+impl<A, B, EN> BridgePair<A, B, EN> {
+    /// Create a new pair of Half-H bridges.
+    ///
+    /// This function takes the input pins of both bridges and their common enable pin. An unused
+    /// bridge can be left unconnected by passing the empty type (`()`) instead of a real pin.
+    #[inline]
+    pub fn new(a: A, b: B, en: EN) -> Self {
+        Self {
+            a: RefCell::new(a),
+            b: RefCell::new(b),
+            en: RefCell::new(en),
+            polarity: [Polarity::Normal; 2],
+            enable_polarity: Polarity::Normal,
+        }
+    }
+
+    /// Create a new pair from the pins and polarities of a split chip.
+    pub(crate) fn from_parts(
+        a: RefCell<A>,
+        b: RefCell<B>,
+        en: RefCell<EN>,
+        polarity: [Polarity; 2],
+        enable_polarity: Polarity,
+    ) -> Self {
+        Self {
+            a,
+            b,
+            en,
+            polarity,
+            enable_polarity,
+        }
+    }
+
+    /// Get the first Half-H bridge of the pair (i.e. output `y1` or `y3`).
+    #[inline]
+    pub fn first(&self) -> HalfH<'_, A, EN> {
+        HalfH::new(&self.a, &self.en).with_polarity(self.polarity[0], self.enable_polarity)
+    }
+
+    /// Get the second Half-H bridge of the pair (i.e. output `y2` or `y4`).
+    #[inline]
+    pub fn second(&self) -> HalfH<'_, B, EN> {
+        HalfH::new(&self.b, &self.en).with_polarity(self.polarity[1], self.enable_polarity)
+    }
+
+    /// Get a Full-H bridge driving a load connected to both outputs of the pair.
+    #[inline]
+    pub fn full_h(&self) -> FullHBridge<'_, A, B, EN> {
+        FullH::new(
+            self.first().with_auto_enable(AutoEnable::Always),
+            self.second().with_auto_enable(AutoEnable::Always),
+        )
+    }
+
+    /// Get mutable references to both input pins of the pair.
+    ///
+    /// The pins are returned as they are, i.e. without applying their polarities.
+    #[inline]
+    pub(crate) fn inputs_mut(&mut self) -> (&mut A, &mut B) {
+        (self.a.get_mut(), self.b.get_mut())
+    }
+
+    /// Get the cells of both input pins and the enable pin of the pair.
+    ///
+    /// The pins are returned as they are, i.e. without applying their polarities.
+    #[inline]
+    pub(crate) fn pins(&self) -> (&RefCell<A>, &RefCell<B>, &RefCell<EN>) {
+        (&self.a, &self.b, &self.en)
+    }
+
+    /// Release the pair and return the two input pins and the enable pin.
+    #[inline]
+    pub fn release(self) -> (A, B, EN) {
+        (
+            self.a.into_inner(),
+            self.b.into_inner(),
+            self.en.into_inner(),
+        )
+    }
+}
+
+impl<A, B, EN> BridgePair<A, B, EN>
+where
+    EN: OutputPin,
+{
+    /// Enable both bridges of the pair.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the common enable pin.
+    pub fn enable(&mut self) -> Result<(), EN::Error> {
+        Polarized::new(self.en.get_mut(), self.enable_polarity).set_high()
+    }
+
+    /// Disable both bridges of the pair.
+    ///
+    /// This sets both outputs into the high impedance mode. The inputs are not changed. To drive
+    /// them low first, use [`disable_holding_low()`](BridgePair::disable_holding_low) instead.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the common enable pin.
+    pub fn disable(&mut self) -> Result<(), EN::Error> {
+        Polarized::new(self.en.get_mut(), self.enable_polarity).set_low()
+    }
+}
+
+impl<A, B, EN> BridgePair<A, B, EN>
+where
+    A: OutputPin,
+    B: OutputPin<Error = A::Error>,
+    EN: OutputPin,
+{
+    /// Drive both inputs low and disable both bridges of the pair afterwards.
+    ///
+    /// In contrast to [`disable()`](BridgePair::disable), this avoids a brief re-assertion of the
+    /// old output states, when the pair is enabled again later on.
+    ///
+    /// # Errors
+    ///
+    /// The enable pin is set low, even if setting one of the inputs fails. The first error of the
+    /// input pins is returned as [InputPinError](OutputStateError::InputPinError) afterwards,
+    /// while an error of the enable pin is returned as
+    /// [EnablePinError](OutputStateError::EnablePinError).
+    pub fn disable_holding_low(&mut self) -> Result<(), OutputStateError<A::Error, EN::Error>> {
+        let inputs = Polarized::new(self.a.get_mut(), self.polarity[0]).set_low();
+        let inputs = inputs.and(Polarized::new(self.b.get_mut(), self.polarity[1]).set_low());
+        Polarized::new(self.en.get_mut(), self.enable_polarity)
+            .set_low()
+            .map_err(OutputStateError::EnablePinError)?;
+        inputs.map_err(OutputStateError::InputPinError)
+    }
+}
+
+impl<A, B, EN> BridgePair<A, B, EN>
+where
+    A: SetDutyCycle,
+    B: SetDutyCycle<Error = A::Error>,
+    EN: OutputPin,
+{
+    /// Turn the duty cycles of both inputs off and disable both bridges of the pair afterwards.
+    ///
+    /// This is the PWM counterpart of [`disable_holding_low()`](BridgePair::disable_holding_low).
+    ///
+    /// # Errors
+    ///
+    /// The enable pin is set low, even if setting one of the duty cycles fails. The first error
+    /// of the input pins is returned as [InputPinError](OutputStateError::InputPinError)
+    /// afterwards, while an error of the enable pin is returned as
+    /// [EnablePinError](OutputStateError::EnablePinError).
+    pub fn disable_duty_cycle_off(&mut self) -> Result<(), OutputStateError<A::Error, EN::Error>> {
+        let inputs = Polarized::new(self.a.get_mut(), self.polarity[0]).set_duty_cycle_fully_off();
+        let inputs = inputs
+            .and(Polarized::new(self.b.get_mut(), self.polarity[1]).set_duty_cycle_fully_off());
+        Polarized::new(self.en.get_mut(), self.enable_polarity)
+            .set_low()
+            .map_err(OutputStateError::EnablePinError)?;
+        inputs.map_err(OutputStateError::InputPinError)
+    }
+}
+
+impl<A, B, EN> BridgePair<A, B, EN>
+where
+    EN: StatefulOutputPin,
+{
+    /// Check whether the bridges of the pair are enabled.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the common enable pin.
+    pub fn is_enabled(&mut self) -> Result<bool, EN::Error> {
+        Polarized::new(self.en.get_mut(), self.enable_polarity).is_set_high()
+    }
+
+    /// Check whether the bridges of the pair are disabled.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the common enable pin.
+    pub fn is_disabled(&mut self) -> Result<bool, EN::Error> {
+        Polarized::new(self.en.get_mut(), self.enable_polarity).is_set_low()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{DigitalPin, PwmPin};
+    use crate::L293x;
+    use coverage_helper::test;
+
+    fn pairs() -> (
+        BridgePair<DigitalPin, DigitalPin, DigitalPin>,
+        BridgePair<DigitalPin, DigitalPin, DigitalPin>,
+    ) {
+        L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        )
+        .split_pairs()
+    }
+
+    #[test]
+    fn test_split_pairs() {
+        let (mut pair12, mut pair34) = pairs();
+
+        pair12.first().set_high().unwrap();
+        assert!(pair12.is_disabled().unwrap());
+        pair12.enable().unwrap();
+        assert!(pair12.is_enabled().unwrap());
+        assert!(pair12.second().is_enabled().unwrap());
+        assert!(pair34.is_disabled().unwrap());
+
+        pair34.enable().unwrap();
+        pair12.disable().unwrap();
+        assert!(pair12.first().is_disabled().unwrap());
+        assert!(pair34.first().is_enabled().unwrap());
+
+        let (mut a1, _, _) = pair12.release();
+        assert!(a1.is_set_high().unwrap());
+    }
+
+    #[test]
+    fn test_full_h() {
+        let (pair12, _) = pairs();
+
+        pair12.full_h().reverse().unwrap();
+        assert!(pair12.first().is_set_low().unwrap());
+        assert!(pair12.second().is_set_high().unwrap());
+
+        pair12.full_h().coast().unwrap();
+        assert!(pair12.first().is_disabled().unwrap());
+    }
+
+    #[test]
+    fn test_disable_holding_low() {
+        let l293x = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            (),
+            (),
+            DigitalPin::new(),
+            (),
+        );
+        let (mut pair12, _) = l293x.split_pairs();
+
+        pair12.first().set_high().unwrap();
+        pair12.disable_holding_low().unwrap();
+        assert!(pair12.is_disabled().unwrap());
+        let (mut a1, _, _) = pair12.release();
+        assert!(a1.is_set_low().unwrap());
+    }
+
+    #[test]
+    fn test_disable_duty_cycle_off() {
+        let (mut pair12, _) =
+            L293x::new(PwmPin::new(), PwmPin::new(), (), (), DigitalPin::new(), ()).split_pairs();
+
+        pair12.first().set_duty_cycle_fully_on().unwrap();
+        pair12.second().set_duty_cycle_fully_off().unwrap();
+        pair12.inputs_mut().1.fail();
+        assert!(matches!(
+            pair12.disable_duty_cycle_off(),
+            Err(OutputStateError::InputPinError(_))
+        ));
+        assert!(pair12.is_disabled().unwrap());
+        assert_eq!(pair12.inputs_mut().0.get_duty_cycle(), 0);
+    }
+
+    #[test]
+    fn test_disable_fail() {
+        let (mut pair12, _) = pairs();
+        pair12.en.get_mut().fail();
+
+        assert!(pair12.enable().is_err());
+        assert!(pair12.disable().is_err());
+        assert!(pair12.is_enabled().is_err());
+        assert!(pair12.is_disabled().is_err());
+    }
+}
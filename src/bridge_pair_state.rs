@@ -0,0 +1,56 @@
+use crate::Direction;
+
+/// The combined state of two Half-H bridges driving a load
+///
+/// When two outputs of a pair sharing a common enable pin (e.g. `y1` and `y2`) drive the terminals
+/// of a load, only a few combinations of the input and enable states are meaningful. This enum
+/// lists these combinations, so that they can be set at once using the
+/// [`set_y1_and_y2_state()`](crate::L293x::set_y1_and_y2_state) or
+/// [`set_y3_and_y4_state()`](crate::L293x::set_y3_and_y4_state) methods:
+///
+/// | State                                 | First input | Second input | Enable pin |
+/// |---------------------------------------|-------------|--------------|------------|
+/// | [Forward](BridgePairState::Forward)   | High        | Low          | High       |
+/// | [Reverse](BridgePairState::Reverse)   | Low         | High         | High       |
+/// | [BrakeLow](BridgePairState::BrakeLow) | Low         | Low          | High       |
+/// | [HighZ](BridgePairState::HighZ)       | Unchanged   | Unchanged    | Low        |
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BridgePairState {
+    /// Drive the load in forward direction.
+    Forward,
+    /// Drive the load in reverse direction.
+    Reverse,
+    /// Brake the load by driving both outputs low.
+    BrakeLow,
+    /// Let the load coast by disabling both outputs.
+    HighZ,
+}
+
+impl From<Direction> for BridgePairState {
+    #[inline]
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::Forward => BridgePairState::Forward,
+            Direction::Reverse => BridgePairState::Reverse,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coverage_helper::test;
+
+    #[test]
+    fn test_from_direction() {
+        assert_eq!(
+            BridgePairState::from(Direction::Forward),
+            BridgePairState::Forward
+        );
+        assert_eq!(
+            BridgePairState::from(Direction::Reverse),
+            BridgePairState::Reverse
+        );
+    }
+}
@@ -0,0 +1,193 @@
+//! # Builder for the L293x chip driver
+//!
+//! The [`L293x::new()`] function takes six positional pin arguments, which makes it easy to
+//! accidentally swap an input and an enable pin. The [L293xBuilder] allows assigning the pins by
+//! name instead:
+//!
+//! ```
+//! use l293x::L293x;
+//!
+//! let l293x = L293x::builder()
+//!     .input1(input1)
+//!     .input2(input2)
+//!     .enable12(enable12)
+//!     .enable34(())
+//!     .build();
+//! ```
+//!
+//! Inputs, which are not assigned, are left unconnected (i.e. they are
+//! [NotConnected](crate::pins::NotConnected)). The enable pins are required. To make sure, that they are not forgotten, the
+//! [`build()`](L293xBuilder::build) method is only available, once both of them are assigned. An
+//! unused enable pin needs to be assigned the empty type (`()`) explicitly.
+//!
+//! ```compile_fail
+//! # use l293x::L293x;
+//! // Does not compile, because the enable pin 3,4EN is not assigned
+//! let l293x = L293x::builder().input1(input1).enable12(enable12).build();
+//! ```
+use crate::pins::NotConnected;
+use crate::L293x;
+
+/// Placeholder of an enable pin, which has not been assigned yet
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Unassigned;
+
+/// An enable pin, which has been assigned to the builder
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Assigned<P>(P);
+
+/// A [L293xBuilder] without any assigned pins
+pub type EmptyL293xBuilder =
+    L293xBuilder<NotConnected, NotConnected, NotConnected, NotConnected, Unassigned, Unassigned>;
+
+/// Builder of a [L293x] chip driver with named pins
+///
+/// The builder is created using the [`L293x::builder()`] method. For more information, please
+/// see the [module documentation](crate::builder).
+#[derive(Debug)]
+#[must_use]
+pub struct L293xBuilder<A1, A2, A3, A4, EN12, EN34> {
+    a1: A1,
+    a2: A2,
+    a3: A3,
+    a4: A4,
+    en12: EN12,
+    en34: EN34,
+}
+
+impl EmptyL293xBuilder {
+    /// Create a new builder, where all inputs are unconnected and no enable pin is assigned.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            a1: NotConnected,
+            a2: NotConnected,
+            a3: NotConnected,
+            a4: NotConnected,
+            en12: Unassigned,
+            en34: Unassigned,
+        }
+    }
+}
+
+impl Default for EmptyL293xBuilder {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A1, A2, A3, A4, EN12, EN34> L293xBuilder<A1, A2, A3, A4, EN12, EN34> {
+    /// Assign the pin connected to the input `1A` of the chip.
+    #[inline]
+    pub fn input1<P>(self, pin: P) -> L293xBuilder<P, A2, A3, A4, EN12, EN34> {
+        L293xBuilder {
+            a1: pin,
+            a2: self.a2,
+            a3: self.a3,
+            a4: self.a4,
+            en12: self.en12,
+            en34: self.en34,
+        }
+    }
+
+    /// Assign the pin connected to the input `2A` of the chip.
+    #[inline]
+    pub fn input2<P>(self, pin: P) -> L293xBuilder<A1, P, A3, A4, EN12, EN34> {
+        L293xBuilder {
+            a1: self.a1,
+            a2: pin,
+            a3: self.a3,
+            a4: self.a4,
+            en12: self.en12,
+            en34: self.en34,
+        }
+    }
+
+    /// Assign the pin connected to the input `3A` of the chip.
+    #[inline]
+    pub fn input3<P>(self, pin: P) -> L293xBuilder<A1, A2, P, A4, EN12, EN34> {
+        L293xBuilder {
+            a1: self.a1,
+            a2: self.a2,
+            a3: pin,
+            a4: self.a4,
+            en12: self.en12,
+            en34: self.en34,
+        }
+    }
+
+    /// Assign the pin connected to the input `4A` of the chip.
+    #[inline]
+    pub fn input4<P>(self, pin: P) -> L293xBuilder<A1, A2, A3, P, EN12, EN34> {
+        L293xBuilder {
+            a1: self.a1,
+            a2: self.a2,
+            a3: self.a3,
+            a4: pin,
+            en12: self.en12,
+            en34: self.en34,
+        }
+    }
+
+    /// Assign the pin connected to the enable pin `1,2EN` of the chip.
+    #[inline]
+    pub fn enable12<P>(self, pin: P) -> L293xBuilder<A1, A2, A3, A4, Assigned<P>, EN34> {
+        L293xBuilder {
+            a1: self.a1,
+            a2: self.a2,
+            a3: self.a3,
+            a4: self.a4,
+            en12: Assigned(pin),
+            en34: self.en34,
+        }
+    }
+
+    /// Assign the pin connected to the enable pin `3,4EN` of the chip.
+    #[inline]
+    pub fn enable34<P>(self, pin: P) -> L293xBuilder<A1, A2, A3, A4, EN12, Assigned<P>> {
+        L293xBuilder {
+            a1: self.a1,
+            a2: self.a2,
+            a3: self.a3,
+            a4: self.a4,
+            en12: self.en12,
+            en34: Assigned(pin),
+        }
+    }
+}
+
+impl<A1, A2, A3, A4, EN12, EN34> L293xBuilder<A1, A2, A3, A4, Assigned<EN12>, Assigned<EN34>> {
+    /// Build the [L293x] chip driver from the assigned pins.
+    #[inline]
+    pub fn build(self) -> L293x<A1, A2, A3, A4, EN12, EN34> {
+        L293x::new(self.a1, self.a2, self.a3, self.a4, self.en12.0, self.en34.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pins::Vcc;
+    use crate::test_util::DigitalPin;
+    use coverage_helper::test;
+
+    #[test]
+    fn test_builder() {
+        let mut l293x = L293xBuilder::default()
+            .enable34(Vcc())
+            .input4(DigitalPin::new())
+            .input3(DigitalPin::new())
+            .input2(DigitalPin::new())
+            .input1(DigitalPin::new())
+            .enable12(DigitalPin::new())
+            .build();
+
+        l293x.set_y2_high().unwrap();
+        l293x.enable_y1_and_y2().unwrap();
+        assert!(l293x.y1_and_y2_enabled().unwrap());
+        assert!(l293x.is_y2_set_high().unwrap());
+        assert!(l293x.is_y1_set_low().unwrap());
+        assert!(l293x.y3_and_y4_enabled().unwrap());
+    }
+}
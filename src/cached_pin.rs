@@ -0,0 +1,197 @@
+use embedded_hal::digital::{self, OutputPin, PinState, StatefulOutputPin};
+use embedded_hal::pwm::{self, SetDutyCycle};
+
+/// A pin, which skips redundant operations
+///
+/// Each change of a pin connected to a port expander or a bit-banged bus costs a bus
+/// transaction. This wrapper remembers the last state or duty cycle set on the pin and skips
+/// setting the same value again. Thus, e.g. repeatedly calling
+/// [`set_y1_high()`](crate::L293x::set_y1_high) becomes a no-op after the first call. Because it
+/// implements the [OutputPin], [StatefulOutputPin] and [SetDutyCycle] traits itself (if the
+/// wrapped pin does), it can be used as any pin of the drivers of this crate.
+///
+/// The state of the wrapped pin is unknown initially, so the first operation is always forwarded.
+/// If an operation fails, the cached value is discarded. If the pin may be changed by other
+/// means, call [`invalidate()`](CachedPin::invalidate) afterwards.
+///
+/// # Examples
+///
+/// ```
+/// use l293x::{CachedPin, L293x};
+///
+/// let mut l293x = L293x::new(
+///     CachedPin::new(input1),
+///     CachedPin::new(input2),
+///     (),
+///     (),
+///     CachedPin::new(enable12),
+///     (),
+/// );
+///
+/// l293x.set_y1_high()?;
+/// // Does not access the pin again
+/// l293x.set_y1_high()?;
+/// ```
+#[derive(Debug)]
+pub struct CachedPin<P> {
+    pin: P,
+    state: Option<PinState>,
+    duty: Option<u16>,
+}
+
+impl<P> CachedPin<P> {
+    /// Create a new cached pin, whose state is unknown.
+    #[inline]
+    pub fn new(pin: P) -> Self {
+        Self {
+            pin,
+            state: None,
+            duty: None,
+        }
+    }
+
+    /// Release the cached pin and return the wrapped pin.
+    #[inline]
+    pub fn release(self) -> P {
+        self.pin
+    }
+
+    /// Discard the cached state and duty cycle, e.g. after the pin has been changed by other
+    /// means.
+    ///
+    /// The next operation is forwarded to the wrapped pin again.
+    #[inline]
+    pub fn invalidate(&mut self) {
+        self.state = None;
+        self.duty = None;
+    }
+
+    /// Forward setting the `state` to the wrapped pin, if it differs from the cached one.
+    fn update_state<E>(
+        &mut self,
+        state: PinState,
+        set: impl FnOnce(&mut P) -> Result<(), E>,
+    ) -> Result<(), E> {
+        if self.state == Some(state) {
+            return Ok(());
+        }
+        self.invalidate();
+        set(&mut self.pin)?;
+        self.state = Some(state);
+        Ok(())
+    }
+}
+
+impl<P: digital::ErrorType> digital::ErrorType for CachedPin<P> {
+    type Error = P::Error;
+}
+
+impl<P: OutputPin> OutputPin for CachedPin<P> {
+    #[inline]
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.update_state(PinState::Low, P::set_low)
+    }
+
+    #[inline]
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.update_state(PinState::High, P::set_high)
+    }
+}
+
+impl<P: StatefulOutputPin> StatefulOutputPin for CachedPin<P> {
+    /// Check whether the pin is set high.
+    ///
+    /// If the state is cached, the wrapped pin is not read.
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        match self.state {
+            Some(state) => Ok(state == PinState::High),
+            None => self.pin.is_set_high(),
+        }
+    }
+
+    /// Check whether the pin is set low.
+    ///
+    /// If the state is cached, the wrapped pin is not read.
+    #[inline]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        self.is_set_high().map(|high| !high)
+    }
+}
+
+impl<P: pwm::ErrorType> pwm::ErrorType for CachedPin<P> {
+    type Error = P::Error;
+}
+
+impl<P: SetDutyCycle> SetDutyCycle for CachedPin<P> {
+    #[inline]
+    fn max_duty_cycle(&self) -> u16 {
+        self.pin.max_duty_cycle()
+    }
+
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+        if self.duty == Some(duty) {
+            return Ok(());
+        }
+        self.invalidate();
+        self.pin.set_duty_cycle(duty)?;
+        self.duty = Some(duty);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{DigitalPin, PwmPin};
+    use crate::L293x;
+    use coverage_helper::test;
+
+    #[test]
+    fn test_digital() {
+        let mut pin = CachedPin::new(DigitalPin::new());
+        assert!(pin.is_set_low().unwrap());
+
+        pin.set_high().unwrap();
+        pin.pin.fail();
+        pin.set_high().unwrap();
+        assert!(pin.is_set_high().unwrap());
+        assert!(pin.set_low().is_err());
+        assert!(pin.set_low().is_err());
+        assert!(pin.is_set_low().is_err());
+    }
+
+    #[test]
+    fn test_duty_cycle() {
+        let mut pin = CachedPin::new(PwmPin::new());
+        assert_eq!(pin.max_duty_cycle(), u16::MAX);
+
+        pin.set_duty_cycle(1000).unwrap();
+        pin.pin.fail();
+        pin.set_duty_cycle(1000).unwrap();
+        assert!(pin.set_duty_cycle(0).is_err());
+        assert!(pin.set_duty_cycle(1000).is_err());
+        assert_eq!(pin.release().get_duty_cycle(), 1000);
+    }
+
+    #[test]
+    fn test_chip() {
+        let mut l293x = L293x::new(
+            CachedPin::new(DigitalPin::new()),
+            (),
+            (),
+            (),
+            CachedPin::new(DigitalPin::new()),
+            (),
+        );
+        l293x.set_y1_high().unwrap();
+        let ((mut a1, ..), (mut en12, _)) = l293x.release();
+        a1.pin.set_low().unwrap();
+        a1.set_high().unwrap();
+        assert!(a1.release().is_set_low().unwrap());
+
+        en12.set_high().unwrap();
+        en12.pin.set_low().unwrap();
+        en12.invalidate();
+        assert!(en12.is_set_low().unwrap());
+    }
+}
@@ -0,0 +1,334 @@
+//! # CAN velocity command interface
+//!
+//! This module is only available, if the `can` feature is enabled. It allows using the motor
+//! drivers of this crate as a node in a CAN based robot. The node listens on an
+//! [embedded-can](embedded_can) interface for velocity, stop and emergency stop commands.
+//!
+//! # Frame format
+//!
+//! All commands use standard (11-bit) data frames. The identifier is the [BASE_ID] plus the id of
+//! the addressed node (`0x200` - `0x2FF`). The data of the frame contains:
+//!
+//! | Byte  | Content                                                              |
+//! |-------|----------------------------------------------------------------------|
+//! | 0     | The id of the motor on the node ([ALL_MOTORS] addresses all motors)  |
+//! | 1     | The command: `0` = velocity, `1` = stop, `2` = emergency stop         |
+//! | 2 - 3 | Velocity commands only: the signed velocity (`i16`, little endian)   |
+//!
+//! The velocity uses the same scale as [`FullH::set_speed()`](crate::FullH::set_speed), i.e.
+//! [`i16::MAX`] is full speed forward. Frames with other identifiers, remote frames and malformed
+//! frames are ignored.
+//!
+//! # Examples
+//!
+//! ```
+//! use l293x::can::{CanNode, ALL_MOTORS};
+//!
+//! // [...] create the CAN interface and the motors
+//! let mut node = CanNode::new(can, 0x12);
+//!
+//! loop {
+//!     if let Some(command) = node.poll()? {
+//!         match command.motor {
+//!             0 => command.command.apply(&mut left)?,
+//!             1 => command.command.apply(&mut right)?,
+//!             ALL_MOTORS => {
+//!                 command.command.apply(&mut left)?;
+//!                 command.command.apply(&mut right)?;
+//!             }
+//!             _ => {}
+//!         }
+//!     }
+//! }
+//! ```
+
+use embedded_can::nb::Can;
+use embedded_can::{Frame, Id, StandardId};
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::OutputPin;
+use embedded_hal::pwm::SetDutyCycle;
+
+use crate::control::FailsafeAction;
+use crate::{FullH, HalfH, OutputStateError};
+
+/// The identifier of the commands sent to the node with the id `0`.
+pub const BASE_ID: u16 = 0x200;
+
+/// The motor id addressing all motors of a node.
+pub const ALL_MOTORS: u8 = 0xFF;
+
+/// A command for a single motor
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Command {
+    /// Drive the motor with the given signed velocity.
+    Velocity(i16),
+    /// Stop the motor.
+    Stop,
+    /// Stop the motor and ignore all further velocity commands, until the emergency stop is
+    /// [cleared](CanNode::clear_emergency_stop).
+    EmergencyStop,
+}
+
+impl Command {
+    /// Apply the command to the given motor.
+    ///
+    /// Velocity commands set the speed of the motor, while both stop commands brake it.
+    ///
+    /// # Errors
+    ///
+    /// The error of the first half-bridge of the motor, which could not be set, will be returned.
+    pub fn apply<A, B>(&self, motor: &mut FullH<A, B>) -> Result<(), A::Error>
+    where
+        A: SetDutyCycle,
+        B: SetDutyCycle<Error = A::Error>,
+    {
+        match self {
+            Command::Velocity(velocity) => motor.set_speed(*velocity),
+            Command::Stop | Command::EmergencyStop => motor.set_speed(0),
+        }
+    }
+
+    /// Apply the command to the given motor, stopping it using the given failsafe action.
+    ///
+    /// Velocity commands set the speed of the motor, like [`apply()`](Command::apply). Both stop
+    /// commands stop the motor using [`FullH::failsafe()`] with the given `action`, so that e.g.
+    /// a heavy load is ramped down instead of being braked abruptly.
+    ///
+    /// # Errors
+    ///
+    /// The error of the first pin of the motor, which could not be set, will be returned.
+    pub fn apply_failsafe<'a, IA, EA, IB, EB, D>(
+        &self,
+        motor: &mut FullH<HalfH<'a, IA, EA>, HalfH<'a, IB, EB>>,
+        action: FailsafeAction,
+        delay: &mut D,
+    ) -> Result<(), OutputStateError<IA::Error, EA::Error>>
+    where
+        IA: SetDutyCycle,
+        IB: SetDutyCycle<Error = IA::Error>,
+        EA: OutputPin,
+        EB: OutputPin<Error = EA::Error>,
+        D: DelayNs,
+    {
+        match self {
+            Command::Velocity(velocity) => motor.set_speed(*velocity),
+            Command::Stop | Command::EmergencyStop => motor.failsafe(action, delay),
+        }
+    }
+}
+
+/// A command addressed to a motor of the node
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CanCommand {
+    /// The id of the addressed motor, or [ALL_MOTORS].
+    pub motor: u8,
+    /// The command for the motor.
+    pub command: Command,
+}
+
+impl CanCommand {
+    /// Decode the command from the given frame.
+    ///
+    /// Returns `None`, if the frame is not addressed to the node with the given id, or if it is
+    /// not a valid command frame.
+    pub fn decode<F: Frame>(node_id: u8, frame: &F) -> Option<Self> {
+        let expected = StandardId::new(BASE_ID + u16::from(node_id))?;
+        if frame.is_remote_frame() || frame.id() != Id::Standard(expected) {
+            return None;
+        }
+        let command = match frame.data() {
+            [_, 0, low, high, ..] => Command::Velocity(i16::from_le_bytes([*low, *high])),
+            [_, 1, ..] => Command::Stop,
+            [_, 2, ..] => Command::EmergencyStop,
+            _ => return None,
+        };
+        Some(Self {
+            motor: frame.data()[0],
+            command,
+        })
+    }
+
+    /// Encode the command into a frame addressed to the node with the given id.
+    ///
+    /// Returns `None`, if the frame could not be created.
+    pub fn encode<F: Frame>(&self, node_id: u8) -> Option<F> {
+        let id = StandardId::new(BASE_ID + u16::from(node_id))?;
+        match self.command {
+            Command::Velocity(velocity) => {
+                let [low, high] = velocity.to_le_bytes();
+                F::new(id, &[self.motor, 0, low, high])
+            }
+            Command::Stop => F::new(id, &[self.motor, 1]),
+            Command::EmergencyStop => F::new(id, &[self.motor, 2]),
+        }
+    }
+}
+
+/// A CAN node receiving motor commands
+///
+/// The node receives the frames from the CAN interface and decodes the commands addressed to it.
+/// Once an emergency stop was received, all velocity commands are ignored, until the emergency
+/// stop is [cleared](CanNode::clear_emergency_stop) by the application.
+#[derive(Debug)]
+pub struct CanNode<C> {
+    can: C,
+    node_id: u8,
+    emergency_stop: bool,
+}
+
+impl<C: Can> CanNode<C> {
+    /// Create a new node with the given id on the CAN interface.
+    #[inline]
+    pub fn new(can: C, node_id: u8) -> Self {
+        Self {
+            can,
+            node_id,
+            emergency_stop: false,
+        }
+    }
+
+    /// Receive the next command addressed to this node.
+    ///
+    /// This method does not block. It returns `None`, if no frame was received, or if the
+    /// received frame does not contain a command for this node.
+    ///
+    /// # Errors
+    ///
+    /// The error of the CAN interface will be returned, if a frame could not be received.
+    pub fn poll(&mut self) -> Result<Option<CanCommand>, C::Error> {
+        let frame = match self.can.receive() {
+            Ok(frame) => frame,
+            Err(nb::Error::WouldBlock) => return Ok(None),
+            Err(nb::Error::Other(error)) => return Err(error),
+        };
+        let command = CanCommand::decode(self.node_id, &frame);
+        Ok(match command {
+            Some(CanCommand {
+                command: Command::Velocity(_),
+                ..
+            }) if self.emergency_stop => None,
+            Some(CanCommand {
+                command: Command::EmergencyStop,
+                ..
+            }) => {
+                self.emergency_stop = true;
+                command
+            }
+            _ => command,
+        })
+    }
+
+    /// Check whether an emergency stop was received.
+    #[inline]
+    pub fn is_emergency_stopped(&self) -> bool {
+        self.emergency_stop
+    }
+
+    /// Clear the emergency stop, so that velocity commands are accepted again.
+    #[inline]
+    pub fn clear_emergency_stop(&mut self) {
+        self.emergency_stop = false;
+    }
+
+    /// Release the node and return the CAN interface.
+    #[inline]
+    pub fn release(self) -> C {
+        self.can
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{CanBus, CanFrame, Delay, DigitalPin, PwmPin};
+    use crate::L293x;
+    use coverage_helper::test;
+    use embedded_can::ErrorKind;
+
+    fn command(motor: u8, command: Command) -> CanCommand {
+        CanCommand { motor, command }
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let commands = [
+            command(1, Command::Velocity(-1234)),
+            command(ALL_MOTORS, Command::Stop),
+            command(0, Command::EmergencyStop),
+        ];
+        for c in commands {
+            let frame: CanFrame = c.encode(0x12).unwrap();
+            assert_eq!(frame.id(), Id::Standard(StandardId::new(0x212).unwrap()));
+            assert_eq!(CanCommand::decode(0x12, &frame), Some(c));
+            assert_eq!(CanCommand::decode(0x13, &frame), None);
+        }
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        let id = StandardId::new(BASE_ID).unwrap();
+        let short = CanFrame::new(id, &[0, 0, 1]).unwrap();
+        let unknown = CanFrame::new(id, &[0, 3]).unwrap();
+        let remote = CanFrame::new_remote(id, 2).unwrap();
+        assert_eq!(CanCommand::decode(0, &short), None);
+        assert_eq!(CanCommand::decode(0, &unknown), None);
+        assert_eq!(CanCommand::decode(0, &remote), None);
+    }
+
+    #[test]
+    fn test_node() {
+        let mut bus = CanBus::new();
+        bus.push(command(0, Command::Velocity(100)).encode(1).unwrap());
+        bus.push(command(0, Command::Velocity(100)).encode(2).unwrap());
+        bus.push(command(0, Command::EmergencyStop).encode(1).unwrap());
+        bus.push(command(0, Command::Velocity(100)).encode(1).unwrap());
+        bus.push(command(0, Command::Velocity(200)).encode(1).unwrap());
+        let mut node = CanNode::new(bus, 1);
+
+        assert_eq!(node.poll(), Ok(Some(command(0, Command::Velocity(100)))));
+        assert_eq!(node.poll(), Ok(None));
+        assert_eq!(node.poll(), Ok(Some(command(0, Command::EmergencyStop))));
+        assert!(node.is_emergency_stopped());
+        assert_eq!(node.poll(), Ok(None));
+        node.clear_emergency_stop();
+        assert_eq!(node.poll(), Ok(Some(command(0, Command::Velocity(200)))));
+        assert_eq!(node.poll(), Ok(None));
+
+        let mut bus = node.release();
+        bus.fail();
+        let mut node = CanNode::new(bus, 1);
+        assert_eq!(node.poll(), Err(ErrorKind::Other));
+    }
+
+    #[test]
+    fn test_apply() {
+        let mut motor = FullH::new(PwmPin::new(), PwmPin::new());
+
+        Command::Velocity(-100).apply(&mut motor).unwrap();
+        assert_eq!(motor.speed(), -100);
+        Command::EmergencyStop.apply(&mut motor).unwrap();
+        assert_eq!(motor.speed(), 0);
+        Command::Velocity(100).apply(&mut motor).unwrap();
+        Command::Stop.apply(&mut motor).unwrap();
+        assert_eq!(motor.speed(), 0);
+    }
+
+    #[test]
+    fn test_apply_failsafe() {
+        let l293x = L293x::new(PwmPin::new(), PwmPin::new(), (), (), DigitalPin::new(), ());
+        let mut motor = l293x.full_h12();
+        let mut delay = Delay::new();
+
+        Command::Velocity(100)
+            .apply_failsafe(&mut motor, FailsafeAction::Coast, &mut delay)
+            .unwrap();
+        assert_eq!(motor.speed(), 100);
+        Command::Stop
+            .apply_failsafe(&mut motor, FailsafeAction::Coast, &mut delay)
+            .unwrap();
+        assert_eq!(motor.speed(), 0);
+        assert!(l293x.y1().is_disabled().unwrap());
+    }
+}
@@ -0,0 +1,232 @@
+use embedded_hal::digital::OutputPin;
+
+use crate::shift_register::shift_out;
+
+/// The bit of the enable pin of the outputs 1 & 2 in the state of a chip.
+const EN12: u8 = 1 << 4;
+
+/// The bit of the enable pin of the outputs 3 & 4 in the state of a chip.
+const EN34: u8 = 1 << 5;
+
+/// Multiple L293x chips driven by a chain of shift registers
+///
+/// Large output banks (e.g. relay or valve boards) often use many L293 chips, whose inputs and
+/// enable pins are driven by a chain of daisy-chained 74HC595 shift registers. This allows
+/// controlling all chips using only three pins of the microcontroller (data, clock and latch).
+///
+/// The chain consists of one shift register per chip. Chip `0` is connected to the first shift
+/// register of the chain (i.e. the one connected to the microcontroller). The outputs of each
+/// shift register are connected to the chip as follows:
+///
+/// | Shift register output | Chip pin |
+/// |-----------------------|----------|
+/// | `Q0` - `Q3`           | `1A` - `4A` (inputs 1 - 4) |
+/// | `Q4`                  | `1,2EN`  |
+/// | `Q5`                  | `3,4EN`  |
+///
+/// In contrast to the [L293x](crate::L293x) driver, changing the state of an output does not
+/// immediately change the pins. Instead, all changes are collected and written to the whole
+/// chain at once using [`commit()`](L293xChain::commit). This allows changing many outputs
+/// consistently with a single latch.
+///
+/// # Examples
+///
+/// ```
+/// use l293x::L293xChain;
+///
+/// // [...] create the data, clock and latch pins
+/// let mut chain: L293xChain<_, _, _, 8> = L293xChain::new(data, clock, latch);
+///
+/// // Switch on output 3 of the chip 5
+/// chain.set_input(5, 3, true);
+/// chain.set_enabled(5, 3, true);
+/// chain.commit()?;
+/// ```
+#[derive(Debug)]
+pub struct L293xChain<DATA, CLK, LATCH, const N: usize> {
+    data: DATA,
+    clock: CLK,
+    latch: LATCH,
+    states: [u8; N],
+}
+
+impl<DATA, CLK, LATCH, const N: usize> L293xChain<DATA, CLK, LATCH, N> {
+    /// Create a new chain of `N` chips from the data, clock and latch pins.
+    ///
+    /// All inputs and enable pins are initially low. They are not written, until the chain is
+    /// [committed](L293xChain::commit).
+    #[inline]
+    pub fn new(data: DATA, clock: CLK, latch: LATCH) -> Self {
+        Self {
+            data,
+            clock,
+            latch,
+            states: [0; N],
+        }
+    }
+
+    /// Set the input (`1` - `4`) of the given chip.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the chip or the input does not exist.
+    #[inline]
+    pub fn set_input(&mut self, chip: usize, input: u8, high: bool) {
+        self.set_bits(chip, Self::input_bit(input), high);
+    }
+
+    /// Check whether the input (`1` - `4`) of the given chip is set high.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the chip or the input does not exist.
+    #[inline]
+    pub fn input(&self, chip: usize, input: u8) -> bool {
+        self.states[chip] & Self::input_bit(input) != 0
+    }
+
+    /// Enable or disable the output (`1` - `4`) of the given chip.
+    ///
+    /// <div class="warning">
+    /// The outputs 1 & 2 and the outputs 3 & 4 share a common enable pin. Thus, enabling or
+    /// disabling one of them enables or disables the other one as well!
+    /// </div>
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the chip or the output does not exist.
+    #[inline]
+    pub fn set_enabled(&mut self, chip: usize, output: u8, enabled: bool) {
+        self.set_bits(chip, Self::enable_bit(output), enabled);
+    }
+
+    /// Check whether the output (`1` - `4`) of the given chip is enabled.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the chip or the output does not exist.
+    #[inline]
+    pub fn is_enabled(&self, chip: usize, output: u8) -> bool {
+        self.states[chip] & Self::enable_bit(output) != 0
+    }
+
+    /// Disable all outputs of all chips.
+    #[inline]
+    pub fn disable_all(&mut self) {
+        for state in self.states.iter_mut() {
+            *state &= !(EN12 | EN34);
+        }
+    }
+
+    /// Release the chain and return the data, clock and latch pins.
+    #[inline]
+    pub fn release(self) -> (DATA, CLK, LATCH) {
+        (self.data, self.clock, self.latch)
+    }
+
+    fn set_bits(&mut self, chip: usize, bits: u8, high: bool) {
+        match high {
+            true => self.states[chip] |= bits,
+            false => self.states[chip] &= !bits,
+        }
+    }
+
+    fn input_bit(input: u8) -> u8 {
+        assert!((1..=4).contains(&input), "invalid input {}", input);
+        1 << (input - 1)
+    }
+
+    fn enable_bit(output: u8) -> u8 {
+        match output {
+            1 | 2 => EN12,
+            3 | 4 => EN34,
+            _ => panic!("invalid output {}", output),
+        }
+    }
+}
+
+impl<DATA, CLK, LATCH, const N: usize> L293xChain<DATA, CLK, LATCH, N>
+where
+    DATA: OutputPin,
+    CLK: OutputPin<Error = DATA::Error>,
+    LATCH: OutputPin<Error = DATA::Error>,
+{
+    /// Write the states of all chips to the chain and latch them at once.
+    ///
+    /// # Errors
+    ///
+    /// The error of the first pin, which could not be set, will be returned. The outputs of the
+    /// chain are not latched in this case.
+    pub fn commit(&mut self) -> Result<(), DATA::Error> {
+        self.latch.set_low()?;
+        // The state of the last chip is shifted through the whole chain
+        for state in self.states.iter().rev() {
+            shift_out(&mut self.data, &mut self.clock, *state)?;
+        }
+        self.latch.set_high()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shift_register::tests::{Chip, ChipPin};
+    use crate::test_util::DigitalPin;
+    use coverage_helper::test;
+
+    #[test]
+    fn test_chain() {
+        // The simulated shift register only holds the last 8 bits, i.e. the state of chip 0
+        let chip = Chip::default();
+        let mut chain: L293xChain<_, _, _, 3> = L293xChain::new(
+            ChipPin::Data(&chip),
+            ChipPin::Clock(&chip),
+            ChipPin::Latch(&chip),
+        );
+
+        chain.set_input(0, 2, true);
+        chain.set_enabled(0, 1, true);
+        chain.set_input(2, 4, true);
+        chain.set_enabled(2, 4, true);
+        assert_eq!(chip.outputs.get(), 0);
+
+        chain.commit().unwrap();
+        assert_eq!(chip.outputs.get(), 0b0001_0010);
+        assert!(chain.input(2, 4));
+        assert!(!chain.input(2, 3));
+        assert!(chain.is_enabled(2, 3));
+        assert!(chain.is_enabled(0, 2));
+
+        chain.set_input(0, 2, false);
+        chain.disable_all();
+        chain.commit().unwrap();
+        assert_eq!(chip.outputs.get(), 0);
+        assert!(!chain.is_enabled(2, 4));
+    }
+
+    #[test]
+    fn test_commit_fail() {
+        let mut clock = DigitalPin::new();
+        clock.fail();
+        let mut chain: L293xChain<_, _, _, 2> =
+            L293xChain::new(DigitalPin::new(), clock, DigitalPin::new());
+
+        assert!(chain.commit().is_err());
+        let (_, _, mut latch) = chain.release();
+        assert!(embedded_hal::digital::StatefulOutputPin::is_set_low(&mut latch).unwrap());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_input() {
+        let mut chain: L293xChain<(), (), (), 1> = L293xChain::new((), (), ());
+        chain.set_input(0, 5, true);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_output() {
+        let chain: L293xChain<(), (), (), 1> = L293xChain::new((), (), ());
+        chain.is_enabled(0, 0);
+    }
+}
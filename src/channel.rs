@@ -0,0 +1,89 @@
+/// An output channel of the [L293](crate::L293x) chip
+///
+/// This enum allows selecting the outputs of a chip at runtime, if all inputs use the same pin
+/// type (see [`output()`](crate::L293x::output)). Thus, code can loop over the outputs instead of
+/// calling the methods of every output separately.
+///
+/// # Examples
+///
+/// ```
+/// use l293x::Channel;
+///
+/// for channel in Channel::ALL {
+///     l293x.set_output_high(channel)?;
+/// }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Channel {
+    /// The output `y1`, which is controlled by the input 1.
+    Y1,
+    /// The output `y2`, which is controlled by the input 2.
+    Y2,
+    /// The output `y3`, which is controlled by the input 3.
+    Y3,
+    /// The output `y4`, which is controlled by the input 4.
+    Y4,
+}
+
+impl Channel {
+    /// All channels of the chip in ascending order.
+    pub const ALL: [Channel; 4] = [Channel::Y1, Channel::Y2, Channel::Y3, Channel::Y4];
+
+    /// Get the zero-based index of the channel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use l293x::Channel;
+    ///
+    /// assert_eq!(Channel::Y3.index(), 2);
+    /// ```
+    #[inline]
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Get the channel with the given zero-based `index`.
+    ///
+    /// Returns `None`, if the index is out of range.
+    #[inline]
+    pub fn from_index(index: usize) -> Option<Self> {
+        Self::ALL.get(index).copied()
+    }
+
+    /// Get the other channel sharing the enable pin with this channel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use l293x::Channel;
+    ///
+    /// assert_eq!(Channel::Y1.paired(), Channel::Y2);
+    /// ```
+    #[inline]
+    pub fn paired(self) -> Self {
+        match self {
+            Channel::Y1 => Channel::Y2,
+            Channel::Y2 => Channel::Y1,
+            Channel::Y3 => Channel::Y4,
+            Channel::Y4 => Channel::Y3,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coverage_helper::test;
+
+    #[test]
+    fn test_index() {
+        for (index, channel) in Channel::ALL.into_iter().enumerate() {
+            assert_eq!(channel.index(), index);
+            assert_eq!(Channel::from_index(index), Some(channel));
+            assert_eq!(channel.paired().paired(), channel);
+        }
+        assert_eq!(Channel::from_index(4), None);
+    }
+}
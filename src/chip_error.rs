@@ -0,0 +1,184 @@
+use core::fmt::{Debug, Display, Formatter};
+
+use crate::{Channel, ErrorKind, OutputStateError};
+
+/// The role of a pin of the [L293](crate::L293x) chip
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PinRole {
+    /// The input pin controlling the level of an output.
+    Input,
+    /// The enable pin shared by two outputs.
+    Enable,
+}
+
+/// Error of a [L293](crate::L293x) chip identifying the channel, which failed.
+///
+/// An [OutputStateError] only tells, whether the input or the enable pin failed, but not the
+/// output it belongs to. If multiple outputs are controlled by the same code, this error attaches
+/// the [Channel] to the error, so that the failure can be logged with actionable diagnostics.
+/// It is returned by the channel setters of the chip (e.g.
+/// [`set_output_high()`](crate::L293x::set_output_high)) and can be created for any other
+/// [OutputStateError] using [`OutputStateError::on_channel()`].
+///
+/// # Examples
+///
+/// ```
+/// for channel in Channel::ALL {
+///     if let Err(error) = l293x.set_output_high(channel) {
+///         // Prints e.g. "Y3: input pin error: [...]"
+///         println!("{error}");
+///     }
+/// }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ChipError<I, E> {
+    /// The channel, which failed.
+    pub channel: Channel,
+    /// The error of the channel.
+    pub error: OutputStateError<I, E>,
+}
+
+impl<I, E> ChipError<I, E> {
+    /// Get the role of the pin, which failed.
+    ///
+    /// Returns `None`, if the error was not caused by a pin, i.e. for a
+    /// [NotEnabled](OutputStateError::NotEnabled) error.
+    #[inline]
+    pub fn pin(&self) -> Option<PinRole> {
+        match self.error {
+            OutputStateError::InputPinError(_) => Some(PinRole::Input),
+            OutputStateError::EnablePinError(_) => Some(PinRole::Enable),
+            OutputStateError::NotEnabled => None,
+        }
+    }
+}
+
+impl<I: 'static, E: 'static> ChipError<I, E> {
+    /// Get the [kind](ErrorKind) of the error.
+    ///
+    /// See [`OutputStateError::error_kind()`] for details.
+    #[inline]
+    pub fn error_kind(&self) -> ErrorKind {
+        self.error.error_kind()
+    }
+}
+
+impl<I, E> OutputStateError<I, E> {
+    /// Attach the `channel`, which caused this error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_hal::digital::OutputPin;
+    /// use l293x::Channel;
+    ///
+    /// l293x.y2().set_high().map_err(|e| e.on_channel(Channel::Y2))?;
+    /// ```
+    #[inline]
+    pub fn on_channel(self, channel: Channel) -> ChipError<I, E> {
+        ChipError {
+            channel,
+            error: self,
+        }
+    }
+}
+
+impl<I, E> embedded_hal::digital::Error for ChipError<I, E>
+where
+    I: embedded_hal::digital::Error,
+    E: embedded_hal::digital::Error,
+{
+    #[inline]
+    fn kind(&self) -> embedded_hal::digital::ErrorKind {
+        embedded_hal::digital::Error::kind(&self.error)
+    }
+}
+
+impl<I, E> embedded_hal::pwm::Error for ChipError<I, E>
+where
+    I: embedded_hal::pwm::Error,
+    E: Debug,
+{
+    #[inline]
+    fn kind(&self) -> embedded_hal::pwm::ErrorKind {
+        embedded_hal::pwm::Error::kind(&self.error)
+    }
+}
+
+impl<I, E> Display for ChipError<I, E>
+where
+    I: Debug,
+    E: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}: {}", self.channel, self.error)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I, E> std::error::Error for ChipError<I, E>
+where
+    I: Debug,
+    E: Debug,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{DigitalError, DigitalPin, PwmError};
+    use crate::{AutoEnable, L293x};
+    use coverage_helper::test;
+    use embedded_hal::digital::OutputPin;
+
+    #[test]
+    fn test_pin() {
+        let input: OutputStateError<DigitalError, DigitalError> =
+            OutputStateError::InputPinError(DigitalError());
+        assert_eq!(input.on_channel(Channel::Y1).pin(), Some(PinRole::Input));
+        let enable: OutputStateError<DigitalError, DigitalError> =
+            OutputStateError::EnablePinError(DigitalError());
+        assert_eq!(enable.on_channel(Channel::Y1).pin(), Some(PinRole::Enable));
+        let not_enabled: OutputStateError<DigitalError, DigitalError> =
+            OutputStateError::NotEnabled;
+        let not_enabled = not_enabled.on_channel(Channel::Y1);
+        assert_eq!(not_enabled.pin(), None);
+        assert_eq!(not_enabled.error_kind(), ErrorKind::NotEnabled);
+    }
+
+    #[test]
+    fn test_kind() {
+        use embedded_hal::digital::Error as _;
+        use embedded_hal::pwm::Error as _;
+
+        let digital: OutputStateError<DigitalError, DigitalError> =
+            OutputStateError::EnablePinError(DigitalError());
+        assert_eq!(
+            digital.on_channel(Channel::Y2).kind(),
+            DigitalError().kind()
+        );
+        let pwm: OutputStateError<PwmError, DigitalError> =
+            OutputStateError::InputPinError(PwmError());
+        assert_eq!(pwm.on_channel(Channel::Y2).kind(), PwmError().kind());
+    }
+
+    #[test]
+    fn test_display() {
+        extern crate std;
+        use std::string::ToString;
+
+        let mut enable = DigitalPin::new();
+        enable.fail();
+        let l293x = L293x::new((), (), DigitalPin::new(), (), (), enable);
+        let error = l293x
+            .y3()
+            .with_auto_enable(AutoEnable::Always)
+            .set_high()
+            .map_err(|e| e.on_channel(Channel::Y3))
+            .unwrap_err();
+        assert_eq!(error.channel, Channel::Y3);
+        assert_eq!(error.to_string(), "Y3: enable pin error: DigitalError");
+    }
+}
@@ -0,0 +1,343 @@
+//! # Serial command protocol
+//!
+//! This module is only available, if the `command` feature is enabled. It parses a small,
+//! human-readable command protocol from any [embedded-io](embedded_io) source, e.g. a UART or
+//! a USB serial port, and applies the commands to the motors. Together, a motor board controlled
+//! over a serial line requires only a few lines of code.
+//!
+//! # Protocol
+//!
+//! Each command is a single line of ASCII text terminated by `\n`. Carriage returns, additional
+//! whitespace and empty lines are ignored, and the commands are not case sensitive. Lines longer
+//! than [MAX_LINE_LENGTH] bytes are rejected.
+//!
+//! | Command        | Meaning                                                              |
+//! |----------------|----------------------------------------------------------------------|
+//! | `M<n> <speed>` | Drive the motor `n` (starting at `1`) with `speed` percent (`-100` - `100`) |
+//! | `STOP`         | Brake all motors                                                     |
+//!
+//! For example, `M1 +75` drives the first motor forward with 75% speed, while `M2 -100` drives
+//! the second motor in reverse with full speed.
+//!
+//! # Examples
+//!
+//! ```
+//! use l293x::command::{CommandError, CommandReader};
+//!
+//! // [...] create the serial port and the L293x chip
+//! let mut reader = CommandReader::new(uart);
+//! let mut motors = [l293x.full_h12(), l293x.full_h34()];
+//!
+//! loop {
+//!     match reader.read_command() {
+//!         Ok(command) => command.apply(&mut motors)?,
+//!         Err(CommandError::InvalidCommand) => uart_tx.write_all(b"ERR\n")?,
+//!         Err(error) => return Err(error),
+//!     }
+//! }
+//! ```
+use core::fmt::{Debug, Display, Formatter};
+
+use embedded_io::Read;
+
+use crate::driver::FullHBridgeDriver;
+
+/// The maximum length of a command line in bytes, excluding the line terminator.
+pub const MAX_LINE_LENGTH: usize = 32;
+
+/// A command received over the serial line
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Command {
+    /// Drive a motor with the given speed.
+    Speed {
+        /// The index of the motor, starting at `0` (i.e. `M1` addresses the motor `0`).
+        motor: u8,
+        /// The signed speed in percent (`-100` - `100`).
+        percent: i8,
+    },
+    /// Brake all motors.
+    Stop,
+}
+
+impl Command {
+    /// Parse the command from a single line of text without its line terminator.
+    ///
+    /// Returns `None`, if the line does not contain a valid command.
+    pub fn parse(line: &[u8]) -> Option<Self> {
+        let line = core::str::from_utf8(line).ok()?;
+        let mut tokens = line.split_ascii_whitespace();
+        let command = match tokens.next()? {
+            token if token.eq_ignore_ascii_case("STOP") => Command::Stop,
+            token => {
+                let motor = match token.as_bytes() {
+                    [b'M' | b'm', ..] => token[1..].parse::<u16>().ok()?.checked_sub(1)?,
+                    _ => return None,
+                };
+                let motor = u8::try_from(motor).ok()?;
+                let percent = tokens.next()?.parse::<i8>().ok()?;
+                if !(-100..=100).contains(&percent) {
+                    return None;
+                }
+                Command::Speed { motor, percent }
+            }
+        };
+        match tokens.next() {
+            Some(_) => None,
+            None => Some(command),
+        }
+    }
+
+    /// Apply the command to the given motors.
+    ///
+    /// Speed commands set the speed of the addressed motor and are ignored, if the motor does
+    /// not exist. Stop commands brake all motors.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the first motor, which could not be set. All
+    /// motors are braked, even if braking one of them fails.
+    pub fn apply<M: FullHBridgeDriver>(&self, motors: &mut [M]) -> Result<(), M::Error> {
+        match self {
+            Command::Speed { motor, percent } => match motors.get_mut(usize::from(*motor)) {
+                Some(motor) => {
+                    motor.set_speed((i32::from(*percent) * i32::from(i16::MAX) / 100) as i16)
+                }
+                None => Ok(()),
+            },
+            Command::Stop => motors
+                .iter_mut()
+                .map(FullHBridgeDriver::brake)
+                .fold(Ok(()), Result::and),
+        }
+    }
+}
+
+impl Display for Command {
+    /// Format the command as a line of the protocol without its line terminator, e.g. to send it
+    /// from a host.
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Command::Speed { motor, percent } => {
+                write!(f, "M{} {:+}", u16::from(*motor) + 1, percent)
+            }
+            Command::Stop => write!(f, "STOP"),
+        }
+    }
+}
+
+/// Error returned by the [CommandReader]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CommandError<E> {
+    /// The source could not be read. The contained error may contain additional information.
+    Read(E),
+    /// The source reached its end.
+    EndOfFile,
+    /// The received line is not a valid command or is too long.
+    InvalidCommand,
+}
+
+impl<E: Debug> Display for CommandError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CommandError::Read(e) => write!(f, "read error: {:?}", e),
+            CommandError::EndOfFile => write!(f, "end of file"),
+            CommandError::InvalidCommand => write!(f, "invalid command"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: Debug> std::error::Error for CommandError<E> {}
+
+/// A reader receiving commands from a serial line
+///
+/// The reader collects the received bytes, until a complete line was received, and parses the
+/// [Command] contained in it.
+#[derive(Debug)]
+pub struct CommandReader<R> {
+    reader: R,
+    line: [u8; MAX_LINE_LENGTH],
+    len: usize,
+    overflow: bool,
+}
+
+impl<R: Read> CommandReader<R> {
+    /// Create a new command reader reading from the given source.
+    #[inline]
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            line: [0; MAX_LINE_LENGTH],
+            len: 0,
+            overflow: false,
+        }
+    }
+
+    /// Read the next command.
+    ///
+    /// This method blocks, until a complete, non-empty line was received. The bytes of an
+    /// incomplete line are kept, so that reading can be continued after an error.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an [InvalidCommand](CommandError::InvalidCommand) error, if the
+    /// received line does not contain a valid command. The line is discarded in this case, so
+    /// that the next call reads the next line. The errors of the source are returned as
+    /// [Read](CommandError::Read) errors.
+    pub fn read_command(&mut self) -> Result<Command, CommandError<R::Error>> {
+        loop {
+            let mut byte = 0;
+            match self.reader.read(core::slice::from_mut(&mut byte)) {
+                Ok(0) => return Err(CommandError::EndOfFile),
+                Ok(_) => {}
+                Err(error) => return Err(CommandError::Read(error)),
+            }
+            if byte != b'\n' {
+                match self.line.get_mut(self.len) {
+                    Some(slot) => {
+                        *slot = byte;
+                        self.len += 1;
+                    }
+                    None => self.overflow = true,
+                }
+                continue;
+            }
+            let line = &self.line[..self.len];
+            let overflow = core::mem::replace(&mut self.overflow, false);
+            self.len = 0;
+            if !overflow && line.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+            return match overflow {
+                true => Err(CommandError::InvalidCommand),
+                false => Command::parse(line).ok_or(CommandError::InvalidCommand),
+            };
+        }
+    }
+
+    /// Release the command reader and return the source.
+    #[inline]
+    pub fn release(self) -> R {
+        self.reader
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{DigitalPin, PwmPin};
+    use crate::L293x;
+    use coverage_helper::test;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            Command::parse(b"M1 +75"),
+            Some(Command::Speed {
+                motor: 0,
+                percent: 75
+            })
+        );
+        assert_eq!(
+            Command::parse(b"  m2\t-100\r"),
+            Some(Command::Speed {
+                motor: 1,
+                percent: -100
+            })
+        );
+        assert_eq!(Command::parse(b"stop"), Some(Command::Stop));
+
+        for line in [
+            &b""[..],
+            b"M0 10",
+            b"M257 10",
+            b"M1 101",
+            b"M1",
+            b"X1 10",
+            b"M1 10 20",
+            b"STOP 1",
+            b"\xff",
+        ] {
+            assert_eq!(Command::parse(line), None);
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        extern crate std;
+        use std::string::ToString;
+
+        for command in [
+            Command::Speed {
+                motor: 2,
+                percent: -5,
+            },
+            Command::Speed {
+                motor: 255,
+                percent: 0,
+            },
+            Command::Stop,
+        ] {
+            let line = command.to_string();
+            assert_eq!(Command::parse(line.as_bytes()), Some(command));
+        }
+        assert_eq!(
+            Command::Speed {
+                motor: 0,
+                percent: 75
+            }
+            .to_string(),
+            "M1 +75"
+        );
+    }
+
+    #[test]
+    fn test_reader() {
+        let input = b"M1 +75\r\n\n  \nFOO\nM1 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0\nSTOP\nM2 1";
+        let mut reader = CommandReader::new(&input[..]);
+        assert_eq!(
+            reader.read_command(),
+            Ok(Command::Speed {
+                motor: 0,
+                percent: 75
+            })
+        );
+        assert_eq!(reader.read_command(), Err(CommandError::InvalidCommand));
+        assert_eq!(reader.read_command(), Err(CommandError::InvalidCommand));
+        assert_eq!(reader.read_command(), Ok(Command::Stop));
+        assert_eq!(reader.read_command(), Err(CommandError::EndOfFile));
+        assert!(reader.release().is_empty());
+    }
+
+    #[test]
+    fn test_apply() {
+        let l293x = L293x::new(
+            PwmPin::new(),
+            PwmPin::new(),
+            PwmPin::new(),
+            PwmPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        );
+        let mut motors = [l293x.full_h12(), l293x.full_h34()];
+
+        Command::parse(b"M2 -100")
+            .unwrap()
+            .apply(&mut motors)
+            .unwrap();
+        assert_eq!(motors[1].speed(), -i16::MAX);
+        Command::parse(b"M3 50")
+            .unwrap()
+            .apply(&mut motors)
+            .unwrap();
+        Command::parse(b"M1 50")
+            .unwrap()
+            .apply(&mut motors)
+            .unwrap();
+        assert_eq!(motors[0].speed(), i16::MAX / 2);
+        Command::Stop.apply(&mut motors).unwrap();
+        assert_eq!((motors[0].speed(), motors[1].speed()), (0, 0));
+    }
+}
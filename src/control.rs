@@ -0,0 +1,384 @@
+//! # Control helpers
+//!
+//! This module contains helpers to coordinate the commands sent to the outputs of the
+//! [L293](crate::L293x) chip, e.g. by multiple sources of different priority, and to keep track
+//! of their usage.
+//!
+//! The helpers in this module do not access any pins. They only decide which command should be
+//! applied, so that they can be combined with any of the drivers of this crate.
+
+/// The action taken to stop a motor for safety reasons
+///
+/// Different loads need to be stopped differently: a conveyor can simply coast to a stop, while
+/// a lifting axis must be braked and a heavy wheel should rather be ramped down to avoid
+/// current spikes. The action is configured per motor by the application and applied using
+/// [`FullH::failsafe()`](crate::FullH::failsafe).
+///
+/// All protection features of this crate stop the loads using their configured action: The
+/// actions of the two pairs of outputs of a chip are configured using
+/// [`L293x::with_failsafe()`](crate::L293x::with_failsafe). They are applied by
+/// [`L293x::failsafe()`](crate::L293x::failsafe), a tripped [DeadmanSwitch](crate::DeadmanSwitch)
+/// and [`L293x::emergency_stop()`](crate::L293x::emergency_stop).
+/// A CAN node applies it to stop commands (see `can::Command::apply_failsafe()`). The
+/// [StopMode](crate::motor::StopMode) of a [DcMotor](crate::motor::DcMotor) converts into the
+/// corresponding action.
+///
+/// # Examples
+///
+/// ```
+/// use l293x::control::FailsafeAction;
+///
+/// // [...] create the motor and the delay
+/// let action = FailsafeAction::RampDown(200);
+/// motor.failsafe(action, &mut delay)?;
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FailsafeAction {
+    /// Disable the bridges and let the motor coast to a stop.
+    Coast,
+    /// Short the terminals of the motor to stop it as fast as possible.
+    Brake,
+    /// Ramp the speed down to `0` within the given number of milliseconds and brake afterwards.
+    RampDown(u32),
+}
+
+impl Default for FailsafeAction {
+    fn default() -> Self {
+        FailsafeAction::Coast
+    }
+}
+
+/// Arbitration between a low-priority and a high-priority command source
+///
+/// Motors are often commanded by more than one source. E.g. a teleoperation or autonomy task
+/// commands the speed of a motor, while a safety supervisor needs to be able to override these
+/// commands at any time. The [ControlArbiter] tracks the latest command of both sources and
+/// decides which of them is [active](ControlArbiter::active):
+///
+/// - As long as an override is set, the override command is active.
+/// - Otherwise, the latest low-priority command is active.
+///
+/// Low-priority commands received while an override is set are not lost. They are stored and
+/// resume automatically, once the override is [released](ControlArbiter::release_override).
+///
+/// One arbiter is meant to be used per motor. The type of the commands (`C`) can be freely
+/// chosen, e.g. a signed speed or a [Direction](crate::Direction).
+///
+/// # Examples
+///
+/// ```
+/// use l293x::control::ControlArbiter;
+///
+/// let mut arbiter = ControlArbiter::new();
+/// arbiter.command(50i16);
+/// assert_eq!(arbiter.active(), Some(&50));
+///
+/// // The safety supervisor stops the motor
+/// arbiter.set_override(0);
+/// arbiter.command(75);
+/// assert_eq!(arbiter.active(), Some(&0));
+///
+/// // The latest low-priority command resumes
+/// arbiter.release_override();
+/// assert_eq!(arbiter.active(), Some(&75));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ControlArbiter<C> {
+    low: Option<C>,
+    high: Option<C>,
+}
+
+impl<C> Default for ControlArbiter<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C> ControlArbiter<C> {
+    /// Create a new arbiter without any commands.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            low: None,
+            high: None,
+        }
+    }
+
+    /// Set the command of the low-priority source.
+    ///
+    /// The command only becomes active, if no override is set.
+    #[inline]
+    pub fn command(&mut self, command: C) {
+        self.low = Some(command);
+    }
+
+    /// Clear the command of the low-priority source.
+    #[inline]
+    pub fn clear_command(&mut self) {
+        self.low = None;
+    }
+
+    /// Override the low-priority commands with the given command.
+    #[inline]
+    pub fn set_override(&mut self, command: C) {
+        self.high = Some(command);
+    }
+
+    /// Release the override.
+    ///
+    /// After releasing the override, the latest low-priority command becomes active again.
+    #[inline]
+    pub fn release_override(&mut self) {
+        self.high = None;
+    }
+
+    /// Check whether the low-priority commands are currently overridden.
+    #[inline]
+    pub fn is_overridden(&self) -> bool {
+        self.high.is_some()
+    }
+
+    /// Get the currently active command.
+    ///
+    /// Returns `None`, if neither source commanded anything yet.
+    #[inline]
+    pub fn active(&self) -> Option<&C> {
+        self.high.as_ref().or(self.low.as_ref())
+    }
+}
+
+/// Accumulator of the runtime of a motor
+///
+/// Maintenance schedules of pumps or fans are often based on their operating hours. The
+/// [RuntimeCounter] accumulates the time a motor was running, as well as the "equivalent
+/// full-load time", i.e. the runtime weighted by the duty cycle. One counter is meant to be used
+/// per channel or motor.
+///
+/// The counter does not measure any time itself. Instead, it needs to be updated periodically
+/// with the time passed since the previous update. The unit of the time (e.g. seconds) can be
+/// freely chosen, but needs to be used consistently.
+///
+/// # Examples
+///
+/// ```
+/// use l293x::control::RuntimeCounter;
+///
+/// let mut counter = RuntimeCounter::new();
+///
+/// // The pump ran for 60 seconds at half speed
+/// counter.update(60, 50, 100);
+/// assert_eq!(counter.runtime(), 60);
+/// assert_eq!(counter.full_load_time(), 30);
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RuntimeCounter {
+    runtime: u64,
+    full_load_time: u64,
+    remainder: u64,
+    max_duty: u16,
+}
+
+impl RuntimeCounter {
+    /// Create a new counter without any runtime.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Account the time `dt` during which the motor ran with the given duty cycle.
+    ///
+    /// The motor counts as running, if the `duty` is not zero. The full-load time increases by
+    /// `dt * duty / max_duty`. Fractions are carried over to the next update, so that no time is
+    /// lost due to rounding. If the `max_duty` differs from the previous update, the carried
+    /// fraction is rescaled to the new range.
+    pub fn update(&mut self, dt: u32, duty: u16, max_duty: u16) {
+        if duty == 0 || max_duty == 0 {
+            return;
+        }
+        if max_duty != self.max_duty {
+            self.remainder = match self.max_duty {
+                0 => 0,
+                previous => self.remainder * u64::from(max_duty) / u64::from(previous),
+            };
+            self.max_duty = max_duty;
+        }
+        let duty = duty.min(max_duty);
+        self.runtime = self.runtime.saturating_add(u64::from(dt));
+        let weighted = u64::from(dt) * u64::from(duty) + self.remainder;
+        self.full_load_time = self
+            .full_load_time
+            .saturating_add(weighted / u64::from(max_duty));
+        self.remainder = weighted % u64::from(max_duty);
+    }
+
+    /// Account the time `dt` during which a digitally driven motor was on or off.
+    ///
+    /// A motor, which is on, runs at full load.
+    #[inline]
+    pub fn update_digital(&mut self, dt: u32, on: bool) {
+        self.update(dt, u16::from(on), 1);
+    }
+
+    /// Get the accumulated runtime.
+    #[inline]
+    pub fn runtime(&self) -> u64 {
+        self.runtime
+    }
+
+    /// Get the accumulated equivalent full-load time.
+    #[inline]
+    pub fn full_load_time(&self) -> u64 {
+        self.full_load_time
+    }
+
+    /// Reset the counter, e.g. after a maintenance.
+    #[inline]
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+#[cfg(feature = "storage")]
+impl crate::persist::Persist for FailsafeAction {
+    const SIZE: usize = 5;
+
+    fn to_bytes(&self, bytes: &mut [u8]) {
+        let (tag, duration) = match self {
+            FailsafeAction::Coast => (0, 0),
+            FailsafeAction::Brake => (1, 0),
+            FailsafeAction::RampDown(duration) => (2, *duration),
+        };
+        bytes[0] = tag;
+        bytes[1..5].copy_from_slice(&duration.to_le_bytes());
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut duration = [0; 4];
+        duration.copy_from_slice(&bytes[1..5]);
+        match bytes[0] {
+            0 => Some(FailsafeAction::Coast),
+            1 => Some(FailsafeAction::Brake),
+            2 => Some(FailsafeAction::RampDown(u32::from_le_bytes(duration))),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "storage")]
+impl crate::persist::Persist for RuntimeCounter {
+    const SIZE: usize = 26;
+
+    fn to_bytes(&self, bytes: &mut [u8]) {
+        bytes[0..8].copy_from_slice(&self.runtime.to_le_bytes());
+        bytes[8..16].copy_from_slice(&self.full_load_time.to_le_bytes());
+        bytes[16..24].copy_from_slice(&self.remainder.to_le_bytes());
+        bytes[24..26].copy_from_slice(&self.max_duty.to_le_bytes());
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut values = [0; 3];
+        for (value, chunk) in values.iter_mut().zip(bytes.chunks_exact(8)) {
+            let mut le_bytes = [0; 8];
+            le_bytes.copy_from_slice(chunk);
+            *value = u64::from_le_bytes(le_bytes);
+        }
+        let max_duty = u16::from_le_bytes([bytes[24], bytes[25]]);
+        // The remainder is a fraction of the maximum duty cycle
+        if values[2] >= u64::from(max_duty.max(1)) {
+            return None;
+        }
+        Some(Self {
+            runtime: values[0],
+            full_load_time: values[1],
+            remainder: values[2],
+            max_duty,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Direction;
+    use coverage_helper::test;
+
+    #[test]
+    fn test_no_commands() {
+        let arbiter: ControlArbiter<i16> = ControlArbiter::default();
+        assert_eq!(arbiter.active(), None);
+        assert!(!arbiter.is_overridden());
+    }
+
+    #[test]
+    fn test_override() {
+        let mut arbiter = ControlArbiter::new();
+        arbiter.command(Direction::Forward);
+        assert_eq!(arbiter.active(), Some(&Direction::Forward));
+
+        arbiter.set_override(Direction::Reverse);
+        assert!(arbiter.is_overridden());
+        assert_eq!(arbiter.active(), Some(&Direction::Reverse));
+
+        arbiter.clear_command();
+        assert_eq!(arbiter.active(), Some(&Direction::Reverse));
+
+        arbiter.release_override();
+        assert!(!arbiter.is_overridden());
+        assert_eq!(arbiter.active(), None);
+    }
+
+    #[test]
+    fn test_resume_after_override() {
+        let mut arbiter = ControlArbiter::new();
+        arbiter.set_override(0);
+        arbiter.command(10);
+        arbiter.command(20);
+        assert_eq!(arbiter.active(), Some(&0));
+
+        arbiter.release_override();
+        assert_eq!(arbiter.active(), Some(&20));
+    }
+
+    #[test]
+    fn test_runtime_counter() {
+        let mut counter = RuntimeCounter::new();
+        counter.update(10, 0, 100);
+        assert_eq!(counter.runtime(), 0);
+
+        counter.update(1, 1, 3);
+        counter.update(1, 1, 3);
+        assert_eq!(counter.full_load_time(), 0);
+        counter.update(1, 1, 3);
+        assert_eq!(counter.runtime(), 3);
+        assert_eq!(counter.full_load_time(), 1);
+
+        counter.update(5, 200, 100);
+        assert_eq!(counter.full_load_time(), 6);
+
+        counter.reset();
+        assert_eq!(counter, RuntimeCounter::default());
+    }
+
+    #[test]
+    fn test_runtime_counter_max_duty_change() {
+        let mut counter = RuntimeCounter::new();
+        // Half of a time unit is carried over
+        counter.update(1, 1, 2);
+        counter.update(1, 1, 4);
+        assert_eq!(counter.full_load_time(), 0);
+        counter.update(1, 1, 4);
+        assert_eq!(counter.full_load_time(), 1);
+    }
+
+    #[test]
+    fn test_runtime_counter_digital() {
+        let mut counter = RuntimeCounter::new();
+        counter.update_digital(10, true);
+        counter.update_digital(10, false);
+        assert_eq!(counter.runtime(), 10);
+        assert_eq!(counter.full_load_time(), 10);
+    }
+}
@@ -0,0 +1,196 @@
+//! # Duty cycle transfer curves
+//!
+//! The speed of a DC motor is not proportional to the duty cycle of its inputs, e.g. due to the
+//! static friction of the motor. Likewise, the perceived brightness of a LED driven through the
+//! chip is not proportional to its duty cycle. This module implements a [CurvedPwm] pin, which
+//! maps each requested duty cycle through a user-provided [TransferCurve] before applying it to
+//! the wrapped PWM pin. This allows linearizing the response of a motor or applying a gamma
+//! correction to a LED.
+//!
+//! A curve is either a closure or a [LookupTable]. It should be monotonic, so that a higher
+//! requested duty cycle never results in a lower actual one.
+//!
+//! # Examples
+//!
+//! ```
+//! use l293x::curve::{CurvedPwm, LookupTable};
+//! use l293x::motor::DcMotor;
+//!
+//! // A rough gamma correction of 2.2 (in fractions of `u16::MAX`)
+//! static GAMMA: [u16; 5] = [0, 3_090, 14_200, 34_150, 65_535];
+//!
+//! let led = CurvedPwm::new(pwm1, LookupTable::new(&GAMMA));
+//! let mut l293x = L293x::new(led, (), (), (), enable12, ());
+//!
+//! // Square the requested duty cycle of both motor inputs
+//! let curve = |duty: u16, max: u16| (u32::from(duty) * u32::from(duty) / u32::from(max)) as u16;
+//! let mut motor = DcMotor::new(CurvedPwm::new(pwm3, curve), CurvedPwm::new(pwm4, curve), en34);
+//! motor.set_speed_percent(50)?;
+//! ```
+use embedded_hal::pwm::{ErrorType, SetDutyCycle};
+
+/// A monotonic mapping between the requested and the actual duty cycle
+pub trait TransferCurve {
+    /// Map the requested duty cycle to the actual one.
+    ///
+    /// Both duty cycles are relative to `max_duty`. Values above `max_duty` are clamped by the
+    /// [CurvedPwm] pin.
+    fn apply(&self, duty: u16, max_duty: u16) -> u16;
+}
+
+impl<F> TransferCurve for F
+where
+    F: Fn(u16, u16) -> u16,
+{
+    #[inline]
+    fn apply(&self, duty: u16, max_duty: u16) -> u16 {
+        self(duty, max_duty)
+    }
+}
+
+/// A transfer curve defined by a lookup table
+///
+/// The entries of the table are the actual duty cycles in fractions of [`u16::MAX`]. They are
+/// spread evenly over the range of the requested duty cycle, i.e. the first entry is used for a
+/// duty cycle of `0` and the last one for the maximum duty cycle. Requested duty cycles between
+/// two entries are interpolated linearly.
+#[derive(Debug, Copy, Clone)]
+pub struct LookupTable<'a> {
+    table: &'a [u16],
+}
+
+impl<'a> LookupTable<'a> {
+    /// Create a new lookup table.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the table contains less than two entries or is not monotonically increasing.
+    pub fn new(table: &'a [u16]) -> Self {
+        assert!(table.len() >= 2, "lookup table too short");
+        assert!(
+            table.windows(2).all(|w| w[0] <= w[1]),
+            "lookup table not monotonic"
+        );
+        Self { table }
+    }
+}
+
+impl TransferCurve for LookupTable<'_> {
+    fn apply(&self, duty: u16, max_duty: u16) -> u16 {
+        if max_duty == 0 {
+            return 0;
+        }
+        let segments = (self.table.len() - 1) as u64;
+        // The position of the duty cycle within the table in fractions of `max_duty`
+        let position = u64::from(duty.min(max_duty)) * segments;
+        let index = (position / u64::from(max_duty)) as usize;
+        let fraction = position % u64::from(max_duty);
+        let from = u64::from(self.table[index]);
+        let to = u64::from(self.table[(index + 1).min(self.table.len() - 1)]);
+        let value = from + (to - from) * fraction / u64::from(max_duty);
+        // Scale from fractions of `u16::MAX` to fractions of `max_duty`
+        (value * u64::from(max_duty) / u64::from(u16::MAX)) as u16
+    }
+}
+
+/// A PWM pin, which maps the requested duty cycle through a transfer curve
+///
+/// Because this type implements the [SetDutyCycle] trait itself, it can be used as an input pin
+/// of the [L293x](crate::L293x), the [DcMotor](crate::motor::DcMotor) or any other driver of
+/// this crate. For more information, please see the [module documentation](crate::curve).
+#[derive(Debug)]
+pub struct CurvedPwm<P, C> {
+    pin: P,
+    curve: C,
+}
+
+impl<P, C> CurvedPwm<P, C>
+where
+    P: SetDutyCycle,
+    C: TransferCurve,
+{
+    /// Create a new PWM pin using the given transfer curve.
+    #[inline]
+    pub fn new(pin: P, curve: C) -> Self {
+        Self { pin, curve }
+    }
+
+    /// Release the curved pin and return the wrapped PWM pin and the transfer curve.
+    #[inline]
+    pub fn release(self) -> (P, C) {
+        (self.pin, self.curve)
+    }
+}
+
+impl<P, C> ErrorType for CurvedPwm<P, C>
+where
+    P: SetDutyCycle,
+{
+    type Error = P::Error;
+}
+
+impl<P, C> SetDutyCycle for CurvedPwm<P, C>
+where
+    P: SetDutyCycle,
+    C: TransferCurve,
+{
+    #[inline]
+    fn max_duty_cycle(&self) -> u16 {
+        self.pin.max_duty_cycle()
+    }
+
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+        let max_duty = self.pin.max_duty_cycle();
+        let duty = self.curve.apply(duty.min(max_duty), max_duty);
+        self.pin.set_duty_cycle(duty.min(max_duty))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::PwmPin;
+    use coverage_helper::test;
+
+    #[test]
+    fn test_closure() {
+        let mut pin = CurvedPwm::new(PwmPin::new(), |duty: u16, _| duty / 2);
+
+        assert_eq!(pin.max_duty_cycle(), u16::MAX);
+        pin.set_duty_cycle(1000).unwrap();
+        assert_eq!(pin.release().0.get_duty_cycle(), 500);
+
+        let mut pin = CurvedPwm::new(PwmPin::new(), |_, _| u16::MAX);
+        pin.set_duty_cycle_fully_off().unwrap();
+        assert_eq!(pin.release().0.get_duty_cycle(), u16::MAX);
+    }
+
+    #[test]
+    fn test_lookup_table() {
+        let table = LookupTable::new(&[0, 10_000, 65_535]);
+
+        assert_eq!(table.apply(0, 100), 0);
+        assert_eq!(table.apply(25, 100), 7);
+        assert_eq!(table.apply(50, 100), 15);
+        assert_eq!(table.apply(75, 100), 57);
+        assert_eq!(table.apply(100, 100), 100);
+        assert_eq!(table.apply(200, 100), 100);
+        assert_eq!(table.apply(1, 0), 0);
+
+        let mut pin = CurvedPwm::new(PwmPin::new(), table);
+        pin.set_duty_cycle(32_768).unwrap();
+        assert_eq!(pin.release().0.get_duty_cycle(), 10_000);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_lookup_table_too_short() {
+        LookupTable::new(&[0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_lookup_table_not_monotonic() {
+        LookupTable::new(&[0, 2, 1]);
+    }
+}
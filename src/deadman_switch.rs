@@ -0,0 +1,246 @@
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::OutputPin;
+use embedded_hal::pwm::SetDutyCycle;
+
+use crate::control::FailsafeAction;
+use crate::{L293x, OutputStateError};
+
+/// A deadman switch stopping the outputs of a L293x chip, if it is not fed regularly
+///
+/// Remote controlled robots need to stop, if they lose the connection to their remote control.
+/// This wrapper takes ownership of a [L293x] chip driver and stops its loads using their
+/// [FailsafeAction] (see [`L293x::with_failsafe()`]), if [`feed()`](DeadmanSwitch::feed) has not
+/// been called within a configurable window. The application feeds the switch whenever it
+/// receives a command and calls [`tick()`](DeadmanSwitch::tick) periodically, e.g. from its main
+/// loop, with the time elapsed since the last call.
+///
+/// Once the switch tripped, the loads are not driven again automatically by feeding it again.
+/// Instead, the application needs to command them explicitly, e.g. after receiving the next
+/// command. The chip is accessed using the [`chip()`](DeadmanSwitch::chip) and
+/// [`chip_mut()`](DeadmanSwitch::chip_mut) methods.
+///
+/// # Examples
+///
+/// ```
+/// use l293x::control::FailsafeAction;
+/// use l293x::{DeadmanSwitch, L293x};
+///
+/// // Ramp the motors down within 200ms, if no command was received within 500ms
+/// let l293x = L293x::new(a1, a2, a3, a4, en12, en34)
+///     .with_failsafe(FailsafeAction::RampDown(200), FailsafeAction::RampDown(200));
+/// let mut switch = DeadmanSwitch::new(l293x, 500);
+///
+/// // Whenever a command is received:
+/// switch.feed();
+/// switch.chip_mut().full_h12().set_speed(command.speed)?;
+///
+/// // Within the main loop running every 10ms:
+/// switch.tick(10, &mut delay)?;
+/// ```
+#[derive(Debug)]
+pub struct DeadmanSwitch<A1, A2, A3, A4, EN12, EN34> {
+    chip: L293x<A1, A2, A3, A4, EN12, EN34>,
+    window: u32,
+    elapsed: u32,
+    tripped: bool,
+}
+
+impl<A1, A2, A3, A4, EN12, EN34> DeadmanSwitch<A1, A2, A3, A4, EN12, EN34> {
+    /// Create a new deadman switch with the given window.
+    ///
+    /// The `window` is measured in the same unit as the time passed to
+    /// [`tick()`](DeadmanSwitch::tick). The switch starts fed, i.e. the full window passes,
+    /// before it trips for the first time.
+    #[inline]
+    pub fn new(chip: L293x<A1, A2, A3, A4, EN12, EN34>, window: u32) -> Self {
+        Self {
+            chip,
+            window,
+            elapsed: 0,
+            tripped: false,
+        }
+    }
+
+    /// Release the deadman switch and return the underlying [L293x] driver.
+    #[inline]
+    pub fn release(self) -> L293x<A1, A2, A3, A4, EN12, EN34> {
+        self.chip
+    }
+
+    /// Get the underlying [L293x] driver.
+    #[inline]
+    pub fn chip(&self) -> &L293x<A1, A2, A3, A4, EN12, EN34> {
+        &self.chip
+    }
+
+    /// Get the underlying [L293x] driver mutably.
+    #[inline]
+    pub fn chip_mut(&mut self) -> &mut L293x<A1, A2, A3, A4, EN12, EN34> {
+        &mut self.chip
+    }
+
+    /// Get the window, within which the switch needs to be fed.
+    #[inline]
+    pub fn window(&self) -> u32 {
+        self.window
+    }
+
+    /// Change the window, within which the switch needs to be fed.
+    #[inline]
+    pub fn set_window(&mut self, window: u32) {
+        self.window = window;
+    }
+
+    /// Check whether the switch tripped since it was fed the last time.
+    #[inline]
+    pub fn is_tripped(&self) -> bool {
+        self.tripped
+    }
+
+    /// Feed the switch, which restarts its window.
+    ///
+    /// This does not enable the outputs again, if the switch tripped before.
+    #[inline]
+    pub fn feed(&mut self) {
+        self.elapsed = 0;
+        self.tripped = false;
+    }
+}
+
+impl<A1, A2, A3, A4, EN12, EN34> DeadmanSwitch<A1, A2, A3, A4, EN12, EN34>
+where
+    A1: SetDutyCycle,
+    A2: SetDutyCycle<Error = A1::Error>,
+    A3: SetDutyCycle<Error = A1::Error>,
+    A4: SetDutyCycle<Error = A1::Error>,
+    EN12: OutputPin,
+    EN34: OutputPin<Error = EN12::Error>,
+{
+    /// Advance the time of the switch by `elapsed`.
+    ///
+    /// If the switch has not been fed within its window, the loads of the chip are stopped using
+    /// their [FailsafeAction] (see [`L293x::failsafe()`]). A
+    /// [RampDown](FailsafeAction::RampDown) blocks for its duration using the `delay`. While the
+    /// switch is tripped, the loads are stopped again on every tick, braking the ramped down
+    /// loads immediately. Chips with digital inputs can be used by wrapping them into
+    /// [DigitalAsPwm](crate::pins::DigitalAsPwm) adapters.
+    ///
+    /// Returns whether the switch is tripped.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the first error of the pins. The switch is considered tripped
+    /// nevertheless, so that the next tick tries to stop the loads again.
+    pub fn tick<D: DelayNs>(
+        &mut self,
+        elapsed: u32,
+        delay: &mut D,
+    ) -> Result<bool, OutputStateError<A1::Error, EN12::Error>> {
+        self.elapsed = self.elapsed.saturating_add(elapsed);
+        if self.tripped {
+            let (action12, action34) = self.chip.failsafe_actions();
+            let hold = |action| match action {
+                FailsafeAction::RampDown(_) => FailsafeAction::Brake,
+                action => action,
+            };
+            self.chip
+                .apply_failsafe([hold(action12), hold(action34)], delay)?;
+        } else if self.elapsed > self.window {
+            self.tripped = true;
+            self.chip.failsafe(delay)?;
+        }
+        Ok(self.tripped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{Delay, DigitalPin, PwmPin};
+    use coverage_helper::test;
+
+    type MockL293x = L293x<PwmPin, PwmPin, PwmPin, PwmPin, DigitalPin, DigitalPin>;
+
+    fn chip() -> MockL293x {
+        L293x::new(
+            PwmPin::new(),
+            PwmPin::new(),
+            PwmPin::new(),
+            PwmPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        )
+    }
+
+    #[test]
+    fn test_trip() {
+        let mut switch = DeadmanSwitch::new(chip(), 100);
+        let mut delay = Delay::new();
+        switch.chip_mut().enable_all().unwrap();
+
+        assert!(!switch.tick(60, &mut delay).unwrap());
+        switch.feed();
+        assert!(!switch.tick(60, &mut delay).unwrap());
+        assert!(!switch.tick(40, &mut delay).unwrap());
+        assert!(switch.chip().y1().is_enabled().unwrap());
+
+        assert!(switch.tick(1, &mut delay).unwrap());
+        assert!(switch.is_tripped());
+        assert!(switch.chip().y1().is_disabled().unwrap());
+        assert!(switch.chip().y4().is_disabled().unwrap());
+
+        // Feeding does not re-enable the outputs
+        switch.feed();
+        assert!(!switch.tick(1, &mut delay).unwrap());
+        assert!(switch.release().y3().is_disabled().unwrap());
+    }
+
+    #[test]
+    fn test_failsafe() {
+        let chip = chip().with_failsafe(FailsafeAction::RampDown(10), FailsafeAction::Brake);
+        let mut switch = DeadmanSwitch::new(chip, 0);
+        let mut delay = Delay::new();
+        switch.chip().full_h12().set_speed(-1000).unwrap();
+        switch.chip().full_h34().set_speed(1000).unwrap();
+
+        assert!(switch.tick(1, &mut delay).unwrap());
+        assert_eq!(delay.elapsed_ns(), 10_000_000);
+        assert_eq!(switch.chip().y2_duty_cycle(), Some(0));
+        assert_eq!(switch.chip().y3_duty_cycle(), Some(0));
+        assert!(switch.chip().y1().is_enabled().unwrap());
+        assert!(switch.chip().y3().is_enabled().unwrap());
+
+        // While tripped, the loads are braked without ramping them down again
+        switch.chip().full_h12().set_speed(1000).unwrap();
+        assert!(switch.tick(1, &mut delay).unwrap());
+        assert_eq!(delay.elapsed_ns(), 10_000_000);
+        assert_eq!(switch.chip().y1_duty_cycle(), Some(0));
+        assert!(switch.chip().y1().is_enabled().unwrap());
+    }
+
+    #[test]
+    fn test_window() {
+        let mut switch = DeadmanSwitch::new(chip(), 0);
+        let mut delay = Delay::new();
+        assert!(switch.tick(1, &mut delay).unwrap());
+
+        switch.set_window(u32::MAX);
+        switch.feed();
+        assert!(!switch.tick(u32::MAX, &mut delay).unwrap());
+        assert!(!switch.tick(u32::MAX, &mut delay).unwrap());
+        assert_eq!(switch.window(), u32::MAX);
+    }
+
+    #[test]
+    fn test_error() {
+        let mut en12 = DigitalPin::new();
+        en12.fail();
+        let (inputs, (_, en34)) = chip().release();
+        let chip = L293x::new(inputs.0, inputs.1, inputs.2, inputs.3, en12, en34);
+        let mut switch = DeadmanSwitch::new(chip, 0);
+
+        assert!(switch.tick(1, &mut Delay::new()).is_err());
+        assert!(switch.is_tripped());
+        assert!(switch.chip().y3().is_disabled().unwrap());
+    }
+}
@@ -0,0 +1,43 @@
+/// Direction of rotation of a motor driven by the [L293](crate::L293x) chip.
+///
+/// Which physical direction corresponds to [`Forward`](Direction::Forward) depends on the wiring of
+/// the motor. Swapping the two motor leads (or coils) swaps the directions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Direction {
+    /// Rotate in forward direction.
+    Forward,
+    /// Rotate in reverse direction.
+    Reverse,
+}
+
+impl Direction {
+    /// Return the opposite direction.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use l293x::Direction;
+    ///
+    /// assert_eq!(Direction::Forward.reversed(), Direction::Reverse);
+    /// ```
+    #[inline]
+    pub fn reversed(self) -> Self {
+        match self {
+            Direction::Forward => Direction::Reverse,
+            Direction::Reverse => Direction::Forward,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coverage_helper::test;
+
+    #[test]
+    fn test_reversed() {
+        assert_eq!(Direction::Forward.reversed(), Direction::Reverse);
+        assert_eq!(Direction::Reverse.reversed(), Direction::Forward);
+    }
+}
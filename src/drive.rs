@@ -0,0 +1,450 @@
+//! # Robot drive helpers
+//!
+//! The most common application of the [L293](crate::L293x) chip is driving the wheels of a small
+//! robot. This module implements the kinematics of common robot chassis on top of the
+//! [FullHBridgeDriver] trait. Thus, the wheels can be driven by [DcMotor](crate::motor::DcMotor)s,
+//! [FullH](crate::FullH) bridges or the motors of any other driver chip.
+//!
+//! - [DifferentialDrive]: Two driven wheels (or tracks) on the left and the right side of the
+//!   chassis, which are steered by driving them at different speeds.
+//! - [MecanumDrive]: Four mecanum wheels, which allow the chassis to move in any direction and to
+//!   rotate at the same time (i.e. a holonomic drive). The four motors are usually driven by two
+//!   chips.
+//!
+//! All speeds are given as signed values, where [`i16::MAX`] means full speed. If the mixed
+//! speed of a wheel exceeds full speed, the speeds of all wheels are scaled down proportionally,
+//! so that the direction of the movement is kept.
+//!
+//! # Examples
+//!
+//! ```
+//! use l293x::drive::DifferentialDrive;
+//! use l293x::motor::DcMotor;
+//!
+//! let (left, right) = l293x.into_motors();
+//! let mut drive = DifferentialDrive::new(left, right);
+//!
+//! // Half speed forward, while turning slightly to the right
+//! drive.drive(i16::MAX / 2, i16::MAX / 8)?;
+//! drive.turn_in_place(-i16::MAX / 4)?;
+//! drive.brake()?;
+//! ```
+use crate::driver::FullHBridgeDriver;
+
+/// Scale the given speeds down proportionally, so that none of them exceeds full speed.
+fn saturate<const N: usize>(speeds: [i32; N]) -> [i16; N] {
+    let max = i32::from(i16::MAX);
+    let magnitude = speeds.iter().map(|speed| speed.abs()).max().unwrap_or(0);
+    speeds.map(|speed| match magnitude > max {
+        true => (i64::from(speed) * i64::from(max) / i64::from(magnitude)) as i16,
+        false => speed as i16,
+    })
+}
+
+/// Return the first error of the given results.
+fn first_error<E, const N: usize>(results: [Result<(), E>; N]) -> Result<(), E> {
+    results.into_iter().collect()
+}
+
+/// A differential drive (or tank drive) robot chassis
+///
+/// The chassis is driven by two motors on its left and right side. It is steered using arcade
+/// mixing: The steering value is added to the speed of the left motor and subtracted from the
+/// speed of the right motor. Thus, positive steering values turn the robot to the right.
+///
+/// Because two motors of the same type never run at exactly the same speed, each motor can be
+/// [trimmed](DifferentialDrive::set_trim), so that the robot drives straight. For more
+/// information, please see the [module documentation](crate::drive).
+#[derive(Debug)]
+pub struct DifferentialDrive<L, R> {
+    left: L,
+    right: R,
+    trim: [u8; 2],
+}
+
+impl<L, R> DifferentialDrive<L, R> {
+    /// Create a new differential drive from the left and the right motor.
+    ///
+    /// If a motor is mounted mirrored, so that it drives the robot backwards at positive speeds,
+    /// its terminals need to be swapped, e.g. by swapping the inputs passed to its driver.
+    #[inline]
+    pub fn new(left: L, right: R) -> Self {
+        Self {
+            left,
+            right,
+            trim: [100; 2],
+        }
+    }
+
+    /// Release the drive and return the left and the right motor.
+    #[inline]
+    pub fn release(self) -> (L, R) {
+        (self.left, self.right)
+    }
+
+    /// Get the left motor.
+    #[inline]
+    pub fn left(&mut self) -> &mut L {
+        &mut self.left
+    }
+
+    /// Get the right motor.
+    #[inline]
+    pub fn right(&mut self) -> &mut R {
+        &mut self.right
+    }
+
+    /// Trim the speeds of both motors.
+    ///
+    /// The speed of each motor is scaled to the given percentage, before it is applied. Values
+    /// above `100` are clamped. By default, both motors are driven at `100` percent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // The left motor is slightly faster than the right one
+    /// drive.set_trim(97, 100);
+    /// ```
+    #[inline]
+    pub fn set_trim(&mut self, left: u8, right: u8) {
+        self.trim = [left.min(100), right.min(100)];
+    }
+
+    /// Get the trim of the left and the right motor in percent.
+    #[inline]
+    pub fn trim(&self) -> (u8, u8) {
+        (self.trim[0], self.trim[1])
+    }
+}
+
+impl<L, R> DifferentialDrive<L, R>
+where
+    L: FullHBridgeDriver,
+    R: FullHBridgeDriver<Error = L::Error>,
+{
+    /// Drive the robot using arcade mixing.
+    ///
+    /// The `throttle` sets the speed forward (positive) or backwards (negative), while the
+    /// `steering` turns the robot to the right (positive) or the left (negative). If the mixed
+    /// speed of one motor exceeds full speed, both speeds are scaled down proportionally.
+    ///
+    /// # Errors
+    ///
+    /// Both motors are set, even if setting the first one fails. The first error is returned.
+    pub fn drive(&mut self, throttle: i16, steering: i16) -> Result<(), L::Error> {
+        let (throttle, steering) = (i32::from(throttle), i32::from(steering));
+        let [left, right] = saturate([throttle + steering, throttle - steering]);
+        self.tank(left, right)
+    }
+
+    /// Turn the robot in place.
+    ///
+    /// Both motors are driven at the same speed in opposite directions. Positive speeds turn the
+    /// robot to the right, negative ones to the left.
+    ///
+    /// # Errors
+    ///
+    /// Both motors are set, even if setting the first one fails. The first error is returned.
+    #[inline]
+    pub fn turn_in_place(&mut self, speed: i16) -> Result<(), L::Error> {
+        self.drive(0, speed)
+    }
+
+    /// Set the speeds of the left and the right motor directly.
+    ///
+    /// The speeds are [trimmed](DifferentialDrive::set_trim), before they are applied.
+    ///
+    /// # Errors
+    ///
+    /// Both motors are set, even if setting the first one fails. The first error is returned.
+    pub fn tank(&mut self, left: i16, right: i16) -> Result<(), L::Error> {
+        let trim = |speed: i16, trim: u8| (i32::from(speed) * i32::from(trim) / 100) as i16;
+        first_error([
+            self.left.set_speed(trim(left, self.trim[0])),
+            self.right.set_speed(trim(right, self.trim[1])),
+        ])
+    }
+
+    /// Actively brake both motors.
+    ///
+    /// # Errors
+    ///
+    /// Both motors are braked, even if braking the first one fails. The first error is returned.
+    pub fn brake(&mut self) -> Result<(), L::Error> {
+        first_error([self.left.brake(), self.right.brake()])
+    }
+
+    /// Let both motors coast to a stop.
+    ///
+    /// # Errors
+    ///
+    /// Both motors are disabled, even if disabling the first one fails. The first error is
+    /// returned.
+    pub fn coast(&mut self) -> Result<(), L::Error> {
+        first_error([self.left.disable(), self.right.disable()])
+    }
+}
+
+/// A mecanum wheel robot chassis
+///
+/// The chassis is driven by four mecanum wheels in the front left, front right, rear left and
+/// rear right corner. The rollers of the wheels need to form an `X` when viewed from above.
+/// Combining the speeds of the wheels moves the robot in any direction, while rotating it at the
+/// same time:
+///
+/// | Wheel       | Speed                |
+/// |-------------|----------------------|
+/// | Front left  | `vx + vy + rotation` |
+/// | Front right | `vx - vy - rotation` |
+/// | Rear left   | `vx - vy + rotation` |
+/// | Rear right  | `vx + vy - rotation` |
+///
+/// If the speed of a wheel exceeds full speed, the speeds of all wheels are scaled down
+/// proportionally. For more information, please see the [module documentation](crate::drive).
+///
+/// # Examples
+///
+/// ```
+/// use l293x::drive::MecanumDrive;
+///
+/// let (front_left, front_right) = front.into_motors();
+/// let (rear_left, rear_right) = rear.into_motors();
+/// let mut drive = MecanumDrive::new(front_left, front_right, rear_left, rear_right);
+///
+/// // Move diagonally forward to the right
+/// drive.drive(i16::MAX / 2, i16::MAX / 2, 0)?;
+/// ```
+#[derive(Debug)]
+pub struct MecanumDrive<FL, FR, RL, RR> {
+    front_left: FL,
+    front_right: FR,
+    rear_left: RL,
+    rear_right: RR,
+}
+
+impl<FL, FR, RL, RR> MecanumDrive<FL, FR, RL, RR> {
+    /// Create a new mecanum drive from the motors of the four wheels.
+    #[inline]
+    pub fn new(front_left: FL, front_right: FR, rear_left: RL, rear_right: RR) -> Self {
+        Self {
+            front_left,
+            front_right,
+            rear_left,
+            rear_right,
+        }
+    }
+
+    /// Release the drive and return the front left, front right, rear left and rear right motor.
+    #[inline]
+    pub fn release(self) -> (FL, FR, RL, RR) {
+        (
+            self.front_left,
+            self.front_right,
+            self.rear_left,
+            self.rear_right,
+        )
+    }
+
+    /// Get the motor of the front left wheel.
+    #[inline]
+    pub fn front_left(&mut self) -> &mut FL {
+        &mut self.front_left
+    }
+
+    /// Get the motor of the front right wheel.
+    #[inline]
+    pub fn front_right(&mut self) -> &mut FR {
+        &mut self.front_right
+    }
+
+    /// Get the motor of the rear left wheel.
+    #[inline]
+    pub fn rear_left(&mut self) -> &mut RL {
+        &mut self.rear_left
+    }
+
+    /// Get the motor of the rear right wheel.
+    #[inline]
+    pub fn rear_right(&mut self) -> &mut RR {
+        &mut self.rear_right
+    }
+}
+
+impl<FL, FR, RL, RR> MecanumDrive<FL, FR, RL, RR>
+where
+    FL: FullHBridgeDriver,
+    FR: FullHBridgeDriver<Error = FL::Error>,
+    RL: FullHBridgeDriver<Error = FL::Error>,
+    RR: FullHBridgeDriver<Error = FL::Error>,
+{
+    /// Move and rotate the robot.
+    ///
+    /// The speed `vx` moves the robot forward (positive) or backwards (negative), while `vy`
+    /// moves it sideways to the right (positive) or the left (negative). A positive `rotation`
+    /// turns the robot clockwise. The speeds are mixed as shown in the
+    /// [struct documentation](MecanumDrive) and scaled down proportionally, if one of the wheels
+    /// would exceed full speed.
+    ///
+    /// Because `move` is a keyword in Rust, this method is called `drive`, like the one of the
+    /// [DifferentialDrive].
+    ///
+    /// # Errors
+    ///
+    /// All motors are set, even if setting one of them fails. The first error is returned.
+    pub fn drive(&mut self, vx: i16, vy: i16, rotation: i16) -> Result<(), FL::Error> {
+        let (vx, vy, rotation) = (i32::from(vx), i32::from(vy), i32::from(rotation));
+        let [front_left, front_right, rear_left, rear_right] = saturate([
+            vx + vy + rotation,
+            vx - vy - rotation,
+            vx - vy + rotation,
+            vx + vy - rotation,
+        ]);
+        first_error([
+            self.front_left.set_speed(front_left),
+            self.front_right.set_speed(front_right),
+            self.rear_left.set_speed(rear_left),
+            self.rear_right.set_speed(rear_right),
+        ])
+    }
+
+    /// Actively brake all motors.
+    ///
+    /// # Errors
+    ///
+    /// All motors are braked, even if braking one of them fails. The first error is returned.
+    pub fn brake(&mut self) -> Result<(), FL::Error> {
+        first_error([
+            self.front_left.brake(),
+            self.front_right.brake(),
+            self.rear_left.brake(),
+            self.rear_right.brake(),
+        ])
+    }
+
+    /// Let all motors coast to a stop.
+    ///
+    /// # Errors
+    ///
+    /// All motors are disabled, even if disabling one of them fails. The first error is
+    /// returned.
+    pub fn coast(&mut self) -> Result<(), FL::Error> {
+        first_error([
+            self.front_left.disable(),
+            self.front_right.disable(),
+            self.rear_left.disable(),
+            self.rear_right.disable(),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::motor::DcMotor;
+    use crate::test_util::{DigitalPin, PwmPin};
+    use coverage_helper::test;
+
+    type Motor = DcMotor<PwmPin, PwmPin, DigitalPin>;
+
+    fn motor() -> Motor {
+        DcMotor::new(PwmPin::new(), PwmPin::new(), DigitalPin::new())
+    }
+
+    fn speeds(drive: &mut DifferentialDrive<Motor, Motor>) -> (i16, i16) {
+        (drive.left().speed(), drive.right().speed())
+    }
+
+    #[test]
+    fn test_saturate() {
+        assert_eq!(saturate([100, -100]), [100, -100]);
+        assert_eq!(saturate([65_534, 0]), [i16::MAX, 0]);
+        assert_eq!(saturate([-65_534, 32_767]), [-i16::MAX, 16_383]);
+        assert_eq!(saturate::<0>([]), []);
+    }
+
+    #[test]
+    fn test_drive() {
+        let mut drive = DifferentialDrive::new(motor(), motor());
+
+        drive.drive(1000, 200).unwrap();
+        assert_eq!(speeds(&mut drive), (1200, 800));
+        drive.drive(i16::MAX, i16::MAX).unwrap();
+        assert_eq!(speeds(&mut drive), (i16::MAX, 0));
+        drive.turn_in_place(-500).unwrap();
+        assert_eq!(speeds(&mut drive), (-500, 500));
+
+        drive.brake().unwrap();
+        assert_eq!(speeds(&mut drive), (0, 0));
+        drive.tank(100, 100).unwrap();
+        drive.coast().unwrap();
+        assert_eq!(speeds(&mut drive), (0, 0));
+    }
+
+    #[test]
+    fn test_trim() {
+        let mut drive = DifferentialDrive::new(motor(), motor());
+        drive.set_trim(90, 200);
+        assert_eq!(drive.trim(), (90, 100));
+
+        drive.tank(1000, -1000).unwrap();
+        assert_eq!(speeds(&mut drive), (900, -1000));
+    }
+
+    #[test]
+    fn test_error() {
+        let mut enable = DigitalPin::new();
+        enable.fail();
+        let left = DcMotor::new(PwmPin::new(), PwmPin::new(), enable);
+        let mut drive = DifferentialDrive::new(left, motor());
+
+        // The right motor is set nevertheless
+        assert!(drive.drive(100, 0).is_err());
+        assert_eq!(drive.right().speed(), 100);
+        assert!(drive.brake().is_err());
+        assert!(drive.coast().is_err());
+        assert_eq!(drive.release().1.speed(), 0);
+    }
+
+    fn wheel_speeds(drive: &mut MecanumDrive<Motor, Motor, Motor, Motor>) -> [i16; 4] {
+        [
+            drive.front_left().speed(),
+            drive.front_right().speed(),
+            drive.rear_left().speed(),
+            drive.rear_right().speed(),
+        ]
+    }
+
+    #[test]
+    fn test_mecanum() {
+        let mut drive = MecanumDrive::new(motor(), motor(), motor(), motor());
+
+        drive.drive(1000, 0, 0).unwrap();
+        assert_eq!(wheel_speeds(&mut drive), [1000; 4]);
+        drive.drive(0, 1000, 100).unwrap();
+        assert_eq!(wheel_speeds(&mut drive), [1100, -1100, -900, 900]);
+        drive.drive(i16::MAX, i16::MAX, 0).unwrap();
+        assert_eq!(wheel_speeds(&mut drive), [i16::MAX, 0, 0, i16::MAX]);
+
+        drive.brake().unwrap();
+        assert_eq!(wheel_speeds(&mut drive), [0; 4]);
+        drive.drive(0, 0, -100).unwrap();
+        assert_eq!(wheel_speeds(&mut drive), [-100, 100, -100, 100]);
+        drive.coast().unwrap();
+        assert_eq!(wheel_speeds(&mut drive), [0; 4]);
+    }
+
+    #[test]
+    fn test_mecanum_error() {
+        let mut enable = DigitalPin::new();
+        enable.fail();
+        let front_right = DcMotor::new(PwmPin::new(), PwmPin::new(), enable);
+        let mut drive = MecanumDrive::new(motor(), front_right, motor(), motor());
+
+        assert!(drive.drive(100, 0, 0).is_err());
+        assert!(drive.brake().is_err());
+        assert!(drive.coast().is_err());
+        let (front_left, _, _, rear_right) = drive.release();
+        assert_eq!(front_left.speed(), 0);
+        assert_eq!(rear_right.speed(), 0);
+    }
+}
@@ -0,0 +1,401 @@
+//! # Generic motor driver traits
+//!
+//! This module defines the [HalfHBridgeDriver] and [FullHBridgeDriver] traits, which abstract over
+//! the Half-H and Full-H bridges of a motor driver chip. Downstream crates (e.g. robotics crates)
+//! can be written against these traits, instead of the concrete types of this crate, to be
+//! generic over the motor driver used.
+//!
+//! The [HalfHBridgeDriver] trait is implemented for the [HalfH] and [OwnedHalfH] bridges with
+//! PWM-capable inputs. The [FullHBridgeDriver] trait is implemented for every [FullH] bridge
+//! composed of two [HalfHBridgeDriver]s, for the [DcMotor] driver and for the motor handles of
+//! the [DRV8833](crate::InInMotor) and [TB6612FNG](crate::Tb6612fngMotor) chips. Both traits are
+//! implemented for mutable references to their implementations as well, so the bridges can be
+//! lent to drivers taking them by value.
+//!
+//! # Examples
+//!
+//! ```
+//! use l293x::driver::FullHBridgeDriver;
+//!
+//! fn drive_square<L: FullHBridgeDriver, R: FullHBridgeDriver>(left: &mut L, right: &mut R) {
+//!     // [...]
+//! }
+//!
+//! # use l293x::L293x;
+//! let l293x = L293x::new(input1, input2, input3, input4, enable12, enable34);
+//! drive_square(&mut l293x.full_h12(), &mut l293x.full_h34());
+//! ```
+use embedded_hal::digital::{OutputPin, PinState};
+use embedded_hal::pwm::SetDutyCycle;
+
+use crate::motor::DcMotor;
+use crate::{Direction, FullH, HalfH, OutputStateError, OwnedHalfH};
+
+/// A single Half-H bridge of a motor driver chip
+///
+/// A Half-H bridge drives one terminal of a load. Its output can be driven high or low, either
+/// statically or using a duty cycle, or be disabled (i.e. set into the high impedance mode).
+pub trait HalfHBridgeDriver {
+    /// The error type of the bridge.
+    type Error;
+
+    /// Enable the output of the bridge.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error, if the output could not be enabled.
+    fn enable(&mut self) -> Result<(), Self::Error>;
+
+    /// Disable the output of the bridge, which sets it into the high impedance mode.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error, if the output could not be disabled.
+    fn disable(&mut self) -> Result<(), Self::Error>;
+
+    /// Drive the output of the bridge statically high or low.
+    ///
+    /// Whether the output is enabled implicitly depends on the bridge, e.g. on the
+    /// [auto-enable policy](crate::AutoEnable) of a [HalfH] bridge.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error, if the state could not be set.
+    fn set_state(&mut self, state: PinState) -> Result<(), Self::Error>;
+
+    /// Get the maximum duty cycle of the bridge.
+    fn max_duty_cycle(&self) -> u16;
+
+    /// Set the duty cycle of the output of the bridge.
+    ///
+    /// Whether the output is enabled implicitly depends on the bridge, e.g. on the
+    /// [auto-enable policy](crate::AutoEnable) of a [HalfH] bridge.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error, if the duty cycle could not be set.
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error>;
+}
+
+/// A Full-H bridge of a motor driver chip
+///
+/// A Full-H bridge drives a load (e.g. a DC motor) in both directions. The speed of the load is
+/// given as a signed value, where the sign selects the direction and [`i16::MAX`] means full
+/// speed.
+pub trait FullHBridgeDriver {
+    /// The error type of the bridge.
+    type Error;
+
+    /// Enable the outputs of the bridge.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error, if the outputs could not be enabled.
+    fn enable(&mut self) -> Result<(), Self::Error>;
+
+    /// Disable the outputs of the bridge, which lets the load coast.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error, if the outputs could not be disabled.
+    fn disable(&mut self) -> Result<(), Self::Error>;
+
+    /// Set the speed of the load.
+    ///
+    /// Positive values drive the load forward, negative values in reverse. A `speed` of `0`
+    /// brakes the load.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error, if the speed could not be set.
+    fn set_speed(&mut self, speed: i16) -> Result<(), Self::Error>;
+
+    /// Get the last commanded speed of the load.
+    fn speed(&self) -> i16;
+
+    /// Drive the load at full speed in the given direction.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error, if the speed could not be set.
+    fn drive(&mut self, direction: Direction) -> Result<(), Self::Error> {
+        match direction {
+            Direction::Forward => self.set_speed(i16::MAX),
+            Direction::Reverse => self.set_speed(-i16::MAX),
+        }
+    }
+
+    /// Actively brake the load.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error, if the load could not be braked.
+    fn brake(&mut self) -> Result<(), Self::Error> {
+        self.set_speed(0)
+    }
+}
+
+impl<T: HalfHBridgeDriver + ?Sized> HalfHBridgeDriver for &mut T {
+    type Error = T::Error;
+
+    #[inline]
+    fn enable(&mut self) -> Result<(), Self::Error> {
+        T::enable(self)
+    }
+
+    #[inline]
+    fn disable(&mut self) -> Result<(), Self::Error> {
+        T::disable(self)
+    }
+
+    #[inline]
+    fn set_state(&mut self, state: PinState) -> Result<(), Self::Error> {
+        T::set_state(self, state)
+    }
+
+    #[inline]
+    fn max_duty_cycle(&self) -> u16 {
+        T::max_duty_cycle(self)
+    }
+
+    #[inline]
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+        T::set_duty_cycle(self, duty)
+    }
+}
+
+impl<'a, INPUT, ENABLE> HalfHBridgeDriver for HalfH<'a, INPUT, ENABLE>
+where
+    INPUT: SetDutyCycle,
+    ENABLE: OutputPin,
+{
+    type Error = OutputStateError<INPUT::Error, ENABLE::Error>;
+
+    #[inline]
+    fn enable(&mut self) -> Result<(), Self::Error> {
+        HalfH::enable(self).map_err(OutputStateError::EnablePinError)
+    }
+
+    #[inline]
+    fn disable(&mut self) -> Result<(), Self::Error> {
+        HalfH::disable(self).map_err(OutputStateError::EnablePinError)
+    }
+
+    fn set_state(&mut self, state: PinState) -> Result<(), Self::Error> {
+        match state {
+            PinState::High => self.set_duty_cycle_fully_on(),
+            PinState::Low => self.set_duty_cycle_fully_off(),
+        }
+    }
+
+    #[inline]
+    fn max_duty_cycle(&self) -> u16 {
+        SetDutyCycle::max_duty_cycle(self)
+    }
+
+    #[inline]
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+        SetDutyCycle::set_duty_cycle(self, duty)
+    }
+}
+
+impl<INPUT, ENABLE> HalfHBridgeDriver for OwnedHalfH<INPUT, ENABLE>
+where
+    INPUT: SetDutyCycle,
+    ENABLE: OutputPin,
+{
+    type Error = OutputStateError<INPUT::Error, ENABLE::Error>;
+
+    #[inline]
+    fn enable(&mut self) -> Result<(), Self::Error> {
+        OwnedHalfH::enable(self).map_err(OutputStateError::EnablePinError)
+    }
+
+    #[inline]
+    fn disable(&mut self) -> Result<(), Self::Error> {
+        OwnedHalfH::disable(self).map_err(OutputStateError::EnablePinError)
+    }
+
+    fn set_state(&mut self, state: PinState) -> Result<(), Self::Error> {
+        match state {
+            PinState::High => self.set_duty_cycle_fully_on(),
+            PinState::Low => self.set_duty_cycle_fully_off(),
+        }
+    }
+
+    #[inline]
+    fn max_duty_cycle(&self) -> u16 {
+        SetDutyCycle::max_duty_cycle(self)
+    }
+
+    #[inline]
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+        SetDutyCycle::set_duty_cycle(self, duty)
+    }
+}
+
+impl<T: FullHBridgeDriver + ?Sized> FullHBridgeDriver for &mut T {
+    type Error = T::Error;
+
+    #[inline]
+    fn enable(&mut self) -> Result<(), Self::Error> {
+        T::enable(self)
+    }
+
+    #[inline]
+    fn disable(&mut self) -> Result<(), Self::Error> {
+        T::disable(self)
+    }
+
+    #[inline]
+    fn set_speed(&mut self, speed: i16) -> Result<(), Self::Error> {
+        T::set_speed(self, speed)
+    }
+
+    #[inline]
+    fn speed(&self) -> i16 {
+        T::speed(self)
+    }
+
+    #[inline]
+    fn drive(&mut self, direction: Direction) -> Result<(), Self::Error> {
+        T::drive(self, direction)
+    }
+
+    #[inline]
+    fn brake(&mut self) -> Result<(), Self::Error> {
+        T::brake(self)
+    }
+}
+
+impl<A, B> FullHBridgeDriver for FullH<A, B>
+where
+    A: HalfHBridgeDriver,
+    B: HalfHBridgeDriver<Error = A::Error>,
+{
+    type Error = A::Error;
+
+    fn enable(&mut self) -> Result<(), Self::Error> {
+        let (a, b) = self.bridges_mut();
+        a.enable()?;
+        b.enable()
+    }
+
+    fn disable(&mut self) -> Result<(), Self::Error> {
+        let (a, b) = self.bridges_mut();
+        a.disable()?;
+        b.disable()?;
+        self.record_speed(0);
+        Ok(())
+    }
+
+    fn set_speed(&mut self, speed: i16) -> Result<(), Self::Error> {
+        let speed = speed.max(-i16::MAX);
+        let (a, b) = self.bridges_mut();
+        if speed < 0 {
+            let duty = crate::full_h::speed_to_duty(speed, b.max_duty_cycle());
+            a.set_state(PinState::Low)?;
+            b.set_duty_cycle(duty)?;
+        } else {
+            let duty = crate::full_h::speed_to_duty(speed, a.max_duty_cycle());
+            b.set_state(PinState::Low)?;
+            a.set_duty_cycle(duty)?;
+        }
+        self.record_speed(speed);
+        Ok(())
+    }
+
+    #[inline]
+    fn speed(&self) -> i16 {
+        FullH::speed(self)
+    }
+}
+
+impl<A, B, EN> FullHBridgeDriver for DcMotor<A, B, EN>
+where
+    A: SetDutyCycle,
+    B: SetDutyCycle<Error = A::Error>,
+    EN: OutputPin,
+{
+    type Error = OutputStateError<A::Error, EN::Error>;
+
+    #[inline]
+    fn enable(&mut self) -> Result<(), Self::Error> {
+        DcMotor::enable(self).map_err(OutputStateError::EnablePinError)
+    }
+
+    #[inline]
+    fn disable(&mut self) -> Result<(), Self::Error> {
+        self.coast().map_err(OutputStateError::EnablePinError)
+    }
+
+    #[inline]
+    fn set_speed(&mut self, speed: i16) -> Result<(), Self::Error> {
+        DcMotor::set_speed(self, speed)
+    }
+
+    #[inline]
+    fn speed(&self) -> i16 {
+        DcMotor::speed(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{DigitalPin, PwmPin};
+    use crate::L293x;
+    use coverage_helper::test;
+
+    fn drive<D: FullHBridgeDriver>(driver: &mut D) -> Result<(), D::Error> {
+        driver.enable()?;
+        driver.drive(Direction::Reverse)?;
+        assert_eq!(driver.speed(), -i16::MAX);
+        driver.set_speed(i16::MIN)?;
+        assert_eq!(driver.speed(), -i16::MAX);
+        driver.brake()?;
+        assert_eq!(driver.speed(), 0);
+        driver.set_speed(100)?;
+        driver.disable()?;
+        assert_eq!(driver.speed(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_full_h() {
+        let l293x = L293x::new(PwmPin::new(), PwmPin::new(), (), (), DigitalPin::new(), ());
+        let mut bridge = l293x.full_h12();
+        drive(&mut bridge).unwrap();
+        assert!(l293x.y1().is_disabled().unwrap());
+    }
+
+    #[test]
+    fn test_owned_half_h() {
+        let mut bridge = FullH::new(
+            OwnedHalfH::new(PwmPin::new(), DigitalPin::new()),
+            OwnedHalfH::new(PwmPin::new(), DigitalPin::new()),
+        );
+        drive(&mut bridge).unwrap();
+        HalfHBridgeDriver::set_state(&mut bridge.release().0, PinState::High).unwrap();
+    }
+
+    #[test]
+    fn test_mut_ref() {
+        let l293x = L293x::new(PwmPin::new(), PwmPin::new(), (), (), DigitalPin::new(), ());
+        let (mut y1, mut y2) = (l293x.y1(), l293x.y2());
+        // Lend the half-bridges to a Full-H bridge and the Full-H bridge to a driver
+        let mut bridge = FullH::new(&mut y1, &mut y2);
+        drive(&mut &mut bridge).unwrap();
+        assert!(y1.is_disabled().unwrap());
+
+        let mut y1 = &mut y1;
+        HalfHBridgeDriver::set_state(&mut y1, PinState::High).unwrap();
+        assert_eq!(HalfHBridgeDriver::max_duty_cycle(&y1), u16::MAX);
+    }
+
+    #[test]
+    fn test_dc_motor() {
+        let mut motor = DcMotor::new(PwmPin::new(), PwmPin::new(), DigitalPin::new());
+        drive(&mut motor).unwrap();
+    }
+}
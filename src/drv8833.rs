@@ -0,0 +1,228 @@
+use embedded_hal::digital::OutputPin;
+
+use crate::pins::Vcc;
+use crate::{BridgePair, HalfH, InInMotor};
+
+/// A DRV8833 Dual H-Bridge Motor Driver
+///
+/// The [DRV8833](https://www.ti.com/lit/ds/symlink/drv8833.pdf) chip uses an IN/IN interface:
+/// Each of its two H-bridges is controlled by two inputs (`AIN1` & `AIN2` or `BIN1` & `BIN2`)
+/// without a separate enable pin. Instead, the whole chip can be put into a low-power sleep mode
+/// using its `nSLEEP` pin.
+///
+/// The motors are accessed using the [`motor_a()`](Drv8833::motor_a) and
+/// [`motor_b()`](Drv8833::motor_b) methods, which return [InInMotor] handles. Unlike the
+/// [Full-H bridges](crate::FullH) of the [L293](crate::L293x) chip, these brake the motor by
+/// setting both inputs high and let it coast by setting both inputs low, as the DRV8833 chip
+/// expects. If the inputs are PWM-capable, the speed of the motors can be set using
+/// [`set_speed()`](InInMotor::set_speed). The enable pins of the bridges are static [Vcc] pins.
+///
+/// # Examples
+///
+/// ```
+/// use l293x::Drv8833;
+///
+/// // [...] create the input pins and the sleep pin
+/// let mut drv8833 = Drv8833::new(ain1, ain2, bin1, bin2, nsleep);
+///
+/// drv8833.wake()?;
+/// drv8833.motor_a().set_speed(i16::MAX / 2)?;
+/// drv8833.motor_b().reverse()?;
+/// drv8833.sleep()?;
+/// ```
+#[derive(Debug)]
+pub struct Drv8833<AIN1, AIN2, BIN1, BIN2, SLEEP> {
+    a: BridgePair<AIN1, AIN2, Vcc>,
+    b: BridgePair<BIN1, BIN2, Vcc>,
+    sleep: SLEEP,
+}
+
+impl<AIN1, AIN2, BIN1, BIN2, SLEEP> Drv8833<AIN1, AIN2, BIN1, BIN2, SLEEP> {
+    /// Create a new DRV8833 chip driver.
+    ///
+    /// If the `nSLEEP` pin of the chip is tied high, [Vcc] can be passed as `sleep` pin.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use l293x::Drv8833;
+    /// let drv8833 = Drv8833::new(ain1, ain2, bin1, bin2, nsleep);
+    /// ```
+    #[inline]
+    pub fn new(ain1: AIN1, ain2: AIN2, bin1: BIN1, bin2: BIN2, sleep: SLEEP) -> Self {
+        Self {
+            a: BridgePair::new(ain1, ain2, Vcc()),
+            b: BridgePair::new(bin1, bin2, Vcc()),
+            sleep,
+        }
+    }
+
+    /// Release the driver and return the input pins and the sleep pin.
+    #[inline]
+    pub fn release(self) -> (AIN1, AIN2, BIN1, BIN2, SLEEP) {
+        let (ain1, ain2, _) = self.a.release();
+        let (bin1, bin2, _) = self.b.release();
+        (ain1, ain2, bin1, bin2, self.sleep)
+    }
+
+    /// Get the Half-H bridge of output `AOUT1`.
+    #[inline]
+    pub fn aout1(&self) -> HalfH<'_, AIN1, Vcc> {
+        self.a.first()
+    }
+
+    /// Get the Half-H bridge of output `AOUT2`.
+    #[inline]
+    pub fn aout2(&self) -> HalfH<'_, AIN2, Vcc> {
+        self.a.second()
+    }
+
+    /// Get the Half-H bridge of output `BOUT1`.
+    #[inline]
+    pub fn bout1(&self) -> HalfH<'_, BIN1, Vcc> {
+        self.b.first()
+    }
+
+    /// Get the Half-H bridge of output `BOUT2`.
+    #[inline]
+    pub fn bout2(&self) -> HalfH<'_, BIN2, Vcc> {
+        self.b.second()
+    }
+
+    /// Get the motor connected to the outputs `AOUT1` and `AOUT2`.
+    #[inline]
+    pub fn motor_a(&self) -> InInMotor<'_, AIN1, AIN2, Vcc> {
+        InInMotor::new(self.a.full_h())
+    }
+
+    /// Get the motor connected to the outputs `BOUT1` and `BOUT2`.
+    #[inline]
+    pub fn motor_b(&self) -> InInMotor<'_, BIN1, BIN2, Vcc> {
+        InInMotor::new(self.b.full_h())
+    }
+}
+
+impl<AIN1, AIN2, BIN1, BIN2, SLEEP> Drv8833<AIN1, AIN2, BIN1, BIN2, SLEEP>
+where
+    AIN1: OutputPin,
+    AIN2: OutputPin<Error = AIN1::Error>,
+{
+    /// Actively brake the motor `A`.
+    ///
+    /// This sets both inputs of the bridge high, which shorts the terminals of the motor.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the first input pin, which could not be set.
+    pub fn brake_a(&mut self) -> Result<(), AIN1::Error> {
+        let (ain1, ain2) = self.a.inputs_mut();
+        ain1.set_high()?;
+        ain2.set_high()
+    }
+}
+
+impl<AIN1, AIN2, BIN1, BIN2, SLEEP> Drv8833<AIN1, AIN2, BIN1, BIN2, SLEEP>
+where
+    BIN1: OutputPin,
+    BIN2: OutputPin<Error = BIN1::Error>,
+{
+    /// Actively brake the motor `B`.
+    ///
+    /// This sets both inputs of the bridge high, which shorts the terminals of the motor.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the first input pin, which could not be set.
+    pub fn brake_b(&mut self) -> Result<(), BIN1::Error> {
+        let (bin1, bin2) = self.b.inputs_mut();
+        bin1.set_high()?;
+        bin2.set_high()
+    }
+}
+
+impl<AIN1, AIN2, BIN1, BIN2, SLEEP> Drv8833<AIN1, AIN2, BIN1, BIN2, SLEEP>
+where
+    SLEEP: OutputPin,
+{
+    /// Put the chip into its low-power sleep mode.
+    ///
+    /// This sets the `nSLEEP` pin low, which disables both bridges and lets the motors coast.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the sleep pin.
+    #[inline]
+    pub fn sleep(&mut self) -> Result<(), SLEEP::Error> {
+        self.sleep.set_low()
+    }
+
+    /// Wake the chip up from its sleep mode.
+    ///
+    /// This sets the `nSLEEP` pin high. Please note, that the chip needs up to 1ms to wake up,
+    /// before the inputs are applied to the outputs.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the sleep pin.
+    #[inline]
+    pub fn wake(&mut self) -> Result<(), SLEEP::Error> {
+        self.sleep.set_high()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{DigitalPin, PwmPin};
+    use coverage_helper::test;
+    use embedded_hal::digital::StatefulOutputPin;
+
+    #[test]
+    fn test_motors() {
+        let mut drv8833 = Drv8833::new(
+            PwmPin::new(),
+            PwmPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        );
+
+        drv8833.motor_a().set_speed(-i16::MAX).unwrap();
+        drv8833.motor_b().forward().unwrap();
+        assert!(drv8833.bout1().is_set_high().unwrap());
+        assert!(drv8833.bout2().is_set_low().unwrap());
+
+        drv8833.brake_b().unwrap();
+        assert!(drv8833.bout2().is_set_high().unwrap());
+        drv8833.motor_b().coast().unwrap();
+        assert!(drv8833.bout1().is_set_low().unwrap());
+        assert!(drv8833.bout2().is_set_low().unwrap());
+        drv8833.motor_b().brake().unwrap();
+        assert!(drv8833.bout1().is_set_high().unwrap());
+        assert!(drv8833.bout2().is_set_high().unwrap());
+
+        let (ain1, ain2, _, _, _) = drv8833.release();
+        assert_eq!(ain1.get_duty_cycle(), 0);
+        assert_eq!(ain2.get_duty_cycle(), u16::MAX);
+    }
+
+    #[test]
+    fn test_sleep() {
+        let mut drv8833 = Drv8833::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        );
+
+        drv8833.wake().unwrap();
+        assert!(drv8833.sleep.is_set_high().unwrap());
+        drv8833.brake_a().unwrap();
+        assert!(drv8833.aout1().is_set_high().unwrap());
+        assert!(drv8833.aout2().is_set_high().unwrap());
+
+        drv8833.sleep().unwrap();
+        assert!(drv8833.sleep.is_set_low().unwrap());
+    }
+}
@@ -0,0 +1,91 @@
+use core::fmt::{Debug, Display, Formatter};
+
+/// Error returned by the [`emergency_stop()`](crate::L293x::emergency_stop) methods of the chip.
+///
+/// An emergency stop tries to set every pin of the chip, even if setting some of them fails.
+/// Thus, this error does not contain a single error, but the errors of all pins, which could not
+/// be set. Pins, which were set successfully, are `None`.
+///
+/// # Examples
+///
+/// ```
+/// if let Err(error) = l293x.emergency_stop() {
+///     if error.enable12.is_some() || error.enable34.is_some() {
+///         // The outputs may still be enabled, cut the power supply of the motors
+///     }
+/// }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct EmergencyStopError<I, E> {
+    /// The error of the enable pin of the channels 1 & 2.
+    pub enable12: Option<E>,
+    /// The error of the enable pin of the channels 3 & 4.
+    pub enable34: Option<E>,
+    /// The errors of the input pins 1 - 4.
+    pub inputs: [Option<I>; 4],
+}
+
+impl<I, E> EmergencyStopError<I, E> {
+    /// Check whether any of the pins failed.
+    #[inline]
+    pub fn any_failed(&self) -> bool {
+        self.enable12.is_some()
+            || self.enable34.is_some()
+            || self.inputs.iter().any(Option::is_some)
+    }
+}
+
+impl<I, E> Display for EmergencyStopError<I, E>
+where
+    I: Debug,
+    E: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "emergency stop failed:")?;
+        if let Some(e) = &self.enable12 {
+            write!(f, " enable12: {:?};", e)?;
+        }
+        if let Some(e) = &self.enable34 {
+            write!(f, " enable34: {:?};", e)?;
+        }
+        for (index, error) in self.inputs.iter().enumerate() {
+            if let Some(e) = error {
+                write!(f, " input{}: {:?};", index + 1, e)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I, E> std::error::Error for EmergencyStopError<I, E>
+where
+    I: Debug,
+    E: Debug,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::DigitalError;
+
+    use super::*;
+
+    #[test]
+    fn test_emergency_stop_error_display() {
+        extern crate std;
+        use std::string::ToString;
+
+        let error: EmergencyStopError<DigitalError, DigitalError> = EmergencyStopError {
+            enable12: None,
+            enable34: Some(DigitalError()),
+            inputs: [Some(DigitalError()), None, None, None],
+        };
+        assert!(error.any_failed());
+        assert_eq!(
+            error.to_string(),
+            "emergency stop failed: enable34: DigitalError; input1: DigitalError;"
+        );
+    }
+}
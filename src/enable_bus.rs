@@ -0,0 +1,154 @@
+use embedded_hal::digital::{ErrorType, OutputPin, StatefulOutputPin};
+
+/// A single logical enable pin driving several physical pins
+///
+/// Boards with several chips often provide a "master enable", which is wired to the enable pins
+/// of all chips. If these enable pins are connected to separate pins of the microcontroller, this
+/// type models the master enable in code: It fans each change out to all of its pins. Because it
+/// implements the [OutputPin] and [StatefulOutputPin] traits itself, it can be passed as the
+/// enable pin of any driver of this crate.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::digital::OutputPin;
+/// use l293x::EnableBus;
+///
+/// // The enable pins of three chips connected to separate pins of the microcontroller
+/// let mut master_enable = EnableBus::new([enable1, enable2, enable3]);
+///
+/// master_enable.set_high()?;
+/// // [...]
+/// master_enable.set_low()?;
+/// ```
+#[derive(Debug)]
+pub struct EnableBus<P, const N: usize> {
+    pins: [P; N],
+}
+
+impl<P, const N: usize> EnableBus<P, N> {
+    /// Create a new enable bus from the given pins.
+    #[inline]
+    pub fn new(pins: [P; N]) -> Self {
+        Self { pins }
+    }
+
+    /// Release the enable bus and return its pins.
+    #[inline]
+    pub fn release(self) -> [P; N] {
+        self.pins
+    }
+
+    /// Get the pins of the enable bus mutably, e.g. to switch a single chip.
+    #[inline]
+    pub fn pins_mut(&mut self) -> &mut [P; N] {
+        &mut self.pins
+    }
+}
+
+impl<P: ErrorType, const N: usize> ErrorType for EnableBus<P, N> {
+    type Error = P::Error;
+}
+
+impl<P: OutputPin, const N: usize> OutputPin for EnableBus<P, N> {
+    /// Set all pins of the bus low.
+    ///
+    /// # Errors
+    ///
+    /// All pins are set, even if setting one of them fails. The first error is returned.
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.pins
+            .iter_mut()
+            .map(OutputPin::set_low)
+            .fold(Ok(()), Result::and)
+    }
+
+    /// Set all pins of the bus high.
+    ///
+    /// # Errors
+    ///
+    /// All pins are set, even if setting one of them fails. The first error is returned.
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.pins
+            .iter_mut()
+            .map(OutputPin::set_high)
+            .fold(Ok(()), Result::and)
+    }
+}
+
+impl<P: StatefulOutputPin, const N: usize> StatefulOutputPin for EnableBus<P, N> {
+    /// Check whether all pins of the bus are set high.
+    ///
+    /// If only some of the pins are high (e.g. after an error), the bus is not considered high.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the first pin, which could not be read.
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        let mut high = true;
+        for pin in self.pins.iter_mut() {
+            high &= pin.is_set_high()?;
+        }
+        Ok(high)
+    }
+
+    /// Check whether the bus is not [set high](EnableBus::is_set_high).
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the first pin, which could not be read.
+    #[inline]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        self.is_set_high().map(|high| !high)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::DigitalPin;
+    use crate::L293x;
+    use coverage_helper::test;
+
+    #[test]
+    fn test_fan_out() {
+        let mut bus = EnableBus::new([DigitalPin::new(), DigitalPin::new(), DigitalPin::new()]);
+        assert!(bus.is_set_low().unwrap());
+
+        bus.set_high().unwrap();
+        assert!(bus.is_set_high().unwrap());
+        bus.pins_mut()[1].set_low().unwrap();
+        assert!(bus.is_set_low().unwrap());
+
+        bus.set_low().unwrap();
+        for mut pin in bus.release() {
+            assert!(pin.is_set_low().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_chip() {
+        let l293x = L293x::new(
+            DigitalPin::new(),
+            (),
+            (),
+            (),
+            EnableBus::new([DigitalPin::new(), DigitalPin::new()]),
+            (),
+        );
+        l293x.y1().enable().unwrap();
+        assert!(l293x.y1().is_enabled().unwrap());
+    }
+
+    #[test]
+    fn test_error() {
+        let mut pin = DigitalPin::new();
+        pin.fail();
+        let mut bus = EnableBus::new([pin, DigitalPin::new()]);
+
+        assert!(bus.set_high().is_err());
+        assert!(bus.pins_mut()[1].is_set_high().unwrap());
+        assert!(bus.set_low().is_err());
+        assert!(bus.is_set_high().is_err());
+    }
+}
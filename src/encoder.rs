@@ -0,0 +1,264 @@
+//! # Quadrature encoder
+//!
+//! Closed-loop control of a DC motor requires a measurement of its position or speed, which is
+//! usually provided by a quadrature encoder mounted on the motor shaft. This module implements a
+//! [QuadratureEncoder], which decodes the two channels of such an encoder into a position count
+//! and estimates the velocity of the motor from it.
+//!
+//! The channels are connected to two [InputPin]s, which are polled using
+//! [`update()`](QuadratureEncoder::update), e.g. from a timer interrupt or from the interrupt
+//! handler of the pins. Each edge of both channels counts as one step (i.e. X4 decoding). If both
+//! channels changed between two updates, the direction of the step cannot be decided. Such
+//! steps are not counted, but recorded as [missed](QuadratureEncoder::missed) instead.
+//!
+//! If the `async` feature is enabled, pins implementing the
+//! [Wait](embedded_hal_async::digital::Wait) trait can be awaited using
+//! [`wait_for_step()`](QuadratureEncoder::wait_for_step) instead.
+//!
+//! Please note, that decoding the channels in software limits the maximum speed of the motor.
+//! For fast motors, the quadrature decoder of a timer peripheral should be used instead.
+//!
+//! # Examples
+//!
+//! ```
+//! use l293x::encoder::QuadratureEncoder;
+//!
+//! let mut encoder = QuadratureEncoder::new(channel_a, channel_b);
+//!
+//! // Within a timer interrupt firing every 100µs:
+//! encoder.update()?;
+//!
+//! // Within the control loop running every 10ms:
+//! let velocity = encoder.sample_velocity(10_000);
+//! motor.set_speed(controller.update(target, velocity))?;
+//! ```
+use embedded_hal::digital::InputPin;
+
+/// Decoder of the two channels of a quadrature encoder
+///
+/// Steps are counted up, if channel `A` leads channel `B`, and down otherwise. For more
+/// information, please see the [module documentation](crate::encoder).
+#[derive(Debug)]
+pub struct QuadratureEncoder<A, B> {
+    a: A,
+    b: B,
+    phase: Option<u8>,
+    position: i32,
+    missed: u32,
+    sampled: i32,
+}
+
+impl<A, B> QuadratureEncoder<A, B> {
+    /// Create a new encoder at position `0`.
+    ///
+    /// The pins are not read, until the encoder is [updated](QuadratureEncoder::update) for the
+    /// first time.
+    #[inline]
+    pub fn new(a: A, b: B) -> Self {
+        Self {
+            a,
+            b,
+            phase: None,
+            position: 0,
+            missed: 0,
+            sampled: 0,
+        }
+    }
+
+    /// Release the encoder and return the pins of both channels.
+    #[inline]
+    pub fn release(self) -> (A, B) {
+        (self.a, self.b)
+    }
+
+    /// Get the current position in steps.
+    #[inline]
+    pub fn position(&self) -> i32 {
+        self.position
+    }
+
+    /// Set the current position, e.g. after homing the motor.
+    ///
+    /// This does not influence the next [velocity estimate](QuadratureEncoder::sample_velocity).
+    #[inline]
+    pub fn set_position(&mut self, position: i32) {
+        self.sampled = self
+            .sampled
+            .wrapping_add(position.wrapping_sub(self.position));
+        self.position = position;
+    }
+
+    /// Get the number of steps, which could not be decoded, because both channels changed at
+    /// once.
+    ///
+    /// A rising number of missed steps indicates, that the encoder is not updated often enough.
+    #[inline]
+    pub fn missed(&self) -> u32 {
+        self.missed
+    }
+
+    /// Estimate the velocity in steps per second.
+    ///
+    /// The velocity is calculated from the steps counted since the last call, which happened
+    /// `elapsed_us` microseconds ago. Thus, this method needs to be called periodically, e.g. from
+    /// the control loop. If `elapsed_us` is `0`, `0` is returned.
+    pub fn sample_velocity(&mut self, elapsed_us: u32) -> i32 {
+        let steps = self.position.wrapping_sub(self.sampled);
+        self.sampled = self.position;
+        match elapsed_us {
+            0 => 0,
+            _ => {
+                let velocity = i64::from(steps) * 1_000_000 / i64::from(elapsed_us);
+                velocity.clamp(i64::from(i32::MIN), i64::from(i32::MAX)) as i32
+            }
+        }
+    }
+
+    /// Count the step between the last and the given phase of the channels.
+    fn step(&mut self, phase: u8) {
+        if let Some(last) = self.phase.replace(phase) {
+            match phase.wrapping_sub(last) % 4 {
+                1 => self.position = self.position.wrapping_add(1),
+                3 => self.position = self.position.wrapping_sub(1),
+                2 => self.missed = self.missed.saturating_add(1),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl<A, B> QuadratureEncoder<A, B>
+where
+    A: InputPin,
+    B: InputPin<Error = A::Error>,
+{
+    /// Read both channels and count the step since the last update.
+    ///
+    /// The first update only reads the initial state of the channels. Returns the current
+    /// [position](QuadratureEncoder::position).
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the first pin, which could not be read. The
+    /// position is not changed in this case.
+    pub fn update(&mut self) -> Result<i32, A::Error> {
+        let phase = match (self.a.is_high()?, self.b.is_high()?) {
+            (false, false) => 0,
+            (true, false) => 1,
+            (true, true) => 2,
+            (false, true) => 3,
+        };
+        self.step(phase);
+        Ok(self.position)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<A, B> QuadratureEncoder<A, B>
+where
+    A: InputPin + embedded_hal_async::digital::Wait,
+    B: InputPin<Error = A::Error> + embedded_hal_async::digital::Wait,
+{
+    /// Wait for an edge on one of the channels and update the encoder afterwards.
+    ///
+    /// Returns the current [position](QuadratureEncoder::position). If the encoder has not been
+    /// updated before, the initial state of the channels is read without waiting.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the first pin, which could not be awaited or read.
+    // The `async` feature requires Rust 1.75 anyway
+    #[clippy::msrv = "1.75"]
+    pub async fn wait_for_step(&mut self) -> Result<i32, A::Error> {
+        use core::future::{poll_fn, Future};
+        use core::pin::pin;
+        use core::task::Poll;
+
+        if self.phase.is_some() {
+            let mut a = pin!(self.a.wait_for_any_edge());
+            let mut b = pin!(self.b.wait_for_any_edge());
+            poll_fn(|cx| match a.as_mut().poll(cx) {
+                Poll::Ready(result) => Poll::Ready(result),
+                Poll::Pending => b.as_mut().poll(cx),
+            })
+            .await?;
+        }
+        self.update()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::DigitalPin;
+    use coverage_helper::test;
+    use embedded_hal::digital::OutputPin;
+
+    fn set(encoder: &mut QuadratureEncoder<DigitalPin, DigitalPin>, a: bool, b: bool) {
+        encoder.a.set_state(a.into()).unwrap();
+        encoder.b.set_state(b.into()).unwrap();
+    }
+
+    #[test]
+    fn test_decode() {
+        let mut encoder = QuadratureEncoder::new(DigitalPin::new(), DigitalPin::new());
+        assert_eq!(encoder.update().unwrap(), 0);
+
+        for (a, b) in [(true, false), (true, true), (false, true), (false, false)] {
+            set(&mut encoder, a, b);
+            encoder.update().unwrap();
+        }
+        assert_eq!(encoder.position(), 4);
+
+        set(&mut encoder, false, true);
+        assert_eq!(encoder.update().unwrap(), 3);
+        assert_eq!(encoder.update().unwrap(), 3);
+
+        set(&mut encoder, true, false);
+        assert_eq!(encoder.update().unwrap(), 3);
+        assert_eq!(encoder.missed(), 1);
+    }
+
+    #[test]
+    fn test_velocity() {
+        let mut encoder = QuadratureEncoder::new(DigitalPin::new(), DigitalPin::new());
+        encoder.position = 50;
+        assert_eq!(encoder.sample_velocity(10_000), 5000);
+        encoder.position = 40;
+        assert_eq!(encoder.sample_velocity(1000), -10_000);
+
+        encoder.set_position(0);
+        assert_eq!(encoder.position(), 0);
+        assert_eq!(encoder.sample_velocity(0), 0);
+
+        encoder.position = i32::MAX;
+        assert_eq!(encoder.sample_velocity(1), i32::MAX);
+    }
+
+    #[test]
+    fn test_error() {
+        let mut b = DigitalPin::new();
+        b.fail();
+        let mut encoder = QuadratureEncoder::new(DigitalPin::new(), b);
+        assert!(encoder.update().is_err());
+
+        let (_, b) = encoder.release();
+        assert!(QuadratureEncoder::new(b, DigitalPin::new())
+            .update()
+            .is_err());
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_wait_for_step() {
+        use crate::test_util::block_on;
+
+        let mut encoder = QuadratureEncoder::new(DigitalPin::new(), DigitalPin::new());
+        assert_eq!(block_on(encoder.wait_for_step()), Ok(0));
+        set(&mut encoder, false, true);
+        assert_eq!(block_on(encoder.wait_for_step()), Ok(-1));
+
+        encoder.b.fail();
+        assert!(block_on(encoder.wait_for_step()).is_err());
+    }
+}
@@ -0,0 +1,93 @@
+use core::any::Any;
+use core::fmt::{Display, Formatter};
+
+use crate::pins::OperationNotSupported;
+
+/// The kind of an error returned by this crate
+///
+/// The [embedded-hal](embedded_hal) error kinds do not allow distinguishing the errors of this
+/// crate: Both, a disabled output and a pin, which does not support an operation, are reported as
+/// [ErrorKind::Other](embedded_hal::digital::ErrorKind::Other). This enum allows generic error
+/// handling to tell an expected state of the chip apart from a real pin fault. It is returned by
+/// the `error_kind()` methods of the errors, e.g.
+/// [`OutputStateError::error_kind()`](crate::OutputStateError::error_kind).
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::digital::StatefulOutputPin;
+/// use l293x::ErrorKind;
+///
+/// match y1.is_set_high() {
+///     Ok(high) => println!("y1 is high: {high}"),
+///     Err(error) if error.error_kind() == ErrorKind::NotEnabled => println!("y1 is disabled"),
+///     Err(error) => return Err(error),
+/// }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ErrorKind {
+    /// An input pin reported an error.
+    InputPin,
+    /// An enable pin reported an error.
+    EnablePin,
+    /// The state of an output was queried, while the output is disabled.
+    NotEnabled,
+    /// The operation is not supported by the pin, e.g. switching a [Vcc](crate::pins::Vcc) pin.
+    NotSupported,
+}
+
+impl ErrorKind {
+    /// Check whether the error was reported by a pin, i.e. it is a real fault and not caused by
+    /// the state of the chip.
+    #[inline]
+    pub fn is_pin_fault(self) -> bool {
+        matches!(self, ErrorKind::InputPin | ErrorKind::EnablePin)
+    }
+}
+
+/// Classification of the errors reported by the pins of the chip
+///
+/// An error of a pin is a pin fault of the given kind, unless the pin does not support the
+/// operation at all: The [Vcc](crate::pins::Vcc) and [Gnd](crate::pins::Gnd) pins report an
+/// [OperationNotSupported] error, which is classified as
+/// [NotSupported](ErrorKind::NotSupported) instead.
+pub(crate) trait PinErrorKind {
+    /// Get the kind of the error, if it is reported by a pin with the given `role`.
+    fn pin_error_kind(&self, role: ErrorKind) -> ErrorKind;
+}
+
+impl<T: Any> PinErrorKind for T {
+    #[inline]
+    fn pin_error_kind(&self, role: ErrorKind) -> ErrorKind {
+        match (self as &dyn Any).downcast_ref::<OperationNotSupported>() {
+            Some(error) => error.error_kind(),
+            None => role,
+        }
+    }
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ErrorKind::InputPin => write!(f, "input pin error"),
+            ErrorKind::EnablePin => write!(f, "enable pin error"),
+            ErrorKind::NotEnabled => write!(f, "output not enabled"),
+            ErrorKind::NotSupported => write!(f, "operation not supported"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coverage_helper::test;
+
+    #[test]
+    fn test_is_pin_fault() {
+        assert!(ErrorKind::InputPin.is_pin_fault());
+        assert!(ErrorKind::EnablePin.is_pin_fault());
+        assert!(!ErrorKind::NotEnabled.is_pin_fault());
+        assert!(!ErrorKind::NotSupported.is_pin_fault());
+    }
+}
@@ -0,0 +1,56 @@
+//! Conversions between floating point values and duty cycles.
+//!
+//! This module is only available, if the `float` feature is enabled. It is the only place of the
+//! crate, where floating point arithmetic is allowed.
+#![allow(clippy::float_arithmetic)]
+
+/// Convert the given signed fraction into a speed.
+///
+/// The fraction is clamped into the range of `-1.0` to `1.0` (inclusive) and then scaled to the
+/// range of `-i16::MAX` to `i16::MAX`. A `NaN` fraction results in a speed of `0`.
+pub(crate) fn speed_from_f32(fraction: f32) -> i16 {
+    let speed = fraction.clamp(-1.0, 1.0) * i16::MAX as f32;
+    match speed < 0.0 {
+        true => (speed - 0.5) as i16,
+        false => (speed + 0.5) as i16,
+    }
+}
+
+/// Convert the given fraction into a duty cycle.
+///
+/// The fraction is clamped into the range of `0.0` to `1.0` (inclusive) and then scaled to the
+/// range of `0` to `max_duty`. A `NaN` fraction results in a duty cycle of `0`.
+pub(crate) fn duty_cycle_from_f32(fraction: f32, max_duty: u16) -> u16 {
+    let fraction = fraction.clamp(0.0, 1.0);
+    (fraction * max_duty as f32 + 0.5) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coverage_helper::test;
+
+    #[test]
+    fn test_duty_cycle_from_f32() {
+        assert_eq!(duty_cycle_from_f32(0.0, 100), 0);
+        assert_eq!(duty_cycle_from_f32(0.5, 100), 50);
+        assert_eq!(duty_cycle_from_f32(1.0, u16::MAX), u16::MAX);
+        assert_eq!(duty_cycle_from_f32(0.333, 3), 1);
+    }
+
+    #[test]
+    fn test_speed_from_f32() {
+        assert_eq!(speed_from_f32(0.5), 16384);
+        assert_eq!(speed_from_f32(-0.5), -16384);
+        assert_eq!(speed_from_f32(-2.0), -i16::MAX);
+        assert_eq!(speed_from_f32(f32::NAN), 0);
+    }
+
+    #[test]
+    fn test_duty_cycle_from_f32_clamped() {
+        assert_eq!(duty_cycle_from_f32(-1.0, 100), 0);
+        assert_eq!(duty_cycle_from_f32(2.0, 100), 100);
+        assert_eq!(duty_cycle_from_f32(f32::NAN, 100), 0);
+        assert_eq!(duty_cycle_from_f32(f32::INFINITY, 100), 100);
+    }
+}
@@ -0,0 +1,17 @@
+use core::cell::RefCell;
+
+use defmt::{Format, Formatter};
+
+/// Format the value of a [RefCell] using defmt.
+///
+/// If the value is mutably borrowed, `<borrowed>` is written instead.
+pub(crate) struct CellFormat<'a, T>(pub(crate) &'a RefCell<T>);
+
+impl<T: Format> Format for CellFormat<'_, T> {
+    fn format(&self, f: Formatter) {
+        match self.0.try_borrow() {
+            Ok(value) => defmt::write!(f, "{}", *value),
+            Err(_) => defmt::write!(f, "<borrowed>"),
+        }
+    }
+}
@@ -0,0 +1,755 @@
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::OutputPin;
+use embedded_hal::pwm::SetDutyCycle;
+
+use crate::control::FailsafeAction;
+use crate::driver::{FullHBridgeDriver, HalfHBridgeDriver};
+use crate::shared_pin::SharedPin;
+use crate::{Direction, HalfH, OwnedHalfH};
+
+/// The maximum magnitude of the speed of a [FullH].
+const MAX_SPEED: i16 = i16::MAX;
+
+/// Convert the magnitude of a `speed` into a duty cycle between `0` and `max_duty`.
+pub(crate) fn speed_to_duty(speed: i16, max_duty: u16) -> u16 {
+    let magnitude = u32::from(speed.max(-MAX_SPEED).unsigned_abs());
+    (magnitude * u32::from(max_duty) / MAX_SPEED as u32) as u16
+}
+
+/// Convert a duty cycle between `0` and `max_duty` into the magnitude of a speed.
+pub(crate) fn duty_to_speed(duty: u16, max_duty: u16) -> i16 {
+    let duty = u32::from(duty.min(max_duty));
+    (duty * MAX_SPEED as u32 / u32::from(max_duty.max(1))) as i16
+}
+
+/// Full-H bridge driven by two channels sharing a common enable pin
+///
+/// This is the most common configuration of a [FullH] bridge: Both terminals of the load are
+/// connected to the outputs of the same chip, which share a common enable pin (i.e. `y1` and `y2`
+/// or `y3` and `y4`). It is returned by the [`full_h12()`](crate::L293x::full_h12) and
+/// [`full_h34()`](crate::L293x::full_h34) methods of the chip and the
+/// [`full_h()`](crate::BridgePair::full_h) method of a bridge pair.
+pub type FullHBridge<'a, A, B, EN> = FullH<HalfH<'a, A, EN>, HalfH<'a, B, EN>>;
+
+/// Full-H bridge owning two channels sharing a common enable pin
+///
+/// This is the owned counterpart of the [FullHBridge]. It is returned by the
+/// [`into_full_bridges()`](crate::L293x::into_full_bridges) method of the chip. Because the
+/// bridge owns its input pins and shares the enable pin using a [SharedPin], it does not borrow
+/// the chip and can be moved freely.
+pub type OwnedFullHBridge<'a, A, B, EN> =
+    FullH<OwnedHalfH<A, SharedPin<'a, EN>>, OwnedHalfH<B, SharedPin<'a, EN>>>;
+
+/// Full-H bridge composed of two Half-H bridges
+///
+/// A full-H bridge allows driving a load (e.g. a DC motor) in both directions. It is built from
+/// two Half-H bridges, each driving one terminal of the load. The bridges do not need to be part
+/// of the same chip. Any two types implementing the [OutputPin] trait (e.g. two [HalfH] bridges)
+/// can be combined. If both half-bridges implement the [SetDutyCycle] trait, the speed of the load
+/// can be controlled using the [`set_speed()`](FullH::set_speed) method.
+///
+/// The states of the load depend on the states of the two half-bridges:
+///
+/// | Bridge A | Bridge B | State                              |
+/// |----------|----------|------------------------------------|
+/// | High     | Low      | [`forward`](FullH::forward)        |
+/// | Low      | High     | [`reverse`](FullH::reverse)        |
+/// | Low      | Low      | [`brake`](FullH::brake)            |
+/// | Z        | Z        | [`coast`](FullH::coast)            |
+///
+/// ## Braking and coasting
+///
+/// There are two ways of stopping a DC motor, which result in different pin states of the chip:
+///
+/// | Operation                  | Inputs   | Enable pin | Motor terminals | Stop      |
+/// |----------------------------|----------|------------|-----------------|-----------|
+/// | [`brake`](FullH::brake)    | Low, Low | High       | Shorted to Gnd  | Fast      |
+/// | [`coast`](FullH::coast)    | Any      | Low        | Disconnected    | Slow      |
+///
+/// While braking, the back-EMF of the spinning motor drives a current through the shorted
+/// terminals, which stops it quickly and holds it in place. Coasting disconnects the motor, which
+/// lets it spin down freely. Coasting is only available for bridges with an enable pin, i.e.
+/// [HalfH] and [OwnedHalfH] bridges.
+///
+/// # Examples
+///
+/// ```
+/// use l293x::{AutoEnable, FullH, L293x};
+///
+/// let l293x = L293x::new(input1, input2, (), (), enable12, ());
+/// let y1 = l293x.y1().with_auto_enable(AutoEnable::Always);
+/// let y2 = l293x.y2().with_auto_enable(AutoEnable::Always);
+/// let mut motor = FullH::new(y1, y2);
+///
+/// motor.forward()?;
+/// motor.brake()?;
+/// motor.reverse()?;
+/// motor.coast()?;
+/// ```
+#[derive(Debug)]
+pub struct FullH<A, B> {
+    a: A,
+    b: B,
+    speed: i16,
+    dead_time_us: u32,
+}
+
+impl<A, B> FullH<A, B> {
+    /// Create a new Full-H bridge from the given half-bridges.
+    ///
+    /// The half-bridge `a` drives the first terminal of the load and the half-bridge `b` the
+    /// second one. [HalfH] and [OwnedHalfH] bridges only drive the load while they are enabled.
+    /// Thus, either enable them explicitly or set their
+    /// [auto-enable policy](HalfH::with_auto_enable) to [Always](crate::AutoEnable::Always), like
+    /// the Full-H bridges returned by the chip (e.g. [`full_h12()`](crate::L293x::full_h12)) do.
+    #[inline]
+    pub fn new(a: A, b: B) -> Self {
+        Self {
+            a,
+            b,
+            speed: 0,
+            dead_time_us: 0,
+        }
+    }
+
+    /// Release the Full-H bridge and return the two half-bridges.
+    #[inline]
+    pub fn release(self) -> (A, B) {
+        (self.a, self.b)
+    }
+
+    /// Get the last commanded speed of the load.
+    ///
+    /// Driving the load [forward](FullH::forward) or in [reverse](FullH::reverse) using the
+    /// digital methods counts as full speed in the corresponding direction. Braking and coasting
+    /// counts as a speed of `0`.
+    #[inline]
+    pub fn speed(&self) -> i16 {
+        self.speed
+    }
+
+    /// Set the dead time inserted when the direction of the load is reversed.
+    ///
+    /// The dead time is used by the [`drive_with_dead_time()`](FullH::drive_with_dead_time) and
+    /// [`set_speed_with_dead_time()`](FullH::set_speed_with_dead_time) methods. When they reverse
+    /// the direction of the load, both half-bridges are driven low for `dead_time_us`
+    /// microseconds, before the new direction is applied. This avoids current spikes caused by
+    /// switching a spinning motor directly into the opposite direction. By default, no dead time
+    /// is inserted.
+    #[inline]
+    pub fn set_dead_time_us(&mut self, dead_time_us: u32) {
+        self.dead_time_us = dead_time_us;
+    }
+
+    /// Get the dead time inserted when the direction of the load is reversed.
+    #[inline]
+    pub fn dead_time_us(&self) -> u32 {
+        self.dead_time_us
+    }
+
+    /// Check whether changing the speed to `speed` reverses the direction of the load.
+    #[inline]
+    fn reverses(&self, speed: i16) -> bool {
+        (self.speed > 0 && speed < 0) || (self.speed < 0 && speed > 0)
+    }
+
+    /// Get mutable references to both half-bridges.
+    #[inline]
+    pub(crate) fn bridges_mut(&mut self) -> (&mut A, &mut B) {
+        (&mut self.a, &mut self.b)
+    }
+
+    /// Record the `speed`, which was set by a generic driver implementation.
+    #[inline]
+    pub(crate) fn record_speed(&mut self, speed: i16) {
+        self.speed = speed;
+    }
+}
+
+impl<A, B> FullH<A, B>
+where
+    A: OutputPin,
+    B: OutputPin<Error = A::Error>,
+{
+    /// Drive the load in forward direction.
+    ///
+    /// This sets the half-bridge `a` high and the half-bridge `b` low. The half-bridge going low
+    /// is switched first, so that the load is never driven in the opposite direction.
+    ///
+    /// # Errors
+    ///
+    /// The error of the first half-bridge, which could not be set, will be returned.
+    pub fn forward(&mut self) -> Result<(), A::Error> {
+        self.b.set_low()?;
+        self.a.set_high()?;
+        self.speed = MAX_SPEED;
+        Ok(())
+    }
+
+    /// Drive the load in reverse direction.
+    ///
+    /// This sets the half-bridge `a` low and the half-bridge `b` high. The half-bridge going low
+    /// is switched first, so that the load is never driven in the opposite direction.
+    ///
+    /// # Errors
+    ///
+    /// The error of the first half-bridge, which could not be set, will be returned.
+    pub fn reverse(&mut self) -> Result<(), A::Error> {
+        self.a.set_low()?;
+        self.b.set_high()?;
+        self.speed = -MAX_SPEED;
+        Ok(())
+    }
+
+    /// Drive the load in the given direction.
+    ///
+    /// # Errors
+    ///
+    /// The error of the first half-bridge, which could not be set, will be returned.
+    pub fn drive(&mut self, direction: Direction) -> Result<(), A::Error> {
+        match direction {
+            Direction::Forward => self.forward(),
+            Direction::Reverse => self.reverse(),
+        }
+    }
+
+    /// Brake the load.
+    ///
+    /// This sets both half-bridges low, which shorts the terminals of the load. For a DC motor,
+    /// this results in a fast stop of the motor. [HalfH] and [OwnedHalfH] bridges with the
+    /// [Always](crate::AutoEnable::Always) auto-enable policy are enabled, so that their outputs
+    /// are actively driven low.
+    ///
+    /// # Errors
+    ///
+    /// The error of the first half-bridge, which could not be set, will be returned.
+    pub fn brake(&mut self) -> Result<(), A::Error> {
+        self.a.set_low()?;
+        self.b.set_low()?;
+        self.speed = 0;
+        Ok(())
+    }
+
+    /// Drive the load in the given direction, inserting the dead time when reversing.
+    ///
+    /// If the load is currently driven in the opposite direction, both half-bridges are driven
+    /// low for the configured [dead time](FullH::set_dead_time_us) first.
+    ///
+    /// # Errors
+    ///
+    /// The error of the first half-bridge, which could not be set, will be returned.
+    pub fn drive_with_dead_time<D: DelayNs>(
+        &mut self,
+        direction: Direction,
+        delay: &mut D,
+    ) -> Result<(), A::Error> {
+        let speed = match direction {
+            Direction::Forward => MAX_SPEED,
+            Direction::Reverse => -MAX_SPEED,
+        };
+        if self.dead_time_us > 0 && self.reverses(speed) {
+            self.brake()?;
+            delay.delay_us(self.dead_time_us);
+        }
+        self.drive(direction)
+    }
+}
+
+impl<A, B> FullH<A, B>
+where
+    A: SetDutyCycle,
+    B: SetDutyCycle<Error = A::Error>,
+{
+    /// Set the speed of the load
+    ///
+    /// The sign of the `speed` selects the direction: positive values drive the load forward,
+    /// negative values in reverse. The magnitude defines the duty cycle of the driving
+    /// half-bridge, where [`i16::MAX`] means fully on. The other half-bridge is set fully off. A
+    /// `speed` of `0` brakes the load.
+    ///
+    /// # Errors
+    ///
+    /// The error of the first half-bridge, which could not be set, will be returned.
+    pub fn set_speed(&mut self, speed: i16) -> Result<(), A::Error> {
+        let speed = speed.max(-MAX_SPEED);
+        if speed < 0 {
+            let duty = speed_to_duty(speed, self.b.max_duty_cycle());
+            self.a.set_duty_cycle_fully_off()?;
+            self.b.set_duty_cycle(duty)?;
+        } else {
+            let duty = speed_to_duty(speed, self.a.max_duty_cycle());
+            self.b.set_duty_cycle_fully_off()?;
+            self.a.set_duty_cycle(duty)?;
+        }
+        self.speed = speed;
+        Ok(())
+    }
+
+    /// Set the speed of the load, inserting the dead time when reversing.
+    ///
+    /// If the sign of the `speed` is opposite to the one of the current [speed](FullH::speed),
+    /// both half-bridges are set fully off for the configured [dead time](FullH::set_dead_time_us)
+    /// first.
+    ///
+    /// # Errors
+    ///
+    /// The error of the first half-bridge, which could not be set, will be returned.
+    pub fn set_speed_with_dead_time<D: DelayNs>(
+        &mut self,
+        speed: i16,
+        delay: &mut D,
+    ) -> Result<(), A::Error> {
+        if self.dead_time_us > 0 && self.reverses(speed) {
+            self.set_speed(0)?;
+            delay.delay_us(self.dead_time_us);
+        }
+        self.set_speed(speed)
+    }
+
+    /// Ramp the speed of the load linearly to the `target` speed.
+    ///
+    /// The ramp starts at the last commanded [speed](FullH::speed). Every millisecond, the speed
+    /// is changed by `step` towards the `target`, until it is reached. This avoids high inrush
+    /// currents on large speed changes. If `step` is zero, the target speed is set immediately.
+    ///
+    /// # Errors
+    ///
+    /// The error of the first half-bridge, which could not be set, will be returned. The ramp is
+    /// aborted in this case.
+    pub fn ramp_to_duty<D: DelayNs>(
+        &mut self,
+        target: i16,
+        step: u16,
+        delay: &mut D,
+    ) -> Result<(), A::Error> {
+        crate::ramp::ramp_by(
+            self.speed.into(),
+            target.into(),
+            step.into(),
+            delay,
+            |speed| self.set_speed(speed as i16),
+        )
+    }
+
+    /// Set the speed of the load by a floating point value
+    ///
+    /// The sign of the `speed` selects the direction: positive values drive the load forward,
+    /// negative values in reverse. The magnitude is clamped to `1.0` and defines the duty cycle of
+    /// the driving half-bridge, while the other half-bridge is set fully off. A `speed` of `0.0`
+    /// brakes the load.
+    ///
+    /// This method is only available, if the `float` feature is enabled.
+    ///
+    /// # Errors
+    ///
+    /// The error of the first half-bridge, which could not be set, will be returned.
+    #[cfg(feature = "float")]
+    pub fn set_speed_f32(&mut self, speed: f32) -> Result<(), A::Error> {
+        self.set_speed(crate::float::speed_from_f32(speed))
+    }
+}
+
+impl<'a, IA, EA, IB, EB> FullH<HalfH<'a, IA, EA>, HalfH<'a, IB, EB>>
+where
+    EA: OutputPin,
+    EB: OutputPin<Error = EA::Error>,
+{
+    /// Let the load coast.
+    ///
+    /// This disables both half-bridges, which disconnects the load. For a DC motor, this results
+    /// in a slow stop of the motor.
+    ///
+    /// <div class="warning">
+    /// If the half-bridges share their enable pins with other bridges, these will be disabled as
+    /// well!
+    /// </div>
+    ///
+    /// # Errors
+    ///
+    /// If one of the enable pins could not be set low, its error will be returned.
+    pub fn coast(&mut self) -> Result<(), EA::Error> {
+        self.a.disable()?;
+        self.b.disable()?;
+        self.speed = 0;
+        Ok(())
+    }
+}
+
+impl<IA, EA, IB, EB> FullH<OwnedHalfH<IA, EA>, OwnedHalfH<IB, EB>>
+where
+    EA: OutputPin,
+    EB: OutputPin<Error = EA::Error>,
+{
+    /// Let the load coast.
+    ///
+    /// This disables both half-bridges, which disconnects the load. For a DC motor, this results
+    /// in a slow stop of the motor.
+    ///
+    /// <div class="warning">
+    /// If the half-bridges share their enable pins with other bridges, these will be disabled as
+    /// well!
+    /// </div>
+    ///
+    /// # Errors
+    ///
+    /// If one of the enable pins could not be set low, its error will be returned.
+    pub fn coast(&mut self) -> Result<(), EA::Error> {
+        self.a.disable()?;
+        self.b.disable()?;
+        self.speed = 0;
+        Ok(())
+    }
+}
+
+impl<A, B> FullH<A, B>
+where
+    A: HalfHBridgeDriver,
+    B: HalfHBridgeDriver<Error = A::Error>,
+{
+    /// Stop the load using the given failsafe action
+    ///
+    /// This is the common stop path used when the load has to be stopped for safety reasons. What
+    /// happens depends on the configured [FailsafeAction]:
+    ///
+    /// * [`Coast`](FailsafeAction::Coast) disables both half-bridges.
+    /// * [`Brake`](FailsafeAction::Brake) sets both half-bridges fully off and enables them.
+    /// * [`RampDown`](FailsafeAction::RampDown) reduces the [speed](FullH::speed) linearly to `0`
+    ///   within the given number of milliseconds, using the `delay` for timing. The load is
+    ///   braked at the end of the ramp.
+    ///
+    /// The half-bridges are enabled explicitly for braking, independent of their
+    /// [auto-enable policy](crate::AutoEnable).
+    ///
+    /// # Errors
+    ///
+    /// The error of the first pin, which could not be set, will be returned. The remaining steps
+    /// of the action will not be executed in this case.
+    pub fn failsafe<D: DelayNs>(
+        &mut self,
+        action: FailsafeAction,
+        delay: &mut D,
+    ) -> Result<(), A::Error> {
+        if let FailsafeAction::RampDown(duration) = action {
+            let start = self.speed.into();
+            crate::ramp::ramp(start, 0, duration, delay, |speed| {
+                FullHBridgeDriver::set_speed(self, speed as i16)
+            })?;
+        }
+        match action {
+            FailsafeAction::Coast => FullHBridgeDriver::disable(self),
+            FailsafeAction::Brake | FailsafeAction::RampDown(_) => {
+                FullHBridgeDriver::set_speed(self, 0)?;
+                FullHBridgeDriver::enable(self)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared_pin::PinCell;
+    use crate::test_util::{Delay, DigitalPin, PwmPin};
+    use crate::{AutoEnable, L293x, OutputStateError};
+    use core::cell::RefCell;
+    use coverage_helper::test;
+    use embedded_hal::digital::StatefulOutputPin;
+
+    type MockL293x = L293x<DigitalPin, DigitalPin, DigitalPin, DigitalPin, DigitalPin, DigitalPin>;
+
+    fn l293x() -> MockL293x {
+        L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        )
+    }
+
+    #[test]
+    fn test_directions() {
+        let l293x = l293x();
+        let mut full_h = l293x.full_h12();
+
+        full_h.forward().unwrap();
+        assert!(l293x.y1().is_set_high().unwrap());
+        assert!(l293x.y2().is_set_low().unwrap());
+
+        full_h.reverse().unwrap();
+        assert!(l293x.y1().is_set_low().unwrap());
+        assert!(l293x.y2().is_set_high().unwrap());
+
+        full_h.drive(Direction::Forward).unwrap();
+        assert!(l293x.y1().is_set_high().unwrap());
+        full_h.drive(Direction::Reverse).unwrap();
+        assert!(l293x.y2().is_set_high().unwrap());
+
+        full_h.brake().unwrap();
+        assert!(l293x.y1().is_set_low().unwrap());
+        assert!(l293x.y2().is_set_low().unwrap());
+    }
+
+    #[test]
+    fn test_dead_time() {
+        let l293x = l293x();
+        let mut full_h = l293x.full_h12();
+        let mut delay = Delay::new();
+
+        full_h.set_dead_time_us(500);
+        assert_eq!(full_h.dead_time_us(), 500);
+        full_h
+            .drive_with_dead_time(Direction::Forward, &mut delay)
+            .unwrap();
+        full_h
+            .drive_with_dead_time(Direction::Forward, &mut delay)
+            .unwrap();
+        assert_eq!(delay.elapsed_ns(), 0);
+
+        full_h
+            .drive_with_dead_time(Direction::Reverse, &mut delay)
+            .unwrap();
+        assert_eq!(delay.elapsed_ns(), 500_000);
+        assert!(l293x.y2().is_set_high().unwrap());
+    }
+
+    #[test]
+    fn test_speed_dead_time() {
+        let l293x = L293x::new(PwmPin::new(), PwmPin::new(), (), (), DigitalPin::new(), ());
+        let mut full_h = l293x.full_h12();
+        let mut delay = Delay::new();
+
+        full_h.set_speed_with_dead_time(100, &mut delay).unwrap();
+        full_h.set_speed_with_dead_time(-100, &mut delay).unwrap();
+        assert_eq!(delay.elapsed_ns(), 0);
+
+        full_h.set_dead_time_us(10);
+        full_h.set_speed_with_dead_time(-200, &mut delay).unwrap();
+        full_h.set_speed_with_dead_time(200, &mut delay).unwrap();
+        assert_eq!(delay.elapsed_ns(), 10_000);
+        assert_eq!(full_h.speed(), 200);
+    }
+
+    #[test]
+    fn test_coast() {
+        let l293x = l293x();
+        let mut full_h = FullH::new(
+            l293x.y1().with_auto_enable(AutoEnable::Always),
+            l293x.y3().with_auto_enable(AutoEnable::Always),
+        );
+
+        full_h.forward().unwrap();
+        full_h.coast().unwrap();
+        assert!(l293x.y1().is_disabled().unwrap());
+        assert!(l293x.y3().is_disabled().unwrap());
+    }
+
+    #[test]
+    fn test_different_chips() {
+        let chip1 = l293x();
+        let chip2 = l293x();
+        let mut full_h = FullH::new(
+            chip1.y4().with_auto_enable(AutoEnable::Always),
+            chip2.y1().with_auto_enable(AutoEnable::Always),
+        );
+
+        full_h.reverse().unwrap();
+        assert!(chip1.y4().is_set_low().unwrap());
+        assert!(chip2.y1().is_set_high().unwrap());
+
+        let (mut a, _) = full_h.release();
+        a.disable().unwrap();
+        assert!(chip1.y3().is_disabled().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "float")]
+    fn test_set_speed_f32() {
+        let a = RefCell::new(PwmPin::new());
+        let b = RefCell::new(PwmPin::new());
+        let enable = RefCell::new(DigitalPin::new());
+        let mut full_h = FullH::new(
+            HalfH::new(&a, &enable).with_auto_enable(AutoEnable::Always),
+            HalfH::new(&b, &enable).with_auto_enable(AutoEnable::Always),
+        );
+
+        full_h.set_speed_f32(0.5).unwrap();
+        assert_eq!(a.borrow().get_duty_cycle(), u16::MAX / 2 + 1);
+        assert_eq!(b.borrow().get_duty_cycle(), 0);
+
+        full_h.set_speed_f32(-2.0).unwrap();
+        assert_eq!(a.borrow().get_duty_cycle(), 0);
+        assert_eq!(b.borrow().get_duty_cycle(), u16::MAX);
+
+        full_h.set_speed_f32(0.0).unwrap();
+        assert_eq!(a.borrow().get_duty_cycle(), 0);
+        assert_eq!(b.borrow().get_duty_cycle(), 0);
+    }
+
+    #[test]
+    fn test_coast_owned() {
+        let (mut en12, mut en34) = (PinCell::new(), PinCell::new());
+        let (y1, y2, _, _) = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            (),
+            (),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        )
+        .into_channels(&mut en12, &mut en34);
+        let mut full_h = FullH::new(y1, y2);
+
+        full_h.brake().unwrap();
+        full_h.coast().unwrap();
+        let (mut y1, mut y2) = full_h.release();
+        assert!(y1.is_disabled().unwrap());
+        assert!(y2.is_disabled().unwrap());
+    }
+
+    #[test]
+    fn test_set_speed() {
+        let a = RefCell::new(PwmPin::new());
+        let b = RefCell::new(PwmPin::new());
+        let enable = RefCell::new(DigitalPin::new());
+        let mut full_h = FullH::new(
+            HalfH::new(&a, &enable).with_auto_enable(AutoEnable::Always),
+            HalfH::new(&b, &enable).with_auto_enable(AutoEnable::Always),
+        );
+
+        full_h.set_speed(i16::MAX).unwrap();
+        assert_eq!(a.borrow().get_duty_cycle(), u16::MAX);
+        assert_eq!(b.borrow().get_duty_cycle(), 0);
+        assert_eq!(full_h.speed(), i16::MAX);
+
+        full_h.set_speed(i16::MIN).unwrap();
+        assert_eq!(a.borrow().get_duty_cycle(), 0);
+        assert_eq!(b.borrow().get_duty_cycle(), u16::MAX);
+        assert_eq!(full_h.speed(), -i16::MAX);
+
+        full_h.set_speed(0).unwrap();
+        assert_eq!(a.borrow().get_duty_cycle(), 0);
+        assert_eq!(b.borrow().get_duty_cycle(), 0);
+    }
+
+    #[test]
+    fn test_set_speed_8bit() {
+        let mut full_h = FullH::new(
+            PwmPin::with_max_duty_cycle(255),
+            PwmPin::with_max_duty_cycle(255),
+        );
+
+        full_h.set_speed(i16::MAX).unwrap();
+        assert_eq!(full_h.bridges_mut().0.get_duty_cycle(), 255);
+        full_h.set_speed(-i16::MAX / 2).unwrap();
+        let (a, b) = full_h.release();
+        assert_eq!((a.get_duty_cycle(), b.get_duty_cycle()), (0, 127));
+    }
+
+    #[test]
+    fn test_ramp_to_duty() {
+        let mut full_h = FullH::new(PwmPin::new(), PwmPin::new());
+        let mut delay = Delay::new();
+
+        full_h.ramp_to_duty(-1000, 400, &mut delay).unwrap();
+        assert_eq!(full_h.speed(), -1000);
+        assert_eq!(delay.elapsed_ns(), 3_000_000);
+    }
+
+    #[test]
+    fn test_failsafe() {
+        let a = RefCell::new(PwmPin::new());
+        let b = RefCell::new(PwmPin::new());
+        let enable = RefCell::new(DigitalPin::new());
+        let mut full_h = FullH::new(
+            HalfH::new(&a, &enable).with_auto_enable(AutoEnable::Always),
+            HalfH::new(&b, &enable).with_auto_enable(AutoEnable::Always),
+        );
+        let mut delay = Delay::new();
+
+        full_h.set_speed(1000).unwrap();
+        full_h.failsafe(FailsafeAction::Brake, &mut delay).unwrap();
+        assert_eq!(a.borrow().get_duty_cycle(), 0);
+        assert!(enable.borrow_mut().is_set_high().unwrap());
+
+        full_h.set_speed(1000).unwrap();
+        full_h.failsafe(FailsafeAction::Coast, &mut delay).unwrap();
+        assert!(enable.borrow_mut().is_set_low().unwrap());
+        assert_eq!(full_h.speed(), 0);
+        assert_eq!(delay.elapsed_ns(), 0);
+
+        full_h.set_speed(-1000).unwrap();
+        full_h
+            .failsafe(FailsafeAction::RampDown(10), &mut delay)
+            .unwrap();
+        assert_eq!(b.borrow().get_duty_cycle(), 0);
+        assert_eq!(full_h.speed(), 0);
+        assert_eq!(delay.elapsed_ns(), 10_000_000);
+    }
+
+    #[test]
+    fn test_failsafe_owned() {
+        let (mut en12, mut en34) = (PinCell::new(), PinCell::new());
+        let (mut left, _) = L293x::new(
+            PwmPin::new(),
+            PwmPin::new(),
+            PwmPin::new(),
+            PwmPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        )
+        .into_full_bridges(&mut en12, &mut en34);
+        let mut delay = Delay::new();
+
+        left.set_speed(1000).unwrap();
+        left.coast().unwrap();
+        left.failsafe(FailsafeAction::Brake, &mut delay).unwrap();
+        let (mut y1, y2) = left.release();
+        assert!(y1.is_enabled().unwrap());
+        let ((a1, _), (a2, _)) = (y1.release(), y2.release());
+        assert_eq!(a1.get_duty_cycle(), 0);
+        assert_eq!(a2.get_duty_cycle(), 0);
+    }
+
+    #[test]
+    fn test_failsafe_fail() {
+        let mut pin = PwmPin::new();
+        pin.fail();
+        let a = RefCell::new(pin);
+        let b = RefCell::new(PwmPin::new());
+        let enable = RefCell::new(DigitalPin::new());
+        let mut full_h = FullH::new(
+            HalfH::new(&a, &enable).with_auto_enable(AutoEnable::Always),
+            HalfH::new(&b, &enable).with_auto_enable(AutoEnable::Always),
+        );
+        let mut delay = Delay::new();
+
+        assert!(matches!(
+            full_h.failsafe(FailsafeAction::RampDown(10), &mut delay),
+            Err(OutputStateError::InputPinError(..))
+        ));
+        assert_eq!(delay.elapsed_ns(), 1_000_000);
+    }
+
+    #[test]
+    fn test_coast_fail() {
+        let mut en12 = DigitalPin::new();
+        en12.fail();
+        let l293x = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            (),
+            (),
+            en12,
+            DigitalPin::new(),
+        );
+        let mut full_h = l293x.full_h12();
+
+        assert!(matches!(
+            full_h.forward(),
+            Err(OutputStateError::EnablePinError(..))
+        ));
+        assert!(full_h.coast().is_err());
+    }
+}
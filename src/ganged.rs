@@ -0,0 +1,240 @@
+use embedded_hal::digital::{OutputPin, PinState, StatefulOutputPin};
+use embedded_hal::pwm::SetDutyCycle;
+
+use crate::pins::Polarized;
+use crate::OutputStateError;
+
+/// Two Half-H bridges driven in parallel
+///
+/// The L293 datasheet allows connecting the outputs of two Half-H bridges sharing a common enable
+/// pin in parallel, to double the output current. In this case, the inputs of both bridges must
+/// never diverge, because the outputs would drive against each other otherwise.
+///
+/// This type is returned by the [`gang_y1_and_y2()`](crate::L293x::gang_y1_and_y2) and
+/// [`gang_y3_and_y4()`](crate::L293x::gang_y3_and_y4) methods of the chip. It exclusively borrows
+/// both inputs and the common enable pin and drives them through a single [OutputPin] or
+/// [SetDutyCycle] facade. To guarantee, that the outputs never drive against each other, every
+/// change is applied while the enable pin is low:
+///
+/// 1. The enable pin is set low.
+/// 2. Both inputs are set to the new state or duty cycle.
+/// 3. The enable pin is set high again.
+///
+/// If one of the inputs could not be set, the enable pin stays low. Please note, that every
+/// change puts the ganged output into the high impedance mode for a short time.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::digital::OutputPin;
+/// use l293x::L293x;
+///
+/// let mut l293x = L293x::new(input1, input2, (), (), enable12, ());
+/// let mut output = l293x.gang_y1_and_y2();
+///
+/// output.set_high()?;
+/// ```
+#[derive(Debug)]
+pub struct GangedOutput<'a, A, B, EN> {
+    a: Polarized<&'a mut A>,
+    b: Polarized<&'a mut B>,
+    enable: Polarized<&'a mut EN>,
+}
+
+impl<'a, A, B, EN> GangedOutput<'a, A, B, EN> {
+    pub(crate) fn new(
+        a: Polarized<&'a mut A>,
+        b: Polarized<&'a mut B>,
+        enable: Polarized<&'a mut EN>,
+    ) -> Self {
+        Self { a, b, enable }
+    }
+}
+
+impl<'a, A, B, EN> GangedOutput<'a, A, B, EN>
+where
+    EN: OutputPin,
+{
+    /// Disable the ganged output.
+    ///
+    /// This sets the common enable pin low, which sets the output into the high impedance mode.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the common enable pin.
+    #[inline]
+    pub fn disable(&mut self) -> Result<(), EN::Error> {
+        self.enable.set_low()
+    }
+
+    /// Apply a change to both inputs, while the outputs are disabled.
+    fn update<E>(
+        &mut self,
+        update_a: impl FnOnce(&mut Polarized<&'a mut A>) -> Result<(), E>,
+        update_b: impl FnOnce(&mut Polarized<&'a mut B>) -> Result<(), E>,
+    ) -> Result<(), OutputStateError<E, EN::Error>> {
+        self.enable
+            .set_low()
+            .map_err(OutputStateError::EnablePinError)?;
+        update_a(&mut self.a).map_err(OutputStateError::InputPinError)?;
+        update_b(&mut self.b).map_err(OutputStateError::InputPinError)?;
+        self.enable
+            .set_high()
+            .map_err(OutputStateError::EnablePinError)
+    }
+}
+
+impl<'a, A, B, EN> embedded_hal::digital::ErrorType for GangedOutput<'a, A, B, EN>
+where
+    A: OutputPin,
+    B: OutputPin<Error = A::Error>,
+    EN: OutputPin,
+{
+    type Error = OutputStateError<A::Error, EN::Error>;
+}
+
+impl<'a, A, B, EN> OutputPin for GangedOutput<'a, A, B, EN>
+where
+    A: OutputPin,
+    B: OutputPin<Error = A::Error>,
+    EN: OutputPin,
+{
+    #[inline]
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.set_state(PinState::Low)
+    }
+
+    #[inline]
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.set_state(PinState::High)
+    }
+
+    /// Set the state of the ganged output
+    ///
+    /// # Errors
+    ///
+    /// If an error occurs while setting the enable pin, a
+    /// [EnablePinError](OutputStateError::EnablePinError) will be returned.
+    ///
+    /// If an error occurs while setting the state of one of the input pins, a
+    /// [InputPinError](OutputStateError::InputPinError) will be returned. The output stays
+    /// disabled in this case.
+    fn set_state(&mut self, state: PinState) -> Result<(), Self::Error> {
+        self.update(|a| a.set_state(state), |b| b.set_state(state))
+    }
+}
+
+impl<'a, A, B, EN> StatefulOutputPin for GangedOutput<'a, A, B, EN>
+where
+    A: StatefulOutputPin,
+    B: StatefulOutputPin<Error = A::Error>,
+    EN: StatefulOutputPin,
+{
+    /// Check whether the ganged output is set high
+    ///
+    /// # Errors
+    ///
+    /// If the output is disabled, a [NotEnabled](OutputStateError::NotEnabled) error will be
+    /// returned.
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        match self
+            .enable
+            .is_set_high()
+            .map_err(OutputStateError::EnablePinError)?
+        {
+            false => Err(OutputStateError::NotEnabled),
+            true => self
+                .a
+                .is_set_high()
+                .map_err(OutputStateError::InputPinError),
+        }
+    }
+
+    /// Check whether the ganged output is set low
+    ///
+    /// # Errors
+    ///
+    /// If the output is disabled, a [NotEnabled](OutputStateError::NotEnabled) error will be
+    /// returned.
+    #[inline]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        self.is_set_high().map(|high| !high)
+    }
+}
+
+impl<'a, A, B, EN> embedded_hal::pwm::ErrorType for GangedOutput<'a, A, B, EN>
+where
+    A: SetDutyCycle,
+    B: SetDutyCycle<Error = A::Error>,
+    EN: OutputPin,
+{
+    type Error = OutputStateError<A::Error, EN::Error>;
+}
+
+impl<'a, A, B, EN> SetDutyCycle for GangedOutput<'a, A, B, EN>
+where
+    A: SetDutyCycle,
+    B: SetDutyCycle<Error = A::Error>,
+    EN: OutputPin,
+{
+    /// Get the maximum duty cycle of the ganged output, which is the one of the first input.
+    #[inline]
+    fn max_duty_cycle(&self) -> u16 {
+        self.a.max_duty_cycle()
+    }
+
+    /// Set the duty cycle of the ganged output
+    ///
+    /// The duty cycle of the second input is scaled to its own maximum duty cycle, so that both
+    /// inputs are active for the same portion of the time.
+    ///
+    /// # Errors
+    ///
+    /// If an error occurs while setting the enable pin, a
+    /// [EnablePinError](OutputStateError::EnablePinError) will be returned.
+    ///
+    /// If an error occurs while setting the duty cycle of one of the input pins, a
+    /// [InputPinError](OutputStateError::InputPinError) will be returned. The output stays
+    /// disabled in this case.
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+        let max_duty = self.a.max_duty_cycle();
+        self.update(
+            |a| a.set_duty_cycle(duty),
+            |b| b.set_duty_cycle_fraction(duty, max_duty),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::DigitalPin;
+    use crate::{L293x, OutputStateError};
+    use coverage_helper::test;
+    use embedded_hal::digital::{OutputPin, StatefulOutputPin};
+
+    #[test]
+    fn test_digital() {
+        let mut l293x = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            (),
+            (),
+            DigitalPin::new(),
+            (),
+        );
+
+        let mut output = l293x.gang_y1_and_y2();
+        assert!(matches!(
+            output.is_set_high(),
+            Err(OutputStateError::NotEnabled)
+        ));
+        output.set_high().unwrap();
+        assert!(output.is_set_high().unwrap());
+        output.set_low().unwrap();
+        assert!(output.is_set_low().unwrap());
+        output.disable().unwrap();
+
+        assert!(l293x.y1().is_disabled().unwrap());
+        assert!(l293x.is_y2_set_low().is_err());
+    }
+}
@@ -1,9 +1,10 @@
-use core::cell::RefCell;
+use core::cell::{Cell, RefCell};
 
 use embedded_hal::digital::{OutputPin, PinState, StatefulOutputPin};
 use embedded_hal::pwm::SetDutyCycle;
 
-use crate::OutputStateError;
+use crate::pins::Polarized;
+use crate::{resolve_output, AutoEnable, OutputLevel, OutputStateError, Polarity};
 
 /// Half-H bridge of the [L293](crate::L293x) chip
 ///
@@ -14,7 +15,8 @@ use crate::OutputStateError;
 /// this, they implement the [embedded_hal::digital::OutputPin],
 /// [embedded_hal::digital::StatefulOutputPin], or the [embedded_hal::pwm::SetDutyCycle] traits,
 /// based on the traits implemented by the corresponding input. Output `y1` is linked with input
-/// `a1`, `y2` with `a2` and so on.
+/// `a1`, `y2` with `a2` and so on. Like any pin, a mutable reference to the bridge implements these
+/// traits as well. Thus, an output can be lent to another driver temporarily, without moving it.
 ///
 /// <div class="warning">
 /// Please keep in mind, that the four Half-H bridges of the L293 chip share two common enable pins.
@@ -41,11 +43,169 @@ use crate::OutputStateError;
 pub struct HalfH<'a, INPUT, ENABLE> {
     input: &'a RefCell<INPUT>,
     enable: &'a RefCell<ENABLE>,
+    command: Option<&'a Cell<InputCommand>>,
+    auto_enable: AutoEnable,
+    input_polarity: Polarity,
+    enable_polarity: Polarity,
 }
 
 impl<'a, INPUT, ENABLE> HalfH<'a, INPUT, ENABLE> {
     pub(crate) fn new(input: &'a RefCell<INPUT>, enable: &'a RefCell<ENABLE>) -> Self {
-        Self { input, enable }
+        Self {
+            input,
+            enable,
+            command: None,
+            auto_enable: AutoEnable::Never,
+            input_polarity: Polarity::Normal,
+            enable_polarity: Polarity::Normal,
+        }
+    }
+
+    /// Set whether setting the state or the duty cycle of the bridge enables its output.
+    ///
+    /// By default, the output is [never](AutoEnable::Never) enabled implicitly. See [AutoEnable]
+    /// for details.
+    #[inline]
+    pub fn with_auto_enable(self, auto_enable: AutoEnable) -> Self {
+        Self {
+            auto_enable,
+            ..self
+        }
+    }
+
+    /// Get whether setting the state or the duty cycle of the bridge enables its output.
+    #[inline]
+    pub fn auto_enable(&self) -> AutoEnable {
+        self.auto_enable
+    }
+
+    /// Remember the states and duty cycles commanded using this bridge in the given cache.
+    pub(crate) fn with_command_cache(self, command: &'a Cell<InputCommand>) -> Self {
+        Self {
+            command: Some(command),
+            ..self
+        }
+    }
+
+    /// Apply the polarities of the pins of the chip to the input and the enable pin.
+    pub(crate) fn with_polarity(self, input_polarity: Polarity, enable_polarity: Polarity) -> Self {
+        Self {
+            input_polarity,
+            enable_polarity,
+            ..self
+        }
+    }
+
+    pub(crate) fn input(&self) -> &'a RefCell<INPUT> {
+        self.input
+    }
+
+    /// Get the last duty cycle commanded using this bridge.
+    ///
+    /// The PWM traits do not allow reading back the duty cycle of a pin. Thus, the bridges of the
+    /// [L293](crate::L293x) chip remember the last duty cycle set using the chip or one of its
+    /// bridges instead. This allows control loops and telemetry to read back the current
+    /// setpoint.
+    ///
+    /// Returns `None`, if no duty cycle has been set yet, if the output has been set to a digital
+    /// state afterwards, or if the bridge does not track its duty cycle (e.g. the bridges of a
+    /// [QuadHalfH](crate::QuadHalfH)).
+    #[inline]
+    pub fn duty_cycle(&self) -> Option<u16> {
+        self.command.and_then(|command| command.get().duty())
+    }
+}
+
+/// The last command applied to the input of a Half-H bridge of the chip
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum InputCommand {
+    /// The input has not been commanded yet or its state is not known.
+    Unknown,
+    /// The input is driven statically to the given state.
+    State(PinState),
+    /// The input is driven using the given duty cycle.
+    Duty(u16),
+}
+
+impl InputCommand {
+    /// Get the duty cycle, if the input is driven using a duty cycle.
+    #[inline]
+    pub(crate) fn duty(self) -> Option<u16> {
+        match self {
+            InputCommand::Duty(duty) => Some(duty),
+            _ => None,
+        }
+    }
+}
+
+impl Default for InputCommand {
+    #[inline]
+    fn default() -> Self {
+        InputCommand::Unknown
+    }
+}
+
+/// Set the duty cycle of a PWM pin using the `set` closure and remember the resulting duty cycle
+/// in the `cache` afterwards.
+///
+/// The resulting duty cycle is calculated by the `duty` closure from the maximum duty cycle of
+/// the pin.
+pub(crate) fn set_duty_tracked<P: SetDutyCycle>(
+    pin: &mut P,
+    cache: Option<&Cell<InputCommand>>,
+    duty: impl FnOnce(u16) -> u16,
+    set: impl FnOnce(&mut P) -> Result<(), P::Error>,
+) -> Result<(), P::Error> {
+    set(pin)?;
+    if let Some(cache) = cache {
+        cache.set(InputCommand::Duty(duty(pin.max_duty_cycle())));
+    }
+    Ok(())
+}
+
+/// Set the digital `state` of a pin and remember it in the `cache` instead of the duty cycle,
+/// because the pin is not driven using a duty cycle anymore.
+///
+/// If the state could not be set, the state of the pin is not known anymore.
+pub(crate) fn set_state_tracked<P: OutputPin>(
+    pin: &mut P,
+    cache: Option<&Cell<InputCommand>>,
+    state: PinState,
+) -> Result<(), P::Error> {
+    if let Some(cache) = cache {
+        cache.set(InputCommand::Unknown);
+    }
+    pin.set_state(state)?;
+    if let Some(cache) = cache {
+        cache.set(InputCommand::State(state));
+    }
+    Ok(())
+}
+
+/// Calculate the duty cycle of the fraction `num` / `denom` of the `max_duty` cycle.
+///
+/// This uses the same calculation as the default implementation of
+/// [`SetDutyCycle::set_duty_cycle_fraction()`].
+pub(crate) fn duty_from_fraction(num: u16, denom: u16, max_duty: u16) -> u16 {
+    (u32::from(num) * u32::from(max_duty))
+        .checked_div(u32::from(denom))
+        .map_or(max_duty, |duty| duty.min(u32::from(max_duty)) as u16)
+}
+
+#[cfg(feature = "defmt")]
+impl<'a, INPUT, ENABLE> defmt::Format for HalfH<'a, INPUT, ENABLE>
+where
+    INPUT: defmt::Format,
+    ENABLE: defmt::Format,
+{
+    fn format(&self, f: defmt::Formatter) {
+        use crate::format::CellFormat;
+        defmt::write!(
+            f,
+            "HalfH {{ input: {}, enable: {} }}",
+            CellFormat(self.input),
+            CellFormat(self.enable)
+        )
     }
 }
 
@@ -65,7 +225,7 @@ where
     /// enable pin to the caller. The concrete error type returned depends on the type of
     /// [OutputPin](embedded_hal::digital::OutputPin) used.
     pub fn enable(&mut self) -> Result<(), ENABLE::Error> {
-        self.enable.borrow_mut().set_high()
+        Polarized::new(&mut *self.enable.borrow_mut(), self.enable_polarity).set_high()
     }
 
     /// Disable the output
@@ -81,7 +241,16 @@ where
     /// enable pin to the caller. The concrete error type returned depends on the type of
     /// [OutputPin](embedded_hal::digital::OutputPin) used.
     pub fn disable(&mut self) -> Result<(), ENABLE::Error> {
-        self.enable.borrow_mut().set_low()
+        Polarized::new(&mut *self.enable.borrow_mut(), self.enable_polarity).set_low()
+    }
+
+    /// Enable the output, if the [auto-enable policy](HalfH::with_auto_enable) of the bridge
+    /// requires it.
+    fn auto_enable_output(&mut self) -> Result<(), ENABLE::Error> {
+        match self.auto_enable {
+            AutoEnable::Always => self.enable(),
+            AutoEnable::Never => Ok(()),
+        }
     }
 }
 
@@ -103,7 +272,7 @@ where
     /// enable pin to the caller. The concrete error type returned depends on the type of
     /// [OutputPin](embedded_hal::digital::OutputPin) used.
     pub fn is_enabled(&mut self) -> Result<bool, ENABLE::Error> {
-        self.enable.borrow_mut().is_set_high()
+        Polarized::new(&mut *self.enable.borrow_mut(), self.enable_polarity).is_set_high()
     }
 
     /// Check whether the output is disabled.
@@ -120,7 +289,137 @@ where
     /// enable pin to the caller. The concrete error type returned depends on the type of
     /// [OutputPin](embedded_hal::digital::OutputPin) used.
     pub fn is_disabled(&mut self) -> Result<bool, ENABLE::Error> {
-        self.enable.borrow_mut().is_set_low()
+        Polarized::new(&mut *self.enable.borrow_mut(), self.enable_polarity).is_set_low()
+    }
+
+    /// Check whether the output is in the high impedance (Z) state.
+    ///
+    /// The output is in the high impedance state, if it is disabled, regardless of its input.
+    /// In contrast to the [`is_set_high()`](HalfH::is_set_high) and
+    /// [`is_set_low()`](HalfH::is_set_low) methods, this does not return a
+    /// [NotEnabled](OutputStateError::NotEnabled) error for a disabled output. Thus, it allows
+    /// distinguishing the three states of the output like this:
+    ///
+    /// ```
+    /// if y1.is_high_impedance()? {
+    ///     // The output is floating
+    /// } else if y1.is_set_high()? {
+    ///     // The output is driven high
+    /// }
+    /// ```
+    ///
+    /// Use [`output_level()`](HalfH::output_level) to get all three states in a single call.
+    ///
+    /// # Errors
+    ///
+    /// This method will return the error of the enable pin.
+    #[inline]
+    pub fn is_high_impedance(&mut self) -> Result<bool, ENABLE::Error> {
+        self.is_disabled()
+    }
+}
+
+impl<'a, INPUT, ENABLE> HalfH<'a, INPUT, ENABLE>
+where
+    INPUT: StatefulOutputPin,
+    ENABLE: StatefulOutputPin,
+{
+    /// Get the level of the output.
+    ///
+    /// In contrast to the [`is_set_high()`](HalfH::is_set_high) and
+    /// [`is_set_low()`](HalfH::is_set_low) methods, this does not fail for disabled outputs, but
+    /// returns [HighZ](OutputLevel::HighZ) instead. The level is resolved from the states of the
+    /// input and the enable pin using [resolve_output].
+    ///
+    /// # Note
+    ///
+    /// Please note, that this method does not check the electrical level of
+    /// the pin, but uses an internal state instead. The electrical level of
+    /// the pin may vary due to the layout of the circuit.
+    ///
+    /// # Errors
+    ///
+    /// If an error occurs, while checking the state of the enable pin, a
+    /// [EnablePinError](OutputStateError::EnablePinError) will be returned, or if an error occurs
+    /// while checking the state of the input pin, a
+    /// [InputPinError](OutputStateError::InputPinError) will be returned instead.
+    pub fn output_level(
+        &mut self,
+    ) -> Result<OutputLevel, OutputStateError<INPUT::Error, ENABLE::Error>> {
+        let enable = Polarized::new(&mut *self.enable.borrow_mut(), self.enable_polarity)
+            .is_set_high()
+            .map_err(OutputStateError::EnablePinError)?;
+        let input = Polarized::new(&mut *self.input.borrow_mut(), self.input_polarity)
+            .is_set_high()
+            .map_err(OutputStateError::InputPinError)?;
+        Ok(resolve_output(input, enable))
+    }
+
+    /// Toggle the state of the output and return the new state.
+    ///
+    /// This behaves like [`toggle()`](HalfH::toggle), but saves querying the new state using an
+    /// additional call to [`is_set_high()`](HalfH::is_set_high).
+    ///
+    /// # Errors
+    ///
+    /// If the bridge is not [enabled](HalfH::enable), this method will return a
+    /// [OutputStateError::NotEnabled] error.
+    ///
+    /// Otherwise, if an error occurs, while checking the state of the enable pin, a
+    /// [EnablePinError](OutputStateError::EnablePinError) will be returned, or if an error occurs
+    /// while toggling the input pin, a [InputPinError](OutputStateError::InputPinError) will be
+    /// returned instead.
+    pub fn toggle_and_get(
+        &mut self,
+    ) -> Result<PinState, OutputStateError<INPUT::Error, ENABLE::Error>> {
+        if !Polarized::new(&mut *self.enable.borrow_mut(), self.enable_polarity)
+            .is_set_high()
+            .map_err(OutputStateError::EnablePinError)?
+        {
+            return Err(OutputStateError::NotEnabled);
+        }
+        let mut input = self.input.borrow_mut();
+        let mut input = Polarized::new(&mut *input, self.input_polarity);
+        let state = PinState::from(
+            input
+                .is_set_low()
+                .map_err(OutputStateError::InputPinError)?,
+        );
+        set_state_tracked(&mut input, self.command, state)
+            .map_err(OutputStateError::InputPinError)?;
+        Ok(state)
+    }
+}
+
+impl<'a, INPUT: OutputPin, ENABLE> HalfH<'a, INPUT, ENABLE> {
+    /// Set the state of the input pin without enabling the output.
+    pub(crate) fn set_input_state(&self, state: PinState) -> Result<(), INPUT::Error> {
+        let mut input = self.input.borrow_mut();
+        set_state_tracked(
+            &mut Polarized::new(&mut *input, self.input_polarity),
+            self.command,
+            state,
+        )
+    }
+}
+
+impl<'a, INPUT: StatefulOutputPin, ENABLE> HalfH<'a, INPUT, ENABLE> {
+    /// Check whether the input pin is set high, without checking the enable pin.
+    pub(crate) fn is_input_set_high(&self) -> Result<bool, INPUT::Error> {
+        Polarized::new(&mut *self.input.borrow_mut(), self.input_polarity).is_set_high()
+    }
+}
+
+impl<'a, INPUT: SetDutyCycle, ENABLE> HalfH<'a, INPUT, ENABLE> {
+    /// Set the duty cycle of the input pin without enabling the output.
+    pub(crate) fn set_input_duty_cycle(&self, duty: u16) -> Result<(), INPUT::Error> {
+        let mut input = self.input.borrow_mut();
+        set_duty_tracked(
+            &mut Polarized::new(&mut *input, self.input_polarity),
+            self.command,
+            |_| duty,
+            |pin| pin.set_duty_cycle(duty),
+        )
     }
 }
 
@@ -141,7 +440,9 @@ where
     ///
     /// # Note
     ///
-    /// This method will enable the bridge, if it is not enabled yet.
+    /// This method only enables the bridge, if its
+    /// [auto-enable policy](HalfH::with_auto_enable) is [Always](AutoEnable::Always). Otherwise,
+    /// the bridge must be enabled explicitly.
     ///
     /// # Errors
     ///
@@ -153,18 +454,23 @@ where
     ///
     /// Both of them contain the original error to provide additional information to the caller.
     fn set_low(&mut self) -> Result<(), Self::Error> {
-        self.enable().map_err(OutputStateError::EnablePinError)?;
-        self.input
-            .borrow_mut()
-            .set_low()
-            .map_err(OutputStateError::InputPinError)
+        self.auto_enable_output()
+            .map_err(OutputStateError::EnablePinError)?;
+        set_state_tracked(
+            &mut Polarized::new(&mut *self.input.borrow_mut(), self.input_polarity),
+            self.command,
+            PinState::Low,
+        )
+        .map_err(OutputStateError::InputPinError)
     }
 
     /// Set the output of the bridge to low state
     ///
     /// # Note
     ///
-    /// This method will enable the bridge, if it is not enabled yet.
+    /// This method only enables the bridge, if its
+    /// [auto-enable policy](HalfH::with_auto_enable) is [Always](AutoEnable::Always). Otherwise,
+    /// the bridge must be enabled explicitly.
     ///
     /// # Errors
     ///
@@ -176,18 +482,23 @@ where
     ///
     /// Both of them contain the original error to provide additional information to the caller.
     fn set_high(&mut self) -> Result<(), Self::Error> {
-        self.enable().map_err(OutputStateError::EnablePinError)?;
-        self.input
-            .borrow_mut()
-            .set_high()
-            .map_err(OutputStateError::InputPinError)
+        self.auto_enable_output()
+            .map_err(OutputStateError::EnablePinError)?;
+        set_state_tracked(
+            &mut Polarized::new(&mut *self.input.borrow_mut(), self.input_polarity),
+            self.command,
+            PinState::High,
+        )
+        .map_err(OutputStateError::InputPinError)
     }
 
     /// Set the state of the output of the bridge
     ///
     /// # Note
     ///
-    /// This method will enable the bridge, if it is not enabled yet.
+    /// This method only enables the bridge, if its
+    /// [auto-enable policy](HalfH::with_auto_enable) is [Always](AutoEnable::Always). Otherwise,
+    /// the bridge must be enabled explicitly.
     ///
     /// # Errors
     ///
@@ -199,11 +510,14 @@ where
     ///
     /// Both of them contain the original error to provide additional information to the caller.
     fn set_state(&mut self, state: PinState) -> Result<(), Self::Error> {
-        self.enable().map_err(OutputStateError::EnablePinError)?;
-        self.input
-            .borrow_mut()
-            .set_state(state)
-            .map_err(OutputStateError::InputPinError)
+        self.auto_enable_output()
+            .map_err(OutputStateError::EnablePinError)?;
+        set_state_tracked(
+            &mut Polarized::new(&mut *self.input.borrow_mut(), self.input_polarity),
+            self.command,
+            state,
+        )
+        .map_err(OutputStateError::InputPinError)
     }
 }
 
@@ -232,16 +546,12 @@ where
     ///
     /// Both of them contain the original error to provide additional information to the caller.
     fn is_set_high(&mut self) -> Result<bool, Self::Error> {
-        match self
-            .enable
-            .borrow_mut()
+        match Polarized::new(&mut *self.enable.borrow_mut(), self.enable_polarity)
             .is_set_high()
             .map_err(OutputStateError::EnablePinError)?
         {
             false => Err(OutputStateError::NotEnabled),
-            true => self
-                .input
-                .borrow_mut()
+            true => Polarized::new(&mut *self.input.borrow_mut(), self.input_polarity)
                 .is_set_high()
                 .map_err(OutputStateError::InputPinError),
         }
@@ -267,16 +577,12 @@ where
     ///
     /// Both of them contain the original error to provide additional information to the caller.
     fn is_set_low(&mut self) -> Result<bool, Self::Error> {
-        match self
-            .enable
-            .borrow_mut()
+        match Polarized::new(&mut *self.enable.borrow_mut(), self.enable_polarity)
             .is_set_high()
             .map_err(OutputStateError::EnablePinError)?
         {
             false => Err(OutputStateError::NotEnabled),
-            true => self
-                .input
-                .borrow_mut()
+            true => Polarized::new(&mut *self.input.borrow_mut(), self.input_polarity)
                 .is_set_low()
                 .map_err(OutputStateError::InputPinError),
         }
@@ -302,18 +608,19 @@ where
     ///
     /// Both of them contain the original error to provide additional information to the caller.
     fn toggle(&mut self) -> Result<(), Self::Error> {
-        match self
-            .enable
-            .borrow_mut()
+        match Polarized::new(&mut *self.enable.borrow_mut(), self.enable_polarity)
             .is_set_high()
             .map_err(OutputStateError::EnablePinError)?
         {
             false => Err(OutputStateError::NotEnabled),
-            true => self
-                .input
-                .borrow_mut()
-                .toggle()
-                .map_err(OutputStateError::InputPinError),
+            true => {
+                if let Some(command) = self.command {
+                    command.set(InputCommand::Unknown);
+                }
+                Polarized::new(&mut *self.input.borrow_mut(), self.input_polarity)
+                    .toggle()
+                    .map_err(OutputStateError::InputPinError)
+            }
         }
     }
 }
@@ -346,7 +653,9 @@ where
     ///
     /// # Note
     ///
-    /// This method will enable the bridge, if it is not enabled yet.
+    /// This method only enables the bridge, if its
+    /// [auto-enable policy](HalfH::with_auto_enable) is [Always](AutoEnable::Always). Otherwise,
+    /// the bridge must be enabled explicitly.
     ///
     /// # Errors
     ///
@@ -358,11 +667,15 @@ where
     ///
     /// Both of them contain the original error to provide additional information to the caller.
     fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
-        self.enable().map_err(OutputStateError::EnablePinError)?;
-        self.input
-            .borrow_mut()
-            .set_duty_cycle(duty)
-            .map_err(OutputStateError::InputPinError)
+        self.auto_enable_output()
+            .map_err(OutputStateError::EnablePinError)?;
+        set_duty_tracked(
+            &mut Polarized::new(&mut *self.input.borrow_mut(), self.input_polarity),
+            self.command,
+            |_| duty,
+            |pin| pin.set_duty_cycle(duty),
+        )
+        .map_err(OutputStateError::InputPinError)
     }
 
     /// Set the duty cycle of the output by a fraction
@@ -384,7 +697,9 @@ where
     ///
     /// # Note
     ///
-    /// This method will enable the bridge, if it is not enabled yet.
+    /// This method only enables the bridge, if its
+    /// [auto-enable policy](HalfH::with_auto_enable) is [Always](AutoEnable::Always). Otherwise,
+    /// the bridge must be enabled explicitly.
     ///
     /// # Errors
     ///
@@ -396,11 +711,15 @@ where
     ///
     /// Both of them contain the original error to provide additional information to the caller.
     fn set_duty_cycle_fraction(&mut self, num: u16, denom: u16) -> Result<(), Self::Error> {
-        self.enable().map_err(OutputStateError::EnablePinError)?;
-        self.input
-            .borrow_mut()
-            .set_duty_cycle_fraction(num, denom)
-            .map_err(OutputStateError::InputPinError)
+        self.auto_enable_output()
+            .map_err(OutputStateError::EnablePinError)?;
+        set_duty_tracked(
+            &mut Polarized::new(&mut *self.input.borrow_mut(), self.input_polarity),
+            self.command,
+            |max_duty| duty_from_fraction(num, denom, max_duty),
+            |pin| pin.set_duty_cycle_fraction(num, denom),
+        )
+        .map_err(OutputStateError::InputPinError)
     }
 
     /// Set the duty cycle of the output by percent
@@ -416,7 +735,9 @@ where
     ///
     /// # Note
     ///
-    /// This method will enable the bridge, if it is not enabled yet.
+    /// This method only enables the bridge, if its
+    /// [auto-enable policy](HalfH::with_auto_enable) is [Always](AutoEnable::Always). Otherwise,
+    /// the bridge must be enabled explicitly.
     ///
     /// # Errors
     ///
@@ -428,18 +749,24 @@ where
     ///
     /// Both of them contain the original error to provide additional information to the caller.
     fn set_duty_cycle_percent(&mut self, percent: u8) -> Result<(), Self::Error> {
-        self.enable().map_err(OutputStateError::EnablePinError)?;
-        self.input
-            .borrow_mut()
-            .set_duty_cycle_percent(percent)
-            .map_err(OutputStateError::InputPinError)
+        self.auto_enable_output()
+            .map_err(OutputStateError::EnablePinError)?;
+        set_duty_tracked(
+            &mut Polarized::new(&mut *self.input.borrow_mut(), self.input_polarity),
+            self.command,
+            |max_duty| duty_from_fraction(percent.into(), 100, max_duty),
+            |pin| pin.set_duty_cycle_percent(percent),
+        )
+        .map_err(OutputStateError::InputPinError)
     }
 
     /// Set the output fully active
     ///
     /// # Note
     ///
-    /// This method will enable the bridge, if it is not enabled yet.
+    /// This method only enables the bridge, if its
+    /// [auto-enable policy](HalfH::with_auto_enable) is [Always](AutoEnable::Always). Otherwise,
+    /// the bridge must be enabled explicitly.
     ///
     /// # Errors
     ///
@@ -451,18 +778,24 @@ where
     ///
     /// Both of them contain the original error to provide additional information to the caller.
     fn set_duty_cycle_fully_on(&mut self) -> Result<(), Self::Error> {
-        self.enable().map_err(OutputStateError::EnablePinError)?;
-        self.input
-            .borrow_mut()
-            .set_duty_cycle_fully_on()
-            .map_err(OutputStateError::InputPinError)
+        self.auto_enable_output()
+            .map_err(OutputStateError::EnablePinError)?;
+        set_duty_tracked(
+            &mut Polarized::new(&mut *self.input.borrow_mut(), self.input_polarity),
+            self.command,
+            |max_duty| max_duty,
+            |pin| pin.set_duty_cycle_fully_on(),
+        )
+        .map_err(OutputStateError::InputPinError)
     }
 
     /// Set the output fully inactive
     ///
     /// # Note
     ///
-    /// This method will enable the bridge, if it is not enabled yet.
+    /// This method only enables the bridge, if its
+    /// [auto-enable policy](HalfH::with_auto_enable) is [Always](AutoEnable::Always). Otherwise,
+    /// the bridge must be enabled explicitly.
     ///
     /// # Errors
     ///
@@ -474,19 +807,58 @@ where
     ///
     /// Both of them contain the original error to provide additional information to the caller.
     fn set_duty_cycle_fully_off(&mut self) -> Result<(), Self::Error> {
-        self.enable().map_err(OutputStateError::EnablePinError)?;
-        self.input
-            .borrow_mut()
-            .set_duty_cycle_fully_off()
-            .map_err(OutputStateError::InputPinError)
+        self.auto_enable_output()
+            .map_err(OutputStateError::EnablePinError)?;
+        set_duty_tracked(
+            &mut Polarized::new(&mut *self.input.borrow_mut(), self.input_polarity),
+            self.command,
+            |_| 0,
+            |pin| pin.set_duty_cycle_fully_off(),
+        )
+        .map_err(OutputStateError::InputPinError)
+    }
+}
+
+#[cfg(feature = "float")]
+impl<'a, INPUT, ENABLE> HalfH<'a, INPUT, ENABLE>
+where
+    INPUT: SetDutyCycle,
+    ENABLE: OutputPin,
+{
+    /// Set the duty cycle of the output by a floating point fraction
+    ///
+    /// The `fraction` is clamped into the range of `0.0` (fully off) to `1.0` (fully on) and
+    /// converted into a duty cycle of the input pin.
+    ///
+    /// This method is only available, if the `float` feature is enabled.
+    ///
+    /// # Note
+    ///
+    /// This method only enables the bridge, if its
+    /// [auto-enable policy](HalfH::with_auto_enable) is [Always](AutoEnable::Always). Otherwise,
+    /// the bridge must be enabled explicitly.
+    ///
+    /// # Errors
+    ///
+    /// If an error occurs while enabling the output, a
+    /// [EnablePinError](OutputStateError::EnablePinError) will be returned.
+    ///
+    /// If an error occurs while setting the duty cycle of the input pin, a
+    /// [InputPinError](OutputStateError::InputPinError) will be returned.
+    pub fn set_duty_cycle_f32(
+        &mut self,
+        fraction: f32,
+    ) -> Result<(), OutputStateError<INPUT::Error, ENABLE::Error>> {
+        let duty = crate::float::duty_cycle_from_f32(fraction, self.max_duty_cycle());
+        self.set_duty_cycle(duty)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::mock::{DigitalPin, PwmPin};
     use crate::pins::Vcc;
+    use crate::test_util::{DigitalPin, PwmPin};
     use crate::L293x;
     use coverage_helper::test;
 
@@ -509,16 +881,19 @@ mod tests {
         bridge.disable().unwrap();
         assert!(!bridge.is_enabled().unwrap());
         assert!(bridge.is_disabled().unwrap());
+        assert!(bridge.is_high_impedance().unwrap());
 
         bridge.enable().unwrap();
         assert!(bridge.is_enabled().unwrap());
         assert!(!bridge.is_disabled().unwrap());
+        assert!(!bridge.is_high_impedance().unwrap());
     }
 
     #[test]
     fn test_set() {
         let l293 = l293();
         let mut bridge = l293.y1();
+        bridge.enable().unwrap();
         bridge.set_low().unwrap();
         assert!(!bridge.is_set_high().unwrap());
         assert!(bridge.is_set_low().unwrap());
@@ -532,6 +907,7 @@ mod tests {
     fn test_set_state() {
         let l293 = l293();
         let mut bridge = l293.y1();
+        bridge.enable().unwrap();
 
         bridge.set_state(PinState::Low).unwrap();
         assert!(!bridge.is_set_high().unwrap());
@@ -545,7 +921,7 @@ mod tests {
     #[test]
     fn test_set_enables_output() {
         let l293 = l293();
-        let mut bridge = l293.y1();
+        let mut bridge = l293.y1().with_auto_enable(AutoEnable::Always);
 
         assert!(!bridge.is_enabled().unwrap());
         bridge.set_high().unwrap();
@@ -559,7 +935,7 @@ mod tests {
     #[test]
     fn test_set_error() {
         let l293 = l293();
-        let mut bridge = l293.y1();
+        let mut bridge = l293.y1().with_auto_enable(AutoEnable::Always);
         bridge.enable.borrow_mut().fail();
 
         assert!(matches!(
@@ -590,6 +966,76 @@ mod tests {
         assert_ne!(bridge.is_set_high().unwrap(), old_state);
     }
 
+    #[test]
+    fn test_output_level() {
+        let l293 = l293();
+        let mut bridge = l293.y1();
+
+        bridge.set_high().unwrap();
+        assert_eq!(bridge.output_level().unwrap(), OutputLevel::HighZ);
+        bridge.enable().unwrap();
+        assert_eq!(bridge.output_level().unwrap(), OutputLevel::High);
+        bridge.set_low().unwrap();
+        assert_eq!(bridge.output_level().unwrap(), OutputLevel::Low);
+
+        bridge.input.borrow_mut().fail();
+        assert!(matches!(
+            bridge.output_level(),
+            Err(OutputStateError::InputPinError(..))
+        ));
+        bridge.enable.borrow_mut().fail();
+        assert!(matches!(
+            bridge.output_level(),
+            Err(OutputStateError::EnablePinError(..))
+        ));
+    }
+
+    #[test]
+    fn test_toggle_and_get() {
+        let l293 = l293();
+        let mut bridge = l293.y1();
+
+        bridge.disable().unwrap();
+        assert!(matches!(
+            bridge.toggle_and_get(),
+            Err(OutputStateError::NotEnabled)
+        ));
+
+        bridge.enable().unwrap();
+        bridge.set_low().unwrap();
+        assert_eq!(bridge.toggle_and_get().unwrap(), PinState::High);
+        assert!(bridge.is_set_high().unwrap());
+        assert_eq!(bridge.toggle_and_get().unwrap(), PinState::Low);
+
+        bridge.input.borrow_mut().fail();
+        assert!(matches!(
+            bridge.toggle_and_get(),
+            Err(OutputStateError::InputPinError(..))
+        ));
+    }
+
+    #[test]
+    fn test_auto_enable() {
+        let l293 = l293();
+        let mut bridge = l293.y1();
+        assert_eq!(bridge.auto_enable(), AutoEnable::Never);
+
+        bridge.set_high().unwrap();
+        bridge.set_state(PinState::Low).unwrap();
+        assert!(bridge.is_disabled().unwrap());
+        assert!(bridge.input.borrow_mut().is_set_low().unwrap());
+
+        let mut bridge = bridge.with_auto_enable(AutoEnable::Always);
+        bridge.set_high().unwrap();
+        assert!(bridge.is_enabled().unwrap());
+
+        let l293 = L293x::new(PwmPin::new(), (), (), (), DigitalPin::new(), ());
+        let mut bridge = l293.y1();
+        bridge.set_duty_cycle_percent(50).unwrap();
+        assert!(bridge.is_disabled().unwrap());
+        assert_eq!(bridge.duty_cycle(), Some(u16::MAX / 2));
+    }
+
     #[test]
     fn test_toggle_error() {
         let input = RefCell::new(DigitalPin::new());
@@ -601,6 +1047,10 @@ mod tests {
             bridge.toggle(),
             Err(OutputStateError::EnablePinError(..))
         ));
+        assert!(matches!(
+            bridge.toggle_and_get(),
+            Err(OutputStateError::EnablePinError(..))
+        ));
     }
 
     #[test]
@@ -638,6 +1088,27 @@ mod tests {
         assert_eq!(pin.borrow().get_duty_cycle(), max_duty / 2);
     }
 
+    #[test]
+    fn test_duty_cycle_cache() {
+        let pin = RefCell::new(PwmPin::new());
+        let enable = RefCell::new(Vcc());
+        let command = Cell::new(InputCommand::Unknown);
+        let mut bridge = HalfH::new(&pin, &enable);
+        bridge.set_duty_cycle(100).unwrap();
+        assert_eq!(bridge.duty_cycle(), None);
+
+        let mut bridge = bridge.with_command_cache(&command);
+        bridge.set_duty_cycle_percent(50).unwrap();
+        assert_eq!(bridge.duty_cycle(), Some(u16::MAX / 2));
+        bridge.set_duty_cycle_fraction(1, 4).unwrap();
+        assert_eq!(command.get(), InputCommand::Duty(u16::MAX / 4));
+
+        pin.borrow_mut().fail();
+        assert!(bridge.set_duty_cycle(0).is_err());
+        assert_eq!(bridge.duty_cycle(), Some(u16::MAX / 4));
+        assert_eq!(duty_from_fraction(1, 0, 100), 100);
+    }
+
     #[test]
     fn test_set_duty_cycle_fully_on() {
         let pin = RefCell::new(PwmPin::new());
@@ -659,11 +1130,27 @@ mod tests {
         assert_eq!(pin.borrow().get_duty_cycle(), 0);
     }
 
+    #[test]
+    #[cfg(feature = "float")]
+    fn test_set_duty_cycle_f32() {
+        let pin = RefCell::new(PwmPin::new());
+        let enable = RefCell::new(DigitalPin::new());
+        let mut bridge = HalfH::new(&pin, &enable).with_auto_enable(AutoEnable::Always);
+        let max_duty = bridge.max_duty_cycle();
+
+        bridge.set_duty_cycle_f32(0.5).unwrap();
+        assert_eq!(pin.borrow().get_duty_cycle(), max_duty / 2 + 1);
+        assert!(bridge.is_enabled().unwrap());
+
+        bridge.set_duty_cycle_f32(1.5).unwrap();
+        assert_eq!(pin.borrow().get_duty_cycle(), max_duty);
+    }
+
     #[test]
     fn test_pwm_fail_enable() {
         let pin = RefCell::new(PwmPin::new());
         let enable = RefCell::new(DigitalPin::new());
-        let mut bridge = HalfH::new(&pin, &enable);
+        let mut bridge = HalfH::new(&pin, &enable).with_auto_enable(AutoEnable::Always);
         enable.borrow_mut().fail();
 
         assert!(matches!(
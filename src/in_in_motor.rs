@@ -0,0 +1,257 @@
+use embedded_hal::digital::{OutputPin, PinState};
+use embedded_hal::pwm::SetDutyCycle;
+
+use crate::driver::{FullHBridgeDriver, HalfHBridgeDriver};
+use crate::{Direction, FullHBridge, OutputStateError};
+
+/// A motor connected to a Full-H bridge with an IN/IN interface
+///
+/// This is returned by the `motor_a()` and `motor_b()` methods of the [DRV8833](crate::Drv8833)
+/// chip. Unlike the L293 chip, this chip lets the motor coast if both inputs are low and shorts
+/// the motor terminals if both inputs are high. Thus, the [`brake()`](InInMotor::brake) and
+/// [`coast()`](InInMotor::coast) methods of this handle set both inputs high or low respectively,
+/// instead of disabling the bridge.
+///
+/// Driving the motor works the same way as for a [FullH] bridge: [`set_speed()`](InInMotor::set_speed)
+/// drives one input with a duty cycle and keeps the other one low.
+///
+/// | Method                            | Input 1 | Input 2 | Motor terminals | Stop |
+/// | --------------------------------- | ------- | ------- | --------------- | ---- |
+/// | [`forward`](InInMotor::forward)   | High    | Low     | Driven          | -    |
+/// | [`reverse`](InInMotor::reverse)   | Low     | High    | Driven          | -    |
+/// | [`brake`](InInMotor::brake)       | High    | High    | Shorted         | Fast |
+/// | [`coast`](InInMotor::coast)       | Low     | Low     | Disconnected    | Slow |
+///
+/// # Examples
+///
+/// ```
+/// use l293x::Drv8833;
+///
+/// let drv8833 = Drv8833::new(ain1, ain2, bin1, bin2, nsleep);
+/// let mut motor = drv8833.motor_a();
+///
+/// motor.forward()?;
+/// motor.brake()?;
+/// motor.reverse()?;
+/// motor.coast()?;
+/// ```
+///
+/// [FullH]: crate::FullH
+#[derive(Debug)]
+pub struct InInMotor<'a, A, B, EN> {
+    bridge: FullHBridge<'a, A, B, EN>,
+}
+
+impl<'a, A, B, EN> InInMotor<'a, A, B, EN> {
+    /// Create a new motor handle driving the given bridge.
+    #[inline]
+    pub(crate) fn new(bridge: FullHBridge<'a, A, B, EN>) -> Self {
+        Self { bridge }
+    }
+
+    /// Get the last commanded speed of the motor.
+    ///
+    /// Braking and coasting counts as a speed of `0`.
+    #[inline]
+    pub fn speed(&self) -> i16 {
+        self.bridge.speed()
+    }
+}
+
+impl<'a, A, B, EN> InInMotor<'a, A, B, EN>
+where
+    A: OutputPin,
+    B: OutputPin<Error = A::Error>,
+    EN: OutputPin,
+{
+    /// Drive the motor in forward direction.
+    ///
+    /// # Errors
+    ///
+    /// The error of the first pin, which could not be set, will be returned.
+    #[inline]
+    pub fn forward(&mut self) -> Result<(), OutputStateError<A::Error, EN::Error>> {
+        self.bridge.forward()
+    }
+
+    /// Drive the motor in reverse direction.
+    ///
+    /// # Errors
+    ///
+    /// The error of the first pin, which could not be set, will be returned.
+    #[inline]
+    pub fn reverse(&mut self) -> Result<(), OutputStateError<A::Error, EN::Error>> {
+        self.bridge.reverse()
+    }
+
+    /// Drive the motor in the given direction.
+    ///
+    /// # Errors
+    ///
+    /// The error of the first pin, which could not be set, will be returned.
+    #[inline]
+    pub fn drive(
+        &mut self,
+        direction: Direction,
+    ) -> Result<(), OutputStateError<A::Error, EN::Error>> {
+        self.bridge.drive(direction)
+    }
+
+    /// Actively brake the motor.
+    ///
+    /// This sets both inputs high, which shorts the terminals of the motor.
+    ///
+    /// # Errors
+    ///
+    /// The error of the first pin, which could not be set, will be returned.
+    pub fn brake(&mut self) -> Result<(), OutputStateError<A::Error, EN::Error>> {
+        self.set_inputs(true)
+    }
+
+    /// Let the motor coast.
+    ///
+    /// This sets both inputs low, which disconnects the motor.
+    ///
+    /// # Errors
+    ///
+    /// The error of the first pin, which could not be set, will be returned.
+    pub fn coast(&mut self) -> Result<(), OutputStateError<A::Error, EN::Error>> {
+        self.set_inputs(false)
+    }
+
+    /// Enable the bridge and set both inputs to the same state.
+    fn set_inputs(&mut self, high: bool) -> Result<(), OutputStateError<A::Error, EN::Error>> {
+        let (a, b) = self.bridge.bridges_mut();
+        a.enable().map_err(OutputStateError::EnablePinError)?;
+        if high {
+            a.set_high()?;
+            b.set_high()?;
+        } else {
+            a.set_low()?;
+            b.set_low()?;
+        }
+        self.bridge.record_speed(0);
+        Ok(())
+    }
+}
+
+impl<'a, A, B, EN> InInMotor<'a, A, B, EN>
+where
+    A: SetDutyCycle,
+    B: SetDutyCycle<Error = A::Error>,
+    EN: OutputPin,
+{
+    /// Set the speed of the motor.
+    ///
+    /// Positive values drive the motor forward, negative values in reverse. A `speed` of `0`
+    /// lets the motor coast.
+    ///
+    /// # Errors
+    ///
+    /// The error of the first pin, which could not be set, will be returned.
+    #[inline]
+    pub fn set_speed(&mut self, speed: i16) -> Result<(), OutputStateError<A::Error, EN::Error>> {
+        self.bridge.set_speed(speed)
+    }
+}
+
+impl<'a, A, B, EN> FullHBridgeDriver for InInMotor<'a, A, B, EN>
+where
+    A: SetDutyCycle,
+    B: SetDutyCycle<Error = A::Error>,
+    EN: OutputPin,
+{
+    type Error = OutputStateError<A::Error, EN::Error>;
+
+    #[inline]
+    fn enable(&mut self) -> Result<(), Self::Error> {
+        FullHBridgeDriver::enable(&mut self.bridge)
+    }
+
+    /// Let the motor coast by setting both inputs fully off.
+    #[inline]
+    fn disable(&mut self) -> Result<(), Self::Error> {
+        self.set_states(PinState::Low)
+    }
+
+    #[inline]
+    fn set_speed(&mut self, speed: i16) -> Result<(), Self::Error> {
+        InInMotor::set_speed(self, speed)
+    }
+
+    #[inline]
+    fn speed(&self) -> i16 {
+        InInMotor::speed(self)
+    }
+
+    /// Brake the motor by setting both inputs fully on.
+    #[inline]
+    fn brake(&mut self) -> Result<(), Self::Error> {
+        self.set_states(PinState::High)
+    }
+}
+
+impl<'a, A, B, EN> InInMotor<'a, A, B, EN>
+where
+    A: SetDutyCycle,
+    B: SetDutyCycle<Error = A::Error>,
+    EN: OutputPin,
+{
+    /// Enable the bridge and set both inputs fully on or off.
+    fn set_states(&mut self, state: PinState) -> Result<(), OutputStateError<A::Error, EN::Error>> {
+        let (a, b) = self.bridge.bridges_mut();
+        HalfHBridgeDriver::enable(a)?;
+        HalfHBridgeDriver::set_state(a, state)?;
+        HalfHBridgeDriver::set_state(b, state)?;
+        self.bridge.record_speed(0);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{DigitalPin, PwmPin};
+    use crate::BridgePair;
+    use coverage_helper::test;
+    use embedded_hal::digital::StatefulOutputPin;
+
+    #[test]
+    fn test_brake_and_coast() {
+        let pair = BridgePair::new(DigitalPin::new(), DigitalPin::new(), DigitalPin::new());
+        let mut motor = InInMotor::new(pair.full_h());
+
+        motor.forward().unwrap();
+        assert_eq!(motor.speed(), i16::MAX);
+
+        motor.brake().unwrap();
+        assert_eq!(motor.speed(), 0);
+        assert!(pair.first().is_set_high().unwrap());
+        assert!(pair.second().is_set_high().unwrap());
+
+        motor.reverse().unwrap();
+        motor.coast().unwrap();
+        assert!(pair.first().is_enabled().unwrap());
+        assert!(pair.first().is_set_low().unwrap());
+        assert!(pair.second().is_set_low().unwrap());
+        assert_eq!(motor.speed(), 0);
+    }
+
+    #[test]
+    fn test_driver() {
+        let pair = BridgePair::new(PwmPin::new(), PwmPin::new(), DigitalPin::new());
+        let mut motor = InInMotor::new(pair.full_h());
+
+        FullHBridgeDriver::set_speed(&mut motor, -i16::MAX).unwrap();
+        assert_eq!(motor.speed(), -i16::MAX);
+        FullHBridgeDriver::brake(&mut motor).unwrap();
+        assert_eq!(motor.speed(), 0);
+        assert_eq!(pair.first().input().borrow().get_duty_cycle(), u16::MAX);
+        assert_eq!(pair.second().input().borrow().get_duty_cycle(), u16::MAX);
+
+        FullHBridgeDriver::disable(&mut motor).unwrap();
+        assert!(pair.first().is_enabled().unwrap());
+        assert_eq!(pair.first().input().borrow().get_duty_cycle(), 0);
+        assert_eq!(pair.second().input().borrow().get_duty_cycle(), 0);
+    }
+}
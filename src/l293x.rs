@@ -1,10 +1,49 @@
-use core::cell::RefCell;
+use core::cell::{Cell, RefCell};
+use core::convert::Infallible;
 use core::fmt::Debug;
 
-use embedded_hal::digital::{OutputPin, StatefulOutputPin};
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::{InputPin, OutputPin, PinState, StatefulOutputPin};
 use embedded_hal::pwm::SetDutyCycle;
 
-use crate::HalfH;
+use crate::builder::{EmptyL293xBuilder, L293xBuilder};
+use crate::control::FailsafeAction;
+use crate::full_h::duty_to_speed;
+use crate::half_h::{duty_from_fraction, set_duty_tracked, set_state_tracked, InputCommand};
+use crate::motor::DcMotor;
+use crate::pins::Polarized;
+use crate::port::{Port, PortOutput, PortPin};
+#[cfg(feature = "critical-section")]
+use crate::shared_pin::{CsPinCell, CsSharedPin};
+use crate::shared_pin::{PinCell, SharedPin};
+use crate::verify::VerifyError;
+use crate::{
+    AutoEnable, BridgePair, BridgePairState, Channel, ChipError, EmergencyStopError, FullH,
+    FullHBridge, GangedOutput, HalfH, L293xPolarity, L293xState, L293xTelemetry, L293xTransaction,
+    OutputLevel, OutputStateError, OwnedFullHBridge, OwnedHalfH, Polarity, QuadHalfH,
+};
+
+/// Borrow a pin of the chip with its configured polarity applied.
+macro_rules! pin {
+    ($chip:ident . a1) => {
+        Polarized::new($chip.a1.get_mut(), $chip.polarity.inputs[0])
+    };
+    ($chip:ident . a2) => {
+        Polarized::new($chip.a2.get_mut(), $chip.polarity.inputs[1])
+    };
+    ($chip:ident . a3) => {
+        Polarized::new($chip.a3.get_mut(), $chip.polarity.inputs[2])
+    };
+    ($chip:ident . a4) => {
+        Polarized::new($chip.a4.get_mut(), $chip.polarity.inputs[3])
+    };
+    ($chip:ident . en12) => {
+        Polarized::new($chip.en12.get_mut(), $chip.polarity.enable12)
+    };
+    ($chip:ident . en34) => {
+        Polarized::new($chip.en34.get_mut(), $chip.polarity.enable34)
+    };
+}
 
 /// L293 or L293D chip driver
 ///
@@ -58,7 +97,9 @@ use crate::HalfH;
 /// let mut l293x = L293x::new(input1, (), (), (), enable12, ());
 /// ```
 ///
-/// This causes the type to only implement the functions for the matching outputs.
+/// This causes the type to only implement the functions for the matching outputs. Instead of the
+/// empty type, the [NotConnected](crate::pins::NotConnected) marker can be used, which results in
+/// clearer compiler errors.
 ///
 /// ```compile_fail
 /// # use l293x::L293x;
@@ -101,8 +142,23 @@ use crate::HalfH;
 /// split.y1().enable()?;
 /// split.y1().set_high()?;
 /// ```
-
-#[derive(Debug)]
+///
+/// ## Placeholder chips
+///
+/// If all pin types implement [Default], the chip implements [Default] as well. Together with the
+/// static [Vcc](crate::pins::Vcc) and [Gnd](crate::pins::Gnd) pins, or the empty type (`()`), this
+/// allows creating placeholder chips. These can be used to compile and test higher-level code
+/// before the real pins exist, or to stub out optional hardware:
+///
+/// ```
+/// # use l293x::L293x;
+/// use l293x::pins::{Gnd, Vcc};
+///
+/// // All inputs are low and all outputs are enabled
+/// let mut l293x: L293x<Gnd, Gnd, Gnd, Gnd, Vcc, Vcc> = L293x::default();
+/// assert!(l293x.is_y1_set_low()?);
+/// ```
+#[derive(Debug, Default)]
 pub struct L293x<A1, A2, A3, A4, EN12, EN34> {
     a1: RefCell<A1>,
     a2: RefCell<A2>,
@@ -110,6 +166,35 @@ pub struct L293x<A1, A2, A3, A4, EN12, EN34> {
     a4: RefCell<A4>,
     en12: RefCell<EN12>,
     en34: RefCell<EN34>,
+    suspended: Option<[bool; 2]>,
+    commands: [Cell<InputCommand>; 4],
+    polarity: L293xPolarity,
+    failsafe: [FailsafeAction; 2],
+}
+
+#[cfg(feature = "defmt")]
+impl<A1, A2, A3, A4, EN12, EN34> defmt::Format for L293x<A1, A2, A3, A4, EN12, EN34>
+where
+    A1: defmt::Format,
+    A2: defmt::Format,
+    A3: defmt::Format,
+    A4: defmt::Format,
+    EN12: defmt::Format,
+    EN34: defmt::Format,
+{
+    fn format(&self, f: defmt::Formatter) {
+        use crate::format::CellFormat;
+        defmt::write!(
+            f,
+            "L293x {{ a1: {}, a2: {}, a3: {}, a4: {}, en12: {}, en34: {} }}",
+            CellFormat(&self.a1),
+            CellFormat(&self.a2),
+            CellFormat(&self.a3),
+            CellFormat(&self.a4),
+            CellFormat(&self.en12),
+            CellFormat(&self.en34)
+        )
+    }
 }
 
 impl<A1, A2, A3, A4, EN12, EN34> L293x<A1, A2, A3, A4, EN12, EN34> {
@@ -134,27 +219,477 @@ impl<A1, A2, A3, A4, EN12, EN34> L293x<A1, A2, A3, A4, EN12, EN34> {
             a4: RefCell::new(a4),
             en12: RefCell::new(en12),
             en34: RefCell::new(en34),
+            suspended: None,
+            commands: Default::default(),
+            polarity: L293xPolarity::default(),
+            failsafe: Default::default(),
+        }
+    }
+
+    /// Create a new L293x chip driver, whose pins are connected with the given polarities.
+    ///
+    /// The chip inverts the levels of the pins with the [Inverted](crate::Polarity::Inverted)
+    /// polarity, whenever it sets or reads them. Thus, the API of the chip follows the schematic,
+    /// even if some of the pins are connected through inverting stages: E.g.
+    /// [`set_y1_high()`](L293x::set_y1_high) always drives the input 1 of the chip high. The
+    /// polarities are applied by the Half-H bridges, channels, pairs and motors of the chip as
+    /// well. [`into_motors()`](L293x::into_motors) and
+    /// [`into_quad_half_h()`](L293x::into_quad_half_h) wrap the pins into [Polarized] adapters
+    /// for this. Only the pins handed out by [`release()`](L293x::release) are not inverted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use l293x::{L293x, L293xPolarity, Polarity};
+    ///
+    /// let polarity = L293xPolarity {
+    ///     enable12: Polarity::Inverted,
+    ///     ..L293xPolarity::default()
+    /// };
+    /// let mut l293x = L293x::with_polarity(input1, input2, (), (), enable12, (), polarity);
+    /// // Sets `enable12` low, which enables the outputs 1 & 2 of the chip
+    /// l293x.enable_y1_and_y2()?;
+    /// ```
+    #[inline]
+    pub fn with_polarity(
+        a1: A1,
+        a2: A2,
+        a3: A3,
+        a4: A4,
+        en12: EN12,
+        en34: EN34,
+        polarity: L293xPolarity,
+    ) -> Self {
+        Self {
+            polarity,
+            ..Self::new(a1, a2, a3, a4, en12, en34)
         }
     }
 
+    /// Get the polarities of the pins of the chip.
+    #[inline]
+    pub fn polarity(&self) -> L293xPolarity {
+        self.polarity
+    }
+
+    /// Configure the [FailsafeAction] of the loads connected to the outputs 1 & 2 and 3 & 4.
+    ///
+    /// The actions are applied whenever the chip stops its outputs for safety reasons, i.e. by
+    /// [`failsafe()`](L293x::failsafe), [`emergency_stop()`](L293x::emergency_stop) and a
+    /// tripped [DeadmanSwitch](crate::DeadmanSwitch). By default, both loads coast.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use l293x::control::FailsafeAction;
+    /// # use l293x::L293x;
+    ///
+    /// // Brake the lifting axis at the outputs 1 & 2 and let the conveyor at 3 & 4 coast
+    /// let l293x = L293x::new(input1, input2, input3, input4, enable12, enable34)
+    ///     .with_failsafe(FailsafeAction::Brake, FailsafeAction::Coast);
+    /// ```
+    #[inline]
+    pub fn with_failsafe(mut self, action12: FailsafeAction, action34: FailsafeAction) -> Self {
+        self.set_failsafe(action12, action34);
+        self
+    }
+
+    /// Change the [FailsafeAction] of the loads connected to the outputs 1 & 2 and 3 & 4.
+    ///
+    /// See [`with_failsafe()`](L293x::with_failsafe) for details.
+    #[inline]
+    pub fn set_failsafe(&mut self, action12: FailsafeAction, action34: FailsafeAction) {
+        self.failsafe = [action12, action34];
+    }
+
+    /// Get the [FailsafeAction] of the loads connected to the outputs 1 & 2 and 3 & 4.
+    #[inline]
+    pub fn failsafe_actions(&self) -> (FailsafeAction, FailsafeAction) {
+        (self.failsafe[0], self.failsafe[1])
+    }
+
+    /// Release the chip driver and return the input pins and the enable pins.
+    ///
+    /// This allows reconfiguring the pins or handing them over to another driver. The pins are
+    /// returned in their current states.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use l293x::L293x;
+    /// let l293x = L293x::new(input1, input2, input3, input4, enable12, enable34);
+    /// let ((input1, input2, input3, input4), (enable12, enable34)) = l293x.release();
+    /// ```
+    #[inline]
+    #[allow(clippy::type_complexity)]
+    pub fn release(self) -> ((A1, A2, A3, A4), (EN12, EN34)) {
+        (
+            (
+                self.a1.into_inner(),
+                self.a2.into_inner(),
+                self.a3.into_inner(),
+                self.a4.into_inner(),
+            ),
+            (self.en12.into_inner(), self.en34.into_inner()),
+        )
+    }
+
+    /// Create a [L293xBuilder], which assigns the pins of the chip by name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use l293x::L293x;
+    /// let l293x = L293x::builder()
+    ///     .input1(input1)
+    ///     .input2(input2)
+    ///     .enable12(enable12)
+    ///     .enable34(())
+    ///     .build();
+    /// ```
+    #[inline]
+    pub fn builder() -> EmptyL293xBuilder {
+        L293xBuilder::new()
+    }
+
     #[inline]
-    pub fn y1(&self) -> HalfH<A1, EN12> {
+    pub fn y1(&self) -> HalfH<'_, A1, EN12> {
         HalfH::new(&self.a1, &self.en12)
+            .with_command_cache(&self.commands[0])
+            .with_polarity(self.polarity.inputs[0], self.polarity.enable12)
     }
 
     #[inline]
-    pub fn y2(&self) -> HalfH<A2, EN12> {
+    pub fn y2(&self) -> HalfH<'_, A2, EN12> {
         HalfH::new(&self.a2, &self.en12)
+            .with_command_cache(&self.commands[1])
+            .with_polarity(self.polarity.inputs[1], self.polarity.enable12)
     }
 
     #[inline]
-    pub fn y3(&self) -> HalfH<A3, EN34> {
+    pub fn y3(&self) -> HalfH<'_, A3, EN34> {
         HalfH::new(&self.a3, &self.en34)
+            .with_command_cache(&self.commands[2])
+            .with_polarity(self.polarity.inputs[2], self.polarity.enable34)
     }
 
     #[inline]
-    pub fn y4(&self) -> HalfH<A4, EN34> {
+    pub fn y4(&self) -> HalfH<'_, A4, EN34> {
         HalfH::new(&self.a4, &self.en34)
+            .with_command_cache(&self.commands[3])
+            .with_polarity(self.polarity.inputs[3], self.polarity.enable34)
+    }
+}
+
+impl<A1, A2, A3, A4, EN12, EN34> L293x<A1, A2, A3, A4, EN12, EN34> {
+    /// Get a Full-H bridge driving a load connected to the outputs `y1` and `y2`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use l293x::L293x;
+    /// let l293x = L293x::new(input1, input2, (), (), enable12, ());
+    /// let mut motor = l293x.full_h12();
+    ///
+    /// motor.forward()?;
+    /// motor.coast()?;
+    /// ```
+    #[inline]
+    pub fn full_h12(&self) -> FullHBridge<'_, A1, A2, EN12> {
+        FullH::new(
+            self.y1().with_auto_enable(AutoEnable::Always),
+            self.y2().with_auto_enable(AutoEnable::Always),
+        )
+    }
+
+    /// Get a Full-H bridge driving a load connected to the outputs `y3` and `y4`.
+    #[inline]
+    pub fn full_h34(&self) -> FullHBridge<'_, A3, A4, EN34> {
+        FullH::new(
+            self.y3().with_auto_enable(AutoEnable::Always),
+            self.y4().with_auto_enable(AutoEnable::Always),
+        )
+    }
+
+    /// Drive the outputs `y1` and `y2` in parallel to double the output current.
+    ///
+    /// The returned [GangedOutput] exclusively borrows the inputs 1 & 2 and their common enable
+    /// pin and guarantees, that both inputs never diverge. The outputs `y1` and `y2` can not be
+    /// accessed otherwise, while the ganged output is in use.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use l293x::L293x;
+    /// let mut l293x = L293x::new(input1, input2, (), (), enable12, ());
+    /// let mut output = l293x.gang_y1_and_y2();
+    ///
+    /// output.set_high()?;
+    /// ```
+    #[inline]
+    pub fn gang_y1_and_y2(&mut self) -> GangedOutput<'_, A1, A2, EN12> {
+        self.commands[0].set(InputCommand::Unknown);
+        self.commands[1].set(InputCommand::Unknown);
+        GangedOutput::new(pin!(self.a1), pin!(self.a2), pin!(self.en12))
+    }
+
+    /// Drive the outputs `y3` and `y4` in parallel to double the output current.
+    ///
+    /// See [`gang_y1_and_y2()`](L293x::gang_y1_and_y2) for details.
+    #[inline]
+    pub fn gang_y3_and_y4(&mut self) -> GangedOutput<'_, A3, A4, EN34> {
+        self.commands[2].set(InputCommand::Unknown);
+        self.commands[3].set(InputCommand::Unknown);
+        GangedOutput::new(pin!(self.a3), pin!(self.a4), pin!(self.en34))
+    }
+
+    /// Split the chip into four independently usable Half-H bridges.
+    ///
+    /// This consumes the chip and returns four [OwnedHalfH] bridges, which own their input pins.
+    /// The enable pins are moved into the given cells and shared between the corresponding
+    /// channels using [SharedPin]s. In contrast to the [`y1()`](L293x::y1) - [`y4()`](L293x::y4)
+    /// methods, the channels do not borrow the chip, and thus, can be moved into different tasks or
+    /// passed by value to other drivers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use l293x::L293x;
+    /// use l293x::shared_pin::PinCell;
+    ///
+    /// let (mut en12, mut en34) = (PinCell::new(), PinCell::new());
+    /// # let l293x = L293x::new(input1, input2, input3, input4, enable12, enable34);
+    /// let (y1, y2, y3, y4) = l293x.into_channels(&mut en12, &mut en34);
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn into_channels<'a>(
+        self,
+        en12: &'a mut PinCell<EN12>,
+        en34: &'a mut PinCell<EN34>,
+    ) -> (
+        OwnedHalfH<A1, SharedPin<'a, EN12>>,
+        OwnedHalfH<A2, SharedPin<'a, EN12>>,
+        OwnedHalfH<A3, SharedPin<'a, EN34>>,
+        OwnedHalfH<A4, SharedPin<'a, EN34>>,
+    ) {
+        let en12 = en12.share(self.en12.into_inner());
+        let en34 = en34.share(self.en34.into_inner());
+        let [p1, p2, p3, p4] = self.polarity.inputs;
+        let (p12, p34) = (self.polarity.enable12, self.polarity.enable34);
+        (
+            OwnedHalfH::new(self.a1.into_inner(), en12).with_polarity(p1, p12),
+            OwnedHalfH::new(self.a2.into_inner(), en12).with_polarity(p2, p12),
+            OwnedHalfH::new(self.a3.into_inner(), en34).with_polarity(p3, p34),
+            OwnedHalfH::new(self.a4.into_inner(), en34).with_polarity(p4, p34),
+        )
+    }
+
+    /// Convert the chip into a generic driver of four Half-H bridges.
+    ///
+    /// The L293 chip is the special case of a [QuadHalfH] driver, where the bridges 1 & 2 and the
+    /// bridges 3 & 4 share a common enable pin. This method moves the enable pins into the given
+    /// cells and passes the same [SharedPin] to both bridges of each pair. This allows code
+    /// written for the [QuadHalfH] driver (e.g. for SN754410-like chips or discrete boards with
+    /// four enable pins) to drive a L293 chip as well.
+    ///
+    /// All pins are wrapped into [Polarized] adapters, which apply the
+    /// [polarities](L293x::with_polarity) configured for the chip.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use l293x::L293x;
+    /// use l293x::shared_pin::PinCell;
+    ///
+    /// let (mut en12, mut en34) = (PinCell::new(), PinCell::new());
+    /// # let l293x = L293x::new(input1, input2, input3, input4, enable12, enable34);
+    /// let quad = l293x.into_quad_half_h(&mut en12, &mut en34);
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn into_quad_half_h<'a>(
+        self,
+        en12: &'a mut PinCell<EN12>,
+        en34: &'a mut PinCell<EN34>,
+    ) -> QuadHalfH<
+        Polarized<A1>,
+        Polarized<A2>,
+        Polarized<A3>,
+        Polarized<A4>,
+        Polarized<SharedPin<'a, EN12>>,
+        Polarized<SharedPin<'a, EN12>>,
+        Polarized<SharedPin<'a, EN34>>,
+        Polarized<SharedPin<'a, EN34>>,
+    > {
+        let [p1, p2, p3, p4] = self.polarity.inputs;
+        let en12 = Polarized::new(en12.share(self.en12.into_inner()), self.polarity.enable12);
+        let en34 = Polarized::new(en34.share(self.en34.into_inner()), self.polarity.enable34);
+        QuadHalfH::new(
+            Polarized::new(self.a1.into_inner(), p1),
+            Polarized::new(self.a2.into_inner(), p2),
+            Polarized::new(self.a3.into_inner(), p3),
+            Polarized::new(self.a4.into_inner(), p4),
+            en12,
+            en12,
+            en34,
+            en34,
+        )
+    }
+
+    /// Convert the chip into two owned Full-H bridges.
+    ///
+    /// The first bridge drives a load connected to the outputs `y1` and `y2`, the second one a
+    /// load connected to the outputs `y3` and `y4`. Like for the
+    /// [`into_channels()`](L293x::into_channels) method, the enable pins are moved into the given
+    /// cells and shared between the half-bridges of each Full-H bridge.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use l293x::L293x;
+    /// use l293x::shared_pin::PinCell;
+    ///
+    /// let (mut en12, mut en34) = (PinCell::new(), PinCell::new());
+    /// # let l293x = L293x::new(input1, input2, input3, input4, enable12, enable34);
+    /// let (mut left, mut right) = l293x.into_full_bridges(&mut en12, &mut en34);
+    ///
+    /// left.forward()?;
+    /// right.coast()?;
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn into_full_bridges<'a>(
+        self,
+        en12: &'a mut PinCell<EN12>,
+        en34: &'a mut PinCell<EN34>,
+    ) -> (
+        OwnedFullHBridge<'a, A1, A2, EN12>,
+        OwnedFullHBridge<'a, A3, A4, EN34>,
+    ) {
+        let (y1, y2, y3, y4) = self.into_channels(en12, en34);
+        let always = AutoEnable::Always;
+        (
+            FullH::new(y1.with_auto_enable(always), y2.with_auto_enable(always)),
+            FullH::new(y3.with_auto_enable(always), y4.with_auto_enable(always)),
+        )
+    }
+
+    /// Convert the chip into two DC motor drivers.
+    ///
+    /// The first motor is driven by the inputs 1 & 2 and the enable pin 1 & 2, while the second
+    /// motor is driven by the inputs 3 & 4 and the enable pin 3 & 4. This matches the common
+    /// wiring of two-wheeled robots.
+    ///
+    /// All pins are wrapped into [Polarized] adapters, which apply the
+    /// [polarities](L293x::with_polarity) configured for the chip. Thus, the motors are enabled
+    /// and driven correctly, even if some pins are connected through inverting stages.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use l293x::L293x;
+    /// let l293x = L293x::new(pwm1, pwm2, pwm3, pwm4, enable12, enable34);
+    /// let (mut left, mut right) = l293x.into_motors();
+    ///
+    /// left.set_speed(i16::MAX)?;
+    /// right.set_speed(-i16::MAX)?;
+    /// ```
+    #[inline]
+    #[allow(clippy::type_complexity)]
+    pub fn into_motors(
+        self,
+    ) -> (
+        DcMotor<Polarized<A1>, Polarized<A2>, Polarized<EN12>>,
+        DcMotor<Polarized<A3>, Polarized<A4>, Polarized<EN34>>,
+    ) {
+        let [p1, p2, p3, p4] = self.polarity.inputs;
+        (
+            DcMotor::new(
+                Polarized::new(self.a1.into_inner(), p1),
+                Polarized::new(self.a2.into_inner(), p2),
+                Polarized::new(self.en12.into_inner(), self.polarity.enable12),
+            ),
+            DcMotor::new(
+                Polarized::new(self.a3.into_inner(), p3),
+                Polarized::new(self.a4.into_inner(), p4),
+                Polarized::new(self.en34.into_inner(), self.polarity.enable34),
+            ),
+        )
+    }
+
+    /// Split the chip into four independently usable, interrupt-safe Half-H bridges.
+    ///
+    /// This is the same as [`into_channels()`](L293x::into_channels), but shares the enable pins
+    /// using [CsSharedPin]s. Because the cells can be placed in `static`s, the channels can be
+    /// moved into interrupt handlers or different tasks.
+    ///
+    /// This method is only available, if the `critical-section` feature is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use l293x::L293x;
+    /// use l293x::shared_pin::CsPinCell;
+    ///
+    /// static ENABLE12: CsPinCell<Enable12Pin> = CsPinCell::new();
+    /// static ENABLE34: CsPinCell<Enable34Pin> = CsPinCell::new();
+    ///
+    /// # let l293x = L293x::new(input1, input2, input3, input4, enable12, enable34);
+    /// let (y1, y2, y3, y4) = l293x.into_cs_channels(&ENABLE12, &ENABLE34);
+    /// ```
+    #[cfg(feature = "critical-section")]
+    #[allow(clippy::type_complexity)]
+    pub fn into_cs_channels<'a>(
+        self,
+        en12: &'a CsPinCell<EN12>,
+        en34: &'a CsPinCell<EN34>,
+    ) -> (
+        OwnedHalfH<A1, CsSharedPin<'a, EN12>>,
+        OwnedHalfH<A2, CsSharedPin<'a, EN12>>,
+        OwnedHalfH<A3, CsSharedPin<'a, EN34>>,
+        OwnedHalfH<A4, CsSharedPin<'a, EN34>>,
+    ) {
+        let en12 = en12.share(self.en12.into_inner());
+        let en34 = en34.share(self.en34.into_inner());
+        let [p1, p2, p3, p4] = self.polarity.inputs;
+        let (p12, p34) = (self.polarity.enable12, self.polarity.enable34);
+        (
+            OwnedHalfH::new(self.a1.into_inner(), en12).with_polarity(p1, p12),
+            OwnedHalfH::new(self.a2.into_inner(), en12).with_polarity(p2, p12),
+            OwnedHalfH::new(self.a3.into_inner(), en34).with_polarity(p3, p34),
+            OwnedHalfH::new(self.a4.into_inner(), en34).with_polarity(p4, p34),
+        )
+    }
+
+    /// Split the chip into its two pairs of Half-H bridges.
+    ///
+    /// This consumes the chip and returns two [BridgePair]s: The first one owns the channels
+    /// 1 & 2 and their enable pin, the second one the channels 3 & 4 and their enable pin. In
+    /// contrast to the [`y1()`](L293x::y1) - [`y4()`](L293x::y4) methods, the pairs do not borrow
+    /// the chip, and thus, can be moved into different tasks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use l293x::L293x;
+    /// # let l293x = L293x::new(input1, input2, input3, input4, enable12, enable34);
+    /// let (pair12, pair34) = l293x.split_pairs();
+    /// ```
+    pub fn split_pairs(self) -> (BridgePair<A1, A2, EN12>, BridgePair<A3, A4, EN34>) {
+        (
+            BridgePair::from_parts(
+                self.a1,
+                self.a2,
+                self.en12,
+                [self.polarity.inputs[0], self.polarity.inputs[1]],
+                self.polarity.enable12,
+            ),
+            BridgePair::from_parts(
+                self.a3,
+                self.a4,
+                self.en34,
+                [self.polarity.inputs[2], self.polarity.inputs[3]],
+                self.polarity.enable34,
+            ),
+        )
     }
 }
 
@@ -174,7 +709,7 @@ where
     /// of the Half-H-Bridges 1 & 2. The concrete error type returned depends
     /// on the [OutputPin](embedded_hal::digital::OutputPin) used.
     pub fn enable_y1_and_y2(&mut self) -> Result<(), EN12::Error> {
-        self.en12.get_mut().set_high()
+        pin!(self.en12).set_high()
     }
 
     /// Disable the output channels 1 & 2.
@@ -183,13 +718,18 @@ where
     /// enable pin. This forces the output channels 1 & 2 to a low level,
     /// independent of the level of the corresponding inputs.
     ///
+    /// The inputs are not changed. Thus, the outputs return to their previous levels, when they
+    /// are enabled again. To avoid this, use
+    /// [L293x::disable_y1_and_y2_holding_low()] or
+    /// [L293x::disable_y1_and_y2_duty_cycle_off()] instead.
+    ///
     /// # Errors
     ///
     /// This function will return the error of the common enable pin
     /// of the Half-H-Bridges 1 & 2. The concrete error type returned depends
     /// on the [OutputPin](embedded_hal::digital::OutputPin) used.
     pub fn disable_y1_and_y2(&mut self) -> Result<(), EN12::Error> {
-        self.en12.get_mut().set_low()
+        pin!(self.en12).set_low()
     }
 }
 
@@ -209,7 +749,7 @@ where
     /// of the Half-H-Bridges 3 & 4. The concrete error type returned depends
     /// on the [OutputPin](embedded_hal::digital::OutputPin) used.
     pub fn enable_y3_and_y4(&mut self) -> Result<(), EN34::Error> {
-        self.en34.get_mut().set_high()
+        pin!(self.en34).set_high()
     }
 
     /// Disable the output channels 3 & 4.
@@ -218,13 +758,18 @@ where
     /// enable pin. This forces the output channels 3 & 4 to a low level,
     /// independent of the level of the corresponding inputs.
     ///
+    /// The inputs are not changed. Thus, the outputs return to their previous levels, when they
+    /// are enabled again. To avoid this, use
+    /// [L293x::disable_y3_and_y4_holding_low()] or
+    /// [L293x::disable_y3_and_y4_duty_cycle_off()] instead.
+    ///
     /// # Errors
     ///
     /// This function will return the error of the common enable pin
     /// of the Half-H-Bridges 3 & 4. The concrete error type returned depends
     /// on the [OutputPin](embedded_hal::digital::OutputPin) used.
     pub fn disable_y3_and_y4(&mut self) -> Result<(), EN34::Error> {
-        self.en34.get_mut().set_low()
+        pin!(self.en34).set_low()
     }
 }
 
@@ -257,7 +802,7 @@ where
     /// assert!(l293x.y1_and_y2_enabled().unwrap());
     /// ```
     pub fn y1_and_y2_enabled(&mut self) -> Result<bool, EN12::Error> {
-        self.en12.get_mut().is_set_high()
+        pin!(self.en12).is_set_high()
     }
 
     /// Check whether the output channels 1 & 2 are disabled.
@@ -285,7 +830,7 @@ where
     /// assert!(l293x.y1_and_y2_disabled().unwrap());
     /// ```
     pub fn y1_and_y2_disabled(&mut self) -> Result<bool, EN12::Error> {
-        self.en12.get_mut().is_set_low()
+        pin!(self.en12).is_set_low()
     }
 }
 
@@ -318,7 +863,7 @@ where
     /// assert!(l293x.y3_and_y4_enabled().unwrap());
     /// ```
     pub fn y3_and_y4_enabled(&mut self) -> Result<bool, EN34::Error> {
-        self.en34.get_mut().is_set_high()
+        pin!(self.en34).is_set_high()
     }
 
     /// Check whether the output channels 3 & 4 are disabled.
@@ -346,343 +891,1107 @@ where
     /// assert!(l293x.y3_and_y4_disabled().unwrap());
     /// ```
     pub fn y3_and_y4_disabled(&mut self) -> Result<bool, EN34::Error> {
-        self.en34.get_mut().is_set_low()
+        pin!(self.en34).is_set_low()
     }
 }
 
-macro_rules! output_pin_impl {
-    ($output:ident, $input:ident, $type_:ty) => {
-        paste::item! {
-            impl<A1, A2, A3, A4, EN12, EN34> L293x<A1, A2, A3, A4, EN12, EN34>
-            where
-                $type_: OutputPin,
-            {
-                #[doc = "Set the output " $output " high"]
-                ///
-                /// # Note
-                ///
-                #[doc = "This function sets the input of the output channel " $output]
-                /// to high.
-                ///
-                /// In contrast to the [`set_high`](HalfH::set_high) method of the
-                /// [`$output()`](L293x::$output), this method does **not** enable the output.
-                ///
-                /// For the output to actually become "high", the corresponding
-                /// output channel needs to be enabled as well either using the
-                /// [L293x::enable_y1_and_y2()] or the [L293x::enable_y3_and_y4()]
-                /// method.
-                ///
-                /// If the channel is disabled, the output will remain in high
-                /// impendance state and the state of the output is depending on the
-                /// components connected to it.
-                ///
-                /// This is useful, if the states of the two outputs sharing an enable pin need
-                /// to change states together i.e. to avoid high currencies in circuit.
-                /// In this case, the outputs can be disabled first, the new states of the outputs
-                /// set and afterwards, both outputs can be enabled again together.
-                ///
-                /// # Errors
-                ///
-                /// In case of an error, while setting the input "high", this method
-                /// returns the error of the corresponding input pin. The actual type
-                /// of error returned depends on the type of the input pin used.
-                pub fn [< set_ $output _high >](
-                    &mut self
-                ) -> Result<(), $type_::Error> {
-                    self.$input.get_mut().set_high()
-                }
+/// Return the first error of the given results.
+fn first_error<E, const N: usize>(results: [Result<(), E>; N]) -> Result<(), E> {
+    results.into_iter().collect()
+}
 
-                #[doc = "Set the output " $output " low"]
-                ///
-                /// # Note
-                ///
-                #[doc = "This function sets the input of the output channel " $output]
-                /// to low.
-                ///
-                /// In contrast to the [`set_low`](HalfH::set_high) method of the
-                /// [`$output()`](L293x::$output), this method does **not** enable the output.
-                ///
-                /// For the output to actually become "low", the corresponding
-                /// output channel needs to be enabled as well either using the
-                /// [L293x::enable_y1_and_y2()] or the [L293x::enable_y3_and_y4()]
-                /// method.
-                ///
-                /// If the channel is disabled, the output will remain in high
-                /// impendance state and the state of the output is depending on the
-                /// components connected to it.
-                ///
-                /// This is useful, if the states of the two outputs sharing an enable pin need
-                /// to change states together i.e. to avoid high currencies in circuit.
-                /// In this case, the outputs can be disabled first, the new states of the outputs
-                /// set and afterwards, both outputs can be enabled again together.
-                ///
-                /// # Errors
-                ///
-                /// In case of an error, while setting the input "low", this method
-                /// returns the error of the corresponding input pin. The actual type
-                /// of error returned depends on the type of the input pin used.
-                pub fn [< set_ $output _low >](
-                    &mut self
-                ) -> Result<(), $type_::Error> {
-                    self.$input.get_mut().set_low()
-                }
+impl<A1, A2, A3, A4, EN12, EN34> L293x<A1, A2, A3, A4, EN12, EN34>
+where
+    EN12: OutputPin,
+    EN34: OutputPin<Error = EN12::Error>,
+{
+    /// Enable all four output channels.
+    ///
+    /// Both enable pins are set high, even if setting the first one fails.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the first error of the enable pins.
+    pub fn enable_all(&mut self) -> Result<(), EN12::Error> {
+        first_error([self.enable_y1_and_y2(), self.enable_y3_and_y4()])
+    }
 
-                #[doc = "Set the state of output " $output]
-                ///
-                /// # Note
-                ///
-                /// This function sets state of the input for output channel
-                #[doc = $output "."]
-                ///
-                /// In contrast to the [`set_state`](HalfH::set_state) method of the
-                /// [`$output()`](L293x::$output), this method does **not** enable the output.
-                ///
-                /// For the output to actually take the given state, the corresponding
-                /// output channel needs to be enabled as well either using the
-                /// [L293x::enable_y1_and_y2()] or the [L293x::enable_y3_and_y4()]
-                /// method.
-                ///
-                /// If the channel is disabled, the output will remain in high
-                /// impendance state and the state of the output is depending on the
-                /// components connected to it.
-                ///
-                /// This is useful, if the states of the two outputs sharing an enable pin need
-                /// to change states together i.e. to avoid high currencies in circuit.
-                /// In this case, the outputs can be disabled first, the new states of the outputs
-                /// set and afterwards, both outputs can be enabled again together.
-                ///
-                /// # Errors
-                ///
-                /// In case of an error, while setting the input state, this method
-                /// returns the error of the corresponding input pin. The actual type
-                /// of error returned depends on the type of the input pin used.
-                pub fn [< set_ $output _state >](
-                    &mut self,
-                    state: embedded_hal::digital::PinState
-                ) -> Result<(), $type_::Error> {
-                    self.$input.get_mut().set_state(state)
-                }
-            }
+    /// Disable all four output channels.
+    ///
+    /// This sets all outputs into the high impedance mode, e.g. for an emergency stop. Both enable
+    /// pins are set low, even if setting the first one fails.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the first error of the enable pins.
+    pub fn disable_all(&mut self) -> Result<(), EN12::Error> {
+        first_error([self.disable_y1_and_y2(), self.disable_y3_and_y4()])
+    }
+}
+
+impl<A1, A2, A3, A4, EN12, EN34> L293x<A1, A2, A3, A4, EN12, EN34>
+where
+    A1: OutputPin,
+    A2: OutputPin<Error = A1::Error>,
+    A3: OutputPin<Error = A1::Error>,
+    A4: OutputPin<Error = A1::Error>,
+    EN12: OutputPin,
+    EN34: OutputPin<Error = EN12::Error>,
+{
+    /// Create a new L293x chip driver and drive all pins into a known, safe state.
+    ///
+    /// In contrast to the [`new()`](L293x::new) method, this sets both enable pins low first and
+    /// all inputs low afterwards. Thus, the chip starts with all outputs in the high impedance
+    /// mode, regardless of the previous levels of the pins.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the first pin, which could not be set. The pins
+    /// are dropped in this case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use l293x::L293x;
+    /// let l293x = L293x::new_initialized(input1, input2, input3, input4, enable12, enable34)?;
+    /// ```
+    pub fn new_initialized(
+        a1: A1,
+        a2: A2,
+        a3: A3,
+        a4: A4,
+        en12: EN12,
+        en34: EN34,
+    ) -> Result<Self, OutputStateError<A1::Error, EN12::Error>> {
+        let mut chip = Self::new(a1, a2, a3, a4, en12, en34);
+        chip.en12
+            .get_mut()
+            .set_low()
+            .map_err(OutputStateError::EnablePinError)?;
+        chip.en34
+            .get_mut()
+            .set_low()
+            .map_err(OutputStateError::EnablePinError)?;
+        chip.set_all_low()
+            .map_err(OutputStateError::InputPinError)?;
+        Ok(chip)
+    }
+}
+
+impl<A1, A2, A3, A4, EN12, EN34> L293x<A1, A2, A3, A4, EN12, EN34>
+where
+    A1: OutputPin,
+    A2: OutputPin<Error = A1::Error>,
+    A3: OutputPin<Error = A1::Error>,
+    A4: OutputPin<Error = A1::Error>,
+{
+    /// Set all four inputs low.
+    ///
+    /// The enable pins are not changed. All inputs are set low, even if setting one of them
+    /// fails.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the first error of the input pins.
+    pub fn set_all_low(&mut self) -> Result<(), A1::Error> {
+        first_error([
+            self.set_y1_low(),
+            self.set_y2_low(),
+            self.set_y3_low(),
+            self.set_y4_low(),
+        ])
+    }
+}
+
+impl<A1, A2, A3, A4, EN12, EN34> L293x<A1, A2, A3, A4, EN12, EN34>
+where
+    A1: SetDutyCycle,
+    A2: SetDutyCycle<Error = A1::Error>,
+    A3: SetDutyCycle<Error = A1::Error>,
+    A4: SetDutyCycle<Error = A1::Error>,
+{
+    /// Set the duty cycles of all four inputs fully off.
+    ///
+    /// The enable pins are not changed. All duty cycles are set, even if setting one of them
+    /// fails.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the first error of the input pins.
+    pub fn set_all_duty_cycle_fully_off(&mut self) -> Result<(), A1::Error> {
+        first_error([
+            self.set_y1_duty_cycle_fully_off(),
+            self.set_y2_duty_cycle_fully_off(),
+            self.set_y3_duty_cycle_fully_off(),
+            self.set_y4_duty_cycle_fully_off(),
+        ])
+    }
+}
+
+impl<A1, A2, A3, A4, EN12, EN34> L293x<A1, A2, A3, A4, EN12, EN34>
+where
+    A1: OutputPin,
+    A2: OutputPin<Error = A1::Error>,
+    A3: OutputPin<Error = A1::Error>,
+    A4: OutputPin<Error = A1::Error>,
+    EN12: OutputPin,
+    EN34: OutputPin<Error = EN12::Error>,
+{
+    /// Stop all outputs of the chip as safely as possible.
+    ///
+    /// This sets both enable pins low first and all inputs low afterwards. In contrast to the
+    /// other methods of the chip, which abort on the first error, every pin is set, even if
+    /// setting an earlier one failed.
+    ///
+    /// The pairs, whose [FailsafeAction] is [Brake](FailsafeAction::Brake) or
+    /// [RampDown](FailsafeAction::RampDown) (see [`with_failsafe()`](L293x::with_failsafe)),
+    /// are enabled again at the end, which brakes their loads. An emergency stop does not wait,
+    /// so the loads are braked immediately instead of being ramped down. Use
+    /// [`failsafe()`](L293x::failsafe) to ramp them down.
+    ///
+    /// # Errors
+    ///
+    /// If any of the pins could not be set, an [EmergencyStopError] is returned, which lists the
+    /// errors of all pins, which failed.
+    pub fn emergency_stop(&mut self) -> Result<(), EmergencyStopError<A1::Error, EN12::Error>> {
+        let mut error = EmergencyStopError {
+            enable12: pin!(self.en12).set_low().err(),
+            enable34: pin!(self.en34).set_low().err(),
+            inputs: [
+                self.set_y1_low().err(),
+                self.set_y2_low().err(),
+                self.set_y3_low().err(),
+                self.set_y4_low().err(),
+            ],
+        };
+        self.brake_after_emergency_stop(&mut error);
+        match error.any_failed() {
+            true => Err(error),
+            false => Ok(()),
         }
-    };
+    }
 }
-output_pin_impl!(y1, a1, A1);
-output_pin_impl!(y2, a2, A2);
-output_pin_impl!(y3, a3, A3);
-output_pin_impl!(y4, a4, A4);
 
-macro_rules! stateful_output_pin_impl {
-    ($output:ident, $type_:ty, $enable_ty:ty) => {
-        paste::item! {
-            impl<A1, A2, A3, A4, EN12, EN34> L293x<A1, A2, A3, A4, EN12, EN34>
-            where
-                $type_: StatefulOutputPin,
-                $enable_ty: StatefulOutputPin,
-            {
-                #[doc = "Check if output " $output " is set high"]
-                ///
-                /// The output of a L293x chip is high, only the output is enabled and
-                /// the if the corresponding input is high.
-                ///
-                /// # Note
-                ///
-                /// Please note, that this method does not check the electrical level of
-                /// the pin, but uses an internal state instead. The electrical level of
-                /// the pin may vary due to the layout of the circuit.
-                ///
-                /// # Errors
-                ///
-                /// If the output is disabled, it is neither high, nor low but remains
-                /// in an high impendance state und thus, its electrical level depends
-                /// on the components connected to it. In this case, a
-                /// [OutputStateError::NotEnabled] error will be returned.
-                ///
-                /// In case of an error, while reading the state of the enable pin,
-                /// this method will return an [OutputStateError::EnablePinError]
-                /// with the actual error of the enable pin returned.
-                ///
-                /// If an error occurs while reading the state of the input pin, an
-                /// [OutputStateError::InputPinError] with the error of the input pin will be
-                /// returned instead.
-                pub fn [< is_ $output _set_high >](
-                    &mut self
-                ) -> Result<bool, <crate::half_h::HalfH<$type_, $enable_ty> as embedded_hal::digital::ErrorType>::Error> {
-                    self.$output().is_set_high()
-                }
+impl<A1, A2, A3, A4, EN12, EN34> L293x<A1, A2, A3, A4, EN12, EN34>
+where
+    EN12: OutputPin,
+    EN34: OutputPin<Error = EN12::Error>,
+{
+    /// Enable the pairs, which brake their loads on an emergency stop.
+    ///
+    /// Pairs, whose inputs could not be set low, stay disabled.
+    fn brake_after_emergency_stop<I>(&mut self, error: &mut EmergencyStopError<I, EN12::Error>) {
+        let brake12 = self.failsafe[0] != FailsafeAction::Coast
+            && error.enable12.is_none()
+            && error.inputs[..2].iter().all(Option::is_none);
+        let brake34 = self.failsafe[1] != FailsafeAction::Coast
+            && error.enable34.is_none()
+            && error.inputs[2..].iter().all(Option::is_none);
+        if brake12 {
+            error.enable12 = pin!(self.en12).set_high().err();
+        }
+        if brake34 {
+            error.enable34 = pin!(self.en34).set_high().err();
+        }
+    }
+}
 
-                #[doc = "Check if output " $output " is set low"]
-                ///
-                /// The output of a L293x chip is low, only the output is enabled and
-                /// the if the corresponding input is low.
-                ///
-                /// # Note
-                ///
-                /// Please note, that this method does not check the electrical level of
-                /// the pin, but uses an internal state instead. The electrical level of
-                /// the pin may vary due to the layout of the circuit.
-                ///
-                /// # Errors
-                ///
-                /// If the output is disabled, it is neither high, nor low but remains
-                /// in an high impendance state und thus, its electrical level depends
-                /// on the components connected to it. In this case, a
-                /// [OutputStateError::NotEnabled] error will be returned.
-                ///
-                /// In case of an error, while reading the state of the enable pin, this method
-                /// will return an [OutputStateError::EnablePinError]
-                /// with the actual error of the enable pin returned.
-                ///
-                /// If an error occurs while reading the state of the input pin, an
-                /// [OutputStateError::InputPinError] with the error of the input pin will be
-                /// returned instead.
-                pub fn [< is_ $output _set_low >](
-                    &mut self
-                ) -> Result<bool, <crate::half_h::HalfH<$type_, $enable_ty> as embedded_hal::digital::ErrorType>::Error> {
-                    self.$output().is_set_low()
-                }
+impl<A1, A2, A3, A4, EN12, EN34> L293x<A1, A2, A3, A4, EN12, EN34>
+where
+    A1: SetDutyCycle,
+    A2: SetDutyCycle<Error = A1::Error>,
+    A3: SetDutyCycle<Error = A1::Error>,
+    A4: SetDutyCycle<Error = A1::Error>,
+    EN12: OutputPin,
+    EN34: OutputPin<Error = EN12::Error>,
+{
+    /// Stop all outputs of a chip with PWM inputs as safely as possible.
+    ///
+    /// This works like the [`emergency_stop()`](L293x::emergency_stop) method, but sets the
+    /// duty cycles of the inputs fully off instead of driving them low.
+    ///
+    /// # Errors
+    ///
+    /// If any of the pins could not be set, an [EmergencyStopError] is returned, which lists the
+    /// errors of all pins, which failed.
+    pub fn emergency_stop_duty_cycle(
+        &mut self,
+    ) -> Result<(), EmergencyStopError<A1::Error, EN12::Error>> {
+        let mut error = EmergencyStopError {
+            enable12: pin!(self.en12).set_low().err(),
+            enable34: pin!(self.en34).set_low().err(),
+            inputs: [
+                self.set_y1_duty_cycle_fully_off().err(),
+                self.set_y2_duty_cycle_fully_off().err(),
+                self.set_y3_duty_cycle_fully_off().err(),
+                self.set_y4_duty_cycle_fully_off().err(),
+            ],
+        };
+        self.brake_after_emergency_stop(&mut error);
+        match error.any_failed() {
+            true => Err(error),
+            false => Ok(()),
+        }
+    }
 
-                #[doc = "Toggle the state of output " $output]
-                ///
-                /// If the pin is considered high, it will be set to low and vice versa.
-                ///
-                /// # Note
-                ///
-                /// This method toggles the state of the corresponding input channel,
-                /// but does *not* enable the output channel, if it is disabled,
-                /// to avoid side effects. Thus, for the output
-                /// to actually toggle, the corresponding output needs to be enabled
-                /// using either the [L293x::enable_y1_and_y2()] or
-                /// [L293x::enable_y3_and_y4()] method.
-                ///
-                /// # Errors
-                ///
-                /// If the output is disabled, it is neither high, nor low but remains
-                /// in an high impendance state und thus, its electrical level depends
-                /// on the components connected to it. In this case, the output cannot be toggled
-                /// and a [OutputStateError::NotEnabled] error will be returned.
-                ///
-                /// If an error occurs while toggling the state of the input pin, the
-                /// error of the input pin will be returned. The actual type of error
-                /// depends on the type of input pin used.
-                pub fn [< toggle_ $output >](
-                    &mut self
-                ) -> Result<(), <crate::half_h::HalfH<$type_, $enable_ty> as embedded_hal::digital::ErrorType>::Error> {
-                    self.$output().toggle()
-                }
-            }
+    /// Stop the loads connected to the outputs 1 & 2 and 3 & 4 using their [FailsafeAction].
+    ///
+    /// The actions are configured using [`with_failsafe()`](L293x::with_failsafe) and applied
+    /// to both pairs using [`FullH::failsafe()`]. A [RampDown](FailsafeAction::RampDown) starts
+    /// at the last duty cycles commanded on the outputs (see
+    /// [`y1_duty_cycle()`](L293x::y1_duty_cycle)) and blocks for its duration. The pairs are
+    /// stopped one after the other.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the first error of the pins. The second pair is stopped, even
+    /// if stopping the first one failed.
+    pub fn failsafe<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<(), OutputStateError<A1::Error, EN12::Error>> {
+        self.apply_failsafe(self.failsafe, delay)
+    }
+
+    /// Stop the loads connected to the outputs 1 & 2 and 3 & 4 using the given actions.
+    pub(crate) fn apply_failsafe<D: DelayNs>(
+        &mut self,
+        [action12, action34]: [FailsafeAction; 2],
+        delay: &mut D,
+    ) -> Result<(), OutputStateError<A1::Error, EN12::Error>> {
+        let speed12 = self.cached_speed(0, self.y1_max_duty_cycle(), self.y2_max_duty_cycle());
+        let speed34 = self.cached_speed(2, self.y3_max_duty_cycle(), self.y4_max_duty_cycle());
+        let mut pair12 = self.full_h12();
+        pair12.record_speed(speed12);
+        let result12 = pair12.failsafe(action12, delay);
+        let mut pair34 = self.full_h34();
+        pair34.record_speed(speed34);
+        let result34 = pair34.failsafe(action34, delay);
+        first_error([result12, result34])
+    }
+
+    /// Get the speed of the load connected to the outputs `index` and `index + 1` from the last
+    /// duty cycles commanded on them.
+    fn cached_speed(&self, index: usize, max_a: u16, max_b: u16) -> i16 {
+        match (
+            self.commands[index].get().duty(),
+            self.commands[index + 1].get().duty(),
+        ) {
+            (Some(a), Some(b)) if a >= b => duty_to_speed(a - b, max_a),
+            (Some(a), Some(b)) => -duty_to_speed(b - a, max_b),
+            _ => 0,
         }
-    };
+    }
 }
-stateful_output_pin_impl!(y1, A1, EN12);
-stateful_output_pin_impl!(y2, A2, EN12);
-stateful_output_pin_impl!(y3, A3, EN34);
-stateful_output_pin_impl!(y4, A4, EN34);
 
-macro_rules! pwm_pin_impl {
-    ($output:ident, $input:ident, $type_:ty) => {
-        paste::item! {
-            impl<A1, A2, A3, A4, EN12, EN34> L293x<A1, A2, A3, A4, EN12, EN34>
-            where
-                $type_: SetDutyCycle,
-            {
-                #[doc = "Get the max duty value of output " $output]
-                ///
-                /// This method returns the maximum value, that can be used in the
-                #[doc = "[L293x::set_" $output "_duty_cycle()]"]
-                /// method.
-                ///
-                /// # Examples
-                ///
-                /// ```
-                #[doc = "let max_duty = l293x." $output "_max_duty_cycle();"]
-                #[doc = "l293x.set_" $output "_duty_cycle(max_duty).unwrap();"]
-                /// ```
-                pub fn [< $output _max_duty_cycle >](&self) -> u16 {
-                    self.$input.borrow().max_duty_cycle()
-                }
+impl<A1, A2, A3, A4, EN12, EN34> L293x<A1, A2, A3, A4, EN12, EN34>
+where
+    A1: StatefulOutputPin,
+    A2: StatefulOutputPin<Error = A1::Error>,
+    A3: StatefulOutputPin<Error = A1::Error>,
+    A4: StatefulOutputPin<Error = A1::Error>,
+    EN12: StatefulOutputPin,
+    EN34: StatefulOutputPin<Error = EN12::Error>,
+{
+    /// Take a snapshot of the states of all pins of the chip.
+    ///
+    /// The snapshot can be re-applied later on using the [`restore()`](L293x::restore) method.
+    /// As the duty cycles of PWM inputs can not be read back using the [embedded_hal] traits,
+    /// the snapshot contains the last duty cycles commanded using the chip or its Half-H bridges
+    /// (see [`y1_duty_cycle()`](L293x::y1_duty_cycle)) instead.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the first pin, whose state could not be read.
+    pub fn snapshot(&mut self) -> Result<L293xState, OutputStateError<A1::Error, EN12::Error>> {
+        let inputs = [
+            pin!(self.a1).is_set_high(),
+            pin!(self.a2).is_set_high(),
+            pin!(self.a3).is_set_high(),
+            pin!(self.a4).is_set_high(),
+        ];
+        let mut state = L293xState {
+            inputs: [false; 4],
+            enable12: pin!(self.en12)
+                .is_set_high()
+                .map_err(OutputStateError::EnablePinError)?,
+            enable34: pin!(self.en34)
+                .is_set_high()
+                .map_err(OutputStateError::EnablePinError)?,
+            duty_cycles: self.cached_commands().map(InputCommand::duty),
+        };
+        for (input, high) in state.inputs.iter_mut().zip(inputs) {
+            *input = high.map_err(OutputStateError::InputPinError)?;
+        }
+        Ok(state)
+    }
 
-                #[doc = "Set the duty cycle of output " $output]
-                ///
-                /// This method sets the duty cycle of output channel
-                #[doc =  $output "."]
-                /// The duty cycle describes the portion of the interval that the
-                /// output should be set "active", which actually might either mean
-                /// high or low. This depends on the configuration of the PWM input
-                /// pin used.
-                ///
-                /// The level of activity scales linearly between `0` and the
-                /// maximum duty cycle value returned by the
-                #[doc = "[L293x::" $output "_max_duty_cycle()] method."]
-                ///
-                /// # Note
-                ///
-                /// In contrast to the [`set_duty_cycle`](HalfH::set_duty_cycle) method of the
-                /// [`$output()`](L293x::$output), this method does **not** enable the output.
-                ///
-                /// For the output to actually become active for the
-                /// same amount of time, the corresponding output channel needs to be
-                /// enabled as well using either the
-                /// [L293x::enable_y1_and_y2()] or [L293x::enable_y3_and_y4()] method.
-                ///
-                /// If the channel is disabled, the output will remain in high
-                /// impendance state and the state of the output is depending on the
-                /// components connected to it.
+    /// Take the telemetry of the chip, e.g. to send it to a debugging dashboard.
+    ///
+    /// The telemetry contains a [snapshot](L293x::snapshot) of the pin states and the last duty
+    /// cycles set on the outputs (see [`y1_duty_cycle()`](L293x::y1_duty_cycle)) and resolves the
+    /// resulting output levels.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the first pin, whose state could not be read.
+    pub fn telemetry(
+        &mut self,
+    ) -> Result<L293xTelemetry, OutputStateError<A1::Error, EN12::Error>> {
+        Ok(L293xTelemetry {
+            state: self.snapshot()?,
+        })
+    }
+
+    /// Re-apply a snapshot of the states of all pins of the chip.
+    ///
+    /// The snapshot is applied as a single [transaction](L293x::transaction). Thus, both pairs
+    /// are disabled first, the inputs are set afterwards, and the pairs, which are enabled in the
+    /// snapshot, are enabled last. Paired outputs never pass through an unintended intermediate
+    /// combination of their old and new inputs, even if they are enabled before and after.
+    ///
+    /// Digital inputs can not be driven using a duty cycle. Thus, the inputs are set to their
+    /// digital states and the [duty cycles](L293xState::duty_cycles) are not applied. Use
+    /// [`restore_duty_cycle()`](L293x::restore_duty_cycle) to restore a snapshot of a chip with
+    /// PWM inputs.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the first pin, which could not be set. If an input
+    /// could not be set, both pairs stay disabled.
+    pub fn restore(
+        &mut self,
+        state: &L293xState,
+    ) -> Result<(), OutputStateError<A1::Error, EN12::Error>> {
+        let [a1, a2, a3, a4] = state.inputs.map(PinState::from);
+        self.transaction(|txn| {
+            txn.set_y1_state(a1)
+                .set_y2_state(a2)
+                .set_y3_state(a3)
+                .set_y4_state(a4);
+            match state.enable12 {
+                true => txn.enable_y1_and_y2(),
+                false => txn.disable_y1_and_y2(),
+            };
+            match state.enable34 {
+                true => txn.enable_y3_and_y4(),
+                false => txn.disable_y3_and_y4(),
+            };
+        })
+    }
+}
+
+impl<A1, A2, A3, A4, EN12, EN34> L293x<A1, A2, A3, A4, EN12, EN34>
+where
+    A1: OutputPin,
+    A2: OutputPin<Error = A1::Error>,
+    A3: OutputPin<Error = A1::Error>,
+    A4: OutputPin<Error = A1::Error>,
+    EN12: StatefulOutputPin,
+    EN34: StatefulOutputPin<Error = EN12::Error>,
+{
+    /// Stage several changes of the pins of the chip and apply them together.
+    ///
+    /// The closure stages the changes on a [L293xTransaction]. Afterwards, the pairs, whose
+    /// inputs change or which are staged to be disabled, are disabled first. Then, the staged
+    /// inputs are set. Finally, the pairs are enabled again, if they were enabled before or are
+    /// staged to be enabled. Thus, paired outputs never pass through an unintended intermediate
+    /// combination of their inputs. Pairs without staged changes are not touched at all.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Reverse a motor connected to the outputs 1 and 2
+    /// l293x.transaction(|txn| {
+    ///     txn.set_y1_low();
+    ///     txn.set_y2_high();
+    /// })?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the first pin, which could not be read or set. If
+    /// an input could not be set, the affected pairs stay disabled.
+    pub fn transaction<F>(&mut self, f: F) -> Result<(), OutputStateError<A1::Error, EN12::Error>>
+    where
+        F: FnOnce(&mut L293xTransaction),
+    {
+        let mut txn = L293xTransaction::new();
+        f(&mut txn);
+
+        let [a1, a2, a3, a4] = txn.inputs;
+        let changed12 = a1.is_some() || a2.is_some();
+        let changed34 = a3.is_some() || a4.is_some();
+        let enable12 = match (txn.enable12, changed12) {
+            (Some(enable), _) => Some(enable),
+            (None, true) => Some(
+                pin!(self.en12)
+                    .is_set_high()
+                    .map_err(OutputStateError::EnablePinError)?,
+            ),
+            (None, false) => None,
+        };
+        let enable34 = match (txn.enable34, changed34) {
+            (Some(enable), _) => Some(enable),
+            (None, true) => Some(
+                pin!(self.en34)
+                    .is_set_high()
+                    .map_err(OutputStateError::EnablePinError)?,
+            ),
+            (None, false) => None,
+        };
+
+        if changed12 || enable12 == Some(false) {
+            pin!(self.en12)
+                .set_low()
+                .map_err(OutputStateError::EnablePinError)?;
+        }
+        if changed34 || enable34 == Some(false) {
+            pin!(self.en34)
+                .set_low()
+                .map_err(OutputStateError::EnablePinError)?;
+        }
+        a1.map_or(Ok(()), |high| self.set_y1_state(high.into()))
+            .and_then(|_| a2.map_or(Ok(()), |high| self.set_y2_state(high.into())))
+            .and_then(|_| a3.map_or(Ok(()), |high| self.set_y3_state(high.into())))
+            .and_then(|_| a4.map_or(Ok(()), |high| self.set_y4_state(high.into())))
+            .map_err(OutputStateError::InputPinError)?;
+        if enable12 == Some(true) {
+            pin!(self.en12)
+                .set_high()
+                .map_err(OutputStateError::EnablePinError)?;
+        }
+        if enable34 == Some(true) {
+            pin!(self.en34)
+                .set_high()
+                .map_err(OutputStateError::EnablePinError)?;
+        }
+        Ok(())
+    }
+}
+
+impl<A1, A2, A3, A4, EN12, EN34> L293x<A1, A2, A3, A4, EN12, EN34> {
+    /// Check whether the chip is [suspended](L293x::suspend).
+    #[inline]
+    pub fn is_suspended(&self) -> bool {
+        self.suspended.is_some()
+    }
+}
+
+impl<A1, A2, A3, A4, EN12, EN34> L293x<A1, A2, A3, A4, EN12, EN34>
+where
+    EN12: StatefulOutputPin,
+    EN34: StatefulOutputPin<Error = EN12::Error>,
+{
+    /// Suspend the chip, e.g. before entering a low-power mode.
+    ///
+    /// This remembers, which pairs of outputs are enabled, and disables both pairs afterwards.
+    /// The inputs are not touched. The chip remembers the last digital states and duty cycles
+    /// commanded using the chip or its Half-H bridges and applies them again, when the chip is
+    /// [resumed](L293x::resume). Thus, the inputs may lose their states while the chip is
+    /// suspended.
+    ///
+    /// Suspending an already suspended chip disables both pairs again, but keeps the states
+    /// remembered by the first call.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the first enable pin, which could not be read or
+    /// set. If an enable pin could not be set, the chip is suspended nevertheless, so that
+    /// suspending it again retries disabling the pairs.
+    pub fn suspend(&mut self) -> Result<(), EN12::Error> {
+        if self.suspended.is_none() {
+            let enabled = [
+                pin!(self.en12).is_set_high()?,
+                pin!(self.en34).is_set_high()?,
+            ];
+            self.suspended = Some(enabled);
+        }
+        pin!(self.en12).set_low()?;
+        pin!(self.en34).set_low()
+    }
+}
+
+impl<A1, A2, A3, A4, EN12, EN34> L293x<A1, A2, A3, A4, EN12, EN34>
+where
+    A1: OutputPin,
+    A2: OutputPin<Error = A1::Error>,
+    A3: OutputPin<Error = A1::Error>,
+    A4: OutputPin<Error = A1::Error>,
+    EN12: OutputPin,
+    EN34: OutputPin<Error = EN12::Error>,
+{
+    /// Resume a [suspended](L293x::suspend) chip.
+    ///
+    /// The last commanded digital states of the inputs are applied again first. Afterwards, the
+    /// pairs of outputs, which were enabled before the chip was suspended, are enabled again.
+    /// Resuming a chip, which is not suspended, does nothing.
+    ///
+    /// Inputs driven using a duty cycle are not touched. Use
+    /// [`resume_duty_cycle()`](L293x::resume_duty_cycle) to apply their duty cycles again.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the first pin, which could not be set. The chip
+    /// stays suspended in this case.
+    pub fn resume(&mut self) -> Result<(), OutputStateError<A1::Error, EN12::Error>> {
+        self.resume_with(|chip| {
+            let [c1, c2, c3, c4] = chip.cached_commands();
+            reapply_state(&mut pin!(chip.a1), c1)?;
+            reapply_state(&mut pin!(chip.a2), c2)?;
+            reapply_state(&mut pin!(chip.a3), c3)?;
+            reapply_state(&mut pin!(chip.a4), c4)
+        })
+    }
+}
+
+impl<A1, A2, A3, A4, EN12, EN34> L293x<A1, A2, A3, A4, EN12, EN34>
+where
+    A1: SetDutyCycle,
+    A2: SetDutyCycle<Error = A1::Error>,
+    A3: SetDutyCycle<Error = A1::Error>,
+    A4: SetDutyCycle<Error = A1::Error>,
+    EN12: OutputPin,
+    EN34: OutputPin<Error = EN12::Error>,
+{
+    /// Resume a [suspended](L293x::suspend) chip with PWM inputs.
+    ///
+    /// This works like the [`resume()`](L293x::resume) method, but applies the last commanded
+    /// duty cycles of the inputs as well. Inputs driven to a digital state are set fully on or
+    /// off.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the first pin, which could not be set. The chip
+    /// stays suspended in this case.
+    pub fn resume_duty_cycle(&mut self) -> Result<(), OutputStateError<A1::Error, EN12::Error>> {
+        self.resume_with(|chip| {
+            let [c1, c2, c3, c4] = chip.cached_commands();
+            reapply_duty_cycle(&mut pin!(chip.a1), c1)?;
+            reapply_duty_cycle(&mut pin!(chip.a2), c2)?;
+            reapply_duty_cycle(&mut pin!(chip.a3), c3)?;
+            reapply_duty_cycle(&mut pin!(chip.a4), c4)
+        })
+    }
+
+    /// Re-apply a [snapshot](L293x::snapshot) of a chip with PWM inputs.
+    ///
+    /// This works like the [`restore()`](L293x::restore) method, but applies the
+    /// [duty cycle](L293xState::duty_cycles) of an input instead of its digital state, whenever
+    /// the snapshot contains one. Inputs without a duty cycle are set fully on or off. Both pairs
+    /// are disabled first and the pairs, which are enabled in the snapshot, are enabled last.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the first pin, which could not be set. If an input
+    /// could not be set, both pairs stay disabled.
+    pub fn restore_duty_cycle(
+        &mut self,
+        state: &L293xState,
+    ) -> Result<(), OutputStateError<A1::Error, EN12::Error>> {
+        pin!(self.en12)
+            .set_low()
+            .map_err(OutputStateError::EnablePinError)?;
+        pin!(self.en34)
+            .set_low()
+            .map_err(OutputStateError::EnablePinError)?;
+        let [c1, c2, c3, c4] = &self.commands;
+        let [s1, s2, s3, s4] = restored_commands(state);
+        restore_duty_cycle(&mut pin!(self.a1), c1, s1)
+            .and_then(|_| restore_duty_cycle(&mut pin!(self.a2), c2, s2))
+            .and_then(|_| restore_duty_cycle(&mut pin!(self.a3), c3, s3))
+            .and_then(|_| restore_duty_cycle(&mut pin!(self.a4), c4, s4))
+            .map_err(OutputStateError::InputPinError)?;
+        if state.enable12 {
+            pin!(self.en12)
+                .set_high()
+                .map_err(OutputStateError::EnablePinError)?;
+        }
+        if state.enable34 {
+            pin!(self.en34)
+                .set_high()
+                .map_err(OutputStateError::EnablePinError)?;
+        }
+        Ok(())
+    }
+}
+
+impl<A1, A2, A3, A4, EN12, EN34> L293x<A1, A2, A3, A4, EN12, EN34>
+where
+    EN12: OutputPin,
+    EN34: OutputPin<Error = EN12::Error>,
+{
+    /// Resume a suspended chip, applying the remembered commands of the inputs using `reapply`
+    /// before enabling the pairs again.
+    fn resume_with<E>(
+        &mut self,
+        reapply: impl FnOnce(&mut Self) -> Result<(), E>,
+    ) -> Result<(), OutputStateError<E, EN12::Error>> {
+        if let Some([enable12, enable34]) = self.suspended {
+            reapply(self).map_err(OutputStateError::InputPinError)?;
+            if enable12 {
+                pin!(self.en12)
+                    .set_high()
+                    .map_err(OutputStateError::EnablePinError)?;
+            }
+            if enable34 {
+                pin!(self.en34)
+                    .set_high()
+                    .map_err(OutputStateError::EnablePinError)?;
+            }
+            self.suspended = None;
+        }
+        Ok(())
+    }
+
+    /// Get the last commands of all four inputs.
+    fn cached_commands(&self) -> [InputCommand; 4] {
+        [
+            self.commands[0].get(),
+            self.commands[1].get(),
+            self.commands[2].get(),
+            self.commands[3].get(),
+        ]
+    }
+}
+
+/// Apply the remembered digital state of an input again.
+fn reapply_state<P: OutputPin>(pin: &mut P, command: InputCommand) -> Result<(), P::Error> {
+    match command {
+        InputCommand::State(state) => pin.set_state(state),
+        InputCommand::Unknown | InputCommand::Duty(_) => Ok(()),
+    }
+}
+
+/// Apply the remembered digital state or duty cycle of a PWM input again.
+fn reapply_duty_cycle<P: SetDutyCycle>(pin: &mut P, command: InputCommand) -> Result<(), P::Error> {
+    match command {
+        InputCommand::State(PinState::High) => pin.set_duty_cycle_fully_on(),
+        InputCommand::State(PinState::Low) => pin.set_duty_cycle_fully_off(),
+        InputCommand::Duty(duty) => pin.set_duty_cycle(duty),
+        InputCommand::Unknown => Ok(()),
+    }
+}
+
+/// Get the commands of the inputs 1 - 4 restoring a snapshot, preferring their duty cycles.
+fn restored_commands(state: &L293xState) -> [InputCommand; 4] {
+    let mut commands = [InputCommand::Unknown; 4];
+    for ((command, high), duty) in commands.iter_mut().zip(state.inputs).zip(state.duty_cycles) {
+        *command = match duty {
+            Some(duty) => InputCommand::Duty(duty),
+            None => InputCommand::State(high.into()),
+        };
+    }
+    commands
+}
+
+/// Apply the restored command of a PWM input and remember it afterwards.
+fn restore_duty_cycle<P: SetDutyCycle>(
+    pin: &mut P,
+    cache: &Cell<InputCommand>,
+    command: InputCommand,
+) -> Result<(), P::Error> {
+    cache.set(InputCommand::Unknown);
+    reapply_duty_cycle(pin, command)?;
+    cache.set(command);
+    Ok(())
+}
+
+impl<P, EN> L293x<P, P, P, P, EN, EN> {
+    /// Get the Half-H bridge of the given output `channel`.
+    ///
+    /// This method is only available, if all inputs and both enable pins use the same pin type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_hal::digital::OutputPin;
+    /// use l293x::Channel;
+    ///
+    /// for channel in Channel::ALL {
+    ///     l293x.output(channel).set_high()?;
+    /// }
+    /// ```
+    #[inline]
+    pub fn output(&self, channel: Channel) -> HalfH<'_, P, EN> {
+        match channel {
+            Channel::Y1 => self.y1(),
+            Channel::Y2 => self.y2(),
+            Channel::Y3 => self.y3(),
+            Channel::Y4 => self.y4(),
+        }
+    }
+
+    /// Iterate over the Half-H bridges of all four outputs in ascending order.
+    ///
+    /// This method is only available, if all inputs and both enable pins use the same pin type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use embedded_hal::digital::OutputPin;
+    ///
+    /// // A simple light chaser
+    /// loop {
+    ///     for mut output in l293x.channels() {
+    ///         output.set_high()?;
+    ///         delay.delay_ms(100);
+    ///         output.set_low()?;
+    ///     }
+    /// }
+    /// ```
+    #[inline]
+    pub fn channels(&self) -> impl Iterator<Item = HalfH<'_, P, EN>> + '_ {
+        Channel::ALL
+            .into_iter()
+            .map(move |channel| self.output(channel))
+    }
+}
+
+impl<P, EN12, EN34> L293x<P, P, P, P, EN12, EN34>
+where
+    P: OutputPin,
+{
+    /// Set the input of the given output `channel` high.
+    ///
+    /// Like [`set_y1_high()`](L293x::set_y1_high), this method does **not** enable the output.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the input pin as a [ChipError] tagged with the
+    /// `channel`.
+    #[inline]
+    pub fn set_output_high(
+        &mut self,
+        channel: Channel,
+    ) -> Result<(), ChipError<P::Error, Infallible>> {
+        self.set_output_state(channel, PinState::High)
+    }
+
+    /// Set the input of the given output `channel` low.
+    ///
+    /// Like [`set_y1_low()`](L293x::set_y1_low), this method does **not** enable the output.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the input pin as a [ChipError] tagged with the
+    /// `channel`.
+    #[inline]
+    pub fn set_output_low(
+        &mut self,
+        channel: Channel,
+    ) -> Result<(), ChipError<P::Error, Infallible>> {
+        self.set_output_state(channel, PinState::Low)
+    }
+
+    /// Set the input of the given output `channel` to the given `state`.
+    ///
+    /// Like [`set_y1_state()`](L293x::set_y1_state), this method does **not** enable the output.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the input pin as a [ChipError] tagged with the
+    /// `channel`.
+    pub fn set_output_state(
+        &mut self,
+        channel: Channel,
+        state: PinState,
+    ) -> Result<(), ChipError<P::Error, Infallible>> {
+        self.set_channel_state(channel, state)
+            .map_err(|e| OutputStateError::InputPinError(e).on_channel(channel))
+    }
+
+    /// Set the input of the given output `channel` and return the plain error of the input pin.
+    pub(crate) fn set_channel_state(
+        &mut self,
+        channel: Channel,
+        state: PinState,
+    ) -> Result<(), P::Error> {
+        match channel {
+            Channel::Y1 => self.set_y1_state(state),
+            Channel::Y2 => self.set_y2_state(state),
+            Channel::Y3 => self.set_y3_state(state),
+            Channel::Y4 => self.set_y4_state(state),
+        }
+    }
+}
+
+impl<P, EN12, EN34> L293x<P, P, P, P, EN12, EN34>
+where
+    P: SetDutyCycle,
+{
+    /// Set the duty cycle of the input of the given output `channel`.
+    ///
+    /// Like [`set_y1_duty_cycle()`](L293x::set_y1_duty_cycle), this method does **not** enable
+    /// the output.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the input pin as a [ChipError] tagged with the
+    /// `channel`.
+    pub fn set_output_duty_cycle(
+        &mut self,
+        channel: Channel,
+        duty: u16,
+    ) -> Result<(), ChipError<P::Error, Infallible>> {
+        self.set_channel_duty_cycle(channel, duty)
+            .map_err(|e| OutputStateError::InputPinError(e).on_channel(channel))
+    }
+
+    /// Set the duty cycle of the given output `channel` and return the plain error of the input
+    /// pin.
+    pub(crate) fn set_channel_duty_cycle(
+        &mut self,
+        channel: Channel,
+        duty: u16,
+    ) -> Result<(), P::Error> {
+        match channel {
+            Channel::Y1 => self.set_y1_duty_cycle(duty),
+            Channel::Y2 => self.set_y2_duty_cycle(duty),
+            Channel::Y3 => self.set_y3_duty_cycle(duty),
+            Channel::Y4 => self.set_y4_duty_cycle(duty),
+        }
+    }
+}
+
+impl<'p, P, EN12, EN34>
+    L293x<PortPin<'p, P>, PortPin<'p, P>, PortPin<'p, P>, PortPin<'p, P>, EN12, EN34>
+{
+    /// Create a new L293x chip driver, whose inputs are connected to the given pins of a [Port].
+    ///
+    /// # Panics
+    ///
+    /// Panics, if one of the pins is greater than `15`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use l293x::L293x;
+    /// use l293x::port::Port;
+    ///
+    /// let port = Port::new(expander);
+    /// let l293x = L293x::from_port(&port, [0, 1, 2, 3], enable12, enable34);
+    /// ```
+    #[inline]
+    pub fn from_port(port: &'p Port<P>, inputs: [u8; 4], en12: EN12, en34: EN34) -> Self {
+        let [a1, a2, a3, a4] = inputs;
+        Self::new(
+            port.pin(a1),
+            port.pin(a2),
+            port.pin(a3),
+            port.pin(a4),
+            en12,
+            en34,
+        )
+    }
+}
+
+impl<'p, P, EN12, EN34>
+    L293x<PortPin<'p, P>, PortPin<'p, P>, PortPin<'p, P>, PortPin<'p, P>, EN12, EN34>
+where
+    P: PortOutput,
+{
+    /// Set all four inputs of the chip using a single write of the [Port].
+    ///
+    /// In contrast to setting the inputs one after another, this collapses the changes into a
+    /// single register or bus write. Like [`set_y1_state()`](L293x::set_y1_state), this method
+    /// does **not** enable the outputs.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the port. If the inputs are connected to different
+    /// ports, a separate write is done for each port and the first error is returned.
+    pub fn set_inputs(&mut self, states: [PinState; 4]) -> Result<(), P::Error> {
+        for command in &self.commands {
+            command.set(InputCommand::Unknown);
+        }
+        let pins = [
+            *self.a1.get_mut(),
+            *self.a2.get_mut(),
+            *self.a3.get_mut(),
+            *self.a4.get_mut(),
+        ];
+        let mut result = Ok(());
+        let mut written = [false; 4];
+        for index in 0..4 {
+            if written[index] {
+                continue;
+            }
+            let port = pins[index].port();
+            let (mut mask, mut state) = (0, 0);
+            for other in index..4 {
+                if core::ptr::eq(pins[other].port(), port) {
+                    written[other] = true;
+                    mask |= pins[other].mask();
+                    let inverted = self.polarity.inputs[other] == Polarity::Inverted;
+                    if (states[other] == PinState::High) ^ inverted {
+                        state |= pins[other].mask();
+                    }
+                }
+            }
+            result = result.and(port.write(mask, state));
+        }
+        if result.is_ok() {
+            for (command, state) in self.commands.iter().zip(states) {
+                command.set(InputCommand::State(state));
+            }
+        }
+        result
+    }
+}
+
+macro_rules! hold_low_impl {
+    ($pair:ident, $enable:ident, $type1:ident, $type2:ident, $entype:ident, $first:literal, $second:literal) => {
+        paste::item! {
+            impl<A1, A2, A3, A4, EN12, EN34> L293x<A1, A2, A3, A4, EN12, EN34>
+            where
+                $type1: OutputPin,
+                $type2: OutputPin<Error = $type1::Error>,
+                $entype: OutputPin,
+            {
+                #[doc = "Drive the inputs " $first " & " $second " low and disable the outputs afterwards."]
                 ///
-                /// This is useful, if the states of the two outputs sharing an enable pin need
-                /// to change states together i.e. to avoid high currencies in circuit.
-                /// In this case, the outputs can be disabled first, the new states of the outputs
-                /// set and afterwards, both outputs can be enabled again together.
+                #[doc = "In contrast to [L293x::disable_" $pair "()], this avoids a brief"]
+                /// re-assertion of the old output states, when the pair is enabled again later on.
                 ///
                 /// # Errors
                 ///
-                /// This method will return the error of the input pin, in case of an
-                /// error while setting the duty cycle of the pin. The actual type of
-                /// error returned depends on the type of input pin used.
+                /// The enable pin is set low, even if setting one of the inputs fails, because the
+                /// outputs should be disabled in any case. The first error of the input pins is
+                /// returned as [InputPinError](OutputStateError::InputPinError) afterwards, while
+                /// an error of the enable pin is returned as
+                /// [EnablePinError](OutputStateError::EnablePinError).
+                pub fn [< disable_ $pair _holding_low >](
+                    &mut self,
+                ) -> Result<(), OutputStateError<$type1::Error, $entype::Error>> {
+                    let inputs = first_error([
+                        self.[< set_y $first _low >](),
+                        self.[< set_y $second _low >](),
+                    ]);
+                    pin!(self.$enable)
+                        .set_low()
+                        .map_err(OutputStateError::EnablePinError)?;
+                    inputs.map_err(OutputStateError::InputPinError)
+                }
+            }
+
+            impl<A1, A2, A3, A4, EN12, EN34> L293x<A1, A2, A3, A4, EN12, EN34>
+            where
+                $type1: SetDutyCycle,
+                $type2: SetDutyCycle<Error = $type1::Error>,
+                $entype: OutputPin,
+            {
+                #[doc = "Turn the duty cycles of the inputs " $first " & " $second " off and disable the outputs afterwards."]
                 ///
-                /// # Examples
+                #[doc = "This is the PWM counterpart of [L293x::disable_" $pair "_holding_low()]."]
                 ///
-                /// To set the PWM pin inactive, set the duty cycle to `0`:
-                /// ```
-                #[doc = "l293x.set_" $output "_duty_cycle(0).unwrap();"]
-                /// ```
+                /// # Errors
                 ///
-                /// To make the pin always active, set it to the max duty value
-                /// returned by the
-                #[doc = "[L293x::" $output "_max_duty_cycle()] method:"]
-                /// ```
-                #[doc = "let max_duty = l293x." $output "_max_duty_cycle();"]
-                #[doc = "l293x.set_" $output "_duty_cycle(max_duty).unwrap();"]
-                /// ```
-                pub fn [< set_ $output _duty_cycle >](
-                    &mut self, duty: u16
-                ) -> Result<(), $type_::Error> {
-                    self.$input.get_mut().set_duty_cycle(duty)
+                /// The enable pin is set low, even if setting one of the duty cycles fails. The
+                /// first error of the input pins is returned as
+                /// [InputPinError](OutputStateError::InputPinError) afterwards, while an error of
+                /// the enable pin is returned as [EnablePinError](OutputStateError::EnablePinError).
+                pub fn [< disable_ $pair _duty_cycle_off >](
+                    &mut self,
+                ) -> Result<(), OutputStateError<$type1::Error, $entype::Error>> {
+                    let inputs = first_error([
+                        self.[< set_y $first _duty_cycle_fully_off >](),
+                        self.[< set_y $second _duty_cycle_fully_off >](),
+                    ]);
+                    pin!(self.$enable)
+                        .set_low()
+                        .map_err(OutputStateError::EnablePinError)?;
+                    inputs.map_err(OutputStateError::InputPinError)
                 }
+            }
+        }
+    };
+}
+hold_low_impl!(y1_and_y2, en12, A1, A2, EN12, 1, 2);
+hold_low_impl!(y3_and_y4, en34, A3, A4, EN34, 3, 4);
 
-                #[doc = "Set the duty cycle of output " $output " by fraction."]
+macro_rules! pair_state_impl {
+    ($pair:ident, $enable:ident, $type1:ident, $type2:ident, $entype:ident, $first:literal, $second:literal) => {
+        paste::item! {
+            impl<A1, A2, A3, A4, EN12, EN34> L293x<A1, A2, A3, A4, EN12, EN34>
+            where
+                $type1: OutputPin,
+                $type2: OutputPin<Error = $type1::Error>,
+                $entype: OutputPin,
+            {
+                #[doc = "Set the combined state of the outputs `y" $first "` and `y" $second "`."]
                 ///
-                /// This method sets the duty cycle of the output channel
-                #[doc = $output "by a fraction defined using the given `num` and"]
-                /// `denom` parameters.
+                /// This method only allows setting the meaningful combinations of the input and
+                /// enable states of a pair driving a load (see [BridgePairState]). The inputs are
+                /// set before the outputs are enabled, while the input going low is always
+                /// switched first. Thus, the load is never driven in the wrong direction.
                 ///
-                /// This method is useful if you want to set the output duty cycle
-                /// depending on an input value that can be between `0` and a
-                /// maximum value.
+                /// [HighZ](BridgePairState::HighZ) disables the outputs like
+                #[doc = "[L293x::disable_" $pair "()]."]
                 ///
-                /// For this, the fraction must be in between `0` and `1`, this means
-                /// that the `denom` must not be `0` and the `nom` must be smaller or
-                /// equal to `denom`.
+                /// # Errors
+                ///
+                /// If an error occurs while setting one of the inputs, a
+                /// [InputPinError](OutputStateError::InputPinError) is returned, while a
+                /// [EnablePinError](OutputStateError::EnablePinError) is returned, if the enable
+                /// pin could not be set.
+                pub fn [< set_ $pair _state >](
+                    &mut self,
+                    state: BridgePairState,
+                ) -> Result<(), OutputStateError<$type1::Error, $entype::Error>> {
+                    let (input1, input2) = match state {
+                        BridgePairState::Forward => (PinState::High, PinState::Low),
+                        BridgePairState::Reverse => (PinState::Low, PinState::High),
+                        BridgePairState::BrakeLow => (PinState::Low, PinState::Low),
+                        BridgePairState::HighZ => {
+                            return self
+                                .[< disable_ $pair >]()
+                                .map_err(OutputStateError::EnablePinError)
+                        }
+                    };
+                    if input1 == PinState::High {
+                        self.[< set_y $second _state >](input2).map_err(OutputStateError::InputPinError)?;
+                        self.[< set_y $first _state >](input1).map_err(OutputStateError::InputPinError)?;
+                    } else {
+                        self.[< set_y $first _state >](input1).map_err(OutputStateError::InputPinError)?;
+                        self.[< set_y $second _state >](input2).map_err(OutputStateError::InputPinError)?;
+                    }
+                    pin!(self.$enable)
+                        .set_high()
+                        .map_err(OutputStateError::EnablePinError)
+                }
+            }
+        }
+    };
+}
+
+pair_state_impl!(y1_and_y2, en12, A1, A2, EN12, 1, 2);
+pair_state_impl!(y3_and_y4, en34, A3, A4, EN34, 3, 4);
+
+macro_rules! output_pin_impl {
+    ($output:ident, $input:ident, $index:literal, $type_:ty) => {
+        paste::item! {
+            impl<A1, A2, A3, A4, EN12, EN34> L293x<A1, A2, A3, A4, EN12, EN34>
+            where
+                $type_: OutputPin,
+            {
+                #[doc = "Set the output " $output " high"]
                 ///
                 /// # Note
                 ///
-                /// In contrast to the [`set_duty_cycle_fraction`](HalfH::set_duty_cycle_fraction)
-                /// method of the [`$output()`](L293x::$output), this method does **not** enable
-                /// the output.
+                #[doc = "This function sets the input of the output channel " $output]
+                /// to high.
                 ///
-                /// For the output to actually become active for the
-                /// same amount of time, the corresponding output channel needs to be
-                /// enabled as well using either the
-                /// [L293x::enable_y1_and_y2()] or [L293x::enable_y3_and_y4()] method.
+                /// Like the [`set_high`](HalfH::set_high) method of the
+                /// [`$output()`](L293x::$output) with the default [Never](AutoEnable::Never)
+                /// auto-enable policy, this method does **not** enable the output.
+                #[doc = "Use [`set_" $output "_high_and_enable()`](L293x::set_" $output "_high_and_enable)"]
+                /// to enable the output as well.
+                ///
+                /// For the output to actually become "high", the corresponding
+                /// output channel needs to be enabled as well either using the
+                /// [L293x::enable_y1_and_y2()] or the [L293x::enable_y3_and_y4()]
+                /// method.
                 ///
                 /// If the channel is disabled, the output will remain in high
                 /// impendance state and the state of the output is depending on the
@@ -695,44 +2004,32 @@ macro_rules! pwm_pin_impl {
                 ///
                 /// # Errors
                 ///
-                /// This method will return the error of the input pin, in case of an
-                /// error while setting the duty cycle of the pin. The actual type of
-                /// error returned depends on the type of input pin used.
-                ///
-                /// # Examples
-                ///
-                /// To set the duty cycle of the output channel depending on another
-                /// input value, set the `denom` to the maximum value possible and the
-                /// `nom` to the current value:
-                ///
-                /// ```
-                /// // `max_value` defines the maximum value of the `input`.
-                #[doc = "l293x.set_" $output "_duty_cycle_fraction(input, max_value).unwrap();"]
-                /// ```
-                pub fn [< set_ $output _duty_cycle_fraction >](
-                    &mut self, num: u16, denom: u16
+                /// In case of an error, while setting the input "high", this method
+                /// returns the error of the corresponding input pin. The actual type
+                /// of error returned depends on the type of the input pin used.
+                pub fn [< set_ $output _high >](
+                    &mut self
                 ) -> Result<(), $type_::Error> {
-                    self.$input.get_mut().set_duty_cycle_fraction(num, denom)
+                    set_state_tracked(&mut pin!(self.$input), Some(&self.commands[$index]), PinState::High)
                 }
 
-                #[doc = "Set the duty cycle of output " $output " by percent"]
-                ///
-                #[doc = "This method sets the output " $output " active for the"]
-                /// given percent of its PWM interval.
-                ///
-                /// The `percent` value must be between `0` and `100` (inclusive),
-                /// where `0` means fully off, and `100` means fully on.
+                #[doc = "Set the output " $output " low"]
                 ///
                 /// # Note
                 ///
-                /// In contrast to the [`set_duty_cycle_percent`](HalfH::set_duty_cycle_percent)
-                /// method of the [`$output()`](L293x::$output), this method does **not** enable
-                /// the output.
+                #[doc = "This function sets the input of the output channel " $output]
+                /// to low.
                 ///
-                /// For the output to actually become active for the
-                /// same amount of time, the corresponding output channel needs to be
-                /// enabled as well using either the
-                /// [L293x::enable_y1_and_y2()] or [L293x::enable_y3_and_y4()] method.
+                /// Like the [`set_low`](HalfH::set_low) method of the [`$output()`](L293x::$output)
+                /// with the default [Never](AutoEnable::Never) auto-enable policy, this method does
+                /// **not** enable the output.
+                #[doc = "Use [`set_" $output "_low_and_enable()`](L293x::set_" $output "_low_and_enable)"]
+                /// to enable the output as well.
+                ///
+                /// For the output to actually become "low", the corresponding
+                /// output channel needs to be enabled as well either using the
+                /// [L293x::enable_y1_and_y2()] or the [L293x::enable_y3_and_y4()]
+                /// method.
                 ///
                 /// If the channel is disabled, the output will remain in high
                 /// impendance state and the state of the output is depending on the
@@ -745,30 +2042,30 @@ macro_rules! pwm_pin_impl {
                 ///
                 /// # Errors
                 ///
-                /// This method will return the error of the input pin, in case of an
-                /// error while setting the duty cycle of the pin. The actual type of
-                /// error returned depends on the type of input pin used.
-                pub fn [< set_ $output _duty_cycle_percent >](
-                    &mut self, percent: u8,
+                /// In case of an error, while setting the input "low", this method
+                /// returns the error of the corresponding input pin. The actual type
+                /// of error returned depends on the type of the input pin used.
+                pub fn [< set_ $output _low >](
+                    &mut self
                 ) -> Result<(), $type_::Error> {
-                    self.$input.get_mut().set_duty_cycle_percent(percent)
+                    set_state_tracked(&mut pin!(self.$input), Some(&self.commands[$index]), PinState::Low)
                 }
 
-                #[doc = "Fully enable the output " $output]
-                ///
-                #[doc = "This method set the PWM output channel " $output]
-                /// fully active.
+                #[doc = "Set the state of output " $output]
                 ///
                 /// # Note
                 ///
-                /// In contrast to the [`set_duty_cycle_fully_on`](HalfH::set_duty_cycle_fully_on)
-                /// method of the [`$output()`](L293x::$output), this method does **not** enable
-                /// the output.
+                /// This function sets state of the input for output channel
+                #[doc = $output "."]
                 ///
-                /// For the output to actually become active for the
-                /// same amount of time, the corresponding output channel needs to be
-                /// enabled as well using either the
-                /// [L293x::enable_y1_and_y2()] or [L293x::enable_y3_and_y4()] method.
+                /// Like the [`set_state`](HalfH::set_state) method of the
+                /// [`$output()`](L293x::$output) with the default [Never](AutoEnable::Never)
+                /// auto-enable policy, this method does **not** enable the output.
+                ///
+                /// For the output to actually take the given state, the corresponding
+                /// output channel needs to be enabled as well either using the
+                /// [L293x::enable_y1_and_y2()] or the [L293x::enable_y3_and_y4()]
+                /// method.
                 ///
                 /// If the channel is disabled, the output will remain in high
                 /// impendance state and the state of the output is depending on the
@@ -781,72 +2078,1427 @@ macro_rules! pwm_pin_impl {
                 ///
                 /// # Errors
                 ///
-                /// This method will return the error of the input pin, in case of an
-                /// error while setting the duty cycle of the pin. The actual type of
-                /// error returned depends on the type of input pin used.
-                pub fn [< set_ $output _duty_cycle_fully_on >](
-                    &mut self
+                /// In case of an error, while setting the input state, this method
+                /// returns the error of the corresponding input pin. The actual type
+                /// of error returned depends on the type of the input pin used.
+                pub fn [< set_ $output _state >](
+                    &mut self,
+                    state: PinState
                 ) -> Result<(), $type_::Error> {
-                    self.$input.get_mut().set_duty_cycle_fully_on()
+                    set_state_tracked(&mut pin!(self.$input), Some(&self.commands[$index]), state)
                 }
+            }
+        }
+    };
+}
+output_pin_impl!(y1, a1, 0, A1);
+output_pin_impl!(y2, a2, 1, A2);
+output_pin_impl!(y3, a3, 2, A3);
+output_pin_impl!(y4, a4, 3, A4);
 
-                #[doc = "Fully disable the output " $output]
+macro_rules! stateful_output_pin_impl {
+    ($output:ident, $type_:ty, $enable_ty:ty) => {
+        paste::item! {
+            impl<A1, A2, A3, A4, EN12, EN34> L293x<A1, A2, A3, A4, EN12, EN34>
+            where
+                $type_: StatefulOutputPin,
+                $enable_ty: StatefulOutputPin,
+            {
+                #[doc = "Check if output " $output " is set high"]
                 ///
-                #[doc = "This method set the PWM output channel " $output]
-                /// fully inactive.
+                /// The output of a L293x chip is high, only the output is enabled and
+                /// the if the corresponding input is high.
                 ///
                 /// # Note
                 ///
-                /// In contrast to the [`set_duty_cycle_fully_off`](HalfH::set_duty_cycle_fully_off)
-                /// method of the [`$output()`](L293x::$output), this method does **not** enable
-                /// the output.
+                /// Please note, that this method does not check the electrical level of
+                /// the pin, but uses an internal state instead. The electrical level of
+                /// the pin may vary due to the layout of the circuit.
                 ///
-                /// For the output to actually become active for the
-                /// same amount of time, the corresponding output channel needs to be
-                /// enabled as well using either the
-                /// [L293x::enable_y1_and_y2()] or [L293x::enable_y3_and_y4()] method.
+                /// # Errors
                 ///
-                /// If the channel is disabled, the output will remain in high
-                /// impendance state and the state of the output is depending on the
-                /// components connected to it.
+                /// If the output is disabled, it is neither high, nor low but remains
+                /// in an high impendance state und thus, its electrical level depends
+                /// on the components connected to it. In this case, a
+                /// [OutputStateError::NotEnabled] error will be returned.
                 ///
-                /// This is useful, if the states of the two outputs sharing an enable pin need
-                /// to change states together i.e. to avoid high currencies in circuit.
-                /// In this case, the outputs can be disabled first, the new states of the outputs
-                /// set and afterwards, both outputs can be enabled again together.
+                /// In case of an error, while reading the state of the enable pin,
+                /// this method will return an [OutputStateError::EnablePinError]
+                /// with the actual error of the enable pin returned.
+                ///
+                /// If an error occurs while reading the state of the input pin, an
+                /// [OutputStateError::InputPinError] with the error of the input pin will be
+                /// returned instead.
+                pub fn [< is_ $output _set_high >](
+                    &mut self
+                ) -> Result<bool, <crate::half_h::HalfH<'_, $type_, $enable_ty> as embedded_hal::digital::ErrorType>::Error> {
+                    self.$output().is_set_high()
+                }
+
+                #[doc = "Check if output " $output " is set low"]
+                ///
+                /// The output of a L293x chip is low, only the output is enabled and
+                /// the if the corresponding input is low.
+                ///
+                /// # Note
+                ///
+                /// Please note, that this method does not check the electrical level of
+                /// the pin, but uses an internal state instead. The electrical level of
+                /// the pin may vary due to the layout of the circuit.
                 ///
                 /// # Errors
                 ///
-                /// This method will return the error of the input pin, in case of an
-                /// error while setting the duty cycle of the pin. The actual type of
-                /// error returned depends on the type of input pin used.
-                pub fn [< set_ $output _duty_cycle_fully_off >](
+                /// If the output is disabled, it is neither high, nor low but remains
+                /// in an high impendance state und thus, its electrical level depends
+                /// on the components connected to it. In this case, a
+                /// [OutputStateError::NotEnabled] error will be returned.
+                ///
+                /// In case of an error, while reading the state of the enable pin, this method
+                /// will return an [OutputStateError::EnablePinError]
+                /// with the actual error of the enable pin returned.
+                ///
+                /// If an error occurs while reading the state of the input pin, an
+                /// [OutputStateError::InputPinError] with the error of the input pin will be
+                /// returned instead.
+                pub fn [< is_ $output _set_low >](
                     &mut self
-                ) -> Result<(), $type_::Error> {
-                    self.$input.get_mut().set_duty_cycle_fully_off()
+                ) -> Result<bool, <crate::half_h::HalfH<'_, $type_, $enable_ty> as embedded_hal::digital::ErrorType>::Error> {
+                    self.$output().is_set_low()
+                }
+
+                #[doc = "Get the level of output " $output]
+                ///
+                /// In contrast to the `is_*_set_[high|low]` methods, a disabled output is not
+                /// reported as an error, but as [HighZ](OutputLevel::HighZ). This allows polling
+                /// the state of the output without interpreting errors.
+                ///
+                /// # Note
+                ///
+                /// Please note, that this method does not check the electrical level of
+                /// the pin, but uses an internal state instead. The electrical level of
+                /// the pin may vary due to the layout of the circuit.
+                ///
+                /// # Errors
+                ///
+                /// In case of an error, while reading the state of the enable pin, this method
+                /// will return an [OutputStateError::EnablePinError]
+                /// with the actual error of the enable pin returned.
+                ///
+                /// If an error occurs while reading the state of the input pin, an
+                /// [OutputStateError::InputPinError] with the error of the input pin will be
+                /// returned instead.
+                pub fn [< get_ $output _state >](
+                    &mut self
+                ) -> Result<OutputLevel, <crate::half_h::HalfH<'_, $type_, $enable_ty> as embedded_hal::digital::ErrorType>::Error> {
+                    self.$output().output_level()
+                }
+
+                #[doc = "Toggle the state of output " $output]
+                ///
+                /// If the pin is considered high, it will be set to low and vice versa.
+                ///
+                /// # Note
+                ///
+                /// This method toggles the state of the corresponding input channel,
+                /// but does *not* enable the output channel, if it is disabled,
+                /// to avoid side effects. Thus, for the output
+                /// to actually toggle, the corresponding output needs to be enabled
+                /// using either the [L293x::enable_y1_and_y2()] or
+                /// [L293x::enable_y3_and_y4()] method.
+                ///
+                /// # Errors
+                ///
+                /// If the output is disabled, it is neither high, nor low but remains
+                /// in an high impendance state und thus, its electrical level depends
+                /// on the components connected to it. In this case, the output cannot be toggled
+                /// and a [OutputStateError::NotEnabled] error will be returned.
+                ///
+                /// If an error occurs while toggling the state of the input pin, the
+                /// error of the input pin will be returned. The actual type of error
+                /// depends on the type of input pin used.
+                pub fn [< toggle_ $output >](
+                    &mut self
+                ) -> Result<(), <crate::half_h::HalfH<'_, $type_, $enable_ty> as embedded_hal::digital::ErrorType>::Error> {
+                    self.$output().toggle()
+                }
+
+                #[doc = "Toggle the state of output " $output " and return the new state"]
+                ///
+                #[doc = "This behaves like [`toggle_" $output "()`](L293x::toggle_" $output "),"]
+                /// but saves querying the new state using an additional call.
+                ///
+                /// # Errors
+                ///
+                /// If the output is disabled, a [OutputStateError::NotEnabled] error will be
+                /// returned. Errors of the input and the enable pin are returned as
+                /// [OutputStateError::InputPinError] and [OutputStateError::EnablePinError].
+                pub fn [< toggle_ $output _and_get >](
+                    &mut self
+                ) -> Result<PinState, <crate::half_h::HalfH<'_, $type_, $enable_ty> as embedded_hal::digital::ErrorType>::Error> {
+                    self.$output().toggle_and_get()
                 }
             }
         }
     };
 }
-pwm_pin_impl!(y1, a1, A1);
-pwm_pin_impl!(y2, a2, A2);
-pwm_pin_impl!(y3, a3, A3);
-pwm_pin_impl!(y4, a4, A4);
+stateful_output_pin_impl!(y1, A1, EN12);
+stateful_output_pin_impl!(y2, A2, EN12);
+stateful_output_pin_impl!(y3, A3, EN34);
+stateful_output_pin_impl!(y4, A4, EN34);
+
+macro_rules! verify_impl {
+    ($output:ident, $type_:ty, $enable_ty:ty) => {
+        paste::item! {
+            impl<A1, A2, A3, A4, EN12, EN34> L293x<A1, A2, A3, A4, EN12, EN34>
+            where
+                $type_: StatefulOutputPin,
+                $enable_ty: StatefulOutputPin,
+            {
+                #[doc = "Verify the electrical level of output " $output " using a feedback pin"]
+                ///
+                /// This method compares the commanded state of the output with the level
+                /// measured on the given `feedback` pin, which must be wired to the output.
+                /// This allows detecting e.g. a broken trace or a damaged chip.
+                ///
+                /// The feedback pin is not stored by the driver, so that the same input can be
+                /// used to verify several outputs, e.g. using an analog multiplexer.
+                ///
+                /// # Errors
+                ///
+                /// If the measured level diverges from the commanded state, a
+                /// [OutputMismatch](VerifyError::OutputMismatch) error will be returned.
+                ///
+                /// If the output is disabled, its level is undefined and a
+                /// [PinError](VerifyError::PinError) containing a
+                /// [OutputStateError::NotEnabled] error will be returned.
+                ///
+                /// Errors, while reading the state of the input or the enable pin are returned
+                /// as [PinError](VerifyError::PinError) as well. Errors of the feedback pin are
+                /// returned as [FeedbackPinError](VerifyError::FeedbackPinError).
+                #[allow(clippy::type_complexity)]
+                pub fn [< verify_ $output >]<F>(
+                    &mut self,
+                    feedback: &mut F,
+                ) -> Result<
+                    (),
+                    VerifyError<OutputStateError<$type_::Error, $enable_ty::Error>, F::Error>,
+                >
+                where
+                    F: InputPin,
+                {
+                    let commanded = self
+                        .[< is_ $output _set_high >]()
+                        .map_err(VerifyError::PinError)?;
+                    let measured = feedback
+                        .is_high()
+                        .map_err(VerifyError::FeedbackPinError)?;
+                    match commanded == measured {
+                        true => Ok(()),
+                        false => Err(VerifyError::OutputMismatch { commanded, measured }),
+                    }
+                }
+            }
+        }
+    };
+}
+verify_impl!(y1, A1, EN12);
+verify_impl!(y2, A2, EN12);
+verify_impl!(y3, A3, EN34);
+verify_impl!(y4, A4, EN34);
+
+macro_rules! set_level_impl {
+    ($output:ident, $enable:ident, $type_:ty, $enable_ty:ty, $other:ident) => {
+        paste::item! {
+            impl<A1, A2, A3, A4, EN12, EN34> L293x<A1, A2, A3, A4, EN12, EN34>
+            where
+                $type_: OutputPin,
+                $enable_ty: OutputPin,
+            {
+                #[doc = "Drive output " $output " to the given `level`"]
+                ///
+                /// This allows driving the full truth table of the chip using a single call:
+                /// [High](OutputLevel::High) and [Low](OutputLevel::Low) set the input first and
+                /// enable the output afterwards, while [HighZ](OutputLevel::HighZ) disables the
+                /// output. The input is not changed in this case.
+                ///
+                /// <div class="warning">
+                #[doc = "The output shares its enable pin with output " $other ". Thus, setting"]
+                #[doc = "the level enables or disables output " $other " as well!"]
+                /// </div>
+                ///
+                /// # Errors
+                ///
+                /// If an error occurs while setting the input pin, an
+                /// [OutputStateError::InputPinError] will be returned and the output is not
+                /// enabled. Errors of the enable pin are returned as
+                /// [OutputStateError::EnablePinError].
+                pub fn [< set_ $output >](
+                    &mut self,
+                    level: OutputLevel,
+                ) -> Result<(), OutputStateError<$type_::Error, $enable_ty::Error>> {
+                    let state = match level {
+                        OutputLevel::High => PinState::High,
+                        OutputLevel::Low => PinState::Low,
+                        OutputLevel::HighZ => {
+                            return pin!(self.$enable)
+                                .set_low()
+                                .map_err(OutputStateError::EnablePinError);
+                        }
+                    };
+                    self.[< set_ $output _state >](state)
+                        .map_err(OutputStateError::InputPinError)?;
+                    pin!(self.$enable)
+                        .set_high()
+                        .map_err(OutputStateError::EnablePinError)
+                }
+
+                #[doc = "Set the output " $output " high and enable it afterwards"]
+                ///
+                #[doc = "In contrast to [`set_" $output "_high()`](L293x::set_" $output "_high), this"]
+                /// method always enables the output afterwards, independent of the
+                /// [auto-enable policy](AutoEnable) of the Half-H bridges.
+                ///
+                /// <div class="warning">
+                #[doc = "The output shares its enable pin with output " $other ". Thus, it enables"]
+                #[doc = "output " $other " as well!"]
+                /// </div>
+                ///
+                /// # Errors
+                ///
+                /// If an error occurs while setting the input pin, an
+                /// [OutputStateError::InputPinError] will be returned and the output is not
+                /// enabled. Errors of the enable pin are returned as
+                /// [OutputStateError::EnablePinError].
+                #[inline]
+                pub fn [< set_ $output _high_and_enable >](
+                    &mut self,
+                ) -> Result<(), OutputStateError<$type_::Error, $enable_ty::Error>> {
+                    self.[< set_ $output >](OutputLevel::High)
+                }
+
+                #[doc = "Set the output " $output " low and enable it afterwards"]
+                ///
+                #[doc = "In contrast to [`set_" $output "_low()`](L293x::set_" $output "_low), this"]
+                /// method always enables the output afterwards, independent of the
+                /// [auto-enable policy](AutoEnable) of the Half-H bridges.
+                ///
+                /// <div class="warning">
+                #[doc = "The output shares its enable pin with output " $other ". Thus, it enables"]
+                #[doc = "output " $other " as well!"]
+                /// </div>
+                ///
+                /// # Errors
+                ///
+                /// If an error occurs while setting the input pin, an
+                /// [OutputStateError::InputPinError] will be returned and the output is not
+                /// enabled. Errors of the enable pin are returned as
+                /// [OutputStateError::EnablePinError].
+                #[inline]
+                pub fn [< set_ $output _low_and_enable >](
+                    &mut self,
+                ) -> Result<(), OutputStateError<$type_::Error, $enable_ty::Error>> {
+                    self.[< set_ $output >](OutputLevel::Low)
+                }
+            }
+        }
+    };
+}
+set_level_impl!(y1, en12, A1, EN12, y2);
+set_level_impl!(y2, en12, A2, EN12, y1);
+set_level_impl!(y3, en34, A3, EN34, y4);
+set_level_impl!(y4, en34, A4, EN34, y3);
+
+macro_rules! pwm_pin_impl {
+    ($output:ident, $input:ident, $index:literal, $type_:ty) => {
+        paste::item! {
+            impl<A1, A2, A3, A4, EN12, EN34> L293x<A1, A2, A3, A4, EN12, EN34>
+            where
+                $type_: SetDutyCycle,
+            {
+                #[doc = "Get the max duty value of output " $output]
+                ///
+                /// This method returns the maximum value, that can be used in the
+                #[doc = "[L293x::set_" $output "_duty_cycle()]"]
+                /// method.
+                ///
+                /// # Examples
+                ///
+                /// ```
+                #[doc = "let max_duty = l293x." $output "_max_duty_cycle();"]
+                #[doc = "l293x.set_" $output "_duty_cycle(max_duty).unwrap();"]
+                /// ```
+                pub fn [< $output _max_duty_cycle >](&self) -> u16 {
+                    self.$input.borrow().max_duty_cycle()
+                }
+
+                #[doc = "Get the last duty cycle commanded for output " $output]
+                ///
+                /// The duty cycles set using the chip or its Half-H bridges are remembered, because
+                /// they can not be read back from the PWM pins. Returns `None`, if no duty cycle
+                /// has been set yet, or if the input has been set to a digital state or handed
+                /// out to a [GangedOutput] afterwards.
+                ///
+                /// # Examples
+                ///
+                /// ```
+                #[doc = "l293x.set_" $output "_duty_cycle(1000).unwrap();"]
+                #[doc = "assert_eq!(l293x." $output "_duty_cycle(), Some(1000));"]
+                /// ```
+                #[inline]
+                pub fn [< $output _duty_cycle >](&self) -> Option<u16> {
+                    self.commands[$index].get().duty()
+                }
+
+                #[doc = "Set the duty cycle of output " $output]
+                ///
+                /// This method sets the duty cycle of output channel
+                #[doc =  $output "."]
+                /// The duty cycle describes the portion of the interval that the
+                /// output should be set "active", which actually might either mean
+                /// high or low. This depends on the configuration of the PWM input
+                /// pin used.
+                ///
+                /// The level of activity scales linearly between `0` and the
+                /// maximum duty cycle value returned by the
+                #[doc = "[L293x::" $output "_max_duty_cycle()] method."]
+                ///
+                /// # Note
+                ///
+                /// Like the [`set_duty_cycle`](HalfH::set_duty_cycle) method of the
+                /// [`$output()`](L293x::$output) with the default [Never](AutoEnable::Never)
+                /// auto-enable policy, this method does **not** enable the output.
+                ///
+                /// For the output to actually become active for the
+                /// same amount of time, the corresponding output channel needs to be
+                /// enabled as well using either the
+                /// [L293x::enable_y1_and_y2()] or [L293x::enable_y3_and_y4()] method.
+                ///
+                /// If the channel is disabled, the output will remain in high
+                /// impendance state and the state of the output is depending on the
+                /// components connected to it.
+                ///
+                /// This is useful, if the states of the two outputs sharing an enable pin need
+                /// to change states together i.e. to avoid high currencies in circuit.
+                /// In this case, the outputs can be disabled first, the new states of the outputs
+                /// set and afterwards, both outputs can be enabled again together.
+                ///
+                /// # Errors
+                ///
+                /// This method will return the error of the input pin, in case of an
+                /// error while setting the duty cycle of the pin. The actual type of
+                /// error returned depends on the type of input pin used.
+                ///
+                /// # Examples
+                ///
+                /// To set the PWM pin inactive, set the duty cycle to `0`:
+                /// ```
+                #[doc = "l293x.set_" $output "_duty_cycle(0).unwrap();"]
+                /// ```
+                ///
+                /// To make the pin always active, set it to the max duty value
+                /// returned by the
+                #[doc = "[L293x::" $output "_max_duty_cycle()] method:"]
+                /// ```
+                #[doc = "let max_duty = l293x." $output "_max_duty_cycle();"]
+                #[doc = "l293x.set_" $output "_duty_cycle(max_duty).unwrap();"]
+                /// ```
+                pub fn [< set_ $output _duty_cycle >](
+                    &mut self, duty: u16
+                ) -> Result<(), $type_::Error> {
+                    set_duty_tracked(
+                        &mut pin!(self.$input),
+                        Some(&self.commands[$index]),
+                        |_| duty,
+                        |pin| pin.set_duty_cycle(duty),
+                    )
+                }
+
+                #[doc = "Set the duty cycle of output " $output " by fraction."]
+                ///
+                /// This method sets the duty cycle of the output channel
+                #[doc = $output "by a fraction defined using the given `num` and"]
+                /// `denom` parameters.
+                ///
+                /// This method is useful if you want to set the output duty cycle
+                /// depending on an input value that can be between `0` and a
+                /// maximum value.
+                ///
+                /// For this, the fraction must be in between `0` and `1`, this means
+                /// that the `denom` must not be `0` and the `nom` must be smaller or
+                /// equal to `denom`.
+                ///
+                /// # Note
+                ///
+                /// Like the [`set_duty_cycle_fraction`](HalfH::set_duty_cycle_fraction) method of
+                /// the [`$output()`](L293x::$output) with the default [Never](AutoEnable::Never)
+                /// auto-enable policy, this method does **not** enable the output.
+                ///
+                /// For the output to actually become active for the
+                /// same amount of time, the corresponding output channel needs to be
+                /// enabled as well using either the
+                /// [L293x::enable_y1_and_y2()] or [L293x::enable_y3_and_y4()] method.
+                ///
+                /// If the channel is disabled, the output will remain in high
+                /// impendance state and the state of the output is depending on the
+                /// components connected to it.
+                ///
+                /// This is useful, if the states of the two outputs sharing an enable pin need
+                /// to change states together i.e. to avoid high currencies in circuit.
+                /// In this case, the outputs can be disabled first, the new states of the outputs
+                /// set and afterwards, both outputs can be enabled again together.
+                ///
+                /// # Errors
+                ///
+                /// This method will return the error of the input pin, in case of an
+                /// error while setting the duty cycle of the pin. The actual type of
+                /// error returned depends on the type of input pin used.
+                ///
+                /// # Examples
+                ///
+                /// To set the duty cycle of the output channel depending on another
+                /// input value, set the `denom` to the maximum value possible and the
+                /// `nom` to the current value:
+                ///
+                /// ```
+                /// // `max_value` defines the maximum value of the `input`.
+                #[doc = "l293x.set_" $output "_duty_cycle_fraction(input, max_value).unwrap();"]
+                /// ```
+                pub fn [< set_ $output _duty_cycle_fraction >](
+                    &mut self, num: u16, denom: u16
+                ) -> Result<(), $type_::Error> {
+                    set_duty_tracked(
+                        &mut pin!(self.$input),
+                        Some(&self.commands[$index]),
+                        |max_duty| duty_from_fraction(num, denom, max_duty),
+                        |pin| pin.set_duty_cycle_fraction(num, denom),
+                    )
+                }
+
+                #[doc = "Set the duty cycle of output " $output " by percent"]
+                ///
+                #[doc = "This method sets the output " $output " active for the"]
+                /// given percent of its PWM interval.
+                ///
+                /// The `percent` value must be between `0` and `100` (inclusive),
+                /// where `0` means fully off, and `100` means fully on.
+                ///
+                /// # Note
+                ///
+                /// Like the [`set_duty_cycle_percent`](HalfH::set_duty_cycle_percent) method of the
+                /// [`$output()`](L293x::$output) with the default [Never](AutoEnable::Never)
+                /// auto-enable policy, this method does **not** enable the output.
+                ///
+                /// For the output to actually become active for the
+                /// same amount of time, the corresponding output channel needs to be
+                /// enabled as well using either the
+                /// [L293x::enable_y1_and_y2()] or [L293x::enable_y3_and_y4()] method.
+                ///
+                /// If the channel is disabled, the output will remain in high
+                /// impendance state and the state of the output is depending on the
+                /// components connected to it.
+                ///
+                /// This is useful, if the states of the two outputs sharing an enable pin need
+                /// to change states together i.e. to avoid high currencies in circuit.
+                /// In this case, the outputs can be disabled first, the new states of the outputs
+                /// set and afterwards, both outputs can be enabled again together.
+                ///
+                /// # Errors
+                ///
+                /// This method will return the error of the input pin, in case of an
+                /// error while setting the duty cycle of the pin. The actual type of
+                /// error returned depends on the type of input pin used.
+                pub fn [< set_ $output _duty_cycle_percent >](
+                    &mut self, percent: u8,
+                ) -> Result<(), $type_::Error> {
+                    set_duty_tracked(
+                        &mut pin!(self.$input),
+                        Some(&self.commands[$index]),
+                        |max_duty| duty_from_fraction(percent.into(), 100, max_duty),
+                        |pin| pin.set_duty_cycle_percent(percent),
+                    )
+                }
+
+                #[doc = "Fully enable the output " $output]
+                ///
+                #[doc = "This method set the PWM output channel " $output]
+                /// fully active.
+                ///
+                /// # Note
+                ///
+                /// Like the [`set_duty_cycle_fully_on`](HalfH::set_duty_cycle_fully_on) method of
+                /// the [`$output()`](L293x::$output) with the default [Never](AutoEnable::Never)
+                /// auto-enable policy, this method does **not** enable the output.
+                ///
+                /// For the output to actually become active for the
+                /// same amount of time, the corresponding output channel needs to be
+                /// enabled as well using either the
+                /// [L293x::enable_y1_and_y2()] or [L293x::enable_y3_and_y4()] method.
+                ///
+                /// If the channel is disabled, the output will remain in high
+                /// impendance state and the state of the output is depending on the
+                /// components connected to it.
+                ///
+                /// This is useful, if the states of the two outputs sharing an enable pin need
+                /// to change states together i.e. to avoid high currencies in circuit.
+                /// In this case, the outputs can be disabled first, the new states of the outputs
+                /// set and afterwards, both outputs can be enabled again together.
+                ///
+                /// # Errors
+                ///
+                /// This method will return the error of the input pin, in case of an
+                /// error while setting the duty cycle of the pin. The actual type of
+                /// error returned depends on the type of input pin used.
+                pub fn [< set_ $output _duty_cycle_fully_on >](
+                    &mut self
+                ) -> Result<(), $type_::Error> {
+                    set_duty_tracked(
+                        &mut pin!(self.$input),
+                        Some(&self.commands[$index]),
+                        |max_duty| max_duty,
+                        |pin| pin.set_duty_cycle_fully_on(),
+                    )
+                }
+
+                #[doc = "Fully disable the output " $output]
+                ///
+                #[doc = "This method set the PWM output channel " $output]
+                /// fully inactive.
+                ///
+                /// # Note
+                ///
+                /// Like the [`set_duty_cycle_fully_off`](HalfH::set_duty_cycle_fully_off) method of
+                /// the [`$output()`](L293x::$output) with the default [Never](AutoEnable::Never)
+                /// auto-enable policy, this method does **not** enable the output.
+                ///
+                /// For the output to actually become active for the
+                /// same amount of time, the corresponding output channel needs to be
+                /// enabled as well using either the
+                /// [L293x::enable_y1_and_y2()] or [L293x::enable_y3_and_y4()] method.
+                ///
+                /// If the channel is disabled, the output will remain in high
+                /// impendance state and the state of the output is depending on the
+                /// components connected to it.
+                ///
+                /// This is useful, if the states of the two outputs sharing an enable pin need
+                /// to change states together i.e. to avoid high currencies in circuit.
+                /// In this case, the outputs can be disabled first, the new states of the outputs
+                /// set and afterwards, both outputs can be enabled again together.
+                ///
+                /// # Errors
+                ///
+                /// This method will return the error of the input pin, in case of an
+                /// error while setting the duty cycle of the pin. The actual type of
+                /// error returned depends on the type of input pin used.
+                pub fn [< set_ $output _duty_cycle_fully_off >](
+                    &mut self
+                ) -> Result<(), $type_::Error> {
+                    set_duty_tracked(
+                        &mut pin!(self.$input),
+                        Some(&self.commands[$index]),
+                        |_| 0,
+                        |pin| pin.set_duty_cycle_fully_off(),
+                    )
+                }
+            }
+        }
+    };
+}
+pwm_pin_impl!(y1, a1, 0, A1);
+pwm_pin_impl!(y2, a2, 1, A2);
+pwm_pin_impl!(y3, a3, 2, A3);
+pwm_pin_impl!(y4, a4, 3, A4);
+
+macro_rules! enable_pwm_impl {
+    ($pair:ident, $enable:ident, $type_:ty) => {
+        paste::item! {
+            impl<A1, A2, A3, A4, EN12, EN34> L293x<A1, A2, A3, A4, EN12, EN34>
+            where
+                $type_: SetDutyCycle,
+            {
+                #[doc = "Get the max duty value of the enable pin of the outputs " $pair]
+                ///
+                /// This method returns the maximum value, that can be used in the
+                #[doc = "[L293x::set_" $pair "_duty_cycle()]"]
+                /// method.
+                pub fn [< $pair _max_duty_cycle >](&self) -> u16 {
+                    self.$enable.borrow().max_duty_cycle()
+                }
+
+                #[doc = "Set the duty cycle of the enable pin of the outputs " $pair]
+                ///
+                /// This supports the common wiring, where the direction of the outputs is set
+                /// using plain digital inputs and the enable pin carries the PWM signal. The
+                /// outputs follow their inputs while the enable pin is active and are in the
+                /// high impedance mode otherwise.
+                ///
+                /// The level of activity scales linearly between `0` and the maximum duty cycle
+                /// value returned by the
+                #[doc = "[L293x::" $pair "_max_duty_cycle()] method."]
+                ///
+                /// # Errors
+                ///
+                /// This method will return the error of the enable pin, in case of an
+                /// error while setting the duty cycle of the pin.
+                ///
+                /// # Examples
+                ///
+                /// ```
+                #[doc = "let max_duty = l293x." $pair "_max_duty_cycle();"]
+                #[doc = "l293x.set_" $pair "_duty_cycle(max_duty / 2).unwrap();"]
+                /// ```
+                pub fn [< set_ $pair _duty_cycle >](
+                    &mut self, duty: u16
+                ) -> Result<(), $type_::Error> {
+                    pin!(self.$enable).set_duty_cycle(duty)
+                }
+
+                #[doc = "Set the duty cycle of the enable pin of the outputs " $pair " by fraction."]
+                ///
+                #[doc = "See [L293x::set_" $pair "_duty_cycle()] for details."]
+                ///
+                /// # Errors
+                ///
+                /// This method will return the error of the enable pin, in case of an
+                /// error while setting the duty cycle of the pin.
+                pub fn [< set_ $pair _duty_cycle_fraction >](
+                    &mut self, num: u16, denom: u16
+                ) -> Result<(), $type_::Error> {
+                    pin!(self.$enable).set_duty_cycle_fraction(num, denom)
+                }
+
+                #[doc = "Set the duty cycle of the enable pin of the outputs " $pair " in percent."]
+                ///
+                #[doc = "See [L293x::set_" $pair "_duty_cycle()] for details."]
+                ///
+                /// # Errors
+                ///
+                /// This method will return the error of the enable pin, in case of an
+                /// error while setting the duty cycle of the pin.
+                pub fn [< set_ $pair _duty_cycle_percent >](
+                    &mut self, percent: u8
+                ) -> Result<(), $type_::Error> {
+                    pin!(self.$enable).set_duty_cycle_percent(percent)
+                }
+            }
+        }
+    };
+}
+enable_pwm_impl!(y1_and_y2, en12, EN12);
+enable_pwm_impl!(y3_and_y4, en34, EN34);
+
+#[cfg(feature = "float")]
+macro_rules! float_pwm_pin_impl {
+    ($output:ident, $input:ident, $type_:ty) => {
+        paste::item! {
+            impl<A1, A2, A3, A4, EN12, EN34> L293x<A1, A2, A3, A4, EN12, EN34>
+            where
+                $type_: SetDutyCycle,
+            {
+                #[doc = "Set the duty cycle of output " $output " by a floating point fraction"]
+                ///
+                /// The `fraction` is clamped into the range of `0.0` (fully off) to `1.0`
+                /// (fully on) and converted into a duty cycle of the input pin.
+                ///
+                /// This method is only available, if the `float` feature is enabled.
+                ///
+                /// # Note
+                ///
+                /// Like the [`set_duty_cycle_f32`](HalfH::set_duty_cycle_f32) method of the
+                /// [`$output()`](L293x::$output) with the default [Never](AutoEnable::Never)
+                /// auto-enable policy, this method does **not** enable the output.
+                ///
+                /// # Errors
+                ///
+                /// This method will return the error of the input pin, in case of an
+                /// error while setting the duty cycle of the pin. The actual type of
+                /// error returned depends on the type of input pin used.
+                pub fn [< set_ $output _duty_cycle_f32 >](
+                    &mut self, fraction: f32
+                ) -> Result<(), $type_::Error> {
+                    let max_duty = pin!(self.$input).max_duty_cycle();
+                    let duty = crate::float::duty_cycle_from_f32(fraction, max_duty);
+                    self.[< set_ $output _duty_cycle >](duty)
+                }
+            }
+        }
+    };
+}
+#[cfg(feature = "float")]
+float_pwm_pin_impl!(y1, a1, A1);
+#[cfg(feature = "float")]
+float_pwm_pin_impl!(y2, a2, A2);
+#[cfg(feature = "float")]
+float_pwm_pin_impl!(y3, a3, A3);
+#[cfg(feature = "float")]
+float_pwm_pin_impl!(y4, a4, A4);
+
+#[cfg(test)]
+mod tests {
+    use coverage_helper::test;
+    use embedded_hal::digital::PinState;
+
+    use crate::pins::{Gnd, Vcc};
+    use crate::test_util::{Clock, Delay, DigitalError, DigitalPin, Event, PwmPin, Recorder};
+    use crate::{OutputStateError, PinRole};
+
+    use super::*;
+
+    #[test]
+    fn test_full_h() {
+        let l293x = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        );
+
+        l293x.full_h12().forward().unwrap();
+        l293x.full_h34().reverse().unwrap();
+        assert!(l293x.y1().is_set_high().unwrap());
+        assert!(l293x.y2().is_set_low().unwrap());
+        assert!(l293x.y3().is_set_low().unwrap());
+        assert!(l293x.y4().is_set_high().unwrap());
+
+        l293x.full_h34().coast().unwrap();
+        assert!(l293x.y3().is_disabled().unwrap());
+        assert!(l293x.y1().is_enabled().unwrap());
+    }
+
+    #[test]
+    fn test_enable_pwm() {
+        let mut l293x = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            PwmPin::new(),
+            PwmPin::new(),
+        );
+
+        l293x.set_y1_high().unwrap();
+        let max_duty = l293x.y1_and_y2_max_duty_cycle();
+        l293x.set_y1_and_y2_duty_cycle(max_duty / 2).unwrap();
+        assert_eq!(l293x.en12.get_mut().get_duty_cycle(), max_duty / 2);
+        l293x.set_y1_and_y2_duty_cycle_fraction(1, 4).unwrap();
+        assert_eq!(l293x.en12.get_mut().get_duty_cycle(), max_duty / 4);
+
+        assert_eq!(l293x.y3_and_y4_max_duty_cycle(), max_duty);
+        l293x.set_y3_and_y4_duty_cycle_percent(100).unwrap();
+        assert_eq!(l293x.en34.get_mut().get_duty_cycle(), max_duty);
+        l293x.set_y3_and_y4_duty_cycle(0).unwrap();
+        l293x.set_y3_and_y4_duty_cycle_fraction(0, 1).unwrap();
+        l293x.set_y1_and_y2_duty_cycle_percent(0).unwrap();
+        assert_eq!(l293x.en12.get_mut().get_duty_cycle(), 0);
+    }
+
+    #[test]
+    fn test_enable12() {
+        let mut l293x = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        );
+
+        l293x.enable_y1_and_y2().unwrap();
+        assert!(l293x.y1_and_y2_enabled().unwrap());
+        assert!(!l293x.y1_and_y2_disabled().unwrap());
+
+        l293x.disable_y1_and_y2().unwrap();
+        assert!(!l293x.y1_and_y2_enabled().unwrap());
+        assert!(l293x.y1_and_y2_disabled().unwrap());
+    }
+
+    #[test]
+    fn test_enable34() {
+        let mut l293x = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        );
+
+        l293x.enable_y3_and_y4().unwrap();
+        assert!(l293x.y3_and_y4_enabled().unwrap());
+        assert!(!l293x.y3_and_y4_disabled().unwrap());
+
+        l293x.disable_y3_and_y4().unwrap();
+        assert!(!l293x.y3_and_y4_enabled().unwrap());
+        assert!(l293x.y3_and_y4_disabled().unwrap());
+    }
+
+    #[test]
+    fn test_enable12_fail() {
+        let mut l293x = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        );
+        l293x.en12.get_mut().fail();
+        assert!(matches!(
+            l293x.is_y1_set_high().unwrap_err(),
+            OutputStateError::EnablePinError(..)
+        ));
+        assert!(matches!(
+            l293x.is_y2_set_high().unwrap_err(),
+            OutputStateError::EnablePinError(..)
+        ));
+        assert!(matches!(
+            l293x.is_y1_set_low().unwrap_err(),
+            OutputStateError::EnablePinError(..)
+        ));
+        assert!(matches!(
+            l293x.is_y2_set_low().unwrap_err(),
+            OutputStateError::EnablePinError(..)
+        ));
+    }
+
+    #[test]
+    fn test_enable34_fail() {
+        let mut l293x = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        );
+        l293x.en34.get_mut().fail();
+        assert!(matches!(
+            l293x.is_y3_set_high().unwrap_err(),
+            OutputStateError::EnablePinError(..)
+        ));
+        assert!(matches!(
+            l293x.is_y4_set_high().unwrap_err(),
+            OutputStateError::EnablePinError(..)
+        ));
+        assert!(matches!(
+            l293x.is_y3_set_low().unwrap_err(),
+            OutputStateError::EnablePinError(..)
+        ));
+        assert!(matches!(
+            l293x.is_y4_set_low().unwrap_err(),
+            OutputStateError::EnablePinError(..)
+        ));
+    }
+
+    macro_rules! test_output {
+        ($name:ident, $input:ident, $bname:ident) => {
+            paste::item! {
+                #[test]
+                fn [< test_ $name >]() {
+                    let mut l293x = L293x::new(
+                        DigitalPin::new(),
+                        DigitalPin::new(),
+                        DigitalPin::new(),
+                        DigitalPin::new(),
+                        DigitalPin::new(),
+                        DigitalPin::new(),
+                    );
+
+                    // LOW, LOW => Z
+                    l293x.[< set_ $name _low >]().unwrap();
+                    l293x.[< disable_ $bname >]().unwrap();
+                    assert!(matches!(l293x.[< is_ $name _set_high >](), Err(OutputStateError::NotEnabled)));
+                    assert!(matches!(l293x.[< is_ $name _set_low >](), Err(OutputStateError::NotEnabled)));
+                    assert_eq!(l293x.[< get_ $name _state >]().unwrap(), OutputLevel::HighZ);
+
+                    // HIGH, LOW => Z
+                    l293x.[< set_ $name _high >]().unwrap();
+                    l293x.[< disable_ $bname >]().unwrap();
+                    assert!(matches!(l293x.[< is_ $name _set_high >](), Err(OutputStateError::NotEnabled)));
+                    assert!(matches!(l293x.[< is_ $name _set_low >](), Err(OutputStateError::NotEnabled)));
+
+                    // LOW, HIGH => LOW
+                    l293x.[< set_ $name _low >]().unwrap();
+                    l293x.[< enable_ $bname >]().unwrap();
+                    assert!(!l293x.[< is_ $name _set_high >]().unwrap());
+                    assert!(l293x.[< is_ $name _set_low >]().unwrap());
+                    assert_eq!(l293x.[< get_ $name _state >]().unwrap(), OutputLevel::Low);
+
+                    // HIGH, HIGH => HIGH
+                    l293x.[< set_ $name _high >]().unwrap();
+                    l293x.[< enable_ $bname >]().unwrap();
+                    assert!(l293x.[< is_ $name _set_high >]().unwrap());
+                    assert!(!l293x.[< is_ $name _set_low >]().unwrap());
+                    assert_eq!(l293x.[< get_ $name _state >]().unwrap(), OutputLevel::High);
+                }
+
+                #[test]
+                fn [< test_ $name _set_state >]() {
+                    let mut l293x = L293x::new(
+                        DigitalPin::new(),
+                        DigitalPin::new(),
+                        DigitalPin::new(),
+                        DigitalPin::new(),
+                        DigitalPin::new(),
+                        DigitalPin::new(),
+                    );
+
+                    // LOW, LOW => Z
+                    l293x.[< set_ $name _state >](PinState::Low).unwrap();
+                    l293x.[< disable_ $bname >]().unwrap();
+                    assert!(matches!(l293x.[< is_ $name _set_high >](), Err(OutputStateError::NotEnabled)));
+                    assert!(matches!(l293x.[< is_ $name _set_low >](), Err(OutputStateError::NotEnabled)));
+
+                    // HIGH, LOW => Z
+                    l293x.[< set_ $name _state >](PinState::High).unwrap();
+                    l293x.[< disable_ $bname >]().unwrap();
+                    assert!(matches!(l293x.[< is_ $name _set_high >](), Err(OutputStateError::NotEnabled)));
+                    assert!(matches!(l293x.[< is_ $name _set_low >](), Err(OutputStateError::NotEnabled)));
+
+                    // LOW, HIGH => LOW
+                    l293x.[< set_ $name _state >](PinState::Low).unwrap();
+                    l293x.[< enable_ $bname >]().unwrap();
+                    assert!(!l293x.[< is_ $name _set_high >]().unwrap());
+                    assert!(l293x.[< is_ $name _set_low >]().unwrap());
+
+                    // HIGH, HIGH => HIGH
+                    l293x.[< set_ $name _state >](PinState::High).unwrap();
+                    l293x.[< enable_ $bname >]().unwrap();
+                    assert!(l293x.[< is_ $name _set_high >]().unwrap());
+                    assert!(!l293x.[< is_ $name _set_low >]().unwrap());
+                }
+
+                #[test]
+                fn [< test_toggle_ $name >]() {
+                    let mut l293x = L293x::new(
+                        DigitalPin::new(),
+                        DigitalPin::new(),
+                        DigitalPin::new(),
+                        DigitalPin::new(),
+                        DigitalPin::new(),
+                        DigitalPin::new(),
+                    );
+                    l293x.[< set_ $name _state >](PinState::Low).unwrap();
+                    l293x.[< enable_ $bname >]().unwrap();
+                    let old_state = l293x.[< is_ $name _set_high >]().unwrap();
+
+                    l293x.[< toggle_ $name >]().unwrap();
+                    assert_ne!(l293x.[< is_ $name _set_high >]().unwrap(), old_state);
+
+                    l293x.[< toggle_ $name >]().unwrap();
+                    assert_eq!(l293x.[< is_ $name _set_high >]().unwrap(), old_state);
+
+                    assert_eq!(l293x.[< toggle_ $name _and_get >]().unwrap(), PinState::High);
+                    assert!(l293x.[< is_ $name _set_high >]().unwrap());
+                }
+
+                #[test]
+                fn [< test_ $name _pwm >]() {
+                    let mut l293x = L293x::new(
+                        PwmPin::new(),
+                        PwmPin::new(),
+                        PwmPin::new(),
+                        PwmPin::new(),
+                        DigitalPin::new(),
+                        DigitalPin::new(),
+                    );
+
+                    let max_duty = l293x.[< $name _max_duty_cycle >]();
+
+                    assert_eq!(l293x.[< $name _duty_cycle >](), None);
+                    l293x.[< set_ $name _duty_cycle >](max_duty).unwrap();
+                    assert_eq!(l293x.$input.borrow().get_duty_cycle(), max_duty);
+                    assert_eq!(l293x.[< $name _duty_cycle >](), Some(max_duty));
+
+                    l293x.[< set_ $name _duty_cycle_fraction >](1, 2).unwrap();
+                    assert_eq!(l293x.$input.borrow().get_duty_cycle(), max_duty / 2);
+                    assert_eq!(l293x.$name().duty_cycle(), Some(max_duty / 2));
+
+                    l293x.[< set_ $name _duty_cycle_percent >](25).unwrap();
+                    assert_eq!(l293x.$input.borrow().get_duty_cycle(), max_duty / 4);
+                    assert_eq!(l293x.[< $name _duty_cycle >](), Some(max_duty / 4));
+
+                    l293x.[< set_ $name _duty_cycle_fully_on >]().unwrap();
+                    assert_eq!(l293x.$input.borrow().get_duty_cycle(), max_duty);
+
+                    l293x.[< set_ $name _duty_cycle_fully_off >]().unwrap();
+                    assert_eq!(l293x.$input.borrow().get_duty_cycle(), 0);
+                    assert_eq!(l293x.[< $name _duty_cycle >](), Some(0));
+
+                    l293x.$name().set_duty_cycle_fully_on().unwrap();
+                    assert_eq!(l293x.[< $name _duty_cycle >](), Some(max_duty));
+                }
+
+                #[test]
+                #[cfg(feature = "float")]
+                fn [< test_ $name _pwm_f32 >]() {
+                    let mut l293x = L293x::new(
+                        PwmPin::new(),
+                        PwmPin::new(),
+                        PwmPin::new(),
+                        PwmPin::new(),
+                        DigitalPin::new(),
+                        DigitalPin::new(),
+                    );
+
+                    l293x.[< set_ $name _duty_cycle_f32 >](1.0).unwrap();
+                    assert_eq!(l293x.$input.borrow().get_duty_cycle(), u16::MAX);
+
+                    l293x.[< set_ $name _duty_cycle_f32 >](-1.0).unwrap();
+                    assert_eq!(l293x.$input.borrow().get_duty_cycle(), 0);
+                }
+            }
+        };
+    }
+    test_output!(y1, a1, y1_and_y2);
+    test_output!(y2, a2, y1_and_y2);
+    test_output!(y3, a3, y3_and_y4);
+    test_output!(y4, a4, y3_and_y4);
+
+    macro_rules! test_fail {
+        ($name:ident, $input:ident) => {
+            paste::item! {
+                #[test]
+                fn [< test_ $name _fail >]() {
+                    let mut l293x = L293x::new(
+                        DigitalPin::new(),
+                        DigitalPin::new(),
+                        DigitalPin::new(),
+                        DigitalPin::new(),
+                        DigitalPin::new(),
+                        DigitalPin::new(),
+                    );
+                    l293x.enable_y1_and_y2().unwrap();
+                    l293x.enable_y3_and_y4().unwrap();
+                    l293x.$input.get_mut().fail();
+
+                    assert!(matches!(l293x.[< set_ $name _low >](), Err(DigitalError(..))));
+                    assert!(matches!(l293x.[< set_ $name _high >](), Err(DigitalError(..))));
+                    assert!(matches!(l293x.[< is_ $name _set_high >](), Err(OutputStateError::InputPinError(..))));
+                    assert!(matches!(l293x.[< is_ $name _set_low >](), Err(OutputStateError::InputPinError(..))));
+                    assert!(matches!(l293x.[< toggle_ $name >](), Err(OutputStateError::InputPinError(..))));
+                }
+            }
+        };
+    }
+    test_fail!(y1, a1);
+    test_fail!(y2, a2);
+    test_fail!(y3, a3);
+    test_fail!(y4, a4);
+
+    #[test]
+    fn test_disable_holding_low() {
+        let mut l293x = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        );
+        l293x.set_y1_high().unwrap();
+        l293x.set_y2_high().unwrap();
+        l293x.set_y3_high().unwrap();
+        l293x.enable_y1_and_y2().unwrap();
+
+        l293x.disable_y1_and_y2().unwrap();
+        assert!(l293x.a1.get_mut().is_set_high().unwrap());
+
+        l293x.enable_y1_and_y2().unwrap();
+        l293x.disable_y1_and_y2_holding_low().unwrap();
+        assert!(l293x.y1_and_y2_disabled().unwrap());
+        assert!(l293x.a1.get_mut().is_set_low().unwrap());
+        assert!(l293x.a2.get_mut().is_set_low().unwrap());
+        assert!(l293x.a3.get_mut().is_set_high().unwrap());
+
+        l293x.disable_y3_and_y4_holding_low().unwrap();
+        assert!(l293x.a3.get_mut().is_set_low().unwrap());
+    }
+
+    #[test]
+    fn test_disable_duty_cycle_off() {
+        let mut l293x = L293x::new(
+            PwmPin::new(),
+            PwmPin::new(),
+            PwmPin::new(),
+            PwmPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        );
+        l293x.set_y2_duty_cycle_fully_on().unwrap();
+        l293x.set_y4_duty_cycle_fully_on().unwrap();
+
+        l293x.disable_y1_and_y2_duty_cycle_off().unwrap();
+        assert_eq!(l293x.a2.get_mut().get_duty_cycle(), 0);
+        assert_eq!(l293x.y2_duty_cycle(), Some(0));
+        l293x.disable_y3_and_y4_duty_cycle_off().unwrap();
+        assert_eq!(l293x.a4.get_mut().get_duty_cycle(), 0);
+        assert_eq!(l293x.y4_duty_cycle(), Some(0));
+    }
+
+    #[test]
+    fn test_digital_state_clears_duty_cycle() {
+        let mut l293x = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        );
+        for command in &l293x.commands {
+            command.set(InputCommand::Duty(1000));
+        }
+
+        l293x.set_y1_high().unwrap();
+        l293x.y2().set_low().unwrap();
+        l293x.set_output_state(Channel::Y3, PinState::High).unwrap();
+        assert_eq!(
+            l293x.telemetry().unwrap().state.duty_cycles,
+            [None, None, None, Some(1000)]
+        );
+
+        l293x.disable_y3_and_y4_holding_low().unwrap();
+        assert_eq!(l293x.commands[3].get(), InputCommand::State(PinState::Low));
+
+        l293x.commands[0].set(InputCommand::Duty(1000));
+        l293x.emergency_stop().unwrap();
+        assert_eq!(l293x.commands[0].get().duty(), None);
+    }
+
+    #[test]
+    fn test_hold_low_input_error() {
+        let mut l293x = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        );
+        l293x.enable_y1_and_y2().unwrap();
+        l293x.set_y2_high().unwrap();
+        l293x.a1.get_mut().fail();
+
+        assert!(matches!(
+            l293x.disable_y1_and_y2_holding_low(),
+            Err(OutputStateError::InputPinError(_))
+        ));
+        assert!(l293x.y1_and_y2_disabled().unwrap());
+        assert!(l293x.a2.get_mut().is_set_low().unwrap());
+
+        l293x.en12.get_mut().fail();
+        assert!(matches!(
+            l293x.disable_y1_and_y2_holding_low(),
+            Err(OutputStateError::EnablePinError(_))
+        ));
+    }
+
+    #[test]
+    fn test_default() {
+        let mut l293x: L293x<Gnd, Gnd, (), (), Vcc, ()> = L293x::default();
+
+        l293x.set_y1_low().unwrap();
+        assert!(l293x.is_y1_set_low().unwrap());
+        assert!(l293x.is_y2_set_low().unwrap());
+        assert!(l293x.set_y2_high().is_err());
+        assert!(l293x.y1_and_y2_enabled().unwrap());
+    }
+
+    #[test]
+    fn test_partial_chip() {
+        let mut l293x_part = L293x::new(DigitalPin::new(), (), (), (), Vcc(), ());
+
+        l293x_part.set_y1_high().unwrap();
+        assert!(l293x_part.is_y1_set_high().unwrap())
+    }
+
+    #[test]
+    fn test_split() {
+        let split = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        );
+        let mut y1 = split.y1();
+        let mut y1_cpy = split.y1();
+
+        y1.enable().unwrap();
+        y1.set_high().unwrap();
+        assert!(y1_cpy.is_set_high().unwrap())
+    }
+
+    #[test]
+    fn test_cascading_l293() {
+        let split = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        );
+        let mut l293x = L293x::new(split.y1(), split.y2(), split.y3(), split.y4(), Vcc(), Vcc());
+        split.y1().enable().unwrap();
+        l293x.set_y1_high().unwrap();
+        assert!(split.y1().is_set_high().unwrap());
+    }
+
+    #[test]
+    fn test_gang_pwm() {
+        let mut l293x = L293x::new((), (), PwmPin::new(), PwmPin::new(), (), DigitalPin::new());
+
+        let mut output = l293x.gang_y3_and_y4();
+        output.set_duty_cycle_percent(50).unwrap();
+        assert_eq!(output.max_duty_cycle(), u16::MAX);
+
+        assert!(l293x.y3().is_enabled().unwrap());
+        assert_eq!(l293x.a3.get_mut().get_duty_cycle(), u16::MAX / 2);
+        assert_eq!(l293x.a4.get_mut().get_duty_cycle(), u16::MAX / 2);
+    }
+
+    #[test]
+    fn test_gang_input_fail() {
+        let mut l293x = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            (),
+            (),
+            DigitalPin::new(),
+            (),
+        );
+        l293x.enable_y1_and_y2().unwrap();
+        l293x.a2.get_mut().fail();
+
+        assert!(matches!(
+            l293x.gang_y1_and_y2().set_high(),
+            Err(OutputStateError::InputPinError(_))
+        ));
+        assert!(l293x.y1().is_disabled().unwrap());
+    }
+
+    #[test]
+    fn test_pair_state() {
+        let mut l293x = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        );
+
+        l293x.set_y1_and_y2_state(BridgePairState::Forward).unwrap();
+        assert!(l293x.is_y1_set_high().unwrap());
+        assert!(l293x.is_y2_set_low().unwrap());
+
+        l293x.set_y3_and_y4_state(BridgePairState::Reverse).unwrap();
+        assert!(l293x.is_y3_set_low().unwrap());
+        assert!(l293x.is_y4_set_high().unwrap());
+
+        l293x
+            .set_y3_and_y4_state(BridgePairState::BrakeLow)
+            .unwrap();
+        assert!(l293x.is_y4_set_low().unwrap());
+
+        l293x.set_y1_and_y2_state(BridgePairState::HighZ).unwrap();
+        assert!(l293x.y1_and_y2_disabled().unwrap());
+    }
+
+    #[test]
+    fn test_pair_state_fail() {
+        let mut l293x = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            (),
+            (),
+            DigitalPin::new(),
+            (),
+        );
+        l293x.a2.get_mut().fail();
+        assert!(matches!(
+            l293x.set_y1_and_y2_state(BridgePairState::Forward),
+            Err(OutputStateError::InputPinError(_))
+        ));
+        assert!(l293x.y1_and_y2_disabled().unwrap());
+
+        l293x.en12.get_mut().fail();
+        assert!(matches!(
+            l293x.set_y1_and_y2_state(BridgePairState::HighZ),
+            Err(OutputStateError::EnablePinError(_))
+        ));
+    }
+
+    #[test]
+    fn test_all() {
+        let mut l293x = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        );
+
+        l293x.enable_all().unwrap();
+        assert!(l293x.y1_and_y2_enabled().unwrap());
+        assert!(l293x.y3_and_y4_enabled().unwrap());
+
+        l293x.set_y4_high().unwrap();
+        l293x.set_all_low().unwrap();
+        assert!(l293x.is_y4_set_low().unwrap());
+
+        l293x.disable_all().unwrap();
+        assert!(l293x.y1_and_y2_disabled().unwrap());
+        assert!(l293x.y3_and_y4_disabled().unwrap());
+    }
+
+    #[test]
+    fn test_all_fail() {
+        let mut l293x = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        );
+        l293x.enable_all().unwrap();
+        l293x.set_y4_high().unwrap();
+        l293x.en12.get_mut().fail();
+        l293x.a1.get_mut().fail();
 
-#[cfg(test)]
-mod tests {
-    use coverage_helper::test;
-    use embedded_hal::digital::PinState;
+        // The remaining pins are set anyway
+        assert!(l293x.disable_all().is_err());
+        assert!(l293x.y3_and_y4_disabled().unwrap());
+        assert!(l293x.set_all_low().is_err());
+        assert!(l293x.a4.get_mut().is_set_low().unwrap());
+        assert!(l293x.enable_all().is_err());
+    }
 
-    use crate::mock::{DigitalError, DigitalPin, PwmPin};
-    use crate::pins::Vcc;
-    use crate::OutputStateError;
+    #[test]
+    fn test_all_duty_cycle_fully_off() {
+        let mut l293x = L293x::new(
+            PwmPin::new(),
+            PwmPin::new(),
+            PwmPin::new(),
+            PwmPin::new(),
+            (),
+            (),
+        );
+        l293x.set_y2_duty_cycle_fully_on().unwrap();
+        l293x.a3.get_mut().fail();
 
-    use super::*;
+        assert!(l293x.set_all_duty_cycle_fully_off().is_err());
+        assert_eq!(l293x.a2.get_mut().get_duty_cycle(), 0);
+    }
 
     #[test]
-    fn test_enable12() {
+    fn test_emergency_stop() {
         let mut l293x = L293x::new(
             DigitalPin::new(),
             DigitalPin::new(),
@@ -855,18 +3507,111 @@ mod tests {
             DigitalPin::new(),
             DigitalPin::new(),
         );
+        l293x.enable_all().unwrap();
+        l293x.set_y1_high().unwrap();
+        l293x.set_y3_high().unwrap();
+        l293x.emergency_stop().unwrap();
+        assert!(l293x.y1_and_y2_disabled().unwrap());
+        assert!(l293x.a3.get_mut().is_set_low().unwrap());
 
-        l293x.enable_y1_and_y2().unwrap();
+        l293x.enable_all().unwrap();
+        l293x.set_y4_high().unwrap();
+        l293x.en12.get_mut().fail();
+        l293x.a2.get_mut().fail();
+        let error = l293x.emergency_stop().unwrap_err();
+        assert!(error.enable12.is_some());
+        assert!(error.enable34.is_none());
+        assert_eq!(
+            error.inputs.map(|e| e.is_some()),
+            [false, true, false, false]
+        );
+        assert!(l293x.y3_and_y4_disabled().unwrap());
+        assert!(l293x.a4.get_mut().is_set_low().unwrap());
+    }
+
+    #[test]
+    fn test_emergency_stop_duty_cycle() {
+        let mut l293x = L293x::new(
+            PwmPin::new(),
+            PwmPin::new(),
+            PwmPin::new(),
+            PwmPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        );
+        l293x.set_y1_duty_cycle_fully_on().unwrap();
+        l293x.emergency_stop_duty_cycle().unwrap();
+        assert_eq!(l293x.a1.get_mut().get_duty_cycle(), 0);
+
+        l293x.a4.get_mut().fail();
+        let error = l293x.emergency_stop_duty_cycle().unwrap_err();
+        assert!(error.inputs[3].is_some());
+    }
+
+    #[test]
+    fn test_emergency_stop_failsafe() {
+        let mut l293x = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        )
+        .with_failsafe(FailsafeAction::Brake, FailsafeAction::RampDown(100));
+        l293x.set_y1_high().unwrap();
+        l293x.emergency_stop().unwrap();
         assert!(l293x.y1_and_y2_enabled().unwrap());
-        assert!(!l293x.y1_and_y2_disabled().unwrap());
+        assert!(l293x.y3_and_y4_enabled().unwrap());
+        assert!(l293x.a1.get_mut().is_set_low().unwrap());
 
-        l293x.disable_y1_and_y2().unwrap();
-        assert!(!l293x.y1_and_y2_enabled().unwrap());
+        // Pairs, whose inputs could not be set low, stay disabled
+        l293x.set_failsafe(FailsafeAction::Brake, FailsafeAction::Coast);
+        assert_eq!(
+            l293x.failsafe_actions(),
+            (FailsafeAction::Brake, FailsafeAction::Coast)
+        );
+        l293x.a2.get_mut().fail();
+        let error = l293x.emergency_stop().unwrap_err();
+        assert!(error.enable12.is_none());
         assert!(l293x.y1_and_y2_disabled().unwrap());
+        assert!(l293x.y3_and_y4_disabled().unwrap());
     }
 
     #[test]
-    fn test_enable34() {
+    fn test_failsafe() {
+        let mut l293x = L293x::new(
+            PwmPin::new(),
+            PwmPin::new(),
+            PwmPin::new(),
+            PwmPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        )
+        .with_failsafe(FailsafeAction::Coast, FailsafeAction::RampDown(20));
+        let mut delay = Delay::new();
+        l293x.full_h12().set_speed(1000).unwrap();
+        l293x.full_h34().set_speed(-1000).unwrap();
+        assert_eq!(
+            l293x.cached_speed(2, u16::MAX, u16::MAX),
+            -duty_to_speed(crate::full_h::speed_to_duty(1000, u16::MAX), u16::MAX)
+        );
+
+        l293x.failsafe(&mut delay).unwrap();
+        assert!(l293x.y1_and_y2_disabled().unwrap());
+        assert!(l293x.y3_and_y4_enabled().unwrap());
+        assert_eq!(l293x.y4_duty_cycle(), Some(0));
+        assert_eq!(delay.elapsed_ns(), 20_000_000);
+
+        // Both pairs are stopped, even if the first one fails
+        l293x.full_h34().set_speed(1000).unwrap();
+        l293x.en12.get_mut().fail();
+        assert!(l293x.failsafe(&mut delay).is_err());
+        assert_eq!(l293x.y3_duty_cycle(), Some(0));
+    }
+
+    #[test]
+    fn test_snapshot_restore() {
         let mut l293x = L293x::new(
             DigitalPin::new(),
             DigitalPin::new(),
@@ -875,18 +3620,62 @@ mod tests {
             DigitalPin::new(),
             DigitalPin::new(),
         );
+        l293x.set_y2_high().unwrap();
+        l293x.set_y3_high().unwrap();
+        l293x.enable_y1_and_y2().unwrap();
+
+        let state = l293x.snapshot().unwrap();
+        assert_eq!(
+            state,
+            L293xState {
+                inputs: [false, true, true, false],
+                enable12: true,
+                enable34: false,
+                duty_cycles: [None; 4],
+            }
+        );
 
+        l293x.emergency_stop().unwrap();
         l293x.enable_y3_and_y4().unwrap();
-        assert!(l293x.y3_and_y4_enabled().unwrap());
-        assert!(!l293x.y3_and_y4_disabled().unwrap());
+        l293x.restore(&state).unwrap();
+        assert_eq!(l293x.snapshot().unwrap(), state);
+    }
 
-        l293x.disable_y3_and_y4().unwrap();
-        assert!(!l293x.y3_and_y4_enabled().unwrap());
-        assert!(l293x.y3_and_y4_disabled().unwrap());
+    #[test]
+    fn test_restore_disables_enabled_pair() {
+        let clock = Clock::new();
+        let mut l293x = L293x::new(
+            Recorder::<_, 8>::new(DigitalPin::new(), &clock),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            Recorder::<_, 8>::new(DigitalPin::new(), &clock),
+            DigitalPin::new(),
+        );
+        l293x.enable_y1_and_y2().unwrap();
+        l293x.a1.get_mut().clear();
+        l293x.en12.get_mut().clear();
+
+        let state = L293xState {
+            inputs: [true, false, false, false],
+            enable12: true,
+            enable34: false,
+            duty_cycles: [None; 4],
+        };
+        l293x.restore(&state).unwrap();
+        assert_eq!(l293x.snapshot().unwrap(), state);
+
+        let ((a1, _, _, _), (en12, _)) = l293x.release();
+        let [disable, enable] = en12.records() else {
+            panic!("unexpected enable pin records: {:?}", en12.records());
+        };
+        assert_eq!((disable.event, enable.event), (Event::Low, Event::High));
+        assert!(disable.time < a1.records()[0].time);
+        assert!(a1.records()[0].time < enable.time);
     }
 
     #[test]
-    fn test_enable12_fail() {
+    fn test_telemetry() {
         let mut l293x = L293x::new(
             DigitalPin::new(),
             DigitalPin::new(),
@@ -895,256 +3684,691 @@ mod tests {
             DigitalPin::new(),
             DigitalPin::new(),
         );
+        l293x.set_y1_high().unwrap();
+        l293x.commands[3].set(InputCommand::Duty(1000));
+
+        let telemetry = l293x.telemetry().unwrap();
+        assert_eq!(telemetry.state, l293x.snapshot().unwrap());
+        assert_eq!(telemetry.state.duty_cycles, [None, None, None, Some(1000)]);
+
         l293x.en12.get_mut().fail();
+        assert!(l293x.telemetry().is_err());
+    }
+
+    #[test]
+    fn test_snapshot_restore_fail() {
+        let mut l293x = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        );
+        l293x.a3.get_mut().fail();
         assert!(matches!(
-            l293x.is_y1_set_high().unwrap_err(),
-            OutputStateError::EnablePinError(..)
+            l293x.snapshot(),
+            Err(OutputStateError::InputPinError(_))
         ));
         assert!(matches!(
-            l293x.is_y2_set_high().unwrap_err(),
-            OutputStateError::EnablePinError(..)
+            l293x.restore(&L293xState::default()),
+            Err(OutputStateError::InputPinError(_))
         ));
+
+        l293x.en34.get_mut().fail();
         assert!(matches!(
-            l293x.is_y1_set_low().unwrap_err(),
-            OutputStateError::EnablePinError(..)
+            l293x.snapshot(),
+            Err(OutputStateError::EnablePinError(_))
         ));
         assert!(matches!(
-            l293x.is_y2_set_low().unwrap_err(),
-            OutputStateError::EnablePinError(..)
+            l293x.restore(&L293xState::default()),
+            Err(OutputStateError::EnablePinError(_))
+        ));
+    }
+
+    #[test]
+    fn test_set_level() {
+        let mut l293x = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        );
+        l293x.set_y1(OutputLevel::High).unwrap();
+        l293x.set_y4(OutputLevel::Low).unwrap();
+        assert_eq!(l293x.get_y1_state().unwrap(), OutputLevel::High);
+        assert_eq!(l293x.get_y2_state().unwrap(), OutputLevel::Low);
+        assert_eq!(l293x.get_y4_state().unwrap(), OutputLevel::Low);
+
+        l293x.set_y2(OutputLevel::HighZ).unwrap();
+        l293x.set_y3(OutputLevel::High).unwrap();
+        assert_eq!(l293x.get_y1_state().unwrap(), OutputLevel::HighZ);
+        assert_eq!(l293x.get_y4_state().unwrap(), OutputLevel::Low);
+        assert!(l293x.is_y3_set_high().unwrap());
+
+        l293x.a1.get_mut().fail();
+        assert!(matches!(
+            l293x.set_y1(OutputLevel::High),
+            Err(OutputStateError::InputPinError(_))
+        ));
+        assert_eq!(l293x.get_y2_state().unwrap(), OutputLevel::HighZ);
+        l293x.en34.get_mut().fail();
+        assert!(matches!(
+            l293x.set_y4(OutputLevel::HighZ),
+            Err(OutputStateError::EnablePinError(_))
         ));
     }
 
     #[test]
-    fn test_enable34_fail() {
+    fn test_set_and_enable() {
+        let mut l293x = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        );
+        l293x.set_y2_high_and_enable().unwrap();
+        assert_eq!(l293x.get_y2_state().unwrap(), OutputLevel::High);
+        assert_eq!(l293x.get_y1_state().unwrap(), OutputLevel::Low);
+
+        l293x.set_y3_high().unwrap();
+        assert_eq!(l293x.get_y3_state().unwrap(), OutputLevel::HighZ);
+        l293x.set_y3_low_and_enable().unwrap();
+        assert_eq!(l293x.get_y3_state().unwrap(), OutputLevel::Low);
+    }
+
+    #[test]
+    fn test_transaction() {
+        let mut l293x = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        );
+        l293x.set_y1_high().unwrap();
+        l293x.enable_y1_and_y2().unwrap();
+
+        l293x
+            .transaction(|txn| {
+                txn.set_y1_low().set_y2_high();
+                txn.set_y3_high().enable_y3_and_y4();
+            })
+            .unwrap();
+        assert_eq!(
+            l293x.snapshot().unwrap(),
+            L293xState {
+                inputs: [false, true, true, false],
+                enable12: true,
+                enable34: true,
+                duty_cycles: [None; 4],
+            }
+        );
+
+        l293x
+            .transaction(|txn| {
+                txn.set_y4_high().disable_y1_and_y2();
+            })
+            .unwrap();
+        assert_eq!(
+            l293x.snapshot().unwrap(),
+            L293xState {
+                inputs: [false, true, true, true],
+                enable12: false,
+                enable34: true,
+                duty_cycles: [None; 4],
+            }
+        );
+
+        l293x.transaction(|_| {}).unwrap();
+        assert!(l293x.y3_and_y4_enabled().unwrap());
+    }
+
+    #[test]
+    fn test_transaction_fail() {
+        let mut l293x = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        );
+        l293x.enable_y1_and_y2().unwrap();
+        l293x.a2.get_mut().fail();
+        assert!(matches!(
+            l293x.transaction(|txn| {
+                txn.set_y2_high();
+            }),
+            Err(OutputStateError::InputPinError(_))
+        ));
+        assert!(!l293x.y1_and_y2_enabled().unwrap());
+
+        l293x.en34.get_mut().fail();
+        assert!(matches!(
+            l293x.transaction(|txn| {
+                txn.set_y3_high();
+            }),
+            Err(OutputStateError::EnablePinError(_))
+        ));
+        assert!(matches!(
+            l293x.transaction(|txn| {
+                txn.disable_y3_and_y4();
+            }),
+            Err(OutputStateError::EnablePinError(_))
+        ));
+    }
+
+    #[test]
+    fn test_port() {
+        use crate::port::tests::Backend;
+
+        let port = Port::new(Backend::default());
+        let other = Port::new(Backend::default());
+        let mut l293x = L293x::from_port(&port, [4, 5, 6, 7], (), ());
+        l293x
+            .set_inputs([PinState::High, PinState::Low, PinState::Low, PinState::High])
+            .unwrap();
+        assert_eq!(port.state(), 0b1001_0000);
+        let ((_, a2, a3, _), _) = l293x.release();
+
+        let mut l293x = L293x::new(other.pin(0), a2, a3, other.pin(1), (), ());
+        l293x
+            .set_inputs([
+                PinState::High,
+                PinState::High,
+                PinState::Low,
+                PinState::High,
+            ])
+            .unwrap();
+        assert_eq!(port.state(), 0b1011_0000);
+        assert_eq!(other.state(), 0b11);
+        assert_eq!(port.release().writes, 2);
+        assert_eq!(other.release().writes, 1);
+    }
+
+    #[test]
+    fn test_with_polarity() {
+        let polarity = L293xPolarity {
+            inputs: [
+                Polarity::Normal,
+                Polarity::Inverted,
+                Polarity::Normal,
+                Polarity::Normal,
+            ],
+            enable12: Polarity::Inverted,
+            ..L293xPolarity::default()
+        };
+        let mut l293x: L293x<DigitalPin, DigitalPin, (), (), DigitalPin, ()> = L293x::with_polarity(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            (),
+            (),
+            DigitalPin::new(),
+            (),
+            polarity,
+        );
+        assert_eq!(l293x.polarity(), polarity);
+        l293x.set_y1_high().unwrap();
+        l293x.set_y2_high().unwrap();
+        l293x.enable_y1_and_y2().unwrap();
+        assert!(l293x.y1_and_y2_enabled().unwrap());
+        assert!(l293x.is_y2_set_high().unwrap());
+        assert!(l293x.en12.get_mut().is_set_low().unwrap());
+        assert!(l293x.a2.get_mut().is_set_low().unwrap());
+
+        l293x.y2().set_low().unwrap();
+        assert!(l293x.y2().is_set_low().unwrap());
+        assert!(l293x.a2.get_mut().is_set_high().unwrap());
+        l293x.y1().disable().unwrap();
+        assert!(l293x.en12.get_mut().is_set_high().unwrap());
+
+        let (mut pair12, _) = l293x.split_pairs();
+        assert!(pair12.first().is_disabled().unwrap());
+        assert!(pair12.second().is_set_low().is_err());
+        pair12.enable().unwrap();
+        pair12.second().set_high().unwrap();
+        let (mut a1, mut a2, mut en12) = pair12.release();
+        assert!(a1.is_set_high().unwrap());
+        assert!(a2.is_set_low().unwrap());
+        assert!(en12.is_set_low().unwrap());
+    }
+
+    #[test]
+    fn test_with_polarity_channels() {
+        let polarity = L293xPolarity {
+            inputs: [Polarity::Inverted; 4],
+            enable12: Polarity::Inverted,
+            enable34: Polarity::Inverted,
+        };
+        let l293x = L293x::with_polarity(
+            DigitalPin::new(),
+            (),
+            (),
+            (),
+            DigitalPin::new(),
+            (),
+            polarity,
+        );
+        let (mut en12, mut en34) = (PinCell::new(), PinCell::new());
+        let (mut y1, ..) = l293x.into_channels(&mut en12, &mut en34);
+        y1.set_high().unwrap();
+        assert!(y1.is_set_high().unwrap());
+        let (mut a1, mut en12) = y1.release();
+        assert!(a1.is_set_low().unwrap());
+        assert!(en12.is_set_low().unwrap());
+    }
+
+    #[test]
+    fn test_restore_duty_cycle() {
+        let mut l293x = L293x::new(
+            PwmPin::new(),
+            PwmPin::new(),
+            PwmPin::new(),
+            PwmPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        );
+        l293x.enable_y3_and_y4().unwrap();
+
+        let state = L293xState {
+            inputs: [false, true, true, false],
+            enable12: true,
+            enable34: false,
+            duty_cycles: [Some(1000), None, Some(0), None],
+        };
+        l293x.restore_duty_cycle(&state).unwrap();
+        assert_eq!(l293x.a1.get_mut().get_duty_cycle(), 1000);
+        assert_eq!(l293x.a2.get_mut().get_duty_cycle(), u16::MAX);
+        assert_eq!(l293x.a3.get_mut().get_duty_cycle(), 0);
+        assert_eq!(l293x.a4.get_mut().get_duty_cycle(), 0);
+        assert!(l293x.y1_and_y2_enabled().unwrap());
+        assert!(l293x.y3_and_y4_disabled().unwrap());
+        assert_eq!(l293x.y1_duty_cycle(), Some(1000));
+        assert_eq!(l293x.y2_duty_cycle(), None);
+        assert_eq!(l293x.y3_duty_cycle(), Some(0));
+
+        l293x.a2.get_mut().fail();
+        assert!(matches!(
+            l293x.restore_duty_cycle(&state),
+            Err(OutputStateError::InputPinError(_))
+        ));
+        assert!(l293x.y1_and_y2_disabled().unwrap());
+        assert_eq!(l293x.y2_duty_cycle(), None);
+
+        l293x.en34.get_mut().fail();
+        assert!(matches!(
+            l293x.restore_duty_cycle(&state),
+            Err(OutputStateError::EnablePinError(_))
+        ));
+    }
+
+    #[test]
+    fn test_suspend_resume() {
+        let mut l293x = L293x::new(
+            PwmPin::new(),
+            PwmPin::new(),
+            PwmPin::new(),
+            PwmPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        );
+        l293x.enable_y1_and_y2().unwrap();
+        l293x.set_y1_duty_cycle_percent(50).unwrap();
+        l293x.set_y2_duty_cycle_fully_on().unwrap();
+        l293x.commands[2].set(InputCommand::State(PinState::High));
+        l293x.resume_duty_cycle().unwrap();
+
+        l293x.suspend().unwrap();
+        l293x.suspend().unwrap();
+        assert!(l293x.is_suspended());
+        assert!(l293x.y1_and_y2_disabled().unwrap());
+
+        // The inputs lose their states in the low-power mode
+        for pin in [&mut l293x.a1, &mut l293x.a2, &mut l293x.a3] {
+            pin.get_mut().set_duty_cycle(0).unwrap();
+        }
+        l293x.resume_duty_cycle().unwrap();
+        assert!(!l293x.is_suspended());
+        assert_eq!(l293x.a1.get_mut().get_duty_cycle(), u16::MAX / 2);
+        assert_eq!(l293x.a2.get_mut().get_duty_cycle(), u16::MAX);
+        assert_eq!(l293x.a3.get_mut().get_duty_cycle(), u16::MAX);
+        assert!(l293x.y1_and_y2_enabled().unwrap());
+        assert!(l293x.y3_and_y4_disabled().unwrap());
+    }
+
+    #[test]
+    fn test_suspend_resume_digital() {
+        let mut l293x = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        );
+        l293x.set_y1_high().unwrap();
+        l293x.enable_y3_and_y4().unwrap();
+        l293x.suspend().unwrap();
+        l293x.a1.get_mut().set_low().unwrap();
+
+        l293x.resume().unwrap();
+        assert!(l293x.a1.get_mut().is_set_high().unwrap());
+        assert!(l293x.a2.get_mut().is_set_low().unwrap());
+        assert!(l293x.y1_and_y2_disabled().unwrap());
+        assert!(l293x.y3_and_y4_enabled().unwrap());
+    }
+
+    #[test]
+    fn test_suspend_resume_fail() {
+        let chip = || {
+            let mut l293x = L293x::new(
+                DigitalPin::new(),
+                DigitalPin::new(),
+                DigitalPin::new(),
+                DigitalPin::new(),
+                DigitalPin::new(),
+                DigitalPin::new(),
+            );
+            l293x.enable_all().unwrap();
+            l293x
+        };
+
+        // The states are captured before the first enable pin is set
         let mut l293x = L293x::new(
             DigitalPin::new(),
             DigitalPin::new(),
             DigitalPin::new(),
             DigitalPin::new(),
-            DigitalPin::new(),
-            DigitalPin::new(),
+            Vcc(),
+            Vcc(),
         );
-        l293x.en34.get_mut().fail();
-        assert!(matches!(
-            l293x.is_y3_set_high().unwrap_err(),
-            OutputStateError::EnablePinError(..)
-        ));
-        assert!(matches!(
-            l293x.is_y4_set_high().unwrap_err(),
-            OutputStateError::EnablePinError(..)
-        ));
+        assert!(l293x.suspend().is_err());
+        assert!(l293x.is_suspended());
+        assert!(l293x.suspend().is_err());
+        l293x.resume().unwrap();
+        assert!(!l293x.is_suspended());
+
+        let mut l293x = chip();
+        l293x.set_y2_high().unwrap();
+        l293x.suspend().unwrap();
+        l293x.a2.get_mut().fail();
         assert!(matches!(
-            l293x.is_y3_set_low().unwrap_err(),
-            OutputStateError::EnablePinError(..)
+            l293x.resume(),
+            Err(OutputStateError::InputPinError(_))
         ));
+        assert!(l293x.is_suspended());
+        assert!(l293x.y1_and_y2_disabled().unwrap());
+
+        let mut l293x = chip();
+        l293x.suspend().unwrap();
+        l293x.en12.get_mut().fail();
         assert!(matches!(
-            l293x.is_y4_set_low().unwrap_err(),
-            OutputStateError::EnablePinError(..)
+            l293x.resume(),
+            Err(OutputStateError::EnablePinError(_))
         ));
+        assert!(l293x.is_suspended());
     }
 
-    macro_rules! test_output {
-        ($name:ident, $input:ident, $bname:ident) => {
-            paste::item! {
-                #[test]
-                fn [< test_ $name >]() {
-                    let mut l293x = L293x::new(
-                        DigitalPin::new(),
-                        DigitalPin::new(),
-                        DigitalPin::new(),
-                        DigitalPin::new(),
-                        DigitalPin::new(),
-                        DigitalPin::new(),
-                    );
-
-                    // LOW, LOW => Z
-                    l293x.[< set_ $name _low >]().unwrap();
-                    l293x.[< disable_ $bname >]().unwrap();
-                    assert!(matches!(l293x.[< is_ $name _set_high >](), Err(OutputStateError::NotEnabled)));
-                    assert!(matches!(l293x.[< is_ $name _set_low >](), Err(OutputStateError::NotEnabled)));
-
-                    // HIGH, LOW => Z
-                    l293x.[< set_ $name _high >]().unwrap();
-                    l293x.[< disable_ $bname >]().unwrap();
-                    assert!(matches!(l293x.[< is_ $name _set_high >](), Err(OutputStateError::NotEnabled)));
-                    assert!(matches!(l293x.[< is_ $name _set_low >](), Err(OutputStateError::NotEnabled)));
-
-                    // LOW, HIGH => LOW
-                    l293x.[< set_ $name _low >]().unwrap();
-                    l293x.[< enable_ $bname >]().unwrap();
-                    assert!(!l293x.[< is_ $name _set_high >]().unwrap());
-                    assert!(l293x.[< is_ $name _set_low >]().unwrap());
-
-                    // HIGH, HIGH => HIGH
-                    l293x.[< set_ $name _high >]().unwrap();
-                    l293x.[< enable_ $bname >]().unwrap();
-                    assert!(l293x.[< is_ $name _set_high >]().unwrap());
-                    assert!(!l293x.[< is_ $name _set_low >]().unwrap());
-                }
-
-                #[test]
-                fn [< test_ $name _set_state >]() {
-                    let mut l293x = L293x::new(
-                        DigitalPin::new(),
-                        DigitalPin::new(),
-                        DigitalPin::new(),
-                        DigitalPin::new(),
-                        DigitalPin::new(),
-                        DigitalPin::new(),
-                    );
-
-                    // LOW, LOW => Z
-                    l293x.[< set_ $name _state >](PinState::Low).unwrap();
-                    l293x.[< disable_ $bname >]().unwrap();
-                    assert!(matches!(l293x.[< is_ $name _set_high >](), Err(OutputStateError::NotEnabled)));
-                    assert!(matches!(l293x.[< is_ $name _set_low >](), Err(OutputStateError::NotEnabled)));
-
-                    // HIGH, LOW => Z
-                    l293x.[< set_ $name _state >](PinState::High).unwrap();
-                    l293x.[< disable_ $bname >]().unwrap();
-                    assert!(matches!(l293x.[< is_ $name _set_high >](), Err(OutputStateError::NotEnabled)));
-                    assert!(matches!(l293x.[< is_ $name _set_low >](), Err(OutputStateError::NotEnabled)));
-
-                    // LOW, HIGH => LOW
-                    l293x.[< set_ $name _state >](PinState::Low).unwrap();
-                    l293x.[< enable_ $bname >]().unwrap();
-                    assert!(!l293x.[< is_ $name _set_high >]().unwrap());
-                    assert!(l293x.[< is_ $name _set_low >]().unwrap());
-
-                    // HIGH, HIGH => HIGH
-                    l293x.[< set_ $name _state >](PinState::High).unwrap();
-                    l293x.[< enable_ $bname >]().unwrap();
-                    assert!(l293x.[< is_ $name _set_high >]().unwrap());
-                    assert!(!l293x.[< is_ $name _set_low >]().unwrap());
-                }
-
-                #[test]
-                fn [< test_toggle_ $name >]() {
-                    let mut l293x = L293x::new(
-                        DigitalPin::new(),
-                        DigitalPin::new(),
-                        DigitalPin::new(),
-                        DigitalPin::new(),
-                        DigitalPin::new(),
-                        DigitalPin::new(),
-                    );
-                    l293x.[< set_ $name _state >](PinState::Low).unwrap();
-                    l293x.[< enable_ $bname >]().unwrap();
-                    let old_state = l293x.[< is_ $name _set_high >]().unwrap();
+    #[test]
+    fn test_channels() {
+        let mut l293x = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        );
+        l293x.enable_all().unwrap();
 
-                    l293x.[< toggle_ $name >]().unwrap();
-                    assert_ne!(l293x.[< is_ $name _set_high >]().unwrap(), old_state);
+        for channel in Channel::ALL {
+            l293x.set_output_high(channel).unwrap();
+            assert!(l293x.output(channel).is_set_high().unwrap());
+            l293x.set_output_low(channel).unwrap();
+            assert!(l293x.output(channel).is_set_low().unwrap());
+            l293x.set_output_state(channel, PinState::High).unwrap();
+        }
+        assert!(l293x.is_y4_set_high().unwrap());
 
-                    l293x.[< toggle_ $name >]().unwrap();
-                    assert_eq!(l293x.[< is_ $name _set_high >]().unwrap(), old_state);
-                }
+        l293x.a3.get_mut().fail();
+        assert_eq!(
+            l293x.set_output_high(Channel::Y3),
+            Err(OutputStateError::InputPinError(DigitalError()).on_channel(Channel::Y3))
+        );
+    }
 
-                #[test]
-                fn [< test_ $name _pwm >]() {
-                    let mut l293x = L293x::new(
-                        PwmPin::new(),
-                        PwmPin::new(),
-                        PwmPin::new(),
-                        PwmPin::new(),
-                        DigitalPin::new(),
-                        DigitalPin::new(),
-                    );
+    #[test]
+    fn test_channels_duty_cycle() {
+        let mut l293x = L293x::new(
+            PwmPin::new(),
+            PwmPin::new(),
+            PwmPin::new(),
+            PwmPin::new(),
+            (),
+            Vcc(),
+        );
+        l293x.set_output_duty_cycle(Channel::Y3, 100).unwrap();
+        assert_eq!(l293x.a3.get_mut().get_duty_cycle(), 100);
 
-                    let max_duty = l293x.[< $name _max_duty_cycle >]();
+        l293x.a2.get_mut().fail();
+        let error = l293x.set_output_duty_cycle(Channel::Y2, 100).unwrap_err();
+        assert_eq!(error.channel, Channel::Y2);
+        assert_eq!(error.pin(), Some(PinRole::Input));
+    }
 
-                    l293x.[< set_ $name _duty_cycle >](max_duty).unwrap();
-                    assert_eq!(l293x.$input.borrow().get_duty_cycle(), max_duty);
+    #[test]
+    fn test_channels_iterator() {
+        let mut l293x = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        );
+        l293x.enable_all().unwrap();
 
-                    l293x.[< set_ $name _duty_cycle_fraction >](1, 2).unwrap();
-                    assert_eq!(l293x.$input.borrow().get_duty_cycle(), max_duty / 2);
+        for (index, mut output) in l293x.channels().enumerate() {
+            output.set_state((index % 2 == 0).into()).unwrap();
+        }
+        assert_eq!(l293x.channels().count(), 4);
+        assert!(l293x.y1().is_set_high().unwrap());
+        assert!(l293x.y2().is_set_low().unwrap());
+        assert!(l293x.y3().is_set_high().unwrap());
+        assert!(l293x.y4().is_set_low().unwrap());
+    }
 
-                    l293x.[< set_ $name _duty_cycle_percent >](25).unwrap();
-                    assert_eq!(l293x.$input.borrow().get_duty_cycle(), max_duty / 4);
+    #[test]
+    fn test_into_motors() {
+        let (mut left, mut right) = L293x::new(
+            PwmPin::new(),
+            PwmPin::new(),
+            PwmPin::new(),
+            PwmPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        )
+        .into_motors();
 
-                    l293x.[< set_ $name _duty_cycle_fully_on >]().unwrap();
-                    assert_eq!(l293x.$input.borrow().get_duty_cycle(), max_duty);
+        left.set_speed(i16::MAX).unwrap();
+        right.set_speed(-i16::MAX).unwrap();
 
-                    l293x.[< set_ $name _duty_cycle_fully_off >]().unwrap();
-                    assert_eq!(l293x.$input.borrow().get_duty_cycle(), 0);
-                }
-            }
-        };
+        let (a1, _, mut en12) = left.release();
+        assert_eq!(a1.release().get_duty_cycle(), u16::MAX);
+        assert!(en12.is_set_high().unwrap());
+        let (a3, a4, _) = right.release();
+        assert_eq!(a3.release().get_duty_cycle(), 0);
+        assert_eq!(a4.release().get_duty_cycle(), u16::MAX);
     }
-    test_output!(y1, a1, y1_and_y2);
-    test_output!(y2, a2, y1_and_y2);
-    test_output!(y3, a3, y3_and_y4);
-    test_output!(y4, a4, y3_and_y4);
-
-    macro_rules! test_fail {
-        ($name:ident, $input:ident) => {
-            paste::item! {
-                #[test]
-                fn [< test_ $name _fail >]() {
-                    let mut l293x = L293x::new(
-                        DigitalPin::new(),
-                        DigitalPin::new(),
-                        DigitalPin::new(),
-                        DigitalPin::new(),
-                        DigitalPin::new(),
-                        DigitalPin::new(),
-                    );
-                    l293x.enable_y1_and_y2().unwrap();
-                    l293x.enable_y3_and_y4().unwrap();
-                    l293x.$input.get_mut().fail();
 
-                    assert!(matches!(l293x.[< set_ $name _low >](), Err(DigitalError(..))));
-                    assert!(matches!(l293x.[< set_ $name _high >](), Err(DigitalError(..))));
-                    assert!(matches!(l293x.[< is_ $name _set_high >](), Err(OutputStateError::InputPinError(..))));
-                    assert!(matches!(l293x.[< is_ $name _set_low >](), Err(OutputStateError::InputPinError(..))));
-                    assert!(matches!(l293x.[< toggle_ $name >](), Err(OutputStateError::InputPinError(..))));
-                }
-            }
+    #[test]
+    fn test_into_motors_polarity() {
+        let polarity = L293xPolarity {
+            inputs: [
+                Polarity::Inverted,
+                Polarity::Normal,
+                Polarity::Normal,
+                Polarity::Normal,
+            ],
+            enable12: Polarity::Inverted,
+            ..L293xPolarity::default()
         };
+        let (mut left, _) = L293x::with_polarity(
+            PwmPin::new(),
+            PwmPin::new(),
+            PwmPin::new(),
+            PwmPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            polarity,
+        )
+        .into_motors();
+
+        left.set_speed(i16::MAX).unwrap();
+        let (a1, a2, en12) = left.release();
+        assert_eq!(a1.release().get_duty_cycle(), 0);
+        assert_eq!(a2.release().get_duty_cycle(), 0);
+        assert!(en12.release().is_set_low().unwrap());
     }
-    test_fail!(y1, a1);
-    test_fail!(y2, a2);
-    test_fail!(y3, a3);
-    test_fail!(y4, a4);
 
     #[test]
-    fn test_partial_chip() {
-        let mut l293x_part = L293x::new(DigitalPin::new(), (), (), (), Vcc(), ());
+    fn test_into_full_bridges() {
+        let (mut en12, mut en34) = (PinCell::new(), PinCell::new());
+        let (mut left, mut right) = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        )
+        .into_full_bridges(&mut en12, &mut en34);
 
-        l293x_part.set_y1_high().unwrap();
-        assert!(l293x_part.is_y1_set_high().unwrap())
+        left.forward().unwrap();
+        right.reverse().unwrap();
+        right.coast().unwrap();
+
+        let (mut y1, mut y2) = left.release();
+        assert!(y1.is_set_high().unwrap());
+        assert!(y2.is_set_low().unwrap());
+        assert!(right.release().0.is_disabled().unwrap());
     }
 
     #[test]
-    fn test_split() {
-        let split = L293x::new(
-            DigitalPin::new(),
+    fn test_release() {
+        let mut l293x = L293x::new(
             DigitalPin::new(),
             DigitalPin::new(),
             DigitalPin::new(),
             DigitalPin::new(),
             DigitalPin::new(),
+            (),
         );
-        let mut y1 = split.y1();
-        let mut y1_cpy = split.y1();
+        l293x.set_y3_high().unwrap();
+        l293x.enable_y1_and_y2().unwrap();
 
-        y1.enable().unwrap();
-        y1.set_high().unwrap();
-        assert!(y1_cpy.is_set_high().unwrap())
+        let ((_, _, mut a3, _), (mut en12, ())) = l293x.release();
+        assert!(a3.is_set_high().unwrap());
+        assert!(en12.is_set_high().unwrap());
     }
 
     #[test]
-    fn test_cascading_l293() {
-        let split = L293x::new(
+    fn test_new_initialized() {
+        let mut a2 = DigitalPin::new();
+        a2.set_high().unwrap();
+        let mut en34 = DigitalPin::new();
+        en34.set_high().unwrap();
+
+        let l293x = L293x::new_initialized(
+            DigitalPin::new(),
+            a2,
             DigitalPin::new(),
             DigitalPin::new(),
             DigitalPin::new(),
+            en34,
+        )
+        .unwrap();
+        let ((_, mut a2, ..), (_, mut en34)) = l293x.release();
+        assert!(a2.is_set_low().unwrap());
+        assert!(en34.is_set_low().unwrap());
+    }
+
+    #[test]
+    fn test_new_initialized_fail() {
+        let mut a4 = DigitalPin::new();
+        a4.fail();
+        assert!(matches!(
+            L293x::new_initialized(
+                DigitalPin::new(),
+                DigitalPin::new(),
+                DigitalPin::new(),
+                a4,
+                DigitalPin::new(),
+                DigitalPin::new()
+            ),
+            Err(OutputStateError::InputPinError(_))
+        ));
+
+        let mut en12 = DigitalPin::new();
+        en12.fail();
+        assert!(matches!(
+            L293x::new_initialized(
+                DigitalPin::new(),
+                DigitalPin::new(),
+                DigitalPin::new(),
+                DigitalPin::new(),
+                en12,
+                DigitalPin::new()
+            ),
+            Err(OutputStateError::EnablePinError(_))
+        ));
+    }
+    #[test]
+    fn test_verify() {
+        let mut l293x = L293x::new(
+            DigitalPin::new(),
+            (),
+            (),
             DigitalPin::new(),
             DigitalPin::new(),
             DigitalPin::new(),
         );
-        let mut l293x = L293x::new(split.y1(), split.y2(), split.y3(), split.y4(), Vcc(), Vcc());
-        split.y1().enable().unwrap();
+        let mut feedback = DigitalPin::new();
+
+        assert_eq!(
+            l293x.verify_y1(&mut feedback),
+            Err(VerifyError::PinError(OutputStateError::NotEnabled))
+        );
+
+        l293x.enable_y1_and_y2().unwrap();
         l293x.set_y1_high().unwrap();
-        assert!(split.y1().is_set_high().unwrap());
+        assert_eq!(
+            l293x.verify_y1(&mut feedback),
+            Err(VerifyError::OutputMismatch {
+                commanded: true,
+                measured: false
+            })
+        );
+        feedback.set_high().unwrap();
+        assert!(l293x.verify_y1(&mut feedback).is_ok());
+
+        l293x.enable_y3_and_y4().unwrap();
+        feedback.fail();
+        assert_eq!(
+            l293x.verify_y4(&mut feedback),
+            Err(VerifyError::FeedbackPinError(DigitalError()))
+        );
+
+        // The feedback pin does not need to share the error type of the inputs
+        let mut l293x = L293x::new(Vcc(), (), (), (), Vcc(), ());
+        let mut feedback = DigitalPin::new();
+        feedback.set_high().unwrap();
+        assert!(l293x.verify_y1(&mut feedback).is_ok());
     }
 }
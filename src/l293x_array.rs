@@ -0,0 +1,304 @@
+use embedded_hal::digital::{OutputPin, PinState};
+use embedded_hal::pwm::SetDutyCycle;
+
+use crate::{Channel, HalfH, L293x};
+
+/// Two L293x chips sharing a single API with the outputs `1` - `8`
+///
+/// See [L293xArray] for more information.
+pub type DualL293x<P, EN> = L293xArray<P, EN, 2>;
+
+/// Multiple L293x chips sharing a single API
+///
+/// Larger arrays of LEDs, relays or motors need more than the four outputs of a single chip. This
+/// type aggregates `N` chips, whose inputs and enable pins use the same pin types, and numbers
+/// their outputs consecutively: The outputs `1` - `4` are the outputs of chip `0`, the outputs
+/// `5` - `8` the ones of chip `1` and so on. Thus, the application does not need its own
+/// dispatch layer to select the chip of an output.
+///
+/// In contrast to the [L293xChain](crate::L293xChain), the chips are driven by pins of the
+/// microcontroller (or a port expander) directly. The most common case of two chips is available
+/// as the [DualL293x] type alias.
+///
+/// # Examples
+///
+/// ```
+/// use l293x::{DualL293x, L293x};
+///
+/// let mut chips = DualL293x::new([
+///     L293x::new(a1, a2, a3, a4, en12, en34),
+///     L293x::new(b1, b2, b3, b4, en56, en78),
+/// ]);
+///
+/// chips.enable_all()?;
+/// chips.set_output_high(7)?;
+/// ```
+#[derive(Debug)]
+pub struct L293xArray<P, EN, const N: usize> {
+    chips: [L293x<P, P, P, P, EN, EN>; N],
+}
+
+impl<P, EN, const N: usize> L293xArray<P, EN, N> {
+    /// The number of outputs of the array.
+    pub const OUTPUTS: usize = 4 * N;
+
+    /// Create a new array from the given chips.
+    #[inline]
+    pub fn new(chips: [L293x<P, P, P, P, EN, EN>; N]) -> Self {
+        Self { chips }
+    }
+
+    /// Release the array and return the chips.
+    #[inline]
+    pub fn release(self) -> [L293x<P, P, P, P, EN, EN>; N] {
+        self.chips
+    }
+
+    /// Get the chip with the given zero-based `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the chip does not exist.
+    #[inline]
+    pub fn chip(&self, index: usize) -> &L293x<P, P, P, P, EN, EN> {
+        &self.chips[index]
+    }
+
+    /// Get the chip with the given zero-based `index` mutably.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the chip does not exist.
+    #[inline]
+    pub fn chip_mut(&mut self, index: usize) -> &mut L293x<P, P, P, P, EN, EN> {
+        &mut self.chips[index]
+    }
+
+    /// Get the Half-H bridge of the given output (`1` - [`OUTPUTS`](L293xArray::OUTPUTS)).
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the output does not exist.
+    #[inline]
+    pub fn output(&self, output: usize) -> HalfH<'_, P, EN> {
+        let (chip, channel) = Self::locate(output);
+        self.chips[chip].output(channel)
+    }
+
+    /// Get the chip and the channel of the given output.
+    fn locate(output: usize) -> (usize, Channel) {
+        assert!(
+            (1..=Self::OUTPUTS).contains(&output),
+            "output {} does not exist",
+            output
+        );
+        let index = output - 1;
+        (index / 4, Channel::ALL[index % 4])
+    }
+}
+
+impl<P, EN, const N: usize> L293xArray<P, EN, N>
+where
+    P: OutputPin,
+{
+    /// Set the input of the given output high.
+    ///
+    /// Like [`L293x::set_output_high()`], this method does **not** enable the output.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the output does not exist.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the input pin.
+    #[inline]
+    pub fn set_output_high(&mut self, output: usize) -> Result<(), P::Error> {
+        self.set_output_state(output, PinState::High)
+    }
+
+    /// Set the input of the given output low.
+    ///
+    /// Like [`L293x::set_output_low()`], this method does **not** enable the output.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the output does not exist.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the input pin.
+    #[inline]
+    pub fn set_output_low(&mut self, output: usize) -> Result<(), P::Error> {
+        self.set_output_state(output, PinState::Low)
+    }
+
+    /// Set the input of the given output to the given `state`.
+    ///
+    /// Like [`L293x::set_output_state()`], this method does **not** enable the output.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the output does not exist.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the input pin.
+    pub fn set_output_state(&mut self, output: usize, state: PinState) -> Result<(), P::Error> {
+        let (chip, channel) = Self::locate(output);
+        self.chips[chip].set_channel_state(channel, state)
+    }
+}
+
+impl<P, EN, const N: usize> L293xArray<P, EN, N>
+where
+    P: SetDutyCycle,
+{
+    /// Set the duty cycle of the input of the given output.
+    ///
+    /// Like [`L293x::set_output_duty_cycle()`], this method does **not** enable the output.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the output does not exist.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the input pin.
+    pub fn set_output_duty_cycle(&mut self, output: usize, duty: u16) -> Result<(), P::Error> {
+        let (chip, channel) = Self::locate(output);
+        self.chips[chip].set_channel_duty_cycle(channel, duty)
+    }
+}
+
+impl<P, EN, const N: usize> L293xArray<P, EN, N>
+where
+    EN: OutputPin,
+{
+    /// Enable all outputs of all chips.
+    ///
+    /// All enable pins are set high, even if setting one of them fails.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the first error of the enable pins.
+    pub fn enable_all(&mut self) -> Result<(), EN::Error> {
+        self.chips
+            .iter_mut()
+            .map(L293x::enable_all)
+            .fold(Ok(()), Result::and)
+    }
+
+    /// Disable all outputs of all chips.
+    ///
+    /// All enable pins are set low, even if setting one of them fails, like for
+    /// [`L293x::disable_all()`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return the first error of the enable pins.
+    pub fn disable_all(&mut self) -> Result<(), EN::Error> {
+        self.chips
+            .iter_mut()
+            .map(L293x::disable_all)
+            .fold(Ok(()), Result::and)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{DigitalPin, PwmPin};
+    use coverage_helper::test;
+    use embedded_hal::digital::StatefulOutputPin;
+
+    fn chip() -> L293x<DigitalPin, DigitalPin, DigitalPin, DigitalPin, DigitalPin, DigitalPin> {
+        L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        )
+    }
+
+    #[test]
+    fn test_outputs() {
+        let mut chips = DualL293x::new([chip(), chip()]);
+        assert_eq!(DualL293x::<DigitalPin, DigitalPin>::OUTPUTS, 8);
+
+        chips.enable_all().unwrap();
+        chips.set_output_high(7).unwrap();
+        chips.set_output_state(1, PinState::High).unwrap();
+        chips.set_output_low(1).unwrap();
+        assert!(chips.output(7).is_set_high().unwrap());
+        assert!(chips.chip_mut(1).is_y3_set_high().unwrap());
+        assert!(chips.chip(0).y1().is_set_low().unwrap());
+
+        chips.disable_all().unwrap();
+        let [first, second] = chips.release();
+        assert!(first.y4().is_disabled().unwrap());
+        assert!(second.y1().is_disabled().unwrap());
+    }
+
+    #[test]
+    fn test_duty_cycle() {
+        let mut chips = L293xArray::new([
+            L293x::new(
+                PwmPin::new(),
+                PwmPin::new(),
+                PwmPin::new(),
+                PwmPin::new(),
+                (),
+                (),
+            ),
+            L293x::new(
+                PwmPin::new(),
+                PwmPin::new(),
+                PwmPin::new(),
+                PwmPin::new(),
+                (),
+                (),
+            ),
+            L293x::new(
+                PwmPin::new(),
+                PwmPin::new(),
+                PwmPin::new(),
+                PwmPin::new(),
+                (),
+                (),
+            ),
+        ]);
+
+        chips.set_output_duty_cycle(12, 1000).unwrap();
+        let [_, _, chip] = chips.release();
+        assert_eq!(chip.release().0 .3.get_duty_cycle(), 1000);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_output() {
+        DualL293x::new([chip(), chip()]).output(0);
+    }
+
+    #[test]
+    fn test_enable_error() {
+        let mut en12 = DigitalPin::new();
+        en12.fail();
+        let failing = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            en12,
+            DigitalPin::new(),
+        );
+        let mut chips = DualL293x::new([failing, chip()]);
+
+        assert!(chips.enable_all().is_err());
+        assert!(chips.chip(1).y1().is_enabled().unwrap());
+        assert!(chips.disable_all().is_err());
+        assert!(chips.chip(1).y1().is_disabled().unwrap());
+    }
+}
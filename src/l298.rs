@@ -0,0 +1,197 @@
+use crate::pins::NotConnected;
+use crate::{FullHBridge, HalfH, L293x};
+
+/// A L298 Dual Full-Bridge Driver
+///
+/// The [L298](https://www.st.com/resource/en/datasheet/l298.pdf) chip (e.g. on the common L298N
+/// breakout boards) uses the same interface as the [L293](L293x) chip: Four inputs (`IN1` -
+/// `IN4`) and two enable pins (`ENA` for the outputs `OUT1` & `OUT2`, `ENB` for the outputs
+/// `OUT3` & `OUT4`). Thus, this driver is a thin wrapper around a [L293x] driver, which provides
+/// the same [HalfH] and [FullH](crate::FullH) handles using the names of the L298 datasheet.
+///
+/// Additionally, the L298 chip exposes the emitters of the lower transistors of both bridges as
+/// current-sense pins (`SENSE A` and `SENSE B`). These pins are optional and are
+/// [NotConnected] by default. As `embedded-hal` does not provide an ADC abstraction, the pins
+/// can be of any type (e.g. an ADC channel of the platform HAL). They are stored by the driver
+/// and can be accessed using the [`sense_a()`](L298::sense_a) and [`sense_b()`](L298::sense_b)
+/// methods.
+///
+/// For all other operations, the underlying [L293x] driver can be accessed using the
+/// [`chip()`](L298::chip) and [`chip_mut()`](L298::chip_mut) methods.
+///
+/// # Examples
+///
+/// ```
+/// use l293x::L298;
+///
+/// // [...] create the input, enable and ADC pins
+/// let mut l298 = L298::new(in1, in2, in3, in4, ena, enb).with_current_sense(adc_a, adc_b);
+///
+/// l298.bridge_a().forward()?;
+/// let current = adc.read(l298.sense_a())?;
+/// ```
+#[derive(Debug)]
+pub struct L298<IN1, IN2, IN3, IN4, ENA, ENB, SA = NotConnected, SB = NotConnected> {
+    chip: L293x<IN1, IN2, IN3, IN4, ENA, ENB>,
+    sense_a: SA,
+    sense_b: SB,
+}
+
+impl<IN1, IN2, IN3, IN4, ENA, ENB> L298<IN1, IN2, IN3, IN4, ENA, ENB> {
+    /// Create a new L298 chip driver without current-sense pins.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use l293x::L298;
+    /// let l298 = L298::new(in1, in2, in3, in4, ena, enb);
+    /// ```
+    #[inline]
+    pub fn new(in1: IN1, in2: IN2, in3: IN3, in4: IN4, ena: ENA, enb: ENB) -> Self {
+        Self::from_l293x(L293x::new(in1, in2, in3, in4, ena, enb))
+    }
+
+    /// Create a new L298 chip driver from an existing [L293x] driver.
+    ///
+    /// This keeps the state of the given driver, e.g. the last commanded duty cycles.
+    #[inline]
+    pub fn from_l293x(chip: L293x<IN1, IN2, IN3, IN4, ENA, ENB>) -> Self {
+        Self {
+            chip,
+            sense_a: NotConnected,
+            sense_b: NotConnected,
+        }
+    }
+
+    /// Add the current-sense pins of both bridges to the driver.
+    ///
+    /// If only one of the bridges has a current-sense pin, [NotConnected] can be passed for the
+    /// other one.
+    #[inline]
+    pub fn with_current_sense<SA, SB>(
+        self,
+        sense_a: SA,
+        sense_b: SB,
+    ) -> L298<IN1, IN2, IN3, IN4, ENA, ENB, SA, SB> {
+        L298 {
+            chip: self.chip,
+            sense_a,
+            sense_b,
+        }
+    }
+}
+
+impl<IN1, IN2, IN3, IN4, ENA, ENB, SA, SB> L298<IN1, IN2, IN3, IN4, ENA, ENB, SA, SB> {
+    /// Release the driver and return the underlying [L293x] driver and the current-sense pins.
+    #[inline]
+    #[allow(clippy::type_complexity)]
+    pub fn release(self) -> (L293x<IN1, IN2, IN3, IN4, ENA, ENB>, SA, SB) {
+        (self.chip, self.sense_a, self.sense_b)
+    }
+
+    /// Get the underlying [L293x] driver.
+    #[inline]
+    pub fn chip(&self) -> &L293x<IN1, IN2, IN3, IN4, ENA, ENB> {
+        &self.chip
+    }
+
+    /// Get the underlying [L293x] driver mutably.
+    #[inline]
+    pub fn chip_mut(&mut self) -> &mut L293x<IN1, IN2, IN3, IN4, ENA, ENB> {
+        &mut self.chip
+    }
+
+    /// Get the current-sense pin of the bridge `A`.
+    #[inline]
+    pub fn sense_a(&mut self) -> &mut SA {
+        &mut self.sense_a
+    }
+
+    /// Get the current-sense pin of the bridge `B`.
+    #[inline]
+    pub fn sense_b(&mut self) -> &mut SB {
+        &mut self.sense_b
+    }
+
+    /// Get the Half-H bridge of output `OUT1`.
+    #[inline]
+    pub fn out1(&self) -> HalfH<'_, IN1, ENA> {
+        self.chip.y1()
+    }
+
+    /// Get the Half-H bridge of output `OUT2`.
+    #[inline]
+    pub fn out2(&self) -> HalfH<'_, IN2, ENA> {
+        self.chip.y2()
+    }
+
+    /// Get the Half-H bridge of output `OUT3`.
+    #[inline]
+    pub fn out3(&self) -> HalfH<'_, IN3, ENB> {
+        self.chip.y3()
+    }
+
+    /// Get the Half-H bridge of output `OUT4`.
+    #[inline]
+    pub fn out4(&self) -> HalfH<'_, IN4, ENB> {
+        self.chip.y4()
+    }
+
+    /// Get the Full-H bridge `A` driving a load connected to the outputs `OUT1` and `OUT2`.
+    #[inline]
+    pub fn bridge_a(&self) -> FullHBridge<'_, IN1, IN2, ENA> {
+        self.chip.full_h12()
+    }
+
+    /// Get the Full-H bridge `B` driving a load connected to the outputs `OUT3` and `OUT4`.
+    #[inline]
+    pub fn bridge_b(&self) -> FullHBridge<'_, IN3, IN4, ENB> {
+        self.chip.full_h34()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::DigitalPin;
+    use coverage_helper::test;
+    use embedded_hal::digital::StatefulOutputPin;
+
+    fn l298() -> L298<DigitalPin, DigitalPin, DigitalPin, DigitalPin, DigitalPin, DigitalPin> {
+        L298::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        )
+    }
+
+    #[test]
+    fn test_bridges() {
+        let l298 = l298();
+
+        l298.bridge_a().forward().unwrap();
+        assert!(l298.out1().is_enabled().unwrap());
+        assert!(l298.out1().is_set_high().unwrap());
+        assert!(l298.out2().is_set_low().unwrap());
+        assert!(l298.out3().is_disabled().unwrap());
+
+        l298.bridge_b().reverse().unwrap();
+        assert!(l298.out4().is_set_high().unwrap());
+    }
+
+    #[test]
+    fn test_current_sense() {
+        let mut l298 = l298().with_current_sense(1u8, NotConnected);
+        *l298.sense_a() += 1;
+        assert_eq!(*l298.sense_b(), NotConnected);
+
+        l298.chip_mut().enable_y3_and_y4().unwrap();
+        assert!(l298.chip().y4().is_enabled().unwrap());
+
+        let (_, sense_a, _) = l298.release();
+        assert_eq!(sense_a, 2);
+    }
+}
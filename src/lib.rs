@@ -101,7 +101,9 @@
 //! let mut l293x = L293x::new(input1, (), (), (), enable12, ());
 //! ```
 //!
-//! This causes the type to only implement the functions for the matching outputs.
+//! This causes the type to only implement the functions for the matching outputs. Instead of the
+//! empty type, the [NotConnected](crate::pins::NotConnected) marker can be used, which results in
+//! clearer compiler errors.
 //!
 //! ```compile_fail
 //! # use l293x::L293x;
@@ -122,21 +124,149 @@
 //! ```
 //!
 //! For more information, see the struct documentation.
+//!
+//! ## Integer-only arithmetic
+//!
+//! All calculations of this crate (e.g. the step timing of the [stepper](crate::stepper) driver)
+//! use integer or fixed-point arithmetic only. This avoids pulling in software floating point
+//! routines on targets without a floating point unit (e.g. Cortex-M0 or AVR). This is enforced
+//! by denying the `clippy::float_arithmetic` lint for the whole crate. Only the optional `float`
+//! feature uses floating point arithmetic.
+//!
+//! ## Optional features
+//!
+//! - `float`: Accept `f32` values in the duty cycle and speed APIs, e.g.
+//!   [`HalfH::set_duty_cycle_f32()`] or [`FullH::set_speed_f32()`]. The values are clamped into
+//!   the valid range and converted into duty cycles internally.
+//! - `storage`: Save and restore the configuration and counters using any
+//!   [embedded-storage](https://docs.rs/embedded-storage) backend. See the `persist` module.
+//! - `can`: Receive velocity and stop commands over CAN using
+//!   [embedded-can](https://docs.rs/embedded-can). See the `can` module.
+//! - `command`: Parse speed and stop commands from a serial line using
+//!   [embedded-io](https://docs.rs/embedded-io). See the `command` module.
+//! - `async`: Non-blocking ramping and pulse helpers for async executors (e.g. embassy) using
+//!   [embedded-hal-async](https://docs.rs/embedded-hal-async). See the `asynch` module. This
+//!   feature requires Rust 1.75 or newer.
+//! - `critical-section`: Interrupt-safe sharing of the enable pins between the channels of a chip
+//!   using [critical-section](https://docs.rs/critical-section). See the `shared_pin` module.
+//! - `coalesce`: Buffer the changes of the pins of a port (e.g. of a port expander) and flush
+//!   them in a single write. See the `port` module.
+//! - `defmt`: Implement [defmt::Format](https://docs.rs/defmt) for the drivers, errors and value
+//!   types, so they can be logged efficiently (e.g. over RTT).
+//! - `serde`: Implement `serde::Serialize` for the [telemetry](L293xTelemetry) and the value
+//!   types, so the state of the driver can be sent to a host in any serde format (e.g. postcard).
+//! - `test-util`: Export the mock pins used by the tests of this crate in the `test_util` module,
+//!   so that crates building on this driver can unit-test their logic on the host.
+//! - `std`: Implement the `std::error::Error` trait for the errors of this crate, so they can be
+//!   propagated in std applications (e.g. using `anyhow`). The crate itself remains `no_std`.
 #![no_std]
 #![deny(unstable_features, unsafe_code)]
+#![deny(clippy::float_arithmetic)]
 #![cfg_attr(all(coverage_nightly, test), allow(unstable_features))]
 #![cfg_attr(all(coverage_nightly, test), feature(coverage_attribute))]
 
-mod l293x;
+#[cfg(feature = "std")]
+extern crate std;
 
-#[cfg(test)]
-mod mock;
+mod l293x;
+mod l293x_array;
+mod l298;
 
 // Exports
+#[cfg(feature = "async")]
+pub mod asynch;
+mod auto_enable;
+mod bridge_pair;
+mod bridge_pair_state;
+pub mod builder;
+mod cached_pin;
+#[cfg(feature = "can")]
+pub mod can;
+mod chain;
+mod channel;
+mod chip_error;
+#[cfg(feature = "command")]
+pub mod command;
+pub mod control;
+pub mod curve;
+mod deadman_switch;
+mod direction;
+pub mod drive;
+pub mod driver;
+mod drv8833;
+mod emergency_stop_error;
+mod enable_bus;
+pub mod encoder;
+mod error_kind;
+#[cfg(feature = "float")]
+mod float;
+#[cfg(feature = "defmt")]
+mod format;
+mod full_h;
+mod ganged;
 mod half_h;
+mod in_in_motor;
+pub mod motor;
+mod output_level;
 mod output_state_error;
+mod owned_half_h;
+#[cfg(feature = "storage")]
+pub mod persist;
 pub mod pins;
+mod polarity;
+pub mod port;
+pub mod profile;
+mod quad_half_h;
+mod ramp;
+pub mod servo;
+pub mod shared_pin;
+pub mod shields;
+pub mod shift_register;
+pub mod sim;
+pub mod soft_pwm;
+mod state;
+pub mod stepper;
+mod tb6612fng;
+mod telemetry;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
+pub mod thermal;
+pub mod tone;
+mod transaction;
+pub mod typestate;
+pub mod ui;
+pub mod verify;
+mod view;
 
+pub use auto_enable::AutoEnable;
+pub use bridge_pair::BridgePair;
+pub use bridge_pair_state::BridgePairState;
+pub use builder::L293xBuilder;
+pub use cached_pin::CachedPin;
+pub use chain::L293xChain;
+pub use channel::Channel;
+pub use chip_error::{ChipError, PinRole};
+pub use deadman_switch::DeadmanSwitch;
+pub use direction::Direction;
+pub use drv8833::Drv8833;
+pub use emergency_stop_error::EmergencyStopError;
+pub use enable_bus::EnableBus;
+pub use error_kind::ErrorKind;
+pub use full_h::{FullH, FullHBridge, OwnedFullHBridge};
+pub use ganged::GangedOutput;
 pub use half_h::HalfH;
+pub use in_in_motor::InInMotor;
 pub use l293x::L293x;
+pub use l293x_array::{DualL293x, L293xArray};
+pub use l298::L298;
+pub use output_level::{resolve_output, OutputLevel};
 pub use output_state_error::OutputStateError;
+pub use owned_half_h::OwnedHalfH;
+pub use polarity::{L293xPolarity, Polarity};
+pub use quad_half_h::QuadHalfH;
+pub use ramp::RampExt;
+pub use state::L293xState;
+pub use tb6612fng::{Tb6612fng, Tb6612fngMotor};
+pub use telemetry::L293xTelemetry;
+pub use transaction::L293xTransaction;
+pub use view::L293xView;
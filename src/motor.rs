@@ -0,0 +1,479 @@
+//! # DC motor driver
+//!
+//! This module implements a high-level driver for a brushed DC motor connected to two outputs of
+//! a [L293](crate::L293x) chip, which share a common enable pin. Both inputs driving the motor
+//! need to be PWM-capable, while the enable pin is a simple digital output.
+//!
+//! The motor can be stopped in two ways: [`brake()`](DcMotor::brake) drives both inputs low while
+//! the enable pin stays high, which shorts the terminals of the motor and stops it quickly.
+//! [`coast()`](DcMotor::coast) sets the enable pin low, which disconnects the motor and lets it
+//! spin down freely. The duty cycles of the inputs are not changed in this case.
+//!
+//! For remote controls and simple user interfaces, the speed can be set in percent using
+//! [`set_speed_percent()`](DcMotor::set_speed_percent) as well. Small duty cycles do not
+//! overcome the static friction of most motors. Thus, non-zero percentages are mapped above a
+//! configurable [minimum speed](DcMotor::set_min_speed), while `0` stops the motor using the
+//! configured [StopMode].
+//!
+//! # Examples
+//!
+//! ```
+//! use l293x::motor::DcMotor;
+//!
+//! // [...] create the two PWM channels and the enable pin
+//! let mut motor = DcMotor::new(pwm1, pwm2, enable12);
+//!
+//! // Half speed forward
+//! motor.set_speed(i16::MAX / 2)?;
+//! // Full speed in reverse
+//! motor.set_speed(-i16::MAX)?;
+//! motor.coast()?;
+//! ```
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::OutputPin;
+use embedded_hal::pwm::SetDutyCycle;
+
+use crate::control::FailsafeAction;
+use crate::{FullH, OutputStateError};
+
+/// The way a [DcMotor] is stopped by [`set_speed_percent()`](DcMotor::set_speed_percent)
+///
+/// The stop modes correspond to the immediate [FailsafeAction]s. Thus, a stop mode can be
+/// converted into the action used when the motor is stopped for safety reasons.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum StopMode {
+    /// Actively brake the motor (see [`DcMotor::brake()`]). This is the default.
+    Brake,
+    /// Let the motor coast to a stop (see [`DcMotor::coast()`]).
+    Coast,
+}
+
+impl Default for StopMode {
+    #[inline]
+    fn default() -> Self {
+        StopMode::Brake
+    }
+}
+
+impl From<StopMode> for FailsafeAction {
+    #[inline]
+    fn from(stop_mode: StopMode) -> Self {
+        match stop_mode {
+            StopMode::Brake => FailsafeAction::Brake,
+            StopMode::Coast => FailsafeAction::Coast,
+        }
+    }
+}
+
+#[cfg(feature = "storage")]
+impl crate::persist::Persist for StopMode {
+    const SIZE: usize = 1;
+
+    fn to_bytes(&self, bytes: &mut [u8]) {
+        bytes[0] = match self {
+            StopMode::Brake => 0,
+            StopMode::Coast => 1,
+        };
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        match bytes[0] {
+            0 => Some(StopMode::Brake),
+            1 => Some(StopMode::Coast),
+            _ => None,
+        }
+    }
+}
+
+/// Brushed DC motor driven by two PWM inputs and a common enable pin
+///
+/// In contrast to a [FullH] bridge, the motor owns its pins. Thus, it can be moved freely, e.g.
+/// into a task or an interrupt handler. For more information, please see the
+/// [module documentation](crate::motor).
+#[derive(Debug)]
+pub struct DcMotor<A, B, EN> {
+    a: A,
+    b: B,
+    enable: EN,
+    speed: i16,
+    dead_time_us: u32,
+    min_speed: i16,
+    stop_mode: StopMode,
+}
+
+impl<A, B, EN> DcMotor<A, B, EN> {
+    /// Create a new DC motor driver.
+    ///
+    /// The input `a` drives the first terminal of the motor and the input `b` the second one. The
+    /// pins are not changed, until the speed of the motor is set for the first time.
+    #[inline]
+    pub fn new(a: A, b: B, enable: EN) -> Self {
+        Self {
+            a,
+            b,
+            enable,
+            speed: 0,
+            dead_time_us: 0,
+            min_speed: 0,
+            stop_mode: StopMode::Brake,
+        }
+    }
+
+    /// Release the motor driver and return the two inputs and the enable pin.
+    #[inline]
+    pub fn release(self) -> (A, B, EN) {
+        (self.a, self.b, self.enable)
+    }
+
+    /// Get the last commanded speed of the motor.
+    ///
+    /// Braking and coasting counts as a speed of `0`.
+    #[inline]
+    pub fn speed(&self) -> i16 {
+        self.speed
+    }
+
+    /// Set the dead time inserted when the direction of the motor is reversed.
+    ///
+    /// When [`set_speed_with_dead_time()`](DcMotor::set_speed_with_dead_time) reverses the
+    /// direction of the motor, it is braked for `dead_time_us` microseconds first. By default, no
+    /// dead time is inserted.
+    #[inline]
+    pub fn set_dead_time_us(&mut self, dead_time_us: u32) {
+        self.dead_time_us = dead_time_us;
+    }
+
+    /// Get the dead time inserted when the direction of the motor is reversed.
+    #[inline]
+    pub fn dead_time_us(&self) -> u32 {
+        self.dead_time_us
+    }
+
+    /// Set the minimum speed of non-zero commands of
+    /// [`set_speed_percent()`](DcMotor::set_speed_percent).
+    ///
+    /// Percentages between `1` and `100` are mapped linearly to the speeds between `min_speed`
+    /// and [`i16::MAX`]. This creates a deadband, which ensures that small commands still
+    /// overcome the static friction of the motor. Negative values are treated as `0`, which is
+    /// the default.
+    #[inline]
+    pub fn set_min_speed(&mut self, min_speed: i16) {
+        self.min_speed = min_speed.max(0);
+    }
+
+    /// Get the minimum speed of non-zero commands of
+    /// [`set_speed_percent()`](DcMotor::set_speed_percent).
+    #[inline]
+    pub fn min_speed(&self) -> i16 {
+        self.min_speed
+    }
+
+    /// Set the way the motor is stopped, if [`set_speed_percent()`](DcMotor::set_speed_percent)
+    /// is called with `0`.
+    #[inline]
+    pub fn set_stop_mode(&mut self, stop_mode: StopMode) {
+        self.stop_mode = stop_mode;
+    }
+
+    /// Get the way the motor is stopped, if [`set_speed_percent()`](DcMotor::set_speed_percent)
+    /// is called with `0`.
+    #[inline]
+    pub fn stop_mode(&self) -> StopMode {
+        self.stop_mode
+    }
+}
+
+impl<A, B, EN> DcMotor<A, B, EN>
+where
+    EN: OutputPin,
+{
+    /// Enable the outputs driving the motor.
+    ///
+    /// This sets the enable pin high. The duty cycles of the inputs are not changed.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the enable pin.
+    #[inline]
+    pub fn enable(&mut self) -> Result<(), EN::Error> {
+        self.enable.set_high()
+    }
+
+    /// Let the motor coast to a stop.
+    ///
+    /// This sets the enable pin low, which puts the outputs driving the motor into the high
+    /// impedance mode. The duty cycles of the inputs are not changed.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the enable pin.
+    pub fn coast(&mut self) -> Result<(), EN::Error> {
+        self.enable.set_low()?;
+        self.speed = 0;
+        Ok(())
+    }
+}
+
+impl<A, B, EN> DcMotor<A, B, EN>
+where
+    A: SetDutyCycle,
+    B: SetDutyCycle<Error = A::Error>,
+    EN: OutputPin,
+{
+    /// Set the speed of the motor.
+    ///
+    /// The sign of the `speed` selects the direction: positive values drive the motor forward,
+    /// negative values in reverse. The magnitude defines the duty cycle of the driving input,
+    /// where [`i16::MAX`] means fully on. A `speed` of `0` brakes the motor. The outputs are
+    /// enabled, if necessary.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the first pin, which could not be set.
+    pub fn set_speed(&mut self, speed: i16) -> Result<(), OutputStateError<A::Error, EN::Error>> {
+        self.enable
+            .set_high()
+            .map_err(OutputStateError::EnablePinError)?;
+        let mut bridge = FullH::new(&mut self.a, &mut self.b);
+        bridge
+            .set_speed(speed)
+            .map_err(OutputStateError::InputPinError)?;
+        self.speed = bridge.speed();
+        Ok(())
+    }
+
+    /// Set the speed of the motor in percent.
+    ///
+    /// The sign of `percent` selects the direction, like for [`set_speed()`](DcMotor::set_speed).
+    /// Values outside of `-100..=100` are clamped. Non-zero values are mapped above the
+    /// [minimum speed](DcMotor::set_min_speed), while `0` stops the motor using the configured
+    /// [stop mode](DcMotor::set_stop_mode).
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the first pin, which could not be set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use l293x::motor::StopMode;
+    ///
+    /// motor.set_min_speed(i16::MAX / 4);
+    /// motor.set_stop_mode(StopMode::Coast);
+    ///
+    /// // Drives the motor with ~26% duty cycle
+    /// motor.set_speed_percent(1)?;
+    /// // Lets the motor coast
+    /// motor.set_speed_percent(0)?;
+    /// ```
+    pub fn set_speed_percent(
+        &mut self,
+        percent: i8,
+    ) -> Result<(), OutputStateError<A::Error, EN::Error>> {
+        let percent = percent.clamp(-100, 100);
+        if percent == 0 {
+            return match self.stop_mode {
+                StopMode::Brake => self.brake(),
+                StopMode::Coast => self.coast().map_err(OutputStateError::EnablePinError),
+            };
+        }
+        let (min, range) = (
+            i32::from(self.min_speed),
+            i32::from(i16::MAX - self.min_speed),
+        );
+        let magnitude = min + range * i32::from(percent.unsigned_abs()) / 100;
+        self.set_speed((magnitude * i32::from(percent.signum())) as i16)
+    }
+
+    /// Set the speed of the motor, inserting the dead time when reversing.
+    ///
+    /// If the sign of the `speed` is opposite to the one of the current [speed](DcMotor::speed),
+    /// the motor is braked for the configured [dead time](DcMotor::set_dead_time_us) first.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the first pin, which could not be set.
+    pub fn set_speed_with_dead_time<D: DelayNs>(
+        &mut self,
+        speed: i16,
+        delay: &mut D,
+    ) -> Result<(), OutputStateError<A::Error, EN::Error>> {
+        let reverses = (self.speed > 0 && speed < 0) || (self.speed < 0 && speed > 0);
+        if self.dead_time_us > 0 && reverses {
+            self.brake()?;
+            delay.delay_us(self.dead_time_us);
+        }
+        self.set_speed(speed)
+    }
+
+    /// Ramp the speed of the motor linearly to the `target` speed.
+    ///
+    /// The ramp starts at the last commanded [speed](DcMotor::speed). Every millisecond, the
+    /// speed is changed by `step` towards the `target`, until it is reached. This avoids high
+    /// inrush currents on large speed changes. If `step` is zero, the target speed is set
+    /// immediately.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the first pin, which could not be set. The ramp is
+    /// aborted in this case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// // Accelerate to full speed within ~330ms
+    /// motor.ramp_to_duty(i16::MAX, 100, &mut delay)?;
+    /// ```
+    pub fn ramp_to_duty<D: DelayNs>(
+        &mut self,
+        target: i16,
+        step: u16,
+        delay: &mut D,
+    ) -> Result<(), OutputStateError<A::Error, EN::Error>> {
+        crate::ramp::ramp_by(
+            self.speed.into(),
+            target.into(),
+            step.into(),
+            delay,
+            |speed| self.set_speed(speed as i16),
+        )
+    }
+
+    /// Actively brake the motor.
+    ///
+    /// This sets the enable pin high and both inputs fully off, which shorts the terminals of the
+    /// motor. This is the same as setting the speed to `0`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the first pin, which could not be set.
+    #[inline]
+    pub fn brake(&mut self) -> Result<(), OutputStateError<A::Error, EN::Error>> {
+        self.set_speed(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{Delay, DigitalPin, PwmPin};
+    use coverage_helper::test;
+    use embedded_hal::digital::StatefulOutputPin;
+
+    #[test]
+    fn test_stop_mode_failsafe() {
+        assert_eq!(FailsafeAction::from(StopMode::Brake), FailsafeAction::Brake);
+        assert_eq!(FailsafeAction::from(StopMode::Coast), FailsafeAction::Coast);
+    }
+
+    #[test]
+    fn test_set_speed() {
+        let mut motor = DcMotor::new(PwmPin::new(), PwmPin::new(), DigitalPin::new());
+
+        motor.set_speed(i16::MAX).unwrap();
+        assert!(motor.enable.is_set_high().unwrap());
+        assert_eq!(motor.a.get_duty_cycle(), u16::MAX);
+        assert_eq!(motor.b.get_duty_cycle(), 0);
+        assert_eq!(motor.speed(), i16::MAX);
+
+        motor.set_speed(i16::MIN).unwrap();
+        assert_eq!(motor.a.get_duty_cycle(), 0);
+        assert_eq!(motor.b.get_duty_cycle(), u16::MAX);
+        assert_eq!(motor.speed(), -i16::MAX);
+
+        motor.brake().unwrap();
+        assert_eq!(motor.b.get_duty_cycle(), 0);
+        assert_eq!(motor.speed(), 0);
+    }
+
+    #[test]
+    fn test_ramp_to_duty() {
+        let mut motor = DcMotor::new(PwmPin::new(), PwmPin::new(), DigitalPin::new());
+        let mut delay = Delay::new();
+
+        motor.ramp_to_duty(i16::MAX, 10_000, &mut delay).unwrap();
+        assert_eq!(motor.speed(), i16::MAX);
+        assert_eq!(motor.a.get_duty_cycle(), u16::MAX);
+        assert_eq!(delay.elapsed_ns(), 4_000_000);
+
+        motor.ramp_to_duty(0, 0, &mut delay).unwrap();
+        assert_eq!(motor.speed(), 0);
+    }
+
+    #[test]
+    fn test_dead_time() {
+        let mut motor = DcMotor::new(PwmPin::new(), PwmPin::new(), DigitalPin::new());
+        let mut delay = Delay::new();
+
+        motor.set_dead_time_us(2);
+        assert_eq!(motor.dead_time_us(), 2);
+        motor.set_speed_with_dead_time(-10, &mut delay).unwrap();
+        motor.set_speed_with_dead_time(10, &mut delay).unwrap();
+        assert_eq!(delay.elapsed_ns(), 2_000);
+        assert_eq!(motor.speed(), 10);
+    }
+
+    #[test]
+    fn test_coast() {
+        let mut motor = DcMotor::new(PwmPin::new(), PwmPin::new(), DigitalPin::new());
+
+        motor.set_speed(-100).unwrap();
+        motor.coast().unwrap();
+        assert_eq!(motor.speed(), 0);
+
+        let (_, b, mut enable) = motor.release();
+        assert!(enable.is_set_low().unwrap());
+        assert_ne!(b.get_duty_cycle(), 0);
+    }
+
+    #[test]
+    fn test_set_speed_fail() {
+        let mut enable = DigitalPin::new();
+        enable.fail();
+        let mut motor = DcMotor::new(PwmPin::new(), PwmPin::new(), enable);
+        assert!(matches!(
+            motor.set_speed(1),
+            Err(OutputStateError::EnablePinError(_))
+        ));
+        assert!(motor.coast().is_err());
+
+        let mut a = PwmPin::new();
+        a.fail();
+        let mut motor = DcMotor::new(a, PwmPin::new(), DigitalPin::new());
+        assert!(matches!(
+            motor.set_speed(1),
+            Err(OutputStateError::InputPinError(_))
+        ));
+        assert_eq!(motor.speed(), 0);
+    }
+
+    #[test]
+    fn test_set_speed_percent() {
+        let mut motor = DcMotor::new(PwmPin::new(), PwmPin::new(), DigitalPin::new());
+
+        motor.set_speed_percent(50).unwrap();
+        assert_eq!(motor.speed(), i16::MAX / 2);
+        motor.set_speed_percent(i8::MIN).unwrap();
+        assert_eq!(motor.speed(), -i16::MAX);
+
+        motor.set_min_speed(1000);
+        assert_eq!(motor.min_speed(), 1000);
+        motor.set_speed_percent(-1).unwrap();
+        assert_eq!(motor.speed(), -1317);
+        motor.set_speed_percent(100).unwrap();
+        assert_eq!(motor.speed(), i16::MAX);
+
+        motor.set_speed_percent(0).unwrap();
+        assert_eq!(motor.speed(), 0);
+        assert!(motor.enable.is_set_high().unwrap());
+        assert_eq!(motor.a.get_duty_cycle(), 0);
+
+        motor.set_min_speed(-1);
+        assert_eq!(motor.min_speed(), 0);
+        motor.set_stop_mode(StopMode::Coast);
+        assert_eq!(motor.stop_mode(), StopMode::Coast);
+        motor.set_speed_percent(0).unwrap();
+        assert!(motor.enable.is_set_low().unwrap());
+    }
+}
@@ -0,0 +1,53 @@
+/// The electrical level of an output of the chip
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum OutputLevel {
+    /// The output is driven high.
+    High,
+    /// The output is driven low.
+    Low,
+    /// The output is disabled and in the high impedance mode.
+    HighZ,
+}
+
+/// Resolve the level of an output from the states of its input and enable pins.
+///
+/// This function implements the truth table of the L293 datasheet for a single Half-H bridge:
+///
+/// | Input | Enable | Output                        |
+/// |-------|--------|-------------------------------|
+/// | High  | High   | [High](OutputLevel::High)     |
+/// | Low   | High   | [Low](OutputLevel::Low)       |
+/// | X     | Low    | [HighZ](OutputLevel::HighZ)   |
+///
+/// # Examples
+///
+/// ```
+/// use l293x::{resolve_output, OutputLevel};
+///
+/// assert_eq!(resolve_output(true, true), OutputLevel::High);
+/// assert_eq!(resolve_output(true, false), OutputLevel::HighZ);
+/// ```
+#[inline]
+pub const fn resolve_output(input: bool, enable: bool) -> OutputLevel {
+    match (input, enable) {
+        (_, false) => OutputLevel::HighZ,
+        (true, true) => OutputLevel::High,
+        (false, true) => OutputLevel::Low,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coverage_helper::test;
+
+    #[test]
+    fn test_truth_table() {
+        assert_eq!(resolve_output(true, true), OutputLevel::High);
+        assert_eq!(resolve_output(false, true), OutputLevel::Low);
+        assert_eq!(resolve_output(true, false), OutputLevel::HighZ);
+        assert_eq!(resolve_output(false, false), OutputLevel::HighZ);
+    }
+}
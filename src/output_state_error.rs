@@ -1,6 +1,9 @@
-use core::fmt::Debug;
+use core::fmt::{Debug, Display, Formatter};
 use embedded_hal::pwm::ErrorKind;
 
+use crate::error_kind::PinErrorKind;
+use crate::ErrorKind as L293xErrorKind;
+
 /// Error returned by the [L293x](crate::L293x) and [HalfH](crate::HalfH) implementations.
 ///
 /// This enumeration combines the possible errors returned by the input pin and the enable pin.
@@ -24,7 +27,8 @@ use embedded_hal::pwm::ErrorKind;
 ///     }
 /// });
 /// ```
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum OutputStateError<I, E> {
     /// An error occurred while setting the state of the input pin. The contained error
     //     /// may contain additional information.
@@ -38,6 +42,25 @@ pub enum OutputStateError<I, E> {
     NotEnabled,
 }
 
+impl<I: 'static, E: 'static> OutputStateError<I, E> {
+    /// Get the [kind](L293xErrorKind) of the error.
+    ///
+    /// Unlike the `kind()` methods of the embedded-hal error traits, which return
+    /// [Other](embedded_hal::digital::ErrorKind::Other) for a disabled output, this allows
+    /// distinguishing a [NotEnabled](OutputStateError::NotEnabled) error from a pin fault. An
+    /// [OperationNotSupported](crate::pins::OperationNotSupported) error of a
+    /// [Vcc](crate::pins::Vcc) or [Gnd](crate::pins::Gnd) pin is reported as
+    /// [NotSupported](L293xErrorKind::NotSupported), not as a pin fault.
+    #[inline]
+    pub fn error_kind(&self) -> L293xErrorKind {
+        match self {
+            OutputStateError::InputPinError(e) => e.pin_error_kind(L293xErrorKind::InputPin),
+            OutputStateError::EnablePinError(e) => e.pin_error_kind(L293xErrorKind::EnablePin),
+            OutputStateError::NotEnabled => L293xErrorKind::NotEnabled,
+        }
+    }
+}
+
 impl<I, E> embedded_hal::digital::Error for OutputStateError<I, E>
 where
     I: embedded_hal::digital::Error,
@@ -65,36 +88,31 @@ where
     }
 }
 
-impl<I, E> PartialEq for OutputStateError<I, E>
+impl<I, E> Display for OutputStateError<I, E>
 where
-    I: PartialEq,
-    E: PartialEq,
+    I: Debug,
+    E: Debug,
 {
-    fn eq(&self, other: &Self) -> bool {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
-            OutputStateError::EnablePinError(e1) => match other {
-                OutputStateError::EnablePinError(e2) => e1 == e2,
-                _ => false,
-            },
-            OutputStateError::InputPinError(e1) => match other {
-                OutputStateError::InputPinError(e2) => e1 == e2,
-                _ => false,
-            },
-            OutputStateError::NotEnabled => matches!(other, OutputStateError::NotEnabled),
+            OutputStateError::InputPinError(e) => write!(f, "input pin error: {:?}", e),
+            OutputStateError::EnablePinError(e) => write!(f, "enable pin error: {:?}", e),
+            OutputStateError::NotEnabled => write!(f, "output not enabled"),
         }
     }
 }
 
-impl<I, E> Eq for OutputStateError<I, E>
+#[cfg(feature = "std")]
+impl<I, E> std::error::Error for OutputStateError<I, E>
 where
-    I: Eq,
-    E: Eq,
+    I: Debug,
+    E: Debug,
 {
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::mock::{DigitalError, PwmError};
+    use crate::test_util::{DigitalError, PwmError};
     use embedded_hal::digital::{Error, ErrorKind};
     use embedded_hal::pwm::{Error as _, ErrorKind as PwmErrorKind};
 
@@ -129,6 +147,60 @@ mod tests {
         assert_eq!(not_enabled.kind(), PwmErrorKind::Other);
     }
 
+    #[test]
+    fn test_output_state_error_error_kind() {
+        let input_error: OutputStateError<DigitalError, DigitalError> =
+            OutputStateError::InputPinError(DigitalError());
+        assert_eq!(input_error.error_kind(), L293xErrorKind::InputPin);
+
+        let enable_error: OutputStateError<DigitalError, DigitalError> =
+            OutputStateError::EnablePinError(DigitalError());
+        assert_eq!(enable_error.error_kind(), L293xErrorKind::EnablePin);
+
+        let not_enabled: OutputStateError<DigitalError, DigitalError> =
+            OutputStateError::NotEnabled;
+        assert_eq!(not_enabled.error_kind(), L293xErrorKind::NotEnabled);
+    }
+
+    #[test]
+    fn test_not_supported_error_kind() {
+        use crate::pins::{Gnd, Vcc};
+        use crate::test_util::DigitalPin;
+        use crate::{BridgePair, Channel, L293x};
+        use embedded_hal::digital::OutputPin;
+
+        let mut pair = BridgePair::new(DigitalPin::new(), DigitalPin::new(), Vcc());
+        let error = pair.disable_holding_low().unwrap_err();
+        assert_eq!(error.error_kind(), L293xErrorKind::NotSupported);
+        assert!(!error.error_kind().is_pin_fault());
+
+        let l293x = L293x::new(Gnd(), (), (), (), DigitalPin::new(), ());
+        let error = l293x.y1().set_high().unwrap_err();
+        assert_eq!(error.error_kind(), L293xErrorKind::NotSupported);
+
+        let mut l293x = L293x::new(Vcc(), Vcc(), Vcc(), Vcc(), (), ());
+        let error = l293x.set_output_low(Channel::Y2).unwrap_err();
+        assert_eq!(error.error_kind(), L293xErrorKind::NotSupported);
+    }
+
+    #[test]
+    fn test_output_state_error_display() {
+        extern crate std;
+        use std::string::ToString;
+
+        let input_error: OutputStateError<DigitalError, DigitalError> =
+            OutputStateError::InputPinError(DigitalError());
+        assert_eq!(input_error.to_string(), "input pin error: DigitalError");
+
+        let enable_error: OutputStateError<DigitalError, DigitalError> =
+            OutputStateError::EnablePinError(DigitalError());
+        assert_eq!(enable_error.to_string(), "enable pin error: DigitalError");
+
+        let not_enabled: OutputStateError<DigitalError, DigitalError> =
+            OutputStateError::NotEnabled;
+        assert_eq!(not_enabled.to_string(), "output not enabled");
+    }
+
     #[test]
     fn test_output_state_error_equality() {
         let i: OutputStateError<DigitalError, DigitalError> =
@@ -0,0 +1,398 @@
+use core::cell::RefCell;
+
+use embedded_hal::digital::{OutputPin, PinState, StatefulOutputPin};
+use embedded_hal::pwm::SetDutyCycle;
+
+use crate::{AutoEnable, HalfH, OutputLevel, OutputStateError, Polarity};
+
+/// Half-H bridge owning its input and enable pin
+///
+/// In contrast to the [HalfH] bridge, which borrows its pins from the [L293](crate::L293x) chip
+/// driver, this bridge owns its pins. Thus, it can be moved into a different task or passed by
+/// value to another driver. The channels of a chip are created using the
+/// [`into_channels()`](crate::L293x::into_channels) method, which shares the enable pins between
+/// the channels using a [SharedPin](crate::shared_pin::SharedPin).
+///
+/// The bridge behaves exactly like a [HalfH] bridge: Setting the state or the duty cycle only
+/// enables the output, if its [auto-enable policy](OwnedHalfH::with_auto_enable) is
+/// [Always](AutoEnable::Always), and querying the state of a disabled output returns a
+/// [NotEnabled](OutputStateError::NotEnabled) error.
+///
+/// <div class="warning">
+/// If the enable pin is shared, enabling or disabling the output will enable or disable the other
+/// outputs sharing the pin as well!
+/// </div>
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::digital::OutputPin;
+/// use l293x::L293x;
+/// use l293x::shared_pin::PinCell;
+///
+/// let (mut en12, mut en34) = (PinCell::new(), PinCell::new());
+/// let l293x = L293x::new(input1, input2, input3, input4, enable12, enable34);
+/// let (mut y1, y2, y3, y4) = l293x.into_channels(&mut en12, &mut en34);
+///
+/// y1.enable()?;
+/// y1.set_high()?;
+/// ```
+#[derive(Debug)]
+pub struct OwnedHalfH<INPUT, ENABLE> {
+    input: RefCell<INPUT>,
+    enable: RefCell<ENABLE>,
+    auto_enable: AutoEnable,
+    input_polarity: Polarity,
+    enable_polarity: Polarity,
+}
+
+impl<INPUT, ENABLE> OwnedHalfH<INPUT, ENABLE> {
+    /// Create a new Half-H bridge from its input and enable pin.
+    #[inline]
+    pub fn new(input: INPUT, enable: ENABLE) -> Self {
+        Self {
+            input: RefCell::new(input),
+            enable: RefCell::new(enable),
+            auto_enable: AutoEnable::Never,
+            input_polarity: Polarity::Normal,
+            enable_polarity: Polarity::Normal,
+        }
+    }
+
+    /// Set the polarities of the pins of a split chip.
+    #[inline]
+    pub(crate) fn with_polarity(self, input_polarity: Polarity, enable_polarity: Polarity) -> Self {
+        Self {
+            input_polarity,
+            enable_polarity,
+            ..self
+        }
+    }
+
+    /// Set whether setting the state or the duty cycle of the bridge enables its output.
+    ///
+    /// See [`HalfH::with_auto_enable()`] for details.
+    #[inline]
+    pub fn with_auto_enable(self, auto_enable: AutoEnable) -> Self {
+        Self {
+            auto_enable,
+            ..self
+        }
+    }
+
+    /// Release the bridge and return the input and the enable pin.
+    #[inline]
+    pub fn release(self) -> (INPUT, ENABLE) {
+        (self.input.into_inner(), self.enable.into_inner())
+    }
+
+    /// Borrow the bridge as a [HalfH] bridge.
+    #[inline]
+    pub fn as_half_h(&self) -> HalfH<'_, INPUT, ENABLE> {
+        HalfH::new(&self.input, &self.enable)
+            .with_auto_enable(self.auto_enable)
+            .with_polarity(self.input_polarity, self.enable_polarity)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<INPUT, ENABLE> defmt::Format for OwnedHalfH<INPUT, ENABLE>
+where
+    INPUT: defmt::Format,
+    ENABLE: defmt::Format,
+{
+    fn format(&self, f: defmt::Formatter) {
+        use crate::format::CellFormat;
+        defmt::write!(
+            f,
+            "OwnedHalfH {{ input: {}, enable: {} }}",
+            CellFormat(&self.input),
+            CellFormat(&self.enable)
+        )
+    }
+}
+
+impl<INPUT, ENABLE> OwnedHalfH<INPUT, ENABLE>
+where
+    ENABLE: OutputPin,
+{
+    /// Enable the output.
+    ///
+    /// See [`HalfH::enable()`] for details.
+    ///
+    /// # Errors
+    ///
+    /// This method will return the error of the enable pin.
+    #[inline]
+    pub fn enable(&mut self) -> Result<(), ENABLE::Error> {
+        self.as_half_h().enable()
+    }
+
+    /// Disable the output.
+    ///
+    /// See [`HalfH::disable()`] for details.
+    ///
+    /// # Errors
+    ///
+    /// This method will return the error of the enable pin.
+    #[inline]
+    pub fn disable(&mut self) -> Result<(), ENABLE::Error> {
+        self.as_half_h().disable()
+    }
+}
+
+impl<INPUT, ENABLE> OwnedHalfH<INPUT, ENABLE>
+where
+    ENABLE: StatefulOutputPin,
+{
+    /// Check whether the output is enabled.
+    ///
+    /// # Errors
+    ///
+    /// This method will return the error of the enable pin.
+    #[inline]
+    pub fn is_enabled(&mut self) -> Result<bool, ENABLE::Error> {
+        self.as_half_h().is_enabled()
+    }
+
+    /// Check whether the output is disabled.
+    ///
+    /// # Errors
+    ///
+    /// This method will return the error of the enable pin.
+    #[inline]
+    pub fn is_disabled(&mut self) -> Result<bool, ENABLE::Error> {
+        self.as_half_h().is_disabled()
+    }
+
+    /// Check whether the output is in the high impedance (Z) state.
+    ///
+    /// See [`HalfH::is_high_impedance()`] for details.
+    ///
+    /// # Errors
+    ///
+    /// This method will return the error of the enable pin.
+    #[inline]
+    pub fn is_high_impedance(&mut self) -> Result<bool, ENABLE::Error> {
+        self.as_half_h().is_high_impedance()
+    }
+}
+
+impl<INPUT, ENABLE> OwnedHalfH<INPUT, ENABLE>
+where
+    INPUT: StatefulOutputPin,
+    ENABLE: StatefulOutputPin,
+{
+    /// Get the level of the output.
+    ///
+    /// See [`HalfH::output_level()`] for details.
+    ///
+    /// # Errors
+    ///
+    /// The error of the pin, which could not be queried, will be returned.
+    #[inline]
+    pub fn output_level(
+        &mut self,
+    ) -> Result<OutputLevel, OutputStateError<INPUT::Error, ENABLE::Error>> {
+        self.as_half_h().output_level()
+    }
+
+    /// Toggle the state of the output and return the new state.
+    ///
+    /// See [`HalfH::toggle_and_get()`] for details.
+    ///
+    /// # Errors
+    ///
+    /// The error of the pin, which could not be queried or toggled, will be returned. If the
+    /// output is disabled, a [NotEnabled](OutputStateError::NotEnabled) error will be returned.
+    #[inline]
+    pub fn toggle_and_get(
+        &mut self,
+    ) -> Result<PinState, OutputStateError<INPUT::Error, ENABLE::Error>> {
+        self.as_half_h().toggle_and_get()
+    }
+}
+
+impl<INPUT, ENABLE> embedded_hal::digital::ErrorType for OwnedHalfH<INPUT, ENABLE>
+where
+    INPUT: OutputPin,
+    ENABLE: OutputPin,
+{
+    type Error = OutputStateError<INPUT::Error, ENABLE::Error>;
+}
+
+impl<INPUT, ENABLE> OutputPin for OwnedHalfH<INPUT, ENABLE>
+where
+    INPUT: OutputPin,
+    ENABLE: OutputPin,
+{
+    #[inline]
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.as_half_h().set_low()
+    }
+
+    #[inline]
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.as_half_h().set_high()
+    }
+
+    #[inline]
+    fn set_state(&mut self, state: PinState) -> Result<(), Self::Error> {
+        self.as_half_h().set_state(state)
+    }
+}
+
+impl<INPUT, ENABLE> StatefulOutputPin for OwnedHalfH<INPUT, ENABLE>
+where
+    INPUT: StatefulOutputPin,
+    ENABLE: StatefulOutputPin,
+{
+    #[inline]
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        self.as_half_h().is_set_high()
+    }
+
+    #[inline]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        self.as_half_h().is_set_low()
+    }
+
+    #[inline]
+    fn toggle(&mut self) -> Result<(), Self::Error> {
+        self.as_half_h().toggle()
+    }
+}
+
+impl<INPUT, ENABLE> embedded_hal::pwm::ErrorType for OwnedHalfH<INPUT, ENABLE>
+where
+    INPUT: SetDutyCycle,
+    ENABLE: OutputPin,
+{
+    type Error = OutputStateError<INPUT::Error, ENABLE::Error>;
+}
+
+impl<INPUT, ENABLE> SetDutyCycle for OwnedHalfH<INPUT, ENABLE>
+where
+    INPUT: SetDutyCycle,
+    ENABLE: OutputPin,
+{
+    #[inline]
+    fn max_duty_cycle(&self) -> u16 {
+        self.as_half_h().max_duty_cycle()
+    }
+
+    #[inline]
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+        self.as_half_h().set_duty_cycle(duty)
+    }
+
+    #[inline]
+    fn set_duty_cycle_fraction(&mut self, num: u16, denom: u16) -> Result<(), Self::Error> {
+        self.as_half_h().set_duty_cycle_fraction(num, denom)
+    }
+
+    #[inline]
+    fn set_duty_cycle_percent(&mut self, percent: u8) -> Result<(), Self::Error> {
+        self.as_half_h().set_duty_cycle_percent(percent)
+    }
+
+    #[inline]
+    fn set_duty_cycle_fully_on(&mut self) -> Result<(), Self::Error> {
+        self.as_half_h().set_duty_cycle_fully_on()
+    }
+
+    #[inline]
+    fn set_duty_cycle_fully_off(&mut self) -> Result<(), Self::Error> {
+        self.as_half_h().set_duty_cycle_fully_off()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared_pin::PinCell;
+    use crate::test_util::{DigitalPin, PwmPin};
+    use crate::L293x;
+    use coverage_helper::test;
+
+    #[test]
+    fn test_into_channels() {
+        let (mut en12, mut en34) = (PinCell::new(), PinCell::new());
+        let l293x = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        );
+        let (y1, mut y2, mut y3, y4) = l293x.into_channels(&mut en12, &mut en34);
+        let mut y1 = y1.with_auto_enable(AutoEnable::Always);
+        let mut y4 = y4.with_auto_enable(AutoEnable::Always);
+
+        y1.set_high().unwrap();
+        assert!(y2.is_enabled().unwrap());
+        assert!(y2.is_set_low().unwrap());
+        assert!(y3.is_disabled().unwrap());
+        assert_eq!(y1.output_level().unwrap(), OutputLevel::High);
+
+        y4.set_state(PinState::High).unwrap();
+        y3.toggle().unwrap();
+        assert_eq!(y3.toggle_and_get().unwrap(), PinState::Low);
+        assert!(y4.is_set_high().unwrap());
+        y4.disable().unwrap();
+        assert_eq!(y3.is_set_low(), Err(OutputStateError::NotEnabled));
+        y2.enable().unwrap();
+
+        let mut en34 = en34.take().unwrap();
+        assert!(en34.is_set_low().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "critical-section")]
+    fn test_into_cs_channels() {
+        use crate::shared_pin::CsPinCell;
+
+        let (en12, en34) = (CsPinCell::new(), CsPinCell::new());
+        let l293x = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        );
+        let (mut y1, mut y2, mut y3, _) = l293x.into_cs_channels(&en12, &en34);
+
+        y1.enable().unwrap();
+        y1.set_high().unwrap();
+        assert!(y2.is_enabled().unwrap());
+        assert!(y3.is_disabled().unwrap());
+    }
+
+    #[test]
+    fn test_duty_cycle() {
+        let mut y1 =
+            OwnedHalfH::new(PwmPin::new(), DigitalPin::new()).with_auto_enable(AutoEnable::Always);
+
+        y1.set_duty_cycle_fully_on().unwrap();
+        assert!(y1.is_enabled().unwrap());
+        assert_eq!(y1.max_duty_cycle(), u16::MAX);
+        y1.set_duty_cycle_percent(50).unwrap();
+        y1.set_duty_cycle_fraction(1, 4).unwrap();
+        y1.set_duty_cycle(100).unwrap();
+        y1.set_duty_cycle_fully_off().unwrap();
+
+        let (input, _) = y1.release();
+        assert_eq!(input.get_duty_cycle(), 0);
+    }
+
+    #[test]
+    fn test_auto_enable() {
+        let mut y1 = OwnedHalfH::new(PwmPin::new(), DigitalPin::new());
+
+        y1.set_duty_cycle_fully_on().unwrap();
+        assert!(y1.is_high_impedance().unwrap());
+        assert_eq!(y1.as_half_h().auto_enable(), AutoEnable::Never);
+    }
+}
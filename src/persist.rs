@@ -0,0 +1,307 @@
+//! # Persistence of configuration and counters
+//!
+//! This module is only available, if the `storage` feature is enabled. It allows saving the
+//! configuration and the counters of the drivers (e.g. a
+//! [RuntimeCounter](crate::control::RuntimeCounter)) to any [embedded-storage](embedded_storage)
+//! backend, like a NOR flash or an EEPROM, and restoring them at startup.
+//!
+//! Each value is stored as a small record at the given offset of the storage. The record contains
+//! a header and a checksum, so that erased or corrupted records are detected while loading. In
+//! this case, `None` is returned and the application can fall back to its defaults.
+//!
+//! The [Persist] trait is implemented for:
+//!
+//! - the [L293xPolarity](crate::L293xPolarity) of the pins,
+//! - the [StopMode](crate::motor::StopMode) of a [DcMotor](crate::motor::DcMotor),
+//! - the [FailsafeAction](crate::control::FailsafeAction) and the
+//!   [RuntimeCounter](crate::control::RuntimeCounter),
+//! - integers, pairs and arrays of persistable values. These store e.g. the trim of a
+//!   [DifferentialDrive](crate::drive::DifferentialDrive), the minimum speed of a
+//!   [DcMotor](crate::motor::DcMotor) or the table of a [LookupTable](crate::curve::LookupTable).
+//!
+//! A record holds at most 32 bytes. Saving or loading a larger value (e.g. a `[u16; 17]` table)
+//! is rejected at compile time.
+//!
+//! # Examples
+//!
+//! ```
+//! use l293x::control::RuntimeCounter;
+//! use l293x::curve::LookupTable;
+//! use l293x::persist;
+//!
+//! // [...] create the storage backend
+//! let mut counter: RuntimeCounter = persist::load(&mut eeprom, 0)?.unwrap_or_default();
+//! counter.update(60, 50, 100);
+//! persist::save(&mut eeprom, 0, &counter)?;
+//!
+//! // Restore the calibration of the drive and the brightness curve
+//! let offset = persist::record_size::<RuntimeCounter>() as u32;
+//! if let Some((left, right)) = persist::load(&mut eeprom, offset)? {
+//!     drive.set_trim(left, right);
+//! }
+//! let offset = offset + persist::record_size::<(u8, u8)>() as u32;
+//! let table: [u16; 5] = persist::load(&mut eeprom, offset)?
+//!     .unwrap_or([0, 1000, 5000, 20000, u16::MAX]);
+//! let curve = LookupTable::new(&table);
+//! ```
+
+use core::marker::PhantomData;
+
+use embedded_storage::{ReadStorage, Storage};
+
+/// The magic bytes at the start of each record.
+const MAGIC: [u8; 2] = *b"L2";
+
+/// The size of the header of a record (magic bytes and payload size).
+const HEADER_SIZE: usize = 3;
+
+/// The maximum size of the payload of a record.
+const MAX_PAYLOAD_SIZE: usize = 32;
+
+/// The maximum size of a record including its header and checksum.
+const MAX_RECORD_SIZE: usize = HEADER_SIZE + MAX_PAYLOAD_SIZE + 1;
+
+/// Compile-time check of the payload size of the type `T`
+///
+/// Using the [`FITS`](PayloadSize::FITS) constant fails the compilation, if the
+/// [`SIZE`](Persist::SIZE) of the type exceeds the maximum payload size.
+struct PayloadSize<T>(PhantomData<T>);
+
+impl<T: Persist> PayloadSize<T> {
+    const FITS: () = assert!(
+        T::SIZE <= MAX_PAYLOAD_SIZE,
+        "the persisted value exceeds 32 bytes"
+    );
+}
+
+/// A value, which can be persisted to a storage
+///
+/// The value is serialized into a fixed number of bytes. All values use little endian byte
+/// order, so that the stored records are independent of the platform.
+pub trait Persist: Sized {
+    /// The number of bytes of the serialized value.
+    ///
+    /// Values of up to 32 bytes can be persisted. Saving or loading a larger value fails to
+    /// compile.
+    const SIZE: usize;
+
+    /// Serialize the value into the given bytes, which have a length of [`SIZE`](Persist::SIZE).
+    fn to_bytes(&self, bytes: &mut [u8]);
+
+    /// Deserialize the value from the given bytes, which have a length of
+    /// [`SIZE`](Persist::SIZE).
+    ///
+    /// Returns `None`, if the bytes do not contain a valid value.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>;
+}
+
+macro_rules! persist_integer {
+    ($($int:ty),*) => {
+        $(
+            impl Persist for $int {
+                const SIZE: usize = core::mem::size_of::<$int>();
+
+                fn to_bytes(&self, bytes: &mut [u8]) {
+                    bytes.copy_from_slice(&self.to_le_bytes());
+                }
+
+                fn from_bytes(bytes: &[u8]) -> Option<Self> {
+                    let mut le_bytes = [0; core::mem::size_of::<$int>()];
+                    le_bytes.copy_from_slice(bytes);
+                    Some(<$int>::from_le_bytes(le_bytes))
+                }
+            }
+        )*
+    };
+}
+
+persist_integer!(u8, i8, u16, i16, u32, i32, u64, i64);
+
+impl<A: Persist, B: Persist> Persist for (A, B) {
+    const SIZE: usize = A::SIZE + B::SIZE;
+
+    fn to_bytes(&self, bytes: &mut [u8]) {
+        let (a, b) = bytes.split_at_mut(A::SIZE);
+        self.0.to_bytes(a);
+        self.1.to_bytes(b);
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let (a, b) = bytes.split_at(A::SIZE);
+        Some((A::from_bytes(a)?, B::from_bytes(b)?))
+    }
+}
+
+impl<T: Persist + Copy + Default, const N: usize> Persist for [T; N] {
+    const SIZE: usize = T::SIZE * N;
+
+    fn to_bytes(&self, bytes: &mut [u8]) {
+        for (value, chunk) in self.iter().zip(bytes.chunks_exact_mut(T::SIZE)) {
+            value.to_bytes(chunk);
+        }
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut values = [T::default(); N];
+        for (value, chunk) in values.iter_mut().zip(bytes.chunks_exact(T::SIZE)) {
+            *value = T::from_bytes(chunk)?;
+        }
+        Some(values)
+    }
+}
+
+/// Get the number of bytes of storage needed for a record of the type `T`.
+///
+/// This can be used to place multiple records after each other.
+#[inline]
+pub fn record_size<T: Persist>() -> usize {
+    HEADER_SIZE + T::SIZE + 1
+}
+
+/// Calculate the CRC-8 (polynomial `0x07`) of the given bytes.
+fn crc8(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0, |crc, byte| {
+        (0..8).fold(crc ^ byte, |crc, _| match crc & 0x80 {
+            0 => crc << 1,
+            _ => crc << 1 ^ 0x07,
+        })
+    })
+}
+
+/// Save the value to the storage at the given offset.
+///
+/// # Errors
+///
+/// The error of the storage will be returned, if the record could not be written.
+pub fn save<S, T>(storage: &mut S, offset: u32, value: &T) -> Result<(), S::Error>
+where
+    S: Storage,
+    T: Persist,
+{
+    let () = PayloadSize::<T>::FITS;
+    let mut record = [0; MAX_RECORD_SIZE];
+    let record = &mut record[..record_size::<T>()];
+    record[..2].copy_from_slice(&MAGIC);
+    record[2] = T::SIZE as u8;
+    value.to_bytes(&mut record[HEADER_SIZE..HEADER_SIZE + T::SIZE]);
+    record[HEADER_SIZE + T::SIZE] = crc8(&record[..HEADER_SIZE + T::SIZE]);
+    storage.write(offset, record)
+}
+
+/// Load a value from the storage at the given offset.
+///
+/// Returns `None`, if the storage does not contain a valid record of the type `T` at the given
+/// offset, e.g. because it was never saved or the storage was erased.
+///
+/// # Errors
+///
+/// The error of the storage will be returned, if the record could not be read.
+pub fn load<S, T>(storage: &mut S, offset: u32) -> Result<Option<T>, S::Error>
+where
+    S: ReadStorage,
+    T: Persist,
+{
+    let () = PayloadSize::<T>::FITS;
+    let mut record = [0; MAX_RECORD_SIZE];
+    let record = &mut record[..record_size::<T>()];
+    storage.read(offset, record)?;
+    let valid = record[..2] == MAGIC
+        && usize::from(record[2]) == T::SIZE
+        && record[HEADER_SIZE + T::SIZE] == crc8(&record[..HEADER_SIZE + T::SIZE]);
+    Ok(match valid {
+        true => T::from_bytes(&record[HEADER_SIZE..HEADER_SIZE + T::SIZE]),
+        false => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::{FailsafeAction, RuntimeCounter};
+    use crate::motor::StopMode;
+    use crate::test_util::MemoryStorage;
+    use crate::{L293xPolarity, Polarity};
+    use coverage_helper::test;
+
+    #[test]
+    fn test_save_and_load() {
+        let mut storage = MemoryStorage::new();
+        let mut counter = RuntimeCounter::new();
+        counter.update(7, 1, 2);
+        let offset = record_size::<RuntimeCounter>() as u32;
+
+        save(&mut storage, 0, &counter).unwrap();
+        save(&mut storage, offset, &FailsafeAction::RampDown(250)).unwrap();
+
+        assert_eq!(load(&mut storage, 0).unwrap(), Some(counter));
+        assert_eq!(
+            load(&mut storage, offset).unwrap(),
+            Some(FailsafeAction::RampDown(250))
+        );
+    }
+
+    #[test]
+    fn test_load_invalid() {
+        let mut storage = MemoryStorage::new();
+        assert_eq!(load::<_, RuntimeCounter>(&mut storage, 0).unwrap(), None);
+
+        save(&mut storage, 0, &FailsafeAction::Brake).unwrap();
+        // Type with a different size
+        assert_eq!(load::<_, RuntimeCounter>(&mut storage, 0).unwrap(), None);
+
+        // Corrupted payload
+        storage.write(3, &[0xff]).unwrap();
+        assert_eq!(load::<_, FailsafeAction>(&mut storage, 0).unwrap(), None);
+    }
+
+    #[test]
+    fn test_configuration() {
+        let mut storage = MemoryStorage::new();
+        let mut polarity = L293xPolarity::default();
+        polarity.inputs[1] = Polarity::Inverted;
+        polarity.enable34 = Polarity::Inverted;
+        let table = [0, 100, 1000, u16::MAX];
+
+        let mut offset = 0;
+        save(&mut storage, offset, &polarity).unwrap();
+        assert_eq!(load(&mut storage, offset).unwrap(), Some(polarity));
+        offset += record_size::<L293xPolarity>() as u32;
+        save(&mut storage, offset, &StopMode::Coast).unwrap();
+        assert_eq!(load(&mut storage, offset).unwrap(), Some(StopMode::Coast));
+        offset += record_size::<StopMode>() as u32;
+        save(&mut storage, offset, &(90u8, 100u8)).unwrap();
+        assert_eq!(load(&mut storage, offset).unwrap(), Some((90u8, 100u8)));
+        offset += record_size::<(u8, u8)>() as u32;
+        save(&mut storage, offset, &-1200i16).unwrap();
+        assert_eq!(load(&mut storage, offset).unwrap(), Some(-1200i16));
+        offset += record_size::<i16>() as u32;
+        save(&mut storage, offset, &table).unwrap();
+        assert_eq!(load(&mut storage, offset).unwrap(), Some(table));
+    }
+
+    #[test]
+    fn test_configuration_invalid() {
+        assert_eq!(L293xPolarity::from_bytes(&[0x40]), None);
+        assert_eq!(StopMode::from_bytes(&[2]), None);
+        assert_eq!(<(StopMode, u8)>::from_bytes(&[2, 0]), None);
+        assert_eq!(<[StopMode; 2]>::from_bytes(&[0, 2]), None);
+        assert_eq!(
+            <[StopMode; 2]>::from_bytes(&[0, 1]),
+            Some([StopMode::Brake, StopMode::Coast])
+        );
+    }
+
+    #[test]
+    fn test_storage_error() {
+        let mut storage = MemoryStorage::new();
+        storage.fail();
+        assert!(save(&mut storage, 0, &FailsafeAction::Coast).is_err());
+        assert!(load::<_, FailsafeAction>(&mut storage, 0).is_err());
+    }
+
+    #[test]
+    fn test_crc8() {
+        assert_eq!(crc8(b""), 0);
+        assert_eq!(crc8(b"123456789"), 0xf4);
+    }
+}
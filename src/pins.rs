@@ -12,6 +12,11 @@
 //! Because the state of the pins cannot be changed, parts of the operations defined in these traits
 //! will always fail and return a [OperationNotSupported] error.
 //!
+//! Additionally, the pins implement the [embedded_hal::pwm::SetDutyCycle] trait. The [Vcc] pin
+//! is always fully on, while the [Gnd] pin is always fully off. Setting any other duty cycle fails.
+//! This allows using the static pins where a PWM pin is required, e.g. as the input of an output
+//! sharing its enable pin with a PWM driven output.
+//!
 //! # Examples
 //!
 //! To define, that an output of the [L293](crate::L293x) chip is always enabled, the
@@ -34,7 +39,44 @@
 //!
 //! let mut l293x = L293x::new(Gnd(), (), (), (), enable12, ());
 //! ```
+//!
+//! # Adapters
+//!
+//! Generic code driving the outputs using duty cycles requires [SetDutyCycle] pins, even if some
+//! outputs are wired to plain GPIOs. The [DigitalAsPwm] adapter implements the [SetDutyCycle]
+//! trait on top of a digital [OutputPin] by setting the pin high for all duty cycles above a
+//! threshold:
+//!
+//! ```
+//! use l293x::pins::DigitalAsPwm;
+//! use l293x::L293x;
+//!
+//! let l293x = L293x::new(pwm1, DigitalAsPwm::new(gpio2), (), (), enable12, ());
+//! let mut motor = l293x.full_h12();
+//! motor.set_speed(-i16::MAX)?;
+//! ```
+//!
+//! The [PwmAsDigital] adapter does the opposite: It implements the [OutputPin] trait on top of a
+//! [SetDutyCycle] pin, e.g. a timer channel of a microcontroller, which can not be used as a GPIO.
+//! This allows using such a channel as an enable pin:
+//!
+//! ```
+//! use l293x::pins::PwmAsDigital;
+//! use l293x::L293x;
+//!
+//! let mut l293x = L293x::new(input1, input2, (), (), PwmAsDigital::new(timer_channel), ());
+//! l293x.enable_y1_and_y2()?;
+//! ```
+//!
+//! If a pin is connected through an inverting stage (e.g. an inverting level shifter or a
+//! transistor), the [ActiveLow] adapter inverts the pin, so that the logical state matches the
+//! state of the chip pin.
+//! If the polarity is only known at runtime (e.g. because it depends on the board revision), the
+//! [Polarized] adapter can be used instead.
 use embedded_hal::digital::{Error, ErrorKind, ErrorType, OutputPin, StatefulOutputPin};
+use embedded_hal::pwm::{self, SetDutyCycle};
+
+use crate::Polarity;
 
 /// Error returned by the [Vcc] and [Gnd] structs, when trying to call an operation which is not
 /// supported by the pin.
@@ -43,15 +85,61 @@ use embedded_hal::digital::{Error, ErrorKind, ErrorType, OutputPin, StatefulOutp
 /// [embedded_hal::digital::OutputPin] ad  [embedded_hal::digital::StatefulOutputPin] traits, but
 /// they do not allow switching states. In these cases, this error will be returned instead, to
 /// signal the caller, that the corresponding operation could not be performed.
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct OperationNotSupported();
 
+impl OperationNotSupported {
+    /// Get the [kind](crate::ErrorKind) of the error, which is always
+    /// [NotSupported](crate::ErrorKind::NotSupported).
+    #[inline]
+    pub fn error_kind(&self) -> crate::ErrorKind {
+        crate::ErrorKind::NotSupported
+    }
+}
+
 impl Error for OperationNotSupported {
     fn kind(&self) -> ErrorKind {
         ErrorKind::Other
     }
 }
 
+impl pwm::Error for OperationNotSupported {
+    fn kind(&self) -> pwm::ErrorKind {
+        pwm::ErrorKind::Other
+    }
+}
+
+impl core::fmt::Display for OperationNotSupported {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "operation not supported by the pin")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for OperationNotSupported {}
+
+/// Marker of a pin, which is not connected.
+///
+/// Like the empty type (`()`), this marker can be passed instead of a real pin for inputs or
+/// enable pins, which are not connected. It does not implement any of the pin traits. Thus, the
+/// methods of the corresponding outputs are not available. In contrast to the empty type, the
+/// compiler errors name the marker, which makes it clear, that the output is not connected:
+///
+/// ```compile_fail
+/// use l293x::L293x;
+/// use l293x::pins::NotConnected;
+///
+/// let mut l293x = L293x::new(input1, NotConnected, NotConnected, NotConnected, enable12, ());
+/// // error: the trait bound `NotConnected: OutputPin` is not satisfied
+/// l293x.set_y2_high()?;
+/// ```
+///
+/// The empty type (`()`) keeps working for backwards compatibility.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct NotConnected;
+
 /// A pin which is connected directly to the Vcc source.
 ///
 /// This pin is always high and cannot be set low. Thus, operations trying to change the state
@@ -76,7 +164,8 @@ impl Error for OperationNotSupported {
 /// and which `en12` pin is always set high (Vcc). In this circuit, the L293 chip acts like an
 /// operation amplifier, which allows to control a large output voltage with the small voltage of
 /// the MCU output.
-#[derive(Debug)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[repr(transparent)]
 pub struct Vcc();
 
@@ -133,6 +222,35 @@ impl StatefulOutputPin for Vcc {
     }
 }
 
+impl pwm::ErrorType for Vcc {
+    type Error = OperationNotSupported;
+}
+
+impl SetDutyCycle for Vcc {
+    /// Get the maximum duty cycle of the Vcc pin, which is [`u16::MAX`].
+    #[inline]
+    fn max_duty_cycle(&self) -> u16 {
+        u16::MAX
+    }
+
+    /// Try to set the duty cycle of the Vcc pin.
+    ///
+    /// Because the pin is always high, setting the maximum duty cycle is a No-Op and always
+    /// succeeds.
+    ///
+    /// # Errors
+    ///
+    /// Setting any duty cycle but the maximum one will *always* fail and return a
+    /// [OperationNotSupported] error.
+    #[inline]
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+        match duty {
+            u16::MAX => Ok(()),
+            _ => Err(OperationNotSupported()),
+        }
+    }
+}
+
 /// A pin which is connected directly to the ground.
 ///
 /// This pin is always low and cannot be set low. Thus, operations trying to change the state
@@ -152,7 +270,8 @@ impl StatefulOutputPin for Vcc {
 ///
 /// let mut l293 = L293x::new(Gnd(), (), (), (), enable12, ());
 /// ```
-#[derive(Debug)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[repr(transparent)]
 pub struct Gnd();
 
@@ -211,6 +330,381 @@ impl StatefulOutputPin for Gnd {
     }
 }
 
+impl pwm::ErrorType for Gnd {
+    type Error = OperationNotSupported;
+}
+
+impl SetDutyCycle for Gnd {
+    /// Get the maximum duty cycle of the ground pin, which is [`u16::MAX`].
+    #[inline]
+    fn max_duty_cycle(&self) -> u16 {
+        u16::MAX
+    }
+
+    /// Try to set the duty cycle of the ground pin.
+    ///
+    /// Because the pin is always low, setting a duty cycle of `0` is a No-Op and always succeeds.
+    ///
+    /// # Errors
+    ///
+    /// Setting any duty cycle but `0` will *always* fail and return a [OperationNotSupported]
+    /// error.
+    #[inline]
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+        match duty {
+            0 => Ok(()),
+            _ => Err(OperationNotSupported()),
+        }
+    }
+}
+
+/// Error returned by the adapters of this module
+///
+/// The adapters implement the traits of one kind of pin on top of another kind of pin. The
+/// errors of the wrapped pin do not implement the error trait of the adapted kind, so they are
+/// wrapped in this error. The contained error is the one of the wrapped pin.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct AdapterError<E>(pub E);
+
+impl<E: Error> pwm::Error for AdapterError<E> {
+    fn kind(&self) -> pwm::ErrorKind {
+        pwm::ErrorKind::Other
+    }
+}
+
+impl<E: pwm::Error> Error for AdapterError<E> {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Other
+    }
+}
+
+impl<E: core::fmt::Debug> core::fmt::Display for AdapterError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "adapted pin error: {:?}", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: core::fmt::Debug> std::error::Error for AdapterError<E> {}
+
+/// A digital output pin used as a PWM pin
+///
+/// This adapter implements the [SetDutyCycle] trait on top of an [OutputPin]. The maximum duty
+/// cycle is [`u16::MAX`]. All duty cycles greater than or equal to the threshold set the pin high,
+/// while lower duty cycles set it low. By default, the threshold is `1`, i.e. only a duty cycle of
+/// `0` sets the pin low.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::pwm::SetDutyCycle;
+/// use l293x::pins::DigitalAsPwm;
+///
+/// // Drive the pin high from 50% on
+/// let mut pin = DigitalAsPwm::with_threshold(gpio, u16::MAX / 2);
+/// pin.set_duty_cycle_percent(75)?;
+/// ```
+#[derive(Debug)]
+pub struct DigitalAsPwm<P> {
+    pin: P,
+    threshold: u16,
+}
+
+impl<P> DigitalAsPwm<P> {
+    /// Create a new adapter, which sets the pin high for all duty cycles but `0`.
+    #[inline]
+    pub fn new(pin: P) -> Self {
+        Self::with_threshold(pin, 1)
+    }
+
+    /// Create a new adapter, which sets the pin high for all duty cycles greater than or equal to
+    /// the given `threshold`.
+    ///
+    /// A `threshold` of `0` is treated as `1`, so that a duty cycle of `0` always sets the pin
+    /// low.
+    #[inline]
+    pub fn with_threshold(pin: P, threshold: u16) -> Self {
+        Self {
+            pin,
+            threshold: threshold.max(1),
+        }
+    }
+
+    /// Release the adapter and return the pin.
+    #[inline]
+    pub fn release(self) -> P {
+        self.pin
+    }
+}
+
+impl<P: ErrorType> pwm::ErrorType for DigitalAsPwm<P> {
+    type Error = AdapterError<P::Error>;
+}
+
+impl<P: OutputPin> SetDutyCycle for DigitalAsPwm<P> {
+    #[inline]
+    fn max_duty_cycle(&self) -> u16 {
+        u16::MAX
+    }
+
+    #[inline]
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+        self.pin
+            .set_state((duty >= self.threshold).into())
+            .map_err(AdapterError)
+    }
+}
+
+/// A PWM pin used as a digital output pin
+///
+/// This adapter implements the [OutputPin] and [StatefulOutputPin] traits on top of a
+/// [SetDutyCycle] pin. Setting the pin high sets the duty cycle fully on, while setting it low
+/// sets the duty cycle fully off.
+///
+/// The duty cycle of the wrapped pin can not be read back. Thus, the adapter remembers the state
+/// set last. It assumes the pin to be low initially.
+#[derive(Debug)]
+pub struct PwmAsDigital<P> {
+    pin: P,
+    high: bool,
+}
+
+impl<P> PwmAsDigital<P> {
+    /// Create a new adapter of the given pin, which is assumed to be low.
+    #[inline]
+    pub fn new(pin: P) -> Self {
+        Self { pin, high: false }
+    }
+
+    /// Release the adapter and return the pin.
+    #[inline]
+    pub fn release(self) -> P {
+        self.pin
+    }
+}
+
+impl<P: pwm::ErrorType> ErrorType for PwmAsDigital<P> {
+    type Error = AdapterError<P::Error>;
+}
+
+impl<P: SetDutyCycle> OutputPin for PwmAsDigital<P> {
+    #[inline]
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.pin.set_duty_cycle_fully_off().map_err(AdapterError)?;
+        self.high = false;
+        Ok(())
+    }
+
+    #[inline]
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.pin.set_duty_cycle_fully_on().map_err(AdapterError)?;
+        self.high = true;
+        Ok(())
+    }
+}
+
+impl<P: SetDutyCycle> StatefulOutputPin for PwmAsDigital<P> {
+    #[inline]
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.high)
+    }
+
+    #[inline]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(!self.high)
+    }
+}
+
+/// A pin with inverted polarity
+///
+/// This adapter inverts the wrapped pin: Setting the adapter high sets the pin low and vice
+/// versa. The duty cycles are inverted as well, i.e. a duty cycle of `d` sets the duty cycle of
+/// the pin to `max - d`. Use this adapter for pins of the chip, which are connected through an
+/// inverting stage, like an inverting level shifter or a transistor.
+///
+/// # Examples
+///
+/// ```
+/// use l293x::pins::ActiveLow;
+/// use l293x::L293x;
+///
+/// // The enable pin is driven by an NPN transistor pulling it low
+/// let mut l293x = L293x::new(input1, input2, (), (), ActiveLow::new(gpio), ());
+/// // Sets `gpio` low, which enables the outputs 1 & 2
+/// l293x.enable_y1_and_y2()?;
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ActiveLow<P> {
+    pin: P,
+}
+
+impl<P> ActiveLow<P> {
+    /// Create a new adapter inverting the given pin.
+    #[inline]
+    pub fn new(pin: P) -> Self {
+        Self { pin }
+    }
+
+    /// Release the adapter and return the pin.
+    #[inline]
+    pub fn release(self) -> P {
+        self.pin
+    }
+}
+
+impl<P: ErrorType> ErrorType for ActiveLow<P> {
+    type Error = P::Error;
+}
+
+impl<P: OutputPin> OutputPin for ActiveLow<P> {
+    #[inline]
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.pin.set_high()
+    }
+
+    #[inline]
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.pin.set_low()
+    }
+}
+
+impl<P: StatefulOutputPin> StatefulOutputPin for ActiveLow<P> {
+    #[inline]
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        self.pin.is_set_low()
+    }
+
+    #[inline]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        self.pin.is_set_high()
+    }
+
+    #[inline]
+    fn toggle(&mut self) -> Result<(), Self::Error> {
+        self.pin.toggle()
+    }
+}
+
+impl<P: pwm::ErrorType> pwm::ErrorType for ActiveLow<P> {
+    type Error = P::Error;
+}
+
+impl<P: SetDutyCycle> SetDutyCycle for ActiveLow<P> {
+    #[inline]
+    fn max_duty_cycle(&self) -> u16 {
+        self.pin.max_duty_cycle()
+    }
+
+    /// Set the inverted duty cycle on the pin.
+    ///
+    /// Duty cycles above the maximum duty cycle are treated as the maximum duty cycle.
+    #[inline]
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+        let max = self.pin.max_duty_cycle();
+        self.pin.set_duty_cycle(max - duty.min(max))
+    }
+}
+
+/// A pin with a polarity configured at runtime
+///
+/// This adapter behaves like the wrapped pin, if the polarity is [Normal](Polarity::Normal), and
+/// like an [ActiveLow] pin, if the polarity is [Inverted](Polarity::Inverted). The chip applies
+/// the polarities configured by [`L293x::with_polarity()`](crate::L293x::with_polarity) the same
+/// way, without changing the types of its pins.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Polarized<P> {
+    pin: P,
+    polarity: Polarity,
+}
+
+impl<P> Polarized<P> {
+    /// Create a new adapter of the given pin with the given polarity.
+    #[inline]
+    pub fn new(pin: P, polarity: Polarity) -> Self {
+        Self { pin, polarity }
+    }
+
+    /// Get the polarity of the pin.
+    #[inline]
+    pub fn polarity(&self) -> Polarity {
+        self.polarity
+    }
+
+    /// Release the adapter and return the pin.
+    #[inline]
+    pub fn release(self) -> P {
+        self.pin
+    }
+
+    /// Convert a logical state into the state of the wrapped pin and vice versa.
+    #[inline]
+    fn apply(&self, high: bool) -> bool {
+        high ^ (self.polarity == Polarity::Inverted)
+    }
+}
+
+impl<P: ErrorType> ErrorType for Polarized<P> {
+    type Error = P::Error;
+}
+
+impl<P: OutputPin> OutputPin for Polarized<P> {
+    #[inline]
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.pin.set_state(self.apply(false).into())
+    }
+
+    #[inline]
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.pin.set_state(self.apply(true).into())
+    }
+}
+
+impl<P: StatefulOutputPin> StatefulOutputPin for Polarized<P> {
+    #[inline]
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        let high = self.pin.is_set_high()?;
+        Ok(self.apply(high))
+    }
+
+    #[inline]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        self.is_set_high().map(|high| !high)
+    }
+
+    #[inline]
+    fn toggle(&mut self) -> Result<(), Self::Error> {
+        self.pin.toggle()
+    }
+}
+
+impl<P: pwm::ErrorType> pwm::ErrorType for Polarized<P> {
+    type Error = P::Error;
+}
+
+impl<P: SetDutyCycle> SetDutyCycle for Polarized<P> {
+    #[inline]
+    fn max_duty_cycle(&self) -> u16 {
+        self.pin.max_duty_cycle()
+    }
+
+    /// Set the duty cycle on the pin, which is inverted for the
+    /// [Inverted](Polarity::Inverted) polarity.
+    ///
+    /// Duty cycles above the maximum duty cycle are treated as the maximum duty cycle.
+    #[inline]
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+        let max = self.pin.max_duty_cycle();
+        let duty = duty.min(max);
+        match self.polarity {
+            Polarity::Normal => self.pin.set_duty_cycle(duty),
+            Polarity::Inverted => self.pin.set_duty_cycle(max - duty),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -222,6 +716,66 @@ mod tests {
         assert_eq!(e.kind(), ErrorKind::Other);
     }
 
+    #[test]
+    fn test_error_display() {
+        extern crate std;
+        use std::string::ToString;
+
+        let e = OperationNotSupported();
+        assert_eq!(e.to_string(), "operation not supported by the pin");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_std_error() {
+        let e: &dyn std::error::Error = &OperationNotSupported();
+        assert!(e.source().is_none());
+    }
+
+    #[test]
+    fn test_pwm_error_kind() {
+        let e = OperationNotSupported();
+        assert_eq!(pwm::Error::kind(&e), pwm::ErrorKind::Other);
+    }
+
+    #[test]
+    fn test_vcc_duty_cycle() {
+        let mut pin = Vcc();
+
+        assert!(pin.set_duty_cycle_fully_on().is_ok());
+        assert!(pin.set_duty_cycle_percent(100).is_ok());
+        assert!(pin.set_duty_cycle_percent(50).is_err());
+        assert_eq!(
+            pin.set_duty_cycle_fully_off().unwrap_err().error_kind(),
+            crate::ErrorKind::NotSupported
+        );
+    }
+
+    #[test]
+    fn test_gnd_duty_cycle() {
+        let mut pin = Gnd();
+
+        assert!(pin.set_duty_cycle_fully_off().is_ok());
+        assert!(pin.set_duty_cycle_fraction(0, 3).is_ok());
+        assert!(pin.set_duty_cycle(1).is_err());
+        assert!(pin.set_duty_cycle_fully_on().is_err());
+    }
+
+    #[test]
+    fn test_not_connected() {
+        let mut l293x = crate::L293x::new(
+            Vcc(),
+            NotConnected,
+            NotConnected,
+            NotConnected,
+            Vcc(),
+            NotConnected,
+        );
+
+        assert!(l293x.is_y1_set_high().unwrap());
+        assert!(l293x.y1_and_y2_enabled().unwrap());
+    }
+
     #[test]
     fn test_vcc() {
         let mut pin = Vcc();
@@ -243,4 +797,95 @@ mod tests {
         assert!(matches!(pin.set_high(), Err(OperationNotSupported(..))));
         assert!(matches!(pin.toggle(), Err(OperationNotSupported(..))));
     }
+
+    #[test]
+    fn test_digital_as_pwm() {
+        let mut pin = DigitalAsPwm::new(crate::test_util::DigitalPin::new());
+        assert_eq!(pin.max_duty_cycle(), u16::MAX);
+        pin.set_duty_cycle(1).unwrap();
+        assert!(pin.pin.is_set_high().unwrap());
+        pin.set_duty_cycle_fully_off().unwrap();
+        assert!(pin.pin.is_set_low().unwrap());
+
+        let mut pin = DigitalAsPwm::with_threshold(pin.release(), u16::MAX / 2);
+        pin.set_duty_cycle_percent(49).unwrap();
+        assert!(pin.pin.is_set_low().unwrap());
+        pin.set_duty_cycle_percent(50).unwrap();
+        assert!(pin.pin.is_set_high().unwrap());
+
+        pin.pin.fail();
+        let error = pin.set_duty_cycle(0).unwrap_err();
+        assert_eq!(error, AdapterError(crate::test_util::DigitalError()));
+        assert_eq!(pwm::Error::kind(&error), pwm::ErrorKind::Other);
+    }
+
+    #[test]
+    fn test_pwm_as_digital() {
+        let mut pin = PwmAsDigital::new(crate::test_util::PwmPin::new());
+        assert!(pin.is_set_low().unwrap());
+        pin.set_high().unwrap();
+        assert!(pin.is_set_high().unwrap());
+        assert_eq!(pin.pin.get_duty_cycle(), u16::MAX);
+        pin.toggle().unwrap();
+        assert!(!pin.is_set_high().unwrap());
+        assert_eq!(pin.pin.get_duty_cycle(), 0);
+
+        pin.pin.fail();
+        let error = pin.set_high().unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::Other);
+        assert!(pin.set_low().is_err());
+        assert_eq!(pin.release().get_duty_cycle(), 0);
+    }
+
+    #[test]
+    fn test_adapter_error_display() {
+        extern crate std;
+        use std::string::ToString;
+
+        let e = AdapterError(crate::test_util::PwmError());
+        assert_eq!(e.to_string(), "adapted pin error: PwmError");
+    }
+
+    #[test]
+    fn test_active_low() {
+        let mut pin = ActiveLow::new(crate::test_util::DigitalPin::new());
+        assert!(pin.is_set_high().unwrap());
+        pin.set_high().unwrap();
+        assert!(pin.pin.is_set_low().unwrap());
+        assert!(!pin.is_set_low().unwrap());
+        pin.toggle().unwrap();
+        assert!(pin.is_set_low().unwrap());
+        assert!(pin.release().is_set_high().unwrap());
+
+        let mut pin = ActiveLow::new(crate::test_util::PwmPin::new());
+        assert_eq!(pin.max_duty_cycle(), u16::MAX);
+        pin.set_duty_cycle(1000).unwrap();
+        assert_eq!(pin.pin.get_duty_cycle(), u16::MAX - 1000);
+        pin.set_duty_cycle_fully_on().unwrap();
+        assert_eq!(pin.release().get_duty_cycle(), 0);
+    }
+
+    #[test]
+    fn test_polarized() {
+        let mut pin = Polarized::new(crate::test_util::DigitalPin::new(), Polarity::Normal);
+        assert_eq!(pin.polarity(), Polarity::Normal);
+        pin.set_high().unwrap();
+        assert!(pin.pin.is_set_high().unwrap());
+        pin.toggle().unwrap();
+        assert!(pin.is_set_low().unwrap());
+
+        let mut pin = Polarized::new(pin.release(), Polarity::Inverted);
+        assert!(pin.is_set_high().unwrap());
+        pin.set_low().unwrap();
+        assert!(pin.pin.is_set_high().unwrap());
+        assert!(!pin.is_set_high().unwrap());
+
+        let mut pin = Polarized::new(crate::test_util::PwmPin::new(), Polarity::Normal);
+        assert_eq!(pin.max_duty_cycle(), u16::MAX);
+        pin.set_duty_cycle(1000).unwrap();
+        assert_eq!(pin.pin.get_duty_cycle(), 1000);
+        let mut pin = Polarized::new(pin.release(), Polarity::Inverted);
+        pin.set_duty_cycle(1000).unwrap();
+        assert_eq!(pin.release().get_duty_cycle(), u16::MAX - 1000);
+    }
 }
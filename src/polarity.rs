@@ -0,0 +1,75 @@
+/// The polarity of the connection between a pin of the microcontroller and a pin of the chip
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Polarity {
+    /// The pin of the chip has the same level as the pin of the microcontroller.
+    Normal,
+    /// The pin of the chip has the inverted level of the pin of the microcontroller, e.g.
+    /// because it is connected through an inverting level shifter or a transistor.
+    Inverted,
+}
+
+impl Default for Polarity {
+    #[inline]
+    fn default() -> Self {
+        Polarity::Normal
+    }
+}
+
+/// The polarities of all pins of a [L293](crate::L293x) chip
+///
+/// The polarities are applied by [`L293x::with_polarity()`](crate::L293x::with_polarity), so
+/// that the API of the chip follows the schematic: E.g. [`set_y1_high()`](crate::L293x::set_y1_high)
+/// drives the input 1 of the chip high, even if the pin of the microcontroller needs to be set low
+/// for this. By default, all pins have the [Normal](Polarity::Normal) polarity.
+///
+/// # Examples
+///
+/// ```
+/// use l293x::{L293xPolarity, Polarity};
+///
+/// // Only the enable pins are driven through inverting transistors
+/// let polarity = L293xPolarity {
+///     enable12: Polarity::Inverted,
+///     enable34: Polarity::Inverted,
+///     ..L293xPolarity::default()
+/// };
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct L293xPolarity {
+    /// The polarities of the inputs 1 - 4.
+    pub inputs: [Polarity; 4],
+    /// The polarity of the enable pin of the channels 1 & 2.
+    pub enable12: Polarity,
+    /// The polarity of the enable pin of the channels 3 & 4.
+    pub enable34: Polarity,
+}
+
+#[cfg(feature = "storage")]
+impl crate::persist::Persist for L293xPolarity {
+    const SIZE: usize = 1;
+
+    fn to_bytes(&self, bytes: &mut [u8]) {
+        let polarities = self.inputs.iter().chain([&self.enable12, &self.enable34]);
+        bytes[0] = polarities
+            .enumerate()
+            .filter(|(_, polarity)| **polarity == Polarity::Inverted)
+            .fold(0, |bits, (i, _)| bits | 1 << i);
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes[0] >> 6 != 0 {
+            return None;
+        }
+        let polarity = |i: usize| match bytes[0] >> i & 1 {
+            0 => Polarity::Normal,
+            _ => Polarity::Inverted,
+        };
+        Some(Self {
+            inputs: [polarity(0), polarity(1), polarity(2), polarity(3)],
+            enable12: polarity(4),
+            enable34: polarity(5),
+        })
+    }
+}
@@ -0,0 +1,381 @@
+//! # Port-write backend
+//!
+//! Setting the input pins of the [L293](crate::L293x) chip one after another requires one write
+//! per pin. If the pins are connected to a port expander (e.g. a PCF8574 or a MCP23017), each
+//! of these writes is a separate bus transaction. Many GPIO peripherals and port expanders allow
+//! writing several pins at once instead. This module provides the [PortOutput] trait to describe
+//! such backends.
+//!
+//! A [Port] wraps a backend and stores the state of its pins. It provides a [PortPin] for each
+//! of its pins, which implements the [OutputPin] and [StatefulOutputPin] traits, and thus, can be
+//! used as input pin of the drivers of this crate. A chip created using
+//! [`L293x::from_port()`](crate::L293x::from_port) can additionally set all of its inputs in a
+//! single write using [`L293x::set_inputs()`](crate::L293x::set_inputs).
+//!
+//! If the `coalesce` feature is enabled, the writes of all pins of a port can be buffered using
+//! [`Port::coalesce()`]. The pins only record their new states and the changes are flushed in a
+//! single write afterwards. This collapses e.g. the four input updates of a
+//! [stepper](crate::stepper) step into a single bus transaction, without the driver knowing about
+//! the port at all.
+//!
+//! # Examples
+//!
+//! ```
+//! use embedded_hal::digital::PinState;
+//! use l293x::port::Port;
+//! use l293x::L293x;
+//!
+//! // [...] create the backend, e.g. a driver of a port expander implementing `PortOutput`
+//! let port = Port::new(expander);
+//! let mut l293x = L293x::from_port(&port, [0, 1, 2, 3], enable12, enable34);
+//!
+//! // Change all four inputs using a single bus transaction
+//! l293x.set_inputs([PinState::High, PinState::Low, PinState::Low, PinState::High])?;
+//! ```
+//!
+//! Coalescing the writes of a stepper motor driver:
+//!
+//! ```
+//! use l293x::port::Port;
+//! use l293x::stepper::Stepper;
+//! use l293x::L293x;
+//!
+//! let port = Port::new(expander);
+//! let mut stepper = Stepper::new(L293x::from_port(&port, [0, 1, 2, 3], enable12, enable34));
+//!
+//! // Within the timer interrupt:
+//! port.coalesce(|| stepper.poll(ticks))??;
+//! ```
+use core::cell::RefCell;
+
+use embedded_hal::digital::{Error, ErrorType, OutputPin, StatefulOutputPin};
+
+/// A backend writing several output pins at once
+///
+/// Implement this trait for GPIO ports or port expanders, which can change the state of several
+/// pins in a single register or bus write. Bit `n` of the masks and states corresponds to pin `n`
+/// of the backend.
+pub trait PortOutput {
+    /// The error returned, if the write failed.
+    type Error: Error;
+
+    /// Set the pins selected by the `mask` to the states of the corresponding bits of `state`.
+    ///
+    /// The other pins of the backend must not be changed.
+    fn write_port(&mut self, mask: u16, state: u16) -> Result<(), Self::Error>;
+}
+
+impl<T: PortOutput + ?Sized> PortOutput for &mut T {
+    type Error = T::Error;
+
+    #[inline]
+    fn write_port(&mut self, mask: u16, state: u16) -> Result<(), Self::Error> {
+        T::write_port(self, mask, state)
+    }
+}
+
+/// The backend and the pin states of a port
+#[derive(Debug)]
+struct Inner<P> {
+    port: P,
+    state: u16,
+    #[cfg(feature = "coalesce")]
+    coalescing: bool,
+    #[cfg(feature = "coalesce")]
+    pending: u16,
+}
+
+/// A [PortOutput] backend shared by several pins
+///
+/// The port stores the state of its pins. The pins are accessed using the
+/// [`pin()`](Port::pin) method.
+#[derive(Debug)]
+pub struct Port<P> {
+    inner: RefCell<Inner<P>>,
+}
+
+impl<P> Port<P> {
+    /// Create a new port from the given backend.
+    ///
+    /// All pins are assumed to be low. Use [`write()`](Port::write) to set the initial state, if
+    /// the state of the backend is unknown.
+    #[inline]
+    pub fn new(port: P) -> Self {
+        Self {
+            inner: RefCell::new(Inner {
+                port,
+                state: 0,
+                #[cfg(feature = "coalesce")]
+                coalescing: false,
+                #[cfg(feature = "coalesce")]
+                pending: 0,
+            }),
+        }
+    }
+
+    /// Get the given pin (`0` - `15`) of the port.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the pin is greater than `15`.
+    #[inline]
+    pub fn pin(&self, pin: u8) -> PortPin<'_, P> {
+        assert!(pin < 16);
+        PortPin {
+            port: self,
+            mask: 1 << pin,
+        }
+    }
+
+    /// Get the current state of all pins.
+    ///
+    /// Bit `n` of the returned value is the state of the pin `n`. While
+    /// [coalescing](Port::coalesce), this includes the changes, which have not been flushed yet.
+    #[inline]
+    pub fn state(&self) -> u16 {
+        self.inner.borrow().state
+    }
+
+    /// Release the port and return the backend.
+    #[inline]
+    pub fn release(self) -> P {
+        self.inner.into_inner().port
+    }
+}
+
+impl<P: PortOutput> Port<P> {
+    /// Set the pins selected by the `mask` to the states of the corresponding bits of `state`
+    /// using a single write.
+    ///
+    /// While [coalescing](Port::coalesce), the changes are only recorded and written by the next
+    /// flush.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the backend. The stored state is not changed in
+    /// this case.
+    pub fn write(&self, mask: u16, state: u16) -> Result<(), P::Error> {
+        let mut inner = self.inner.borrow_mut();
+        #[cfg(feature = "coalesce")]
+        if inner.coalescing {
+            inner.pending |= mask;
+            inner.state = inner.state & !mask | state & mask;
+            return Ok(());
+        }
+        inner.port.write_port(mask, state)?;
+        inner.state = inner.state & !mask | state & mask;
+        #[cfg(feature = "coalesce")]
+        {
+            inner.pending &= !mask;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "coalesce")]
+impl<P: PortOutput> Port<P> {
+    /// Buffer the writes of all pins of the port, while running the given closure.
+    ///
+    /// Within the closure, setting a pin only records its new state. Afterwards, all pending
+    /// changes are flushed in a single write. If this method is called within the closure of
+    /// another call, the changes are flushed by the outermost call. Returns the result of the
+    /// closure.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the backend, if the changes could not be flushed.
+    /// The changes stay pending in this case and are written by the next call to
+    /// [`flush()`](Port::flush).
+    pub fn coalesce<F, R>(&self, f: F) -> Result<R, P::Error>
+    where
+        F: FnOnce() -> R,
+    {
+        let nested = core::mem::replace(&mut self.inner.borrow_mut().coalescing, true);
+        let result = f();
+        if !nested {
+            self.inner.borrow_mut().coalescing = false;
+            self.flush()?;
+        }
+        Ok(result)
+    }
+
+    /// Write all pending changes of the pins in a single write.
+    ///
+    /// This does nothing, if there are no pending changes.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the backend. The changes stay pending in this case.
+    pub fn flush(&self) -> Result<(), P::Error> {
+        let mut inner = self.inner.borrow_mut();
+        let inner = &mut *inner;
+        if inner.pending != 0 {
+            inner.port.write_port(inner.pending, inner.state)?;
+            inner.pending = 0;
+        }
+        Ok(())
+    }
+}
+
+/// A single pin of a [Port]
+///
+/// Setting the pin writes only this pin of the backend.
+#[derive(Debug)]
+pub struct PortPin<'a, P> {
+    port: &'a Port<P>,
+    mask: u16,
+}
+
+impl<'a, P> PortPin<'a, P> {
+    /// Get the port of the pin.
+    #[inline]
+    pub fn port(&self) -> &'a Port<P> {
+        self.port
+    }
+
+    /// Get the mask selecting the pin within its port.
+    #[inline]
+    pub fn mask(&self) -> u16 {
+        self.mask
+    }
+}
+
+impl<P> Clone for PortPin<'_, P> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<P> Copy for PortPin<'_, P> {}
+
+impl<P: PortOutput> ErrorType for PortPin<'_, P> {
+    type Error = P::Error;
+}
+
+impl<P: PortOutput> OutputPin for PortPin<'_, P> {
+    #[inline]
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.port.write(self.mask, 0)
+    }
+
+    #[inline]
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.port.write(self.mask, self.mask)
+    }
+}
+
+impl<P: PortOutput> StatefulOutputPin for PortPin<'_, P> {
+    #[inline]
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.port.state() & self.mask != 0)
+    }
+
+    #[inline]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.port.state() & self.mask == 0)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::test_util::DigitalError;
+    use coverage_helper::test;
+
+    /// Simulated backend recording its writes
+    #[derive(Debug, Default)]
+    pub(crate) struct Backend {
+        pub(crate) state: u16,
+        pub(crate) writes: usize,
+        pub(crate) fail: bool,
+    }
+
+    impl PortOutput for Backend {
+        type Error = DigitalError;
+
+        fn write_port(&mut self, mask: u16, state: u16) -> Result<(), Self::Error> {
+            if self.fail {
+                return Err(DigitalError());
+            }
+            self.state = self.state & !mask | state & mask;
+            self.writes += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_pins() {
+        let mut backend = Backend::default();
+        let port = Port::new(&mut backend);
+        let mut pin = port.pin(3);
+        assert_eq!(pin.mask(), 0b1000);
+
+        pin.set_high().unwrap();
+        port.pin(15).set_high().unwrap();
+        assert!(pin.is_set_high().unwrap());
+        assert_eq!(pin.port().state(), 0x8008);
+        pin.set_low().unwrap();
+        assert!(pin.is_set_low().unwrap());
+
+        port.write(0x00ff, 0xffff).unwrap();
+        assert_eq!(port.state(), 0x80ff);
+        port.release();
+        assert_eq!((backend.state, backend.writes), (0x80ff, 4));
+    }
+
+    #[test]
+    fn test_error() {
+        let port = Port::new(Backend {
+            fail: true,
+            ..Backend::default()
+        });
+        assert!(port.pin(0).set_high().is_err());
+        assert_eq!(port.state(), 0);
+    }
+
+    #[cfg(feature = "coalesce")]
+    #[test]
+    fn test_coalesce() {
+        use crate::stepper::Stepper;
+        use crate::test_util::DigitalPin;
+        use crate::{Direction, L293x};
+
+        let port = Port::new(Backend::default());
+        let chip = L293x::from_port(&port, [0, 1, 2, 3], DigitalPin::new(), DigitalPin::new());
+        let mut stepper = Stepper::new(chip);
+        stepper.enable().unwrap();
+        stepper.run(Direction::Forward, 1);
+        let writes = port.inner.borrow().port.writes;
+        assert!(port.coalesce(|| stepper.poll(0)).unwrap().unwrap());
+        assert_eq!(port.state(), 0b0110);
+        assert_eq!(port.inner.borrow().port.writes, writes + 1);
+
+        port.coalesce(|| {
+            port.pin(4).set_high().unwrap();
+            port.coalesce(|| port.pin(5).set_high()).unwrap().unwrap();
+            assert!(port.pin(5).is_set_high().unwrap());
+        })
+        .unwrap();
+        assert_eq!(port.release().writes, writes + 2);
+    }
+
+    #[cfg(feature = "coalesce")]
+    #[test]
+    fn test_coalesce_error() {
+        let port = Port::new(Backend::default());
+        port.inner.borrow_mut().port.fail = true;
+        assert!(port.coalesce(|| port.pin(0).set_high()).is_err());
+        assert!(port.pin(1).set_high().is_err());
+
+        port.inner.borrow_mut().port.fail = false;
+        port.flush().unwrap();
+        let backend = port.release();
+        assert_eq!((backend.state, backend.writes), (0b01, 1));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_pin() {
+        Port::new(Backend::default()).pin(16);
+    }
+}
@@ -0,0 +1,236 @@
+//! # Speed profiles
+//!
+//! This module implements generators for speed profiles, which allow starting and stopping motors
+//! smoothly. Each profile is an [Iterator], which yields one value per tick of the control loop.
+//! The values can be passed to e.g. [`FullH::set_speed()`](crate::FullH::set_speed) or, for
+//! profiles with non-negative values, to
+//! [`SetDutyCycle::set_duty_cycle()`](embedded_hal::pwm::SetDutyCycle::set_duty_cycle). The
+//! values are computed using integer arithmetic only.
+//!
+//! The following profiles are available:
+//!
+//! - [Linear]: Changes the value with a constant rate (i.e. constant acceleration).
+//! - [SCurve]: Starts and ends the change smoothly, which avoids jerks at the start and the end
+//!   of the ramp.
+//! - [Trapezoidal]: Accelerates to a peak value, holds it and decelerates back to zero.
+//!
+//! # Examples
+//!
+//! ```
+//! use l293x::profile::Trapezoidal;
+//!
+//! // Accelerate within 100 ticks, hold full speed for 500 ticks and stop within 50 ticks
+//! for speed in Trapezoidal::new(i16::MAX.into(), 100, 500, 50) {
+//!     motor.set_speed(speed as i16)?;
+//!     delay.delay_ms(1);
+//! }
+//! ```
+use core::iter::Chain;
+
+/// Get the size hint of a profile with the given number of `remaining` values.
+///
+/// On targets with a 16-bit `usize` (e.g. AVR), the number of values may exceed `usize::MAX`.
+fn size_hint(remaining: u32) -> (usize, Option<usize>) {
+    match usize::try_from(remaining) {
+        Ok(remaining) => (remaining, Some(remaining)),
+        Err(_) => (usize::MAX, None),
+    }
+}
+
+/// A linear ramp between two values
+///
+/// The ramp yields the given number of values, where the last one is the target value. If the
+/// number of steps is zero, the target value is yielded once.
+#[derive(Debug, Clone)]
+pub struct Linear {
+    from: i32,
+    to: i32,
+    steps: u32,
+    step: u32,
+}
+
+impl Linear {
+    /// Create a new linear ramp from `from` to `to` in the given number of steps.
+    #[inline]
+    pub fn new(from: i32, to: i32, steps: u32) -> Self {
+        Self {
+            from,
+            to,
+            steps,
+            step: 0,
+        }
+    }
+}
+
+impl Iterator for Linear {
+    type Item = i32;
+
+    fn next(&mut self) -> Option<i32> {
+        if self.step >= self.steps.max(1) {
+            return None;
+        }
+        self.step += 1;
+        match self.steps {
+            0 => Some(self.to),
+            _ => Some(crate::ramp::ramp_value(
+                self.from, self.to, self.step, self.steps,
+            )),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        size_hint(self.steps.max(1) - self.step)
+    }
+}
+
+/// A smooth ramp between two values
+///
+/// The values follow the smoothstep function `3x² - 2x³`. Thus, the rate of change starts at
+/// zero, is highest in the middle of the ramp and ends at zero again. Like the [Linear] ramp, it
+/// yields the given number of values, where the last one is the target value.
+#[derive(Debug, Clone)]
+pub struct SCurve {
+    from: i32,
+    to: i32,
+    steps: u32,
+    step: u32,
+}
+
+impl SCurve {
+    /// Create a new S-curve ramp from `from` to `to` in the given number of steps.
+    #[inline]
+    pub fn new(from: i32, to: i32, steps: u32) -> Self {
+        Self {
+            from,
+            to,
+            steps,
+            step: 0,
+        }
+    }
+}
+
+impl Iterator for SCurve {
+    type Item = i32;
+
+    fn next(&mut self) -> Option<i32> {
+        if self.step >= self.steps.max(1) {
+            return None;
+        }
+        self.step += 1;
+        if self.steps == 0 {
+            return Some(self.to);
+        }
+        let (t, n) = (i128::from(self.step), i128::from(self.steps));
+        let (from, to) = (i128::from(self.from), i128::from(self.to));
+        // from + (to - from) * (3t²n - 2t³) / n³
+        let value = from + (to - from) * (3 * t * t * n - 2 * t * t * t) / (n * n * n);
+        Some(value as i32)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        size_hint(self.steps.max(1) - self.step)
+    }
+}
+
+/// A trapezoidal speed profile
+///
+/// The profile accelerates linearly from zero to the `peak` value, holds it for the given number
+/// of ticks, and decelerates linearly back to zero afterwards.
+#[derive(Debug, Clone)]
+pub struct Trapezoidal {
+    phases: Chain<Chain<Linear, Linear>, Linear>,
+}
+
+impl Trapezoidal {
+    /// Create a new trapezoidal profile.
+    ///
+    /// The acceleration takes `accelerate` ticks, the peak value is held for `hold` ticks, and the
+    /// deceleration takes `decelerate` ticks. Thus, the profile yields
+    /// `accelerate + hold + decelerate` values in total, where phases of zero ticks are skipped.
+    pub fn new(peak: i32, accelerate: u32, hold: u32, decelerate: u32) -> Self {
+        let mut up = Linear::new(0, peak, accelerate);
+        // A ramp from the peak to itself counts the ticks in `u32`, unlike `repeat().take()`
+        let mut hold_phase = Linear::new(peak, peak, hold);
+        let mut down = Linear::new(peak, 0, decelerate);
+        // Skip phases without ticks instead of jumping to their target value
+        if accelerate == 0 {
+            up.step = 1;
+        }
+        if hold == 0 {
+            hold_phase.step = 1;
+        }
+        if decelerate == 0 {
+            down.step = 1;
+        }
+        Self {
+            phases: up.chain(hold_phase).chain(down),
+        }
+    }
+}
+
+impl Iterator for Trapezoidal {
+    type Item = i32;
+
+    #[inline]
+    fn next(&mut self) -> Option<i32> {
+        self.phases.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.phases.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coverage_helper::test;
+
+    fn collect<const N: usize>(mut iter: impl Iterator<Item = i32>) -> [i32; N] {
+        let mut values = [0; N];
+        for value in values.iter_mut() {
+            *value = iter.next().unwrap();
+        }
+        assert_eq!(iter.next(), None);
+        values
+    }
+
+    #[test]
+    fn test_linear() {
+        let ramp = Linear::new(0, 100, 4);
+        assert_eq!(ramp.size_hint(), (4, Some(4)));
+        assert_eq!(collect::<4>(ramp), [25, 50, 75, 100]);
+        assert_eq!(collect::<1>(Linear::new(0, -100, 0)), [-100]);
+    }
+
+    #[test]
+    fn test_s_curve() {
+        let ramp = SCurve::new(0, 1000, 4);
+        assert_eq!(ramp.size_hint(), (4, Some(4)));
+        assert_eq!(collect::<4>(ramp), [156, 500, 843, 1000]);
+        assert_eq!(collect::<1>(SCurve::new(100, 0, 0)), [0]);
+    }
+
+    #[test]
+    fn test_trapezoidal() {
+        let profile = Trapezoidal::new(100, 2, 3, 4);
+        assert_eq!(profile.size_hint(), (9, Some(9)));
+        assert_eq!(
+            collect::<9>(profile),
+            [50, 100, 100, 100, 100, 75, 50, 25, 0]
+        );
+        assert_eq!(collect::<2>(Trapezoidal::new(-100, 0, 2, 0)), [-100, -100]);
+        assert_eq!(collect::<2>(Trapezoidal::new(100, 1, 0, 1)), [100, 0]);
+    }
+
+    #[test]
+    fn test_size_hint() {
+        assert_eq!(size_hint(3), (3, Some(3)));
+        let profile = Trapezoidal::new(100, 0, u32::MAX, 0);
+        assert_eq!(
+            profile.size_hint(),
+            (u32::MAX as usize, Some(u32::MAX as usize))
+        );
+    }
+}
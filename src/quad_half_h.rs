@@ -0,0 +1,294 @@
+use core::cell::RefCell;
+
+use embedded_hal::digital::OutputPin;
+
+use crate::HalfH;
+
+/// Four independent Half-H bridges
+///
+/// In contrast to the [L293](crate::L293x) chip, where the four Half-H bridges share two common
+/// enable pins, each of the bridges of this driver has its own enable pin. This allows driving
+/// hardware built from discrete half-bridges (or chips with four enable pins) using the same API.
+/// A [L293](crate::L293x) chip can be converted into this driver using the
+/// [`into_quad_half_h()`](crate::L293x::into_quad_half_h) method.
+///
+/// The bridges are accessed using the [`y1()`](QuadHalfH::y1) - [`y4()`](QuadHalfH::y4) methods,
+/// which return a [HalfH] for the corresponding output. Because the enable pins are not shared,
+/// enabling or disabling one of the bridges does not affect any of the other bridges.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::digital::OutputPin;
+/// use l293x::QuadHalfH;
+///
+/// // [...] create the input and enable pins
+/// let quad = QuadHalfH::new(input1, input2, input3, input4, enable1, enable2, enable3, enable4);
+///
+/// quad.y1().enable()?;
+/// quad.y1().set_high()?;
+/// // This does not affect the output 1
+/// quad.y2().disable()?;
+/// ```
+#[derive(Debug)]
+pub struct QuadHalfH<A1, A2, A3, A4, EN1, EN2, EN3, EN4> {
+    a1: RefCell<A1>,
+    a2: RefCell<A2>,
+    a3: RefCell<A3>,
+    a4: RefCell<A4>,
+    en1: RefCell<EN1>,
+    en2: RefCell<EN2>,
+    en3: RefCell<EN3>,
+    en4: RefCell<EN4>,
+}
+
+impl<A1, A2, A3, A4, EN1, EN2, EN3, EN4> QuadHalfH<A1, A2, A3, A4, EN1, EN2, EN3, EN4> {
+    /// Create a new driver for four independent Half-H bridges.
+    ///
+    /// This function takes the input pins and the enable pins of the four bridges. Unused
+    /// bridges can be left unconnected by passing the empty type (`()`) instead of a real pin.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use l293x::QuadHalfH;
+    /// let quad = QuadHalfH::new(input1, input2, input3, input4, enable1, enable2, enable3, enable4);
+    /// ```
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(a1: A1, a2: A2, a3: A3, a4: A4, en1: EN1, en2: EN2, en3: EN3, en4: EN4) -> Self {
+        Self {
+            a1: RefCell::new(a1),
+            a2: RefCell::new(a2),
+            a3: RefCell::new(a3),
+            a4: RefCell::new(a4),
+            en1: RefCell::new(en1),
+            en2: RefCell::new(en2),
+            en3: RefCell::new(en3),
+            en4: RefCell::new(en4),
+        }
+    }
+
+    /// Release the driver and return the input pins and the enable pins.
+    #[inline]
+    #[allow(clippy::type_complexity)]
+    pub fn release(self) -> ((A1, A2, A3, A4), (EN1, EN2, EN3, EN4)) {
+        (
+            (
+                self.a1.into_inner(),
+                self.a2.into_inner(),
+                self.a3.into_inner(),
+                self.a4.into_inner(),
+            ),
+            (
+                self.en1.into_inner(),
+                self.en2.into_inner(),
+                self.en3.into_inner(),
+                self.en4.into_inner(),
+            ),
+        )
+    }
+
+    /// Get the Half-H bridge of output `y1`.
+    #[inline]
+    pub fn y1(&self) -> HalfH<'_, A1, EN1> {
+        HalfH::new(&self.a1, &self.en1)
+    }
+
+    /// Get the Half-H bridge of output `y2`.
+    #[inline]
+    pub fn y2(&self) -> HalfH<'_, A2, EN2> {
+        HalfH::new(&self.a2, &self.en2)
+    }
+
+    /// Get the Half-H bridge of output `y3`.
+    #[inline]
+    pub fn y3(&self) -> HalfH<'_, A3, EN3> {
+        HalfH::new(&self.a3, &self.en3)
+    }
+
+    /// Get the Half-H bridge of output `y4`.
+    #[inline]
+    pub fn y4(&self) -> HalfH<'_, A4, EN4> {
+        HalfH::new(&self.a4, &self.en4)
+    }
+}
+
+impl<A1, A2, A3, A4, EN1, EN2, EN3, EN4> QuadHalfH<A1, A2, A3, A4, EN1, EN2, EN3, EN4>
+where
+    EN1: OutputPin,
+    EN2: OutputPin<Error = EN1::Error>,
+    EN3: OutputPin<Error = EN1::Error>,
+    EN4: OutputPin<Error = EN1::Error>,
+{
+    /// Enable all four outputs.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the first enable pin, which could not be set
+    /// high. The remaining enable pins will not be changed in this case.
+    pub fn enable_all(&mut self) -> Result<(), EN1::Error> {
+        self.en1.get_mut().set_high()?;
+        self.en2.get_mut().set_high()?;
+        self.en3.get_mut().set_high()?;
+        self.en4.get_mut().set_high()
+    }
+
+    /// Disable all four outputs.
+    ///
+    /// This sets all outputs into the high impedance mode.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the first enable pin, which could not be set
+    /// low. The remaining enable pins will not be changed in this case.
+    pub fn disable_all(&mut self) -> Result<(), EN1::Error> {
+        self.en1.get_mut().set_low()?;
+        self.en2.get_mut().set_low()?;
+        self.en3.get_mut().set_low()?;
+        self.en4.get_mut().set_low()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pins::Vcc;
+    use crate::shared_pin::PinCell;
+    use crate::test_util::DigitalPin;
+    use crate::{L293xPolarity, Polarity};
+    use coverage_helper::test;
+    use embedded_hal::digital::StatefulOutputPin;
+
+    type MockQuad = QuadHalfH<
+        DigitalPin,
+        DigitalPin,
+        DigitalPin,
+        DigitalPin,
+        DigitalPin,
+        DigitalPin,
+        DigitalPin,
+        DigitalPin,
+    >;
+
+    fn quad() -> MockQuad {
+        QuadHalfH::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        )
+    }
+
+    #[test]
+    fn test_independent_enables() {
+        let quad = quad();
+
+        quad.y1().enable().unwrap();
+        quad.y1().set_high().unwrap();
+        quad.y2().set_high().unwrap();
+        assert!(quad.y2().is_disabled().unwrap());
+        quad.y2().enable().unwrap();
+        quad.y2().disable().unwrap();
+
+        assert!(quad.y1().is_enabled().unwrap());
+        assert!(quad.y1().is_set_high().unwrap());
+        assert!(quad.y2().is_disabled().unwrap());
+        assert!(quad.y3().is_disabled().unwrap());
+        assert!(quad.y4().is_disabled().unwrap());
+    }
+
+    #[test]
+    fn test_enable_all() {
+        let mut quad = quad();
+
+        quad.enable_all().unwrap();
+        assert!(quad.y1().is_enabled().unwrap());
+        assert!(quad.y2().is_enabled().unwrap());
+        assert!(quad.y3().is_enabled().unwrap());
+        assert!(quad.y4().is_enabled().unwrap());
+
+        quad.disable_all().unwrap();
+        assert!(quad.y1().is_disabled().unwrap());
+        assert!(quad.y2().is_disabled().unwrap());
+        assert!(quad.y3().is_disabled().unwrap());
+        assert!(quad.y4().is_disabled().unwrap());
+    }
+
+    #[test]
+    fn test_enable_all_fail() {
+        let mut quad = quad();
+        quad.en3.get_mut().fail();
+
+        assert!(quad.enable_all().is_err());
+        assert!(quad.y2().is_enabled().unwrap());
+        assert!(quad.y4().is_disabled().unwrap());
+        assert!(quad.disable_all().is_err());
+    }
+
+    #[test]
+    fn test_from_l293x() {
+        let (mut en12, mut en34) = (PinCell::new(), PinCell::new());
+        let mut quad = crate::L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        )
+        .into_quad_half_h(&mut en12, &mut en34);
+
+        quad.y1().enable().unwrap();
+        quad.y1().set_high().unwrap();
+        // The bridges 1 & 2 share their enable pin
+        assert!(quad.y2().is_enabled().unwrap());
+        assert!(quad.y3().is_disabled().unwrap());
+
+        quad.enable_all().unwrap();
+        assert!(quad.y4().is_enabled().unwrap());
+
+        let ((mut a1, ..), _) = quad.release();
+        assert!(a1.is_set_high().unwrap());
+    }
+
+    #[test]
+    fn test_from_l293x_polarity() {
+        let (mut en12, mut en34) = (PinCell::new(), PinCell::new());
+        let polarity = L293xPolarity {
+            inputs: [Polarity::Inverted; 4],
+            enable34: Polarity::Inverted,
+            ..L293xPolarity::default()
+        };
+        let quad = crate::L293x::with_polarity(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            polarity,
+        )
+        .into_quad_half_h(&mut en12, &mut en34);
+
+        quad.y3().enable().unwrap();
+        quad.y3().set_high().unwrap();
+        assert!(quad.y4().is_enabled().unwrap());
+
+        let ((_, _, a3, _), (_, _, en3, _)) = quad.release();
+        assert!(a3.release().is_set_low().unwrap());
+        assert!(en3.release().is_set_low().unwrap());
+    }
+
+    #[test]
+    fn test_partial() {
+        let quad = QuadHalfH::new(DigitalPin::new(), (), (), (), Vcc(), (), (), ());
+
+        quad.y1().set_high().unwrap();
+        assert!(quad.y1().is_set_high().unwrap());
+    }
+}
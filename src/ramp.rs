@@ -0,0 +1,198 @@
+use embedded_hal::delay::DelayNs;
+use embedded_hal::pwm::SetDutyCycle;
+
+/// Ramp a value linearly from `from` to `to` in the given number of steps.
+///
+/// Each step takes one millisecond. After waiting for the step, the intermediate value is passed
+/// to `set`. If the number of steps is zero, the final value is set immediately.
+pub(crate) fn ramp<D, E>(
+    from: i32,
+    to: i32,
+    steps: u32,
+    delay: &mut D,
+    mut set: impl FnMut(i32) -> Result<(), E>,
+) -> Result<(), E>
+where
+    D: DelayNs,
+{
+    for step in 1..=steps {
+        delay.delay_ms(1);
+        set(ramp_value(from, to, step, steps))?;
+    }
+    match steps {
+        0 => set(to),
+        _ => Ok(()),
+    }
+}
+
+/// Ramp a value linearly from `from` to `to` by changing it at most `step` per millisecond.
+///
+/// After waiting for each step, the intermediate value is passed to `set`. If `step` is zero, the
+/// final value is set immediately.
+pub(crate) fn ramp_by<D, E>(
+    from: i32,
+    to: i32,
+    step: u32,
+    delay: &mut D,
+    mut set: impl FnMut(i32) -> Result<(), E>,
+) -> Result<(), E>
+where
+    D: DelayNs,
+{
+    if step == 0 {
+        return set(to);
+    }
+    let (mut value, to, step) = (i64::from(from), i64::from(to), i64::from(step));
+    while value != to {
+        delay.delay_ms(1);
+        value = match value < to {
+            true => (value + step).min(to),
+            false => (value - step).max(to),
+        };
+        set(value as i32)?;
+    }
+    Ok(())
+}
+
+/// Get the value of the given step of a linear ramp from `from` to `to`.
+pub(crate) fn ramp_value(from: i32, to: i32, step: u32, steps: u32) -> i32 {
+    let (from, to) = (i64::from(from), i64::from(to));
+    (from + (to - from) * i64::from(step) / i64::from(steps)) as i32
+}
+
+/// Ramping of the duty cycle of any PWM pin
+///
+/// Switching a motor on at full duty cycle results in high inrush currents. Ramping the duty
+/// cycle up slowly (i.e. a soft-start) avoids this. This extension trait implements the ramping
+/// for every [SetDutyCycle] type. This includes the Half-H bridges of this crate, as well as raw
+/// PWM pins of a HAL.
+///
+/// # Examples
+///
+/// ```
+/// use l293x::RampExt;
+///
+/// // [...] create the PWM pin and the delay
+/// // Soft-start within 500ms
+/// pwm.ramp_to(0, pwm.max_duty_cycle(), 500, &mut delay)?;
+/// ```
+pub trait RampExt: SetDutyCycle {
+    /// Ramp the duty cycle linearly from `from` to `to`.
+    ///
+    /// The ramp consists of the given number of steps, each taking one millisecond, which are
+    /// timed using the `delay`. Because the current duty cycle of a [SetDutyCycle] pin cannot be
+    /// read back, the starting duty cycle needs to be given as well. If the number of steps is
+    /// zero, the final duty cycle is set immediately.
+    ///
+    /// # Errors
+    ///
+    /// The error of the pin will be returned, if a duty cycle could not be set. The ramp is
+    /// aborted in this case.
+    fn ramp_to<D: DelayNs>(
+        &mut self,
+        from: u16,
+        to: u16,
+        steps: u32,
+        delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        ramp(from.into(), to.into(), steps, delay, |duty| {
+            self.set_duty_cycle(duty as u16)
+        })
+    }
+
+    /// Ramp the duty cycle linearly from `from` to `target` with the given step size.
+    ///
+    /// Every millisecond, the duty cycle is changed by `step` towards the `target`, until it is
+    /// reached. Thus, the duration of the ramp depends on the distance between the two duty
+    /// cycles. This is useful to limit the rate of change, e.g. to limit the inrush current of a
+    /// motor. If `step` is zero, the target duty cycle is set immediately.
+    ///
+    /// # Errors
+    ///
+    /// The error of the pin will be returned, if a duty cycle could not be set. The ramp is
+    /// aborted in this case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use l293x::RampExt;
+    ///
+    /// // Increase the duty cycle by at most 100 per millisecond
+    /// pwm.ramp_to_duty(0, pwm.max_duty_cycle(), 100, &mut delay)?;
+    /// ```
+    fn ramp_to_duty<D: DelayNs>(
+        &mut self,
+        from: u16,
+        target: u16,
+        step: u16,
+        delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        ramp_by(from.into(), target.into(), step.into(), delay, |duty| {
+            self.set_duty_cycle(duty as u16)
+        })
+    }
+}
+
+impl<P: SetDutyCycle + ?Sized> RampExt for P {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{Delay, PwmPin};
+    use coverage_helper::test;
+
+    #[test]
+    fn test_ramp_to() {
+        let mut pin = PwmPin::new();
+        let mut delay = Delay::new();
+
+        pin.ramp_to(0, 1000, 10, &mut delay).unwrap();
+        assert_eq!(pin.get_duty_cycle(), 1000);
+        assert_eq!(delay.elapsed_ns(), 10_000_000);
+
+        pin.ramp_to(1000, 500, 0, &mut delay).unwrap();
+        assert_eq!(pin.get_duty_cycle(), 500);
+        assert_eq!(delay.elapsed_ns(), 10_000_000);
+    }
+
+    #[test]
+    fn test_ramp_values() {
+        let mut values = [0; 4];
+        let mut index = 0;
+        ramp::<_, ()>(100, -100, 4, &mut Delay::new(), |value| {
+            values[index] = value;
+            index += 1;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(values, [50, 0, -50, -100]);
+    }
+
+    #[test]
+    fn test_ramp_to_duty() {
+        let mut pin = PwmPin::new();
+        let mut delay = Delay::new();
+
+        pin.ramp_to_duty(0, 1000, 300, &mut delay).unwrap();
+        assert_eq!(pin.get_duty_cycle(), 1000);
+        assert_eq!(delay.elapsed_ns(), 4_000_000);
+
+        pin.ramp_to_duty(1000, 100, 1000, &mut delay).unwrap();
+        assert_eq!(pin.get_duty_cycle(), 100);
+        assert_eq!(delay.elapsed_ns(), 5_000_000);
+
+        pin.ramp_to_duty(100, 200, 0, &mut delay).unwrap();
+        assert_eq!(pin.get_duty_cycle(), 200);
+        assert_eq!(delay.elapsed_ns(), 5_000_000);
+    }
+
+    #[test]
+    fn test_ramp_fail() {
+        let mut pin = PwmPin::new();
+        pin.fail();
+        let mut delay = Delay::new();
+
+        assert!(pin.ramp_to(0, 1000, 10, &mut delay).is_err());
+        assert_eq!(delay.elapsed_ns(), 1_000_000);
+    }
+}
@@ -0,0 +1,152 @@
+//! # Servo signals
+//!
+//! Hobby servos and ESCs are controlled by a pulse repeated every PWM period (typically 20ms).
+//! The width of the pulse (typically 1ms - 2ms) defines the angle of the servo or the throttle of
+//! the ESC. This module implements a [Servo], which converts an angle into the duty cycle of such
+//! a pulse on any PWM pin, e.g. a [HalfH](crate::HalfH) output of the [L293](crate::L293x) chip,
+//! whose input is driven by a PWM with the configured period.
+//!
+//! # Examples
+//!
+//! ```
+//! use l293x::servo::Servo;
+//! use l293x::AutoEnable;
+//!
+//! // The input 1 is driven by a 50 Hz PWM, i.e. a period of 20ms
+//! let mut servo = Servo::new(l293x.y1().with_auto_enable(AutoEnable::Always), 20_000);
+//! servo.set_angle(90)?;
+//! ```
+
+use embedded_hal::pwm::SetDutyCycle;
+
+/// The maximum angle of a servo in degrees.
+pub const MAX_ANGLE: u8 = 180;
+
+/// A servo driven by a PWM pin
+///
+/// The angles `0°` - [`180°`](MAX_ANGLE) are mapped linearly to pulse widths of `1000µs` -
+/// `2000µs` by default. Because servos differ in their ranges, the pulse widths can be changed
+/// using [`with_pulse_range()`](Servo::with_pulse_range).
+#[derive(Debug)]
+pub struct Servo<P> {
+    pin: P,
+    period_us: u32,
+    min_pulse_us: u32,
+    max_pulse_us: u32,
+}
+
+impl<P> Servo<P> {
+    /// Create a new servo driven by the `pin`, whose PWM has a period of `period_us`
+    /// microseconds.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the period is zero.
+    #[inline]
+    pub fn new(pin: P, period_us: u32) -> Self {
+        assert!(period_us > 0);
+        Self {
+            pin,
+            period_us,
+            min_pulse_us: 1_000,
+            max_pulse_us: 2_000,
+        }
+    }
+
+    /// Use the given pulse widths in microseconds for the angles `0°` and `180°`.
+    ///
+    /// The pulse widths may be swapped to reverse the direction of the servo. Pulses longer than
+    /// the period are clamped to the period.
+    #[inline]
+    pub fn with_pulse_range(mut self, min_pulse_us: u32, max_pulse_us: u32) -> Self {
+        self.min_pulse_us = min_pulse_us;
+        self.max_pulse_us = max_pulse_us;
+        self
+    }
+
+    /// Get the period of the PWM in microseconds.
+    #[inline]
+    pub fn period_us(&self) -> u32 {
+        self.period_us
+    }
+
+    /// Release the servo and return the pin.
+    #[inline]
+    pub fn release(self) -> P {
+        self.pin
+    }
+}
+
+impl<P: SetDutyCycle> Servo<P> {
+    /// Move the servo to the given angle in degrees.
+    ///
+    /// Angles greater than [MAX_ANGLE] are treated as [MAX_ANGLE].
+    ///
+    /// # Errors
+    ///
+    /// The error of the pin will be returned, if the duty cycle could not be set.
+    pub fn set_angle(&mut self, degrees: u8) -> Result<(), P::Error> {
+        let degrees = i64::from(degrees.min(MAX_ANGLE));
+        let (min, max) = (i64::from(self.min_pulse_us), i64::from(self.max_pulse_us));
+        let pulse_us = min + (max - min) * degrees / i64::from(MAX_ANGLE);
+        // The pulse is between the minimum and maximum pulse widths, which are both `u32`
+        self.set_pulse_us(pulse_us as u32)
+    }
+
+    /// Set the width of the pulse in microseconds.
+    ///
+    /// This allows driving ESCs or servos with a custom mapping. Pulses longer than the period
+    /// are clamped to the period.
+    ///
+    /// # Errors
+    ///
+    /// The error of the pin will be returned, if the duty cycle could not be set.
+    pub fn set_pulse_us(&mut self, pulse_us: u32) -> Result<(), P::Error> {
+        let pulse_us = u64::from(pulse_us.min(self.period_us));
+        let max_duty = u64::from(self.pin.max_duty_cycle());
+        // The pulse is clamped to the period, thus, the duty cycle fits into `u16`
+        let duty = pulse_us * max_duty / u64::from(self.period_us);
+        self.pin.set_duty_cycle(duty as u16)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{DigitalPin, PwmPin};
+    use crate::{AutoEnable, L293x};
+    use coverage_helper::test;
+
+    #[test]
+    fn test_set_angle() {
+        let mut servo = Servo::new(PwmPin::with_max_duty_cycle(20_000), 20_000);
+        assert_eq!(servo.period_us(), 20_000);
+        servo.set_angle(0).unwrap();
+        assert_eq!(servo.pin.get_duty_cycle(), 1_000);
+        servo.set_angle(90).unwrap();
+        assert_eq!(servo.pin.get_duty_cycle(), 1_500);
+        servo.set_angle(255).unwrap();
+        assert_eq!(servo.pin.get_duty_cycle(), 2_000);
+
+        let mut servo = servo.with_pulse_range(2_500, 500);
+        servo.set_angle(45).unwrap();
+        assert_eq!(servo.pin.get_duty_cycle(), 2_000);
+        servo.set_pulse_us(30_000).unwrap();
+        assert_eq!(servo.release().get_duty_cycle(), 20_000);
+    }
+
+    #[test]
+    fn test_half_h() {
+        let l293x = L293x::new(PwmPin::new(), (), (), (), DigitalPin::new(), ());
+        let mut servo = Servo::new(l293x.y1().with_auto_enable(AutoEnable::Always), 20_000);
+        servo.set_angle(180).unwrap();
+        assert!(l293x.y1().is_enabled().unwrap());
+        assert_eq!(l293x.release().0 .0.get_duty_cycle(), u16::MAX / 10);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_period() {
+        Servo::new(PwmPin::new(), 0);
+    }
+}
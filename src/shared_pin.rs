@@ -0,0 +1,312 @@
+//! # Shared pins
+//!
+//! The four outputs of a [L293](crate::L293x) chip share two common enable pins. To use the
+//! outputs independently of each other (e.g. from different tasks or drivers), each of them needs
+//! access to its enable pin. This module provides the [SharedPin] type, which gives multiple
+//! owners access to the same pin.
+//!
+//! A [SharedPin] borrows its pin from a [PinCell]. Because the borrow is not bound to the chip
+//! driver, the outputs can be moved and passed by value freely, as long as the cell lives long
+//! enough. No allocator is required.
+//!
+//! A [PinCell] cannot be shared between interrupt handlers or tasks. If the `critical-section`
+//! feature is enabled, the `CsPinCell` and `CsSharedPin` types are available, which protect each
+//! access to the pin by a critical section. A `CsPinCell` can be placed in a `static`.
+//!
+//! # Examples
+//!
+//! ```
+//! use l293x::L293x;
+//! use l293x::shared_pin::PinCell;
+//!
+//! let mut en12 = PinCell::new();
+//! let mut en34 = PinCell::new();
+//! let l293x = L293x::new(input1, input2, input3, input4, enable12, enable34);
+//! let (y1, y2, y3, y4) = l293x.into_channels(&mut en12, &mut en34);
+//! ```
+use core::cell::RefCell;
+
+use embedded_hal::digital::{ErrorType, OutputPin, PinState, StatefulOutputPin};
+
+/// Storage of a pin, which is shared by multiple [SharedPin]s
+#[derive(Debug)]
+pub struct PinCell<P> {
+    pin: Option<RefCell<P>>,
+}
+
+impl<P> PinCell<P> {
+    /// Create a new, empty cell.
+    #[inline]
+    pub const fn new() -> Self {
+        Self { pin: None }
+    }
+
+    /// Move the pin into the cell and return a [SharedPin] to access it.
+    ///
+    /// The returned pin can be copied as often as needed. Any pin previously stored in the cell
+    /// is dropped.
+    #[inline]
+    pub fn share(&mut self, pin: P) -> SharedPin<'_, P> {
+        SharedPin {
+            pin: self.pin.insert(RefCell::new(pin)),
+        }
+    }
+
+    /// Take the pin out of the cell.
+    ///
+    /// This is possible, once all [SharedPin]s borrowing the cell are dropped. Returns `None`, if
+    /// the cell is empty.
+    #[inline]
+    pub fn take(&mut self) -> Option<P> {
+        self.pin.take().map(RefCell::into_inner)
+    }
+}
+
+impl<P> Default for PinCell<P> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Pin shared by multiple owners
+///
+/// All copies of a shared pin access the same pin stored in a [PinCell]. Setting the state of one
+/// of the copies changes the state of all of them. For more information, please see the
+/// [module documentation](crate::shared_pin).
+#[derive(Debug)]
+pub struct SharedPin<'a, P> {
+    pin: &'a RefCell<P>,
+}
+
+impl<'a, P> Clone for SharedPin<'a, P> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, P> Copy for SharedPin<'a, P> {}
+
+impl<'a, P: ErrorType> ErrorType for SharedPin<'a, P> {
+    type Error = P::Error;
+}
+
+impl<'a, P: OutputPin> OutputPin for SharedPin<'a, P> {
+    #[inline]
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.pin.borrow_mut().set_low()
+    }
+
+    #[inline]
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.pin.borrow_mut().set_high()
+    }
+
+    #[inline]
+    fn set_state(&mut self, state: PinState) -> Result<(), Self::Error> {
+        self.pin.borrow_mut().set_state(state)
+    }
+}
+
+impl<'a, P: StatefulOutputPin> StatefulOutputPin for SharedPin<'a, P> {
+    #[inline]
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        self.pin.borrow_mut().is_set_high()
+    }
+
+    #[inline]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        self.pin.borrow_mut().is_set_low()
+    }
+
+    #[inline]
+    fn toggle(&mut self) -> Result<(), Self::Error> {
+        self.pin.borrow_mut().toggle()
+    }
+}
+
+/// Interrupt-safe storage of a pin, which is shared by multiple [CsSharedPin]s
+///
+/// In contrast to the [PinCell], this cell is protected by a
+/// [critical section](https://docs.rs/critical-section). Thus, it can be placed in a `static` and
+/// the [CsSharedPin]s borrowing it can be used from interrupt handlers and different tasks (e.g.
+/// RTIC or embassy tasks).
+///
+/// This type is only available, if the `critical-section` feature is enabled.
+///
+/// # Examples
+///
+/// ```
+/// use l293x::shared_pin::CsPinCell;
+///
+/// static ENABLE12: CsPinCell<Enable12Pin> = CsPinCell::new();
+///
+/// let (y1, y2, y3, y4) = l293x.into_cs_channels(&ENABLE12, &ENABLE34);
+/// ```
+#[cfg(feature = "critical-section")]
+#[derive(Debug)]
+pub struct CsPinCell<P> {
+    pin: critical_section::Mutex<RefCell<Option<P>>>,
+}
+
+#[cfg(feature = "critical-section")]
+impl<P> CsPinCell<P> {
+    /// Create a new, empty cell.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            pin: critical_section::Mutex::new(RefCell::new(None)),
+        }
+    }
+
+    /// Move the pin into the cell and return a [CsSharedPin] to access it.
+    ///
+    /// The returned pin can be copied as often as needed. Any pin previously stored in the cell
+    /// is dropped.
+    pub fn share(&self, pin: P) -> CsSharedPin<'_, P> {
+        critical_section::with(|cs| self.pin.borrow_ref_mut(cs).replace(pin));
+        CsSharedPin { cell: self }
+    }
+
+    /// Take the pin out of the cell.
+    ///
+    /// Returns `None`, if the cell is empty. Accessing a [CsSharedPin] of the cell afterwards
+    /// panics.
+    pub fn take(&self) -> Option<P> {
+        critical_section::with(|cs| self.pin.borrow_ref_mut(cs).take())
+    }
+
+    /// Run the given function with the pin stored in the cell within a critical section.
+    fn with<R>(&self, f: impl FnOnce(&mut P) -> R) -> R {
+        critical_section::with(|cs| {
+            let mut pin = self.pin.borrow_ref_mut(cs);
+            f(pin.as_mut().expect("shared pin taken out of its cell"))
+        })
+    }
+}
+
+#[cfg(feature = "critical-section")]
+impl<P> Default for CsPinCell<P> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Interrupt-safe pin shared by multiple owners
+///
+/// All copies of the pin access the same pin stored in a [CsPinCell]. Each access is done within
+/// a critical section. Thus, the copies can be used from interrupt handlers and different tasks.
+///
+/// This type is only available, if the `critical-section` feature is enabled.
+#[cfg(feature = "critical-section")]
+#[derive(Debug)]
+pub struct CsSharedPin<'a, P> {
+    cell: &'a CsPinCell<P>,
+}
+
+#[cfg(feature = "critical-section")]
+impl<'a, P> Clone for CsSharedPin<'a, P> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+#[cfg(feature = "critical-section")]
+impl<'a, P> Copy for CsSharedPin<'a, P> {}
+
+#[cfg(feature = "critical-section")]
+impl<'a, P: ErrorType> ErrorType for CsSharedPin<'a, P> {
+    type Error = P::Error;
+}
+
+#[cfg(feature = "critical-section")]
+impl<'a, P: OutputPin> OutputPin for CsSharedPin<'a, P> {
+    #[inline]
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.cell.with(P::set_low)
+    }
+
+    #[inline]
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.cell.with(P::set_high)
+    }
+
+    #[inline]
+    fn set_state(&mut self, state: PinState) -> Result<(), Self::Error> {
+        self.cell.with(|pin| pin.set_state(state))
+    }
+}
+
+#[cfg(feature = "critical-section")]
+impl<'a, P: StatefulOutputPin> StatefulOutputPin for CsSharedPin<'a, P> {
+    #[inline]
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        self.cell.with(P::is_set_high)
+    }
+
+    #[inline]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        self.cell.with(P::is_set_low)
+    }
+
+    #[inline]
+    fn toggle(&mut self) -> Result<(), Self::Error> {
+        self.cell.with(P::toggle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::DigitalPin;
+    use coverage_helper::test;
+
+    #[test]
+    fn test_shared_pin() {
+        let mut cell = PinCell::default();
+        let mut first = cell.share(DigitalPin::new());
+        let mut second = first;
+
+        first.set_high().unwrap();
+        assert!(second.is_set_high().unwrap());
+        second.toggle().unwrap();
+        assert!(first.is_set_low().unwrap());
+        second.set_state(PinState::High).unwrap();
+
+        let mut pin = cell.take().unwrap();
+        assert!(pin.is_set_high().unwrap());
+        assert!(cell.take().is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "critical-section")]
+    fn test_cs_shared_pin() {
+        static CELL: CsPinCell<DigitalPin> = CsPinCell::new();
+        let mut first = CELL.share(DigitalPin::new());
+        let mut second = first;
+
+        first.set_high().unwrap();
+        assert!(second.is_set_high().unwrap());
+        second.toggle().unwrap();
+        assert!(first.is_set_low().unwrap());
+        second.set_state(PinState::High).unwrap();
+        first.set_low().unwrap();
+
+        let mut pin = CELL.take().unwrap();
+        assert!(pin.is_set_low().unwrap());
+        assert!(CsPinCell::<DigitalPin>::default().take().is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "critical-section")]
+    #[should_panic]
+    fn test_cs_shared_pin_taken() {
+        let cell = CsPinCell::new();
+        let mut pin = cell.share(DigitalPin::new());
+        cell.take();
+        let _ = pin.set_high();
+    }
+}
@@ -0,0 +1,195 @@
+//! # Presets for common motor driver boards
+//!
+//! This module contains presets for boards, which wire the [L293](crate::L293x) chips in a fixed
+//! way. The presets create the drivers for the chips and return ready-made [FullH] handles for
+//! the motor outputs of the board.
+
+use embedded_hal::digital::OutputPin;
+
+use crate::shift_register::{ShiftRegister, ShiftRegisterPin};
+use crate::{AutoEnable, FullH, HalfH, L293x};
+
+/// The shift register outputs of the direction inputs of the motors `M1` - `M4` (`A`, `B`).
+const MOTOR_OUTPUTS: [(u8, u8); 4] = [(2, 3), (1, 4), (5, 7), (0, 6)];
+
+/// A motor handle of the [AdafruitMotorShieldV1]
+pub type ShieldMotor<'a, 'r, DATA, CLK, LATCH, EN> = FullH<
+    HalfH<'a, ShiftRegisterPin<'r, DATA, CLK, LATCH>, EN>,
+    HalfH<'a, ShiftRegisterPin<'r, DATA, CLK, LATCH>, EN>,
+>;
+
+/// The chip type used on the [AdafruitMotorShieldV1]
+type ShieldChip<'r, DATA, CLK, LATCH, ENA, ENB> = L293x<
+    ShiftRegisterPin<'r, DATA, CLK, LATCH>,
+    ShiftRegisterPin<'r, DATA, CLK, LATCH>,
+    ShiftRegisterPin<'r, DATA, CLK, LATCH>,
+    ShiftRegisterPin<'r, DATA, CLK, LATCH>,
+    ENA,
+    ENB,
+>;
+
+/// Adafruit Motor Shield V1
+///
+/// This Arduino shield drives four DC motors (`M1` - `M4`) using two L293D chips. The direction
+/// inputs of the chips are driven by a 74HC595 [ShiftRegister], while each motor has its own enable
+/// pin, which is connected to a PWM capable pin of the Arduino:
+///
+/// | Arduino pin | Function                                  |
+/// |-------------|-------------------------------------------|
+/// | D4          | Shift register clock                      |
+/// | D7          | Shift register output enable (active low) |
+/// | D8          | Shift register data                       |
+/// | D12         | Shift register latch                      |
+/// | D11         | Enable of `M1`                            |
+/// | D3          | Enable of `M2`                            |
+/// | D6          | Enable of `M3`                            |
+/// | D5          | Enable of `M4`                            |
+///
+/// The shift register is created by the user and borrowed by the shield. The motors are accessed
+/// using the [`m1()`](AdafruitMotorShieldV1::m1) - [`m4()`](AdafruitMotorShieldV1::m4) methods.
+///
+/// # Examples
+///
+/// ```
+/// use l293x::shields::AdafruitMotorShieldV1;
+/// use l293x::shift_register::ShiftRegister;
+///
+/// // [...] create the pins of the Arduino
+/// let register = ShiftRegister::new(d8, d4, d12);
+/// let shield = AdafruitMotorShieldV1::new(&register, d7, d11, d3, d6, d5)?;
+///
+/// let mut m1 = shield.m1();
+/// m1.forward()?;
+/// ```
+#[derive(Debug)]
+pub struct AdafruitMotorShieldV1<'r, DATA, CLK, LATCH, OE, EN1, EN2, EN3, EN4> {
+    output_enable: OE,
+    chip1: ShieldChip<'r, DATA, CLK, LATCH, EN1, EN2>,
+    chip2: ShieldChip<'r, DATA, CLK, LATCH, EN3, EN4>,
+}
+
+impl<'r, DATA, CLK, LATCH, OE, EN1, EN2, EN3, EN4>
+    AdafruitMotorShieldV1<'r, DATA, CLK, LATCH, OE, EN1, EN2, EN3, EN4>
+where
+    DATA: OutputPin,
+    CLK: OutputPin<Error = DATA::Error>,
+    LATCH: OutputPin<Error = DATA::Error>,
+    OE: OutputPin<Error = DATA::Error>,
+{
+    /// Create the preset for the shield.
+    ///
+    /// This takes the shift register, its output enable pin and the enable pins of the four
+    /// motors. All direction inputs are set low, before the outputs of the shift register are
+    /// enabled.
+    ///
+    /// # Errors
+    ///
+    /// The error of the first shift register pin, which could not be set, will be returned.
+    pub fn new(
+        register: &'r ShiftRegister<DATA, CLK, LATCH>,
+        mut output_enable: OE,
+        en1: EN1,
+        en2: EN2,
+        en3: EN3,
+        en4: EN4,
+    ) -> Result<Self, DATA::Error> {
+        register.write(0)?;
+        output_enable.set_low()?;
+        let pins = MOTOR_OUTPUTS.map(|(a, b)| (register.pin(a), register.pin(b)));
+        Ok(Self {
+            output_enable,
+            chip1: L293x::new(pins[0].0, pins[0].1, pins[1].0, pins[1].1, en1, en2),
+            chip2: L293x::new(pins[2].0, pins[2].1, pins[3].0, pins[3].1, en3, en4),
+        })
+    }
+
+    /// Disable the outputs of the shift register.
+    ///
+    /// This floats the direction inputs of all motors. To stop the motors, disable them instead.
+    ///
+    /// # Errors
+    ///
+    /// The error of the output enable pin will be returned.
+    pub fn disable_shift_register(&mut self) -> Result<(), DATA::Error> {
+        self.output_enable.set_high()
+    }
+}
+
+impl<'r, DATA, CLK, LATCH, OE, EN1, EN2, EN3, EN4>
+    AdafruitMotorShieldV1<'r, DATA, CLK, LATCH, OE, EN1, EN2, EN3, EN4>
+{
+    /// Get the motor `M1`.
+    #[inline]
+    pub fn m1(&self) -> ShieldMotor<'_, 'r, DATA, CLK, LATCH, EN1> {
+        FullH::new(
+            self.chip1.y1().with_auto_enable(AutoEnable::Always),
+            self.chip1.y2().with_auto_enable(AutoEnable::Always),
+        )
+    }
+
+    /// Get the motor `M2`.
+    #[inline]
+    pub fn m2(&self) -> ShieldMotor<'_, 'r, DATA, CLK, LATCH, EN2> {
+        FullH::new(
+            self.chip1.y3().with_auto_enable(AutoEnable::Always),
+            self.chip1.y4().with_auto_enable(AutoEnable::Always),
+        )
+    }
+
+    /// Get the motor `M3`.
+    #[inline]
+    pub fn m3(&self) -> ShieldMotor<'_, 'r, DATA, CLK, LATCH, EN3> {
+        FullH::new(
+            self.chip2.y1().with_auto_enable(AutoEnable::Always),
+            self.chip2.y2().with_auto_enable(AutoEnable::Always),
+        )
+    }
+
+    /// Get the motor `M4`.
+    #[inline]
+    pub fn m4(&self) -> ShieldMotor<'_, 'r, DATA, CLK, LATCH, EN4> {
+        FullH::new(
+            self.chip2.y3().with_auto_enable(AutoEnable::Always),
+            self.chip2.y4().with_auto_enable(AutoEnable::Always),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shift_register::tests::{register, Chip};
+    use crate::test_util::DigitalPin;
+    use coverage_helper::test;
+
+    #[test]
+    fn test_motors() {
+        let chip = Chip::default();
+        let register = register(&chip);
+        register.write(0xff).unwrap();
+        let mut shield = AdafruitMotorShieldV1::new(
+            &register,
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        )
+        .unwrap();
+        assert_eq!(chip.outputs.get(), 0);
+
+        shield.m1().forward().unwrap();
+        assert_eq!(chip.outputs.get(), 0b0000_0100);
+        shield.m2().reverse().unwrap();
+        assert_eq!(chip.outputs.get(), 0b0001_0100);
+        shield.m3().forward().unwrap();
+        assert_eq!(chip.outputs.get(), 0b0011_0100);
+        shield.m4().reverse().unwrap();
+        assert_eq!(chip.outputs.get(), 0b0111_0100);
+        shield.m1().reverse().unwrap();
+        assert_eq!(chip.outputs.get(), 0b0111_1000);
+
+        assert!(shield.m3().coast().is_ok());
+        assert!(shield.disable_shift_register().is_ok());
+    }
+}
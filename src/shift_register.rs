@@ -0,0 +1,308 @@
+//! # 74HC595 shift register adapter
+//!
+//! Some boards do not connect the input pins of the [L293](crate::L293x) chip directly to the
+//! microcontroller, but drive them using a 74HC595 serial-in, parallel-out shift register. This
+//! saves pins of the microcontroller, because all eight outputs of the shift register are
+//! controlled using only three pins (data, clock and latch).
+//!
+//! The [ShiftRegister] implemented in this module provides a [ShiftRegisterPin] for each of its
+//! outputs. These implement the [OutputPin] and [StatefulOutputPin] traits, and thus, can be used
+//! as input pins of the drivers of this crate. Each change of a pin shifts out the state of all
+//! outputs.
+//!
+//! # Examples
+//!
+//! ```
+//! use l293x::shift_register::ShiftRegister;
+//! use l293x::L293x;
+//!
+//! // [...] create the data, clock and latch pins
+//! let register = ShiftRegister::new(data, clock, latch);
+//! let l293x = L293x::new(
+//!     register.pin(0),
+//!     register.pin(1),
+//!     register.pin(2),
+//!     register.pin(3),
+//!     enable12,
+//!     enable34,
+//! );
+//! ```
+
+use core::cell::RefCell;
+
+use embedded_hal::digital::{ErrorType, OutputPin, StatefulOutputPin};
+
+/// Shift out the given byte MSB first, without latching it.
+pub(crate) fn shift_out<DATA, CLK>(
+    data: &mut DATA,
+    clock: &mut CLK,
+    byte: u8,
+) -> Result<(), DATA::Error>
+where
+    DATA: OutputPin,
+    CLK: OutputPin<Error = DATA::Error>,
+{
+    for bit in (0..8).rev() {
+        clock.set_low()?;
+        data.set_state((byte & 1 << bit != 0).into())?;
+        clock.set_high()?;
+    }
+    Ok(())
+}
+
+/// The pins and the output state of the shift register
+#[derive(Debug)]
+struct Inner<DATA, CLK, LATCH> {
+    data: DATA,
+    clock: CLK,
+    latch: LATCH,
+    state: u8,
+}
+
+/// 74HC595 shift register
+///
+/// The register stores the state of its eight outputs. The outputs are accessed using the
+/// [`pin()`](ShiftRegister::pin) method. The state is shifted out MSB first (i.e. output `7`
+/// first), whenever one of the pins changes.
+#[derive(Debug)]
+pub struct ShiftRegister<DATA, CLK, LATCH> {
+    inner: RefCell<Inner<DATA, CLK, LATCH>>,
+}
+
+impl<DATA, CLK, LATCH> ShiftRegister<DATA, CLK, LATCH> {
+    /// Create a new shift register driver from its data, clock and latch pins.
+    ///
+    /// All outputs are assumed to be low. Use [`write()`](ShiftRegister::write) to shift out the
+    /// initial state, if the state of the register is unknown.
+    #[inline]
+    pub fn new(data: DATA, clock: CLK, latch: LATCH) -> Self {
+        Self {
+            inner: RefCell::new(Inner {
+                data,
+                clock,
+                latch,
+                state: 0,
+            }),
+        }
+    }
+
+    /// Get the pin of the given output (`0` - `7`).
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the output is greater than `7`.
+    #[inline]
+    pub fn pin(&self, output: u8) -> ShiftRegisterPin<'_, DATA, CLK, LATCH> {
+        assert!(output < 8);
+        ShiftRegisterPin {
+            register: self,
+            mask: 1 << output,
+        }
+    }
+
+    /// Get the current state of all outputs.
+    ///
+    /// Bit `n` of the returned value is the state of the output `n`.
+    #[inline]
+    pub fn state(&self) -> u8 {
+        self.inner.borrow().state
+    }
+
+    /// Release the shift register and return the data, clock and latch pins.
+    #[inline]
+    pub fn release(self) -> (DATA, CLK, LATCH) {
+        let inner = self.inner.into_inner();
+        (inner.data, inner.clock, inner.latch)
+    }
+}
+
+impl<DATA, CLK, LATCH> ShiftRegister<DATA, CLK, LATCH>
+where
+    DATA: OutputPin,
+    CLK: OutputPin<Error = DATA::Error>,
+    LATCH: OutputPin<Error = DATA::Error>,
+{
+    /// Set the state of all outputs and shift it out.
+    ///
+    /// Bit `n` of the given value is the state of the output `n`.
+    ///
+    /// # Errors
+    ///
+    /// The error of the first pin, which could not be set, will be returned. The state of the
+    /// outputs is undefined in this case.
+    pub fn write(&self, state: u8) -> Result<(), DATA::Error> {
+        let mut inner = self.inner.borrow_mut();
+        inner.state = state;
+        let inner = &mut *inner;
+        inner.latch.set_low()?;
+        shift_out(&mut inner.data, &mut inner.clock, state)?;
+        inner.latch.set_high()
+    }
+
+    /// Change the outputs selected by the `mask` and shift out the new state.
+    fn update(&self, mask: u8, high: bool) -> Result<(), DATA::Error> {
+        let state = self.state();
+        self.write(match high {
+            true => state | mask,
+            false => state & !mask,
+        })
+    }
+}
+
+/// A single output of a [ShiftRegister]
+///
+/// Setting the pin shifts out the state of all outputs of the register.
+#[derive(Debug)]
+pub struct ShiftRegisterPin<'a, DATA, CLK, LATCH> {
+    register: &'a ShiftRegister<DATA, CLK, LATCH>,
+    mask: u8,
+}
+
+impl<DATA, CLK, LATCH> Clone for ShiftRegisterPin<'_, DATA, CLK, LATCH> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<DATA, CLK, LATCH> Copy for ShiftRegisterPin<'_, DATA, CLK, LATCH> {}
+
+impl<DATA, CLK, LATCH> ErrorType for ShiftRegisterPin<'_, DATA, CLK, LATCH>
+where
+    DATA: OutputPin,
+{
+    type Error = DATA::Error;
+}
+
+impl<DATA, CLK, LATCH> OutputPin for ShiftRegisterPin<'_, DATA, CLK, LATCH>
+where
+    DATA: OutputPin,
+    CLK: OutputPin<Error = DATA::Error>,
+    LATCH: OutputPin<Error = DATA::Error>,
+{
+    #[inline]
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.register.update(self.mask, false)
+    }
+
+    #[inline]
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.register.update(self.mask, true)
+    }
+}
+
+impl<DATA, CLK, LATCH> StatefulOutputPin for ShiftRegisterPin<'_, DATA, CLK, LATCH>
+where
+    DATA: OutputPin,
+    CLK: OutputPin<Error = DATA::Error>,
+    LATCH: OutputPin<Error = DATA::Error>,
+{
+    #[inline]
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.register.state() & self.mask != 0)
+    }
+
+    #[inline]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.register.state() & self.mask == 0)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::test_util::{DigitalError, DigitalPin};
+    use core::cell::Cell;
+    use coverage_helper::test;
+
+    /// Simulated 74HC595 chip
+    #[derive(Debug, Default)]
+    pub(crate) struct Chip {
+        data: Cell<bool>,
+        shift: Cell<u8>,
+        pub(crate) outputs: Cell<u8>,
+    }
+
+    /// A pin connected to the simulated chip
+    #[derive(Debug)]
+    pub(crate) enum ChipPin<'a> {
+        Data(&'a Chip),
+        Clock(&'a Chip),
+        Latch(&'a Chip),
+    }
+
+    impl ErrorType for ChipPin<'_> {
+        type Error = DigitalError;
+    }
+
+    impl OutputPin for ChipPin<'_> {
+        fn set_low(&mut self) -> Result<(), Self::Error> {
+            if let ChipPin::Data(chip) = self {
+                chip.data.set(false);
+            }
+            Ok(())
+        }
+
+        fn set_high(&mut self) -> Result<(), Self::Error> {
+            match self {
+                ChipPin::Data(chip) => chip.data.set(true),
+                ChipPin::Clock(chip) => chip
+                    .shift
+                    .set(chip.shift.get() << 1 | chip.data.get() as u8),
+                ChipPin::Latch(chip) => chip.outputs.set(chip.shift.get()),
+            }
+            Ok(())
+        }
+    }
+
+    pub(crate) fn register(chip: &Chip) -> ShiftRegister<ChipPin<'_>, ChipPin<'_>, ChipPin<'_>> {
+        ShiftRegister::new(
+            ChipPin::Data(chip),
+            ChipPin::Clock(chip),
+            ChipPin::Latch(chip),
+        )
+    }
+
+    #[test]
+    fn test_pins() {
+        let chip = Chip::default();
+        let register = register(&chip);
+
+        register.pin(0).set_high().unwrap();
+        register.pin(7).set_high().unwrap();
+        assert_eq!(chip.outputs.get(), 0b1000_0001);
+        assert!(register.pin(7).is_set_high().unwrap());
+        assert!(register.pin(1).is_set_low().unwrap());
+
+        register.pin(0).set_low().unwrap();
+        assert_eq!(chip.outputs.get(), 0b1000_0000);
+        assert_eq!(register.state(), 0b1000_0000);
+        assert!(register.pin(0).clone().is_set_low().unwrap());
+    }
+
+    #[test]
+    fn test_write() {
+        let chip = Chip::default();
+        let register = register(&chip);
+
+        register.write(0b0101_1010).unwrap();
+        assert_eq!(chip.outputs.get(), 0b0101_1010);
+        let (data, _, _) = register.release();
+        assert!(matches!(data, ChipPin::Data(..)));
+    }
+
+    #[test]
+    fn test_write_fail() {
+        let mut latch = DigitalPin::new();
+        latch.fail();
+        let register = ShiftRegister::new(DigitalPin::new(), DigitalPin::new(), latch);
+
+        assert!(register.pin(3).set_high().is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_pin() {
+        let register = ShiftRegister::new((), (), ());
+        register.pin(8);
+    }
+}
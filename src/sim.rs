@@ -0,0 +1,525 @@
+//! # Simulation of the L293 chip
+//!
+//! This module implements a [SimulatedL293x], which allows testing applications on the host,
+//! without the real hardware. The simulated chip provides pins, which can be passed to the
+//! [L293x](crate::L293x) driver, and allows checking the resulting levels of the outputs.
+//!
+//! Additionally, faults of the real chip can be injected, to test the detection and recovery
+//! logic of the application:
+//!
+//! - [Thermal shutdown](SimulatedL293x::set_thermal_shutdown): The real chip silently disables
+//!   all outputs, if it overheats due to a sustained load.
+//!
+//! To integration-test higher-level logic (e.g. of a robot), a [MotorLoad] can be connected to
+//! each pair of outputs. The simulated motors accelerate, brake and coast depending on the levels
+//! of their outputs, and their speeds and positions can be checked after each
+//! [tick](SimulatedL293x::tick).
+//!
+//! # Examples
+//!
+//! ```
+//! use l293x::sim::SimulatedL293x;
+//! use l293x::{L293x, OutputLevel};
+//!
+//! let sim = SimulatedL293x::new();
+//! let l293x = L293x::new(sim.a1(), sim.a2(), sim.a3(), sim.a4(), sim.en12(), sim.en34());
+//!
+//! l293x.y1().enable()?;
+//! l293x.y1().set_high()?;
+//! assert_eq!(sim.y1(), OutputLevel::High);
+//! assert_eq!(sim.y3(), OutputLevel::HighZ);
+//! ```
+
+use core::cell::Cell;
+use core::convert::Infallible;
+
+use embedded_hal::digital::{ErrorType, OutputPin, StatefulOutputPin};
+
+use crate::{resolve_output, OutputLevel};
+
+/// Configuration of the simulated thermal shutdown
+///
+/// All durations are given in [ticks](SimulatedL293x::tick) of the simulation.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ThermalShutdown {
+    /// The number of consecutive ticks under load, after which the chip shuts down.
+    pub trip_after: u32,
+    /// The number of consecutive ticks without load, after which the chip recovers.
+    pub recover_after: u32,
+}
+
+/// Configuration of a simulated DC motor connected to a pair of outputs
+///
+/// The motor is modeled as a first-order lag: In every [tick](SimulatedL293x::tick), its speed
+/// approaches the target speed by the fraction `1 / time_constant` of the remaining difference.
+/// The target speed depends on the levels of the outputs:
+///
+/// | First output | Second output | Target speed   | Behavior              |
+/// |--------------|---------------|----------------|-----------------------|
+/// | High         | Low           | `max_speed`    | Drive forward         |
+/// | Low          | High          | `-max_speed`   | Drive in reverse      |
+/// | High / Low   | Same level    | `0`            | Brake                 |
+/// | HighZ        | Any           | `0`            | Coast (slow decay)    |
+///
+/// Because the speed is averaged over the ticks, toggling the inputs between the ticks (e.g.
+/// using a software PWM) results in a proportional speed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MotorLoad {
+    /// The speed of the motor in position steps per tick, if it is driven at full voltage.
+    pub max_speed: i32,
+    /// The number of ticks, after which the speed approached the target speed, while the motor is
+    /// driven or braked.
+    pub time_constant: u32,
+    /// The number of ticks, after which the speed approached zero, while the motor coasts.
+    pub coast_time_constant: u32,
+}
+
+#[derive(Debug, Default)]
+struct Motor {
+    load: Option<MotorLoad>,
+    speed: Cell<i32>,
+    position: Cell<i64>,
+}
+
+impl Motor {
+    fn set_load(&mut self, load: Option<MotorLoad>) {
+        self.load = load;
+        self.speed.set(0);
+        self.position.set(0);
+    }
+
+    fn tick(&self, a: OutputLevel, b: OutputLevel) {
+        let load = match self.load {
+            Some(load) => load,
+            None => return,
+        };
+        let (target, time_constant) = match (a, b) {
+            (OutputLevel::HighZ, _) | (_, OutputLevel::HighZ) => (0, load.coast_time_constant),
+            (OutputLevel::High, OutputLevel::Low) => (load.max_speed, load.time_constant),
+            (OutputLevel::Low, OutputLevel::High) => {
+                (load.max_speed.saturating_neg(), load.time_constant)
+            }
+            _ => (0, load.time_constant),
+        };
+        let speed = i64::from(self.speed.get());
+        let difference = i64::from(target) - speed;
+        let step = match difference / i64::from(time_constant.max(1)) {
+            0 => difference.signum(),
+            step => step,
+        };
+        let speed = speed + step;
+        // The speed is always between the previous speed and the target, which are both `i32`
+        self.speed.set(speed as i32);
+        self.position.set(self.position.get().saturating_add(speed));
+    }
+}
+
+/// A pin of the [SimulatedL293x]
+///
+/// The pin never fails. Its state is stored in the simulated chip, which it borrows.
+#[derive(Debug, Copy, Clone)]
+pub struct SimPin<'a> {
+    state: &'a Cell<bool>,
+}
+
+impl ErrorType for SimPin<'_> {
+    type Error = Infallible;
+}
+
+impl OutputPin for SimPin<'_> {
+    #[inline]
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.state.set(false);
+        Ok(())
+    }
+
+    #[inline]
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.state.set(true);
+        Ok(())
+    }
+}
+
+impl StatefulOutputPin for SimPin<'_> {
+    #[inline]
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.state.get())
+    }
+
+    #[inline]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(!self.state.get())
+    }
+}
+
+/// Simulated L293 or L293D chip
+///
+/// The simulated chip models the truth table of the datasheet (see [resolve_output]): An enabled
+/// output follows its input, while a disabled output is in the high impedance mode. The states of
+/// the pins are changed using the [SimPin]s returned by the [`a1()`](SimulatedL293x::a1) -
+/// [`en34()`](SimulatedL293x::en34) methods. The resulting levels of the outputs can be checked
+/// using the [`y1()`](SimulatedL293x::y1) - [`y4()`](SimulatedL293x::y4) methods.
+///
+/// Time-dependent behavior (e.g. the thermal shutdown or the [motors](MotorLoad)) is advanced
+/// explicitly by calling [`tick()`](SimulatedL293x::tick).
+#[derive(Debug, Default)]
+pub struct SimulatedL293x {
+    inputs: [Cell<bool>; 4],
+    enables: [Cell<bool>; 2],
+    thermal_shutdown: Option<ThermalShutdown>,
+    load_ticks: Cell<u32>,
+    idle_ticks: Cell<u32>,
+    shut_down: Cell<bool>,
+    motors: [Motor; 2],
+}
+
+impl SimulatedL293x {
+    /// Create a new simulated chip with all pins low.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the simulated input pin 1.
+    #[inline]
+    pub fn a1(&self) -> SimPin<'_> {
+        SimPin {
+            state: &self.inputs[0],
+        }
+    }
+
+    /// Get the simulated input pin 2.
+    #[inline]
+    pub fn a2(&self) -> SimPin<'_> {
+        SimPin {
+            state: &self.inputs[1],
+        }
+    }
+
+    /// Get the simulated input pin 3.
+    #[inline]
+    pub fn a3(&self) -> SimPin<'_> {
+        SimPin {
+            state: &self.inputs[2],
+        }
+    }
+
+    /// Get the simulated input pin 4.
+    #[inline]
+    pub fn a4(&self) -> SimPin<'_> {
+        SimPin {
+            state: &self.inputs[3],
+        }
+    }
+
+    /// Get the simulated enable pin of the outputs 1 & 2.
+    #[inline]
+    pub fn en12(&self) -> SimPin<'_> {
+        SimPin {
+            state: &self.enables[0],
+        }
+    }
+
+    /// Get the simulated enable pin of the outputs 3 & 4.
+    #[inline]
+    pub fn en34(&self) -> SimPin<'_> {
+        SimPin {
+            state: &self.enables[1],
+        }
+    }
+
+    /// Get the level of the output 1.
+    #[inline]
+    pub fn y1(&self) -> OutputLevel {
+        self.output(0)
+    }
+
+    /// Get the level of the output 2.
+    #[inline]
+    pub fn y2(&self) -> OutputLevel {
+        self.output(1)
+    }
+
+    /// Get the level of the output 3.
+    #[inline]
+    pub fn y3(&self) -> OutputLevel {
+        self.output(2)
+    }
+
+    /// Get the level of the output 4.
+    #[inline]
+    pub fn y4(&self) -> OutputLevel {
+        self.output(3)
+    }
+
+    /// Inject thermal shutdowns after a sustained load.
+    ///
+    /// The chip is considered to be under load, as long as at least one of the pairs of outputs
+    /// is enabled and drives its outputs to different levels (i.e. a current flows through a
+    /// load connected between them). After the load was sustained for the configured number of
+    /// ticks, the chip shuts down: all outputs silently go into the high impedance mode, while
+    /// the pins still report their commanded states. Once the chip was without load for the
+    /// configured number of ticks, it recovers and the outputs follow their inputs again.
+    ///
+    /// Passing `None` disables the injection and recovers the chip immediately.
+    pub fn set_thermal_shutdown(&mut self, thermal_shutdown: Option<ThermalShutdown>) {
+        self.thermal_shutdown = thermal_shutdown;
+        self.load_ticks.set(0);
+        self.idle_ticks.set(0);
+        self.shut_down.set(false);
+    }
+
+    /// Check whether the chip is currently in thermal shutdown.
+    #[inline]
+    pub fn is_thermal_shutdown(&self) -> bool {
+        self.shut_down.get()
+    }
+
+    /// Connect a simulated motor to the outputs 1 & 2.
+    ///
+    /// The motor starts at standstill in position `0`. Passing `None` disconnects the motor.
+    #[inline]
+    pub fn set_motor12(&mut self, load: Option<MotorLoad>) {
+        self.motors[0].set_load(load);
+    }
+
+    /// Connect a simulated motor to the outputs 3 & 4.
+    ///
+    /// The motor starts at standstill in position `0`. Passing `None` disconnects the motor.
+    #[inline]
+    pub fn set_motor34(&mut self, load: Option<MotorLoad>) {
+        self.motors[1].set_load(load);
+    }
+
+    /// Get the speed of the motor connected to the outputs 1 & 2 in position steps per tick.
+    ///
+    /// Positive speeds mean, that the output 1 drives the motor forward.
+    #[inline]
+    pub fn motor12_speed(&self) -> i32 {
+        self.motors[0].speed.get()
+    }
+
+    /// Get the speed of the motor connected to the outputs 3 & 4 in position steps per tick.
+    ///
+    /// Positive speeds mean, that the output 3 drives the motor forward.
+    #[inline]
+    pub fn motor34_speed(&self) -> i32 {
+        self.motors[1].speed.get()
+    }
+
+    /// Get the position of the motor connected to the outputs 1 & 2, i.e. the sum of its speeds.
+    #[inline]
+    pub fn motor12_position(&self) -> i64 {
+        self.motors[0].position.get()
+    }
+
+    /// Get the position of the motor connected to the outputs 3 & 4, i.e. the sum of its speeds.
+    #[inline]
+    pub fn motor34_position(&self) -> i64 {
+        self.motors[1].position.get()
+    }
+
+    /// Advance the simulation by one tick.
+    ///
+    /// The motors are advanced using the output levels before the thermal shutdown is updated.
+    pub fn tick(&self) {
+        self.motors[0].tick(self.output(0), self.output(1));
+        self.motors[1].tick(self.output(2), self.output(3));
+        self.tick_thermal_shutdown();
+    }
+
+    fn tick_thermal_shutdown(&self) {
+        let config = match self.thermal_shutdown {
+            Some(config) => config,
+            None => return,
+        };
+        if self.is_loaded() {
+            self.idle_ticks.set(0);
+            self.load_ticks.set(self.load_ticks.get().saturating_add(1));
+            if self.load_ticks.get() >= config.trip_after {
+                self.shut_down.set(true);
+            }
+        } else {
+            self.load_ticks.set(0);
+            self.idle_ticks.set(self.idle_ticks.get().saturating_add(1));
+            if self.idle_ticks.get() >= config.recover_after {
+                self.shut_down.set(false);
+            }
+        }
+    }
+
+    fn output(&self, index: usize) -> OutputLevel {
+        let enable = self.enables[index / 2].get() && !self.shut_down.get();
+        resolve_output(self.inputs[index].get(), enable)
+    }
+
+    fn is_loaded(&self) -> bool {
+        let pair_loaded = |first: usize| {
+            let a = self.output(first);
+            let b = self.output(first + 1);
+            a != OutputLevel::HighZ && b != OutputLevel::HighZ && a != b
+        };
+        pair_loaded(0) || pair_loaded(2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::L293x;
+    use coverage_helper::test;
+
+    #[test]
+    fn test_truth_table() {
+        let sim = SimulatedL293x::new();
+        let l293x = L293x::new(
+            sim.a1(),
+            sim.a2(),
+            sim.a3(),
+            sim.a4(),
+            sim.en12(),
+            sim.en34(),
+        );
+
+        assert_eq!(sim.y1(), OutputLevel::HighZ);
+        l293x.y1().enable().unwrap();
+        l293x.y1().set_high().unwrap();
+        assert_eq!(sim.y1(), OutputLevel::High);
+        assert_eq!(sim.y2(), OutputLevel::Low);
+        assert_eq!(sim.y3(), OutputLevel::HighZ);
+
+        l293x.y4().enable().unwrap();
+        l293x.y4().set_high().unwrap();
+        assert_eq!(sim.y3(), OutputLevel::Low);
+        assert_eq!(sim.y4(), OutputLevel::High);
+        assert!(l293x.y4().is_set_high().unwrap());
+    }
+
+    #[test]
+    fn test_thermal_shutdown() {
+        let mut sim = SimulatedL293x::new();
+        sim.set_thermal_shutdown(Some(ThermalShutdown {
+            trip_after: 3,
+            recover_after: 2,
+        }));
+        let l293x = L293x::new(sim.a1(), sim.a2(), (), (), sim.en12(), ());
+
+        l293x.y1().enable().unwrap();
+        l293x.y1().set_high().unwrap();
+        l293x.y2().set_low().unwrap();
+        sim.tick();
+        sim.tick();
+        assert_eq!(sim.y1(), OutputLevel::High);
+        sim.tick();
+        assert!(sim.is_thermal_shutdown());
+        assert_eq!(sim.y1(), OutputLevel::HighZ);
+        assert_eq!(sim.y2(), OutputLevel::HighZ);
+        // The driver does not notice the shutdown
+        assert!(l293x.y1().is_set_high().unwrap());
+
+        // Without load, the chip cools down
+        sim.tick();
+        assert!(sim.is_thermal_shutdown());
+        sim.tick();
+        assert!(!sim.is_thermal_shutdown());
+        assert_eq!(sim.y1(), OutputLevel::High);
+    }
+
+    #[test]
+    fn test_no_load() {
+        let mut sim = SimulatedL293x::new();
+        sim.set_thermal_shutdown(Some(ThermalShutdown {
+            trip_after: 1,
+            recover_after: 1,
+        }));
+        let l293x = L293x::new(sim.a1(), sim.a2(), (), (), sim.en12(), ());
+
+        // Both outputs on the same level do not drive a current
+        l293x.y1().set_high().unwrap();
+        l293x.y2().set_high().unwrap();
+        sim.tick();
+        assert!(!sim.is_thermal_shutdown());
+
+        l293x.y2().set_low().unwrap();
+        sim.set_thermal_shutdown(None);
+        sim.tick();
+        assert!(!sim.is_thermal_shutdown());
+    }
+
+    #[test]
+    fn test_motor_load() {
+        let mut sim = SimulatedL293x::new();
+        let load = MotorLoad {
+            max_speed: 100,
+            time_constant: 2,
+            coast_time_constant: 10,
+        };
+        sim.set_motor12(Some(load));
+        sim.set_motor34(Some(load));
+        let l293x = L293x::new(
+            sim.a1(),
+            sim.a2(),
+            sim.a3(),
+            sim.a4(),
+            sim.en12(),
+            sim.en34(),
+        );
+
+        // Disabled outputs do not move the motors
+        sim.tick();
+        assert_eq!((sim.motor12_speed(), sim.motor34_speed()), (0, 0));
+
+        l293x.y1().enable().unwrap();
+        l293x.y4().enable().unwrap();
+        l293x.y1().set_high().unwrap();
+        l293x.y4().set_high().unwrap();
+        for _ in 0..3 {
+            sim.tick();
+        }
+        assert_eq!(sim.motor12_speed(), 87);
+        assert_eq!(sim.motor12_position(), 50 + 75 + 87);
+        assert_eq!(sim.motor34_speed(), -87);
+        assert_eq!(sim.motor34_position(), -(50 + 75 + 87));
+        for _ in 0..10 {
+            sim.tick();
+        }
+        assert_eq!(sim.motor12_speed(), 100);
+
+        // Braking stops the motor quickly, coasting slowly
+        l293x.y2().set_high().unwrap();
+        l293x.y3().disable().unwrap();
+        sim.tick();
+        assert_eq!(sim.motor12_speed(), 50);
+        assert_eq!(sim.motor34_speed(), -90);
+        for _ in 0..10 {
+            sim.tick();
+        }
+        assert_eq!(sim.motor12_speed(), 0);
+        assert_eq!(sim.motor34_speed(), -34);
+    }
+
+    #[test]
+    fn test_motor_load_extreme_speed() {
+        let mut sim = SimulatedL293x::new();
+        sim.set_motor12(Some(MotorLoad {
+            max_speed: i32::MIN,
+            time_constant: 0,
+            coast_time_constant: 0,
+        }));
+        let l293x = L293x::new(
+            sim.a1(),
+            sim.a2(),
+            sim.a3(),
+            sim.a4(),
+            sim.en12(),
+            sim.en34(),
+        );
+
+        l293x.y1().enable().unwrap();
+        l293x.y2().set_high().unwrap();
+        sim.tick();
+        assert_eq!(sim.motor12_speed(), i32::MAX);
+    }
+}
@@ -0,0 +1,180 @@
+//! # Software PWM
+//!
+//! Small microcontrollers often do not have enough hardware PWM channels to control the speed of
+//! all outputs of the [L293](crate::L293x) chip. This module implements a software PWM, which
+//! generates the PWM signal on a plain digital [OutputPin] instead.
+//!
+//! The [SoftPwm] needs to be [ticked](SoftPwm::tick) periodically, e.g. from a timer interrupt.
+//! If the `async` feature is enabled, it can be ticked by an async task using
+//! [`run_soft_pwm()`](crate::asynch::run_soft_pwm) instead.
+//! Each tick advances the PWM signal by one step. The duty cycle is set using a [SoftPwmChannel],
+//! which implements the [SetDutyCycle] trait, and thus, can be used as an input pin of the drivers
+//! of this crate.
+//!
+//! # Examples
+//!
+//! ```
+//! use l293x::soft_pwm::SoftPwm;
+//! use l293x::L293x;
+//!
+//! // [...] create the pins
+//! // 100 ticks per period, i.e. 100 Hz with a tick every 100µs
+//! let pwm1 = SoftPwm::new(gpio1, 100);
+//! let l293x = L293x::new(pwm1.channel(), (), (), (), enable12, ());
+//!
+//! l293x.y1().enable()?;
+//! l293x.y1().set_duty_cycle_percent(50)?;
+//! loop {
+//!     pwm1.tick()?;
+//!     delay.delay_us(100);
+//! }
+//! ```
+
+use core::cell::{Cell, RefCell};
+use core::convert::Infallible;
+
+use embedded_hal::digital::OutputPin;
+use embedded_hal::pwm::{ErrorType, SetDutyCycle};
+
+/// Software PWM on a digital output pin
+///
+/// The PWM period consists of the given number of ticks. During each period, the pin is set high
+/// for the first `duty` ticks and low for the remaining ticks. The pin is only set, if its level
+/// changes.
+#[derive(Debug)]
+pub struct SoftPwm<P> {
+    pin: RefCell<P>,
+    period: u16,
+    duty: Cell<u16>,
+    counter: Cell<u16>,
+    level: Cell<Option<bool>>,
+}
+
+impl<P> SoftPwm<P> {
+    /// Create a new software PWM on the given pin.
+    ///
+    /// The `period` is the number of ticks per PWM period and is the maximum duty cycle of the
+    /// PWM. A `period` of `0` is treated as `1`. The duty cycle is initially `0`.
+    #[inline]
+    pub fn new(pin: P, period: u16) -> Self {
+        Self {
+            pin: RefCell::new(pin),
+            period: period.max(1),
+            duty: Cell::new(0),
+            counter: Cell::new(0),
+            level: Cell::new(None),
+        }
+    }
+
+    /// Get a channel to set the duty cycle of the PWM.
+    #[inline]
+    pub fn channel(&self) -> SoftPwmChannel<'_> {
+        SoftPwmChannel {
+            period: self.period,
+            duty: &self.duty,
+        }
+    }
+
+    /// Release the software PWM and return the pin.
+    #[inline]
+    pub fn release(self) -> P {
+        self.pin.into_inner()
+    }
+}
+
+impl<P: OutputPin> SoftPwm<P> {
+    /// Advance the PWM signal by one tick.
+    ///
+    /// # Errors
+    ///
+    /// The error of the pin will be returned, if its level could not be changed. The level is
+    /// set again on the next tick in this case.
+    pub fn tick(&self) -> Result<(), P::Error> {
+        let counter = self.counter.get();
+        let level = counter < self.duty.get();
+        self.counter.set(match counter + 1 >= self.period {
+            true => 0,
+            false => counter + 1,
+        });
+        if self.level.get() != Some(level) {
+            self.level.set(None);
+            self.pin.borrow_mut().set_state(level.into())?;
+            self.level.set(Some(level));
+        }
+        Ok(())
+    }
+}
+
+/// Channel setting the duty cycle of a [SoftPwm]
+///
+/// The maximum duty cycle of the channel is the period of the PWM. The new duty cycle takes
+/// effect with the next tick of the PWM. Setting the duty cycle never fails.
+#[derive(Debug, Copy, Clone)]
+pub struct SoftPwmChannel<'a> {
+    period: u16,
+    duty: &'a Cell<u16>,
+}
+
+impl ErrorType for SoftPwmChannel<'_> {
+    type Error = Infallible;
+}
+
+impl SetDutyCycle for SoftPwmChannel<'_> {
+    #[inline]
+    fn max_duty_cycle(&self) -> u16 {
+        self.period
+    }
+
+    #[inline]
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+        self.duty.set(duty.min(self.period));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::DigitalPin;
+    use coverage_helper::test;
+    use embedded_hal::digital::StatefulOutputPin;
+
+    /// Tick the PWM for one period and return the number of ticks the pin was high.
+    fn high_ticks(pwm: &SoftPwm<DigitalPin>) -> u16 {
+        let mut high = 0;
+        for _ in 0..pwm.period {
+            pwm.tick().unwrap();
+            if pwm.pin.borrow_mut().is_set_high().unwrap() {
+                high += 1;
+            }
+        }
+        high
+    }
+
+    #[test]
+    fn test_duty_cycle() {
+        let pwm = SoftPwm::new(DigitalPin::new(), 10);
+        let mut channel = pwm.channel();
+        assert_eq!(channel.max_duty_cycle(), 10);
+        assert_eq!(high_ticks(&pwm), 0);
+
+        channel.set_duty_cycle_percent(30).unwrap();
+        assert_eq!(high_ticks(&pwm), 3);
+        channel.set_duty_cycle_fully_on().unwrap();
+        assert_eq!(high_ticks(&pwm), 10);
+        channel.set_duty_cycle(20).unwrap();
+        assert_eq!(high_ticks(&pwm), 10);
+    }
+
+    #[test]
+    fn test_tick_fail() {
+        let pwm = SoftPwm::new(DigitalPin::new(), 0);
+        pwm.pin.borrow_mut().fail();
+        pwm.channel().set_duty_cycle_fully_on().unwrap();
+
+        assert!(pwm.tick().is_err());
+        assert!(pwm.tick().is_err());
+        assert_eq!(pwm.level.get(), None);
+        pwm.release();
+    }
+}
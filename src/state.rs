@@ -0,0 +1,112 @@
+/// A snapshot of the pin states of a [L293](crate::L293x) chip
+///
+/// The snapshot is taken using the [`snapshot()`](crate::L293x::snapshot) method and can be
+/// re-applied later on using the [`restore()`](crate::L293x::restore) or
+/// [`restore_duty_cycle()`](crate::L293x::restore_duty_cycle) method, e.g. after leaving a
+/// low-power mode. It is a plain value, which can be stored or copied freely.
+///
+/// # Examples
+///
+/// ```
+/// let state = l293x.snapshot()?;
+/// // [...] enter and leave a low-power mode
+/// l293x.restore(&state)?;
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct L293xState {
+    /// Whether the inputs 1 - 4 are set high.
+    pub inputs: [bool; 4],
+    /// Whether the enable pin of the channels 1 & 2 is set high.
+    pub enable12: bool,
+    /// Whether the enable pin of the channels 3 & 4 is set high.
+    pub enable34: bool,
+    /// The last duty cycles set on the inputs 1 - 4, or `None`, if no duty cycle has been set.
+    pub duty_cycles: [Option<u16>; 4],
+}
+
+impl L293xState {
+    /// The number of bytes of the [encoded](L293xState::to_bytes) state.
+    pub const ENCODED_SIZE: usize = 6;
+
+    /// Encode the state into a fixed number of bytes, e.g. to send it over a radio link.
+    ///
+    /// Each pin is encoded as a single byte, which is `1`, if the pin is set high, and `0`
+    /// otherwise. The bytes contain the inputs 1 - 4, followed by the enable pins of the
+    /// channels 1 & 2 and 3 & 4. The [duty cycles](L293xState::duty_cycles) are not encoded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use l293x::L293xState;
+    ///
+    /// let state = L293xState {
+    ///     inputs: [true, false, false, true],
+    ///     enable12: true,
+    ///     enable34: false,
+    ///     duty_cycles: [None; 4],
+    /// };
+    /// assert_eq!(state.to_bytes(), [1, 0, 0, 1, 1, 0]);
+    /// ```
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_SIZE] {
+        let [a1, a2, a3, a4] = self.inputs;
+        [a1, a2, a3, a4, self.enable12, self.enable34].map(u8::from)
+    }
+
+    /// Decode a state [encoded](L293xState::to_bytes) into bytes.
+    ///
+    /// Returns `None`, if the length of the bytes is not
+    /// [`ENCODED_SIZE`](L293xState::ENCODED_SIZE) or any of the bytes is neither `0` nor `1`.
+    /// Thus, most corruptions of the bytes (e.g. on a lossy radio link) are detected and the
+    /// state is not [restored](crate::L293x::restore) partially. The decoded state does not
+    /// contain any duty cycles.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != Self::ENCODED_SIZE {
+            return None;
+        }
+        let mut pins = [false; Self::ENCODED_SIZE];
+        for (pin, byte) in pins.iter_mut().zip(bytes) {
+            *pin = match byte {
+                0 => false,
+                1 => true,
+                _ => return None,
+            };
+        }
+        let [a1, a2, a3, a4, enable12, enable34] = pins;
+        Some(Self {
+            inputs: [a1, a2, a3, a4],
+            enable12,
+            enable34,
+            duty_cycles: [None; 4],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coverage_helper::test;
+
+    #[test]
+    fn test_bytes() {
+        let state = L293xState {
+            inputs: [false, true, true, false],
+            enable12: false,
+            enable34: true,
+            duty_cycles: [None; 4],
+        };
+        let bytes = state.to_bytes();
+        assert_eq!(bytes, [0, 1, 1, 0, 0, 1]);
+        assert_eq!(L293xState::from_bytes(&bytes), Some(state));
+
+        assert_eq!(L293xState::from_bytes(&bytes[..5]), None);
+        assert_eq!(L293xState::from_bytes(&[0, 1, 2, 0, 0, 1]), None);
+
+        let state = L293xState {
+            duty_cycles: [Some(1000), None, None, None],
+            ..state
+        };
+        assert_eq!(state.to_bytes(), bytes);
+    }
+}
@@ -0,0 +1,702 @@
+//! # Bipolar stepper motor driver
+//!
+//! This module implements a driver for bipolar stepper motors connected to the four outputs of a
+//! [L293](crate::L293x) chip. The first coil of the motor is connected to the outputs `y1` and
+//! `y2`, and the second coil to the outputs `y3` and `y4`.
+//!
+//! The [Stepper] is designed to be driven from a periodic timer interrupt. Each call to
+//! [`poll()`](Stepper::poll) checks, whether the next step is due and performs at most one
+//! commutation. This keeps the time spent in the interrupt handler bounded and does not require
+//! any allocations.
+//!
+//! By default, the motor is driven using the [FULL_STEP] sequence. Other sequences, like the
+//! [HALF_STEP] sequence, can be selected using [`Stepper::with_step_table()`].
+//!
+//! The rate of a movement can be changed at any time using
+//! [`set_interval()`](Stepper::set_interval). To accelerate and decelerate the motor smoothly,
+//! [`poll_profile()`](Stepper::poll_profile) takes the interval of each step from an iterator,
+//! e.g. one of the speed [profiles](crate::profile) converted into intervals.
+//!
+//! Optionally, a stall detection hook can be registered using
+//! [`Stepper::with_stall_detection()`]. It is called after each step of a movement and may check
+//! e.g. the coil current or an encoder reading. If it detects a stall, the movement is aborted and
+//! [`poll()`](Stepper::poll) returns a [Stalled](PollError::Stalled) error.
+//!
+//! # Examples
+//!
+//! ```
+//! use l293x::{Direction, L293x};
+//! use l293x::stepper::Stepper;
+//!
+//! let mut stepper = Stepper::new(L293x::new(a1, a2, a3, a4, en12, en34));
+//! stepper.enable()?;
+//! // Do a step every 10 timer ticks
+//! stepper.run(Direction::Forward, 10);
+//!
+//! // Within the timer interrupt:
+//! stepper.poll(ticks)?;
+//! ```
+use embedded_hal::digital::{Error, ErrorKind, OutputPin, PinState};
+
+use crate::{Direction, L293x, OutputStateError};
+
+/// The full-step sequence of a bipolar stepper motor.
+///
+/// Each entry defines the states of the inputs `a1` - `a4`. Two coils are energized at any time,
+/// which provides the maximum torque. This is the default step table of a [Stepper].
+pub const FULL_STEP: [[bool; 4]; 4] = [
+    [true, false, true, false],
+    [false, true, true, false],
+    [false, true, false, true],
+    [true, false, false, true],
+];
+
+/// The wave drive sequence of a bipolar stepper motor.
+///
+/// Only one coil is energized at any time. This halves the power consumption compared to the
+/// [FULL_STEP] sequence, at the cost of a lower torque.
+pub const WAVE_DRIVE: [[bool; 4]; 4] = [
+    [true, false, false, false],
+    [false, false, true, false],
+    [false, true, false, false],
+    [false, false, false, true],
+];
+
+/// The half-step sequence of a bipolar stepper motor.
+///
+/// This alternates between energizing one and two coils, which doubles the resolution of the
+/// motor.
+pub const HALF_STEP: [[bool; 4]; 8] = [
+    [true, false, true, false],
+    [false, false, true, false],
+    [false, true, true, false],
+    [false, true, false, false],
+    [false, true, false, true],
+    [false, false, false, true],
+    [true, false, false, true],
+    [true, false, false, false],
+];
+
+/// Error returned by the [Stepper].
+///
+/// This is the [OutputStateError] of the input pins and the enable pins of the chip.
+pub type StepperError<A, EN> = OutputStateError<
+    <A as embedded_hal::digital::ErrorType>::Error,
+    <EN as embedded_hal::digital::ErrorType>::Error,
+>;
+
+/// Error returned by the [`poll()`](Stepper::poll) method of the [Stepper].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PollError<E> {
+    /// An error occurred while setting the pins of the chip. The contained error may contain
+    /// additional information.
+    PinError(E),
+    /// The stall detection hook detected a stall of the motor and the movement has been aborted.
+    Stalled {
+        /// The index of the step of the aborted movement, after which the stall was detected.
+        step: u32,
+    },
+}
+
+impl<E: Error> Error for PollError<E> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            PollError::PinError(e) => e.kind(),
+            PollError::Stalled { .. } => ErrorKind::Other,
+        }
+    }
+}
+
+/// Bipolar stepper motor driver
+///
+/// This struct takes ownership of a [L293x] chip driver and uses its four outputs to drive the two
+/// coils of a bipolar stepper motor. For more information, please see the
+/// [module documentation](crate::stepper).
+#[derive(Debug)]
+pub struct Stepper<A1, A2, A3, A4, EN12, EN34, S = fn(i32) -> bool> {
+    chip: L293x<A1, A2, A3, A4, EN12, EN34>,
+    table: &'static [[bool; 4]],
+    index: usize,
+    position: i32,
+    direction: Direction,
+    interval: Option<u32>,
+    remaining: Option<u32>,
+    last_step: Option<u32>,
+    steps: u32,
+    stall_detector: Option<S>,
+}
+
+impl<A1, A2, A3, A4, EN12, EN34> Stepper<A1, A2, A3, A4, EN12, EN34> {
+    /// Create a new stepper motor driver.
+    ///
+    /// The stepper is created in the stopped state. To start moving the motor, use either the
+    /// [`run()`](Stepper::run) or the [`move_by()`](Stepper::move_by) method.
+    #[inline]
+    pub fn new(chip: L293x<A1, A2, A3, A4, EN12, EN34>) -> Self {
+        Self::with_step_table(chip, &FULL_STEP)
+    }
+
+    /// Create a new stepper motor driver using the given step table.
+    ///
+    /// Each entry of the table defines the states of the inputs `a1` - `a4` for one step. Stepping
+    /// forward walks through the table from the first to the last entry and wraps around. The
+    /// [FULL_STEP], [WAVE_DRIVE] and [HALF_STEP] sequences are provided, but any custom sequence
+    /// can be used as well.
+    ///
+    /// # Panics
+    ///
+    /// Panics, if the step table is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use l293x::L293x;
+    /// use l293x::stepper::{Stepper, HALF_STEP};
+    ///
+    /// let stepper = Stepper::with_step_table(L293x::new(a1, a2, a3, a4, en12, en34), &HALF_STEP);
+    /// ```
+    pub fn with_step_table(
+        chip: L293x<A1, A2, A3, A4, EN12, EN34>,
+        table: &'static [[bool; 4]],
+    ) -> Self {
+        assert!(!table.is_empty(), "empty step table");
+        Self {
+            chip,
+            table,
+            index: 0,
+            position: 0,
+            direction: Direction::Forward,
+            interval: None,
+            remaining: None,
+            last_step: None,
+            steps: 0,
+            stall_detector: None,
+        }
+    }
+}
+
+impl<A1, A2, A3, A4, EN12, EN34, S> Stepper<A1, A2, A3, A4, EN12, EN34, S> {
+    /// Register a stall detection hook.
+    ///
+    /// The hook is called by [`poll()`](Stepper::poll) after each step of a movement with the new
+    /// [position](Stepper::position) of the motor. It returns whether the motor stalled, e.g.
+    /// based on a current measurement or by comparing the position with an encoder reading. If
+    /// it does, the movement is aborted and a [Stalled](PollError::Stalled) error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use l293x::L293x;
+    /// use l293x::stepper::Stepper;
+    ///
+    /// let mut stepper = Stepper::new(L293x::new(a1, a2, a3, a4, en12, en34))
+    ///     .with_stall_detection(|position| encoder.position() != position);
+    /// ```
+    pub fn with_stall_detection<F>(self, detector: F) -> Stepper<A1, A2, A3, A4, EN12, EN34, F>
+    where
+        F: FnMut(i32) -> bool,
+    {
+        Stepper {
+            chip: self.chip,
+            table: self.table,
+            index: self.index,
+            position: self.position,
+            direction: self.direction,
+            interval: self.interval,
+            remaining: self.remaining,
+            last_step: self.last_step,
+            steps: self.steps,
+            stall_detector: Some(detector),
+        }
+    }
+
+    /// Release the stepper driver and return the underlying [L293x] chip driver.
+    #[inline]
+    pub fn release(self) -> L293x<A1, A2, A3, A4, EN12, EN34> {
+        self.chip
+    }
+
+    /// Get the current position of the motor in steps.
+    ///
+    /// The position is counted relative to the position of the motor when the driver was
+    /// created. Steps in [`Forward`](Direction::Forward) direction increase the position, steps
+    /// in [`Reverse`](Direction::Reverse) direction decrease it. The position wraps around at
+    /// the bounds of an [`i32`], so that a continuously rotating motor never panics.
+    #[inline]
+    pub fn position(&self) -> i32 {
+        self.position
+    }
+
+    /// Check whether the motor is currently moving.
+    #[inline]
+    pub fn is_running(&self) -> bool {
+        self.interval.is_some()
+    }
+
+    /// Continuously rotate the motor in the given direction.
+    ///
+    /// The `interval` defines the number of timer ticks between two steps. It is measured in the
+    /// same unit as the `now` value passed to [`poll()`](Stepper::poll). An interval of `0`
+    /// performs a step on every call to [`poll()`](Stepper::poll).
+    pub fn run(&mut self, direction: Direction, interval: u32) {
+        self.direction = direction;
+        self.interval = Some(interval);
+        self.remaining = None;
+        self.steps = 0;
+    }
+
+    /// Move the motor by the given number of steps.
+    ///
+    /// The sign of `steps` defines the direction of the movement. The `interval` defines the
+    /// number of timer ticks between two steps. Once all steps are done, the motor stops.
+    pub fn move_by(&mut self, steps: i32, interval: u32) {
+        self.direction = match steps < 0 {
+            true => Direction::Reverse,
+            false => Direction::Forward,
+        };
+        self.remaining = Some(steps.unsigned_abs());
+        self.steps = 0;
+        self.interval = match steps {
+            0 => None,
+            _ => Some(interval),
+        };
+    }
+
+    /// Change the interval of the current movement.
+    ///
+    /// The new `interval` applies starting with the next step, while the direction and the number
+    /// of remaining steps are kept. If the motor is stopped, this does nothing.
+    pub fn set_interval(&mut self, interval: u32) {
+        if let Some(current) = self.interval.as_mut() {
+            *current = interval;
+        }
+    }
+
+    /// Stop the motor.
+    ///
+    /// The coils remain energized, which holds the motor in its current position. To release the
+    /// motor, the outputs need to be [disabled](Stepper::disable) as well.
+    #[inline]
+    pub fn stop(&mut self) {
+        self.interval = None;
+        self.remaining = None;
+    }
+}
+
+impl<A1, A2, A3, A4, EN12, EN34, S> Stepper<A1, A2, A3, A4, EN12, EN34, S>
+where
+    A1: OutputPin,
+    A2: OutputPin<Error = A1::Error>,
+    A3: OutputPin<Error = A1::Error>,
+    A4: OutputPin<Error = A1::Error>,
+    EN12: OutputPin,
+    EN34: OutputPin<Error = EN12::Error>,
+    S: FnMut(i32) -> bool,
+{
+    /// Enable both coils of the motor.
+    ///
+    /// # Errors
+    ///
+    /// If one of the enable pins cannot be set, an
+    /// [EnablePinError](OutputStateError::EnablePinError) will be returned.
+    pub fn enable(&mut self) -> Result<(), StepperError<A1, EN12>> {
+        self.chip
+            .enable_y1_and_y2()
+            .map_err(OutputStateError::EnablePinError)?;
+        self.chip
+            .enable_y3_and_y4()
+            .map_err(OutputStateError::EnablePinError)
+    }
+
+    /// Disable both coils of the motor.
+    ///
+    /// This releases the motor, so that it can rotate freely.
+    ///
+    /// # Errors
+    ///
+    /// If one of the enable pins cannot be set, an
+    /// [EnablePinError](OutputStateError::EnablePinError) will be returned.
+    pub fn disable(&mut self) -> Result<(), StepperError<A1, EN12>> {
+        self.chip
+            .disable_y1_and_y2()
+            .map_err(OutputStateError::EnablePinError)?;
+        self.chip
+            .disable_y3_and_y4()
+            .map_err(OutputStateError::EnablePinError)
+    }
+
+    /// Perform the next step, if it is due.
+    ///
+    /// This method is meant to be called periodically, e.g. from a timer interrupt, with the
+    /// current value of a free-running tick counter. The counter is allowed to wrap around.
+    ///
+    /// If the motor is running and at least the configured interval passed since the last step,
+    /// exactly one step will be performed. The interval is measured from the time the last step
+    /// was actually performed, so that late calls do not result in bursts of steps.
+    ///
+    /// Returns whether a step has been performed.
+    ///
+    /// # Errors
+    ///
+    /// If an error occurs while setting the input pins, a [PinError](PollError::PinError)
+    /// containing an [InputPinError](OutputStateError::InputPinError) will be returned.
+    ///
+    /// If the [stall detection hook](Stepper::with_stall_detection) detects a stall after a
+    /// step, the motor is stopped and a [Stalled](PollError::Stalled) error containing the index
+    /// of the step within the current movement will be returned.
+    pub fn poll(&mut self, now: u32) -> Result<bool, PollError<StepperError<A1, EN12>>> {
+        self.poll_profile(now, &mut core::iter::empty())
+    }
+
+    /// Perform the next step, if it is due, and take the interval of the following step from the
+    /// given profile.
+    ///
+    /// This behaves like [`poll()`](Stepper::poll), but after each performed step, the next value
+    /// of `intervals` becomes the [interval](Stepper::set_interval) until the next step. Thus,
+    /// the iterator yields one interval per step. Once it is exhausted, the last interval is kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use l293x::Direction;
+    /// use l293x::profile::Trapezoidal;
+    ///
+    /// // Accelerate to 500 steps per second within 100 steps, hold the speed for 800 steps and
+    /// // decelerate within 100 steps, using a 10kHz timer
+    /// let mut intervals =
+    ///     Trapezoidal::new(500, 100, 800, 100).map(|speed| 10_000 / speed.max(1) as u32);
+    /// stepper.move_by(1000, intervals.next().unwrap());
+    ///
+    /// // Within the timer interrupt:
+    /// stepper.poll_profile(ticks, &mut intervals)?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// See [`poll()`](Stepper::poll).
+    pub fn poll_profile<I>(
+        &mut self,
+        now: u32,
+        intervals: &mut I,
+    ) -> Result<bool, PollError<StepperError<A1, EN12>>>
+    where
+        I: Iterator<Item = u32>,
+    {
+        let interval = match self.interval {
+            Some(interval) => interval,
+            None => return Ok(false),
+        };
+        if let Some(last_step) = self.last_step {
+            if now.wrapping_sub(last_step) < interval {
+                return Ok(false);
+            }
+        }
+        self.step(self.direction).map_err(PollError::PinError)?;
+        self.last_step = Some(now);
+        let step = self.steps;
+        self.steps = self.steps.wrapping_add(1);
+        if let Some(detector) = self.stall_detector.as_mut() {
+            if detector(self.position) {
+                self.stop();
+                return Err(PollError::Stalled { step });
+            }
+        }
+        if let Some(remaining) = self.remaining.as_mut() {
+            *remaining -= 1;
+            if *remaining == 0 {
+                self.stop();
+            }
+        }
+        if let Some(interval) = intervals.next() {
+            self.set_interval(interval);
+        }
+        Ok(true)
+    }
+
+    /// Immediately perform a single step in forward direction.
+    ///
+    /// This does not change the current movement of the motor (see [`run()`](Stepper::run) and
+    /// [`move_by()`](Stepper::move_by)), but updates the [position](Stepper::position).
+    ///
+    /// # Errors
+    ///
+    /// If an error occurs while setting the input pins, an
+    /// [InputPinError](OutputStateError::InputPinError) will be returned.
+    #[inline]
+    pub fn step_forward(&mut self) -> Result<(), StepperError<A1, EN12>> {
+        self.step(Direction::Forward)
+    }
+
+    /// Immediately perform a single step in reverse direction.
+    ///
+    /// This does not change the current movement of the motor (see [`run()`](Stepper::run) and
+    /// [`move_by()`](Stepper::move_by)), but updates the [position](Stepper::position).
+    ///
+    /// # Errors
+    ///
+    /// If an error occurs while setting the input pins, an
+    /// [InputPinError](OutputStateError::InputPinError) will be returned.
+    #[inline]
+    pub fn step_backward(&mut self) -> Result<(), StepperError<A1, EN12>> {
+        self.step(Direction::Reverse)
+    }
+
+    /// Perform a single step in the given direction.
+    fn step(&mut self, direction: Direction) -> Result<(), StepperError<A1, EN12>> {
+        let (index, position) = match direction {
+            Direction::Forward => (
+                (self.index + 1) % self.table.len(),
+                self.position.wrapping_add(1),
+            ),
+            Direction::Reverse => (
+                (self.index + self.table.len() - 1) % self.table.len(),
+                self.position.wrapping_sub(1),
+            ),
+        };
+        self.commutate(index)?;
+        self.index = index;
+        self.position = position;
+        Ok(())
+    }
+
+    /// Set the inputs of the chip to the given entry of the step sequence.
+    fn commutate(&mut self, index: usize) -> Result<(), StepperError<A1, EN12>> {
+        let [a1, a2, a3, a4] = self.table[index].map(PinState::from);
+        self.chip
+            .set_y1_state(a1)
+            .and_then(|_| self.chip.set_y2_state(a2))
+            .and_then(|_| self.chip.set_y3_state(a3))
+            .and_then(|_| self.chip.set_y4_state(a4))
+            .map_err(OutputStateError::InputPinError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::DigitalPin;
+    use coverage_helper::test;
+
+    type MockStepper =
+        Stepper<DigitalPin, DigitalPin, DigitalPin, DigitalPin, DigitalPin, DigitalPin>;
+
+    fn stepper() -> MockStepper {
+        let mut stepper = Stepper::new(L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        ));
+        stepper.enable().unwrap();
+        stepper
+    }
+
+    fn inputs(stepper: &mut MockStepper) -> [bool; 4] {
+        [
+            stepper.chip.is_y1_set_high().unwrap(),
+            stepper.chip.is_y2_set_high().unwrap(),
+            stepper.chip.is_y3_set_high().unwrap(),
+            stepper.chip.is_y4_set_high().unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_stopped() {
+        let mut stepper = stepper();
+        assert!(!stepper.is_running());
+        assert!(!stepper.poll(0).unwrap());
+        assert_eq!(stepper.position(), 0);
+    }
+
+    #[test]
+    fn test_poll_interval() {
+        let mut stepper = stepper();
+        stepper.run(Direction::Forward, 10);
+
+        assert!(stepper.poll(5).unwrap());
+        assert!(!stepper.poll(14).unwrap());
+        assert!(stepper.poll(15).unwrap());
+        assert!(!stepper.poll(16).unwrap());
+        assert_eq!(stepper.position(), 2);
+    }
+
+    #[test]
+    fn test_set_interval() {
+        let mut stepper = stepper();
+        stepper.set_interval(10);
+        assert!(!stepper.is_running());
+
+        stepper.move_by(3, 10);
+        assert!(stepper.poll(0).unwrap());
+        stepper.set_interval(2);
+        assert!(!stepper.poll(1).unwrap());
+        assert!(stepper.poll(2).unwrap());
+        assert!(stepper.poll(4).unwrap());
+        assert!(!stepper.is_running());
+        assert_eq!(stepper.position(), 3);
+    }
+
+    #[test]
+    fn test_poll_profile() {
+        let mut stepper = stepper();
+        let mut intervals = [5, 2].into_iter();
+        stepper.run(Direction::Reverse, 10);
+
+        assert!(stepper.poll_profile(0, &mut intervals).unwrap());
+        assert!(!stepper.poll_profile(4, &mut intervals).unwrap());
+        assert!(stepper.poll_profile(5, &mut intervals).unwrap());
+        assert!(stepper.poll_profile(7, &mut intervals).unwrap());
+        // The last interval is kept, once the profile is exhausted
+        assert!(!stepper.poll_profile(8, &mut intervals).unwrap());
+        assert!(stepper.poll_profile(9, &mut intervals).unwrap());
+        assert_eq!(stepper.position(), -4);
+    }
+
+    #[test]
+    fn test_position_wrapping() {
+        let mut stepper = stepper();
+        stepper.position = i32::MAX;
+
+        stepper.step(Direction::Forward).unwrap();
+        assert_eq!(stepper.position(), i32::MIN);
+        stepper.step(Direction::Reverse).unwrap();
+        assert_eq!(stepper.position(), i32::MAX);
+    }
+
+    #[test]
+    fn test_poll_wrapping() {
+        let mut stepper = stepper();
+        stepper.run(Direction::Forward, 10);
+
+        assert!(stepper.poll(u32::MAX - 2).unwrap());
+        assert!(!stepper.poll(3).unwrap());
+        assert!(stepper.poll(7).unwrap());
+    }
+
+    #[test]
+    fn test_sequence() {
+        let mut stepper = stepper();
+        stepper.run(Direction::Forward, 0);
+
+        for expected in [1, 2, 3, 0, 1] {
+            stepper.poll(0).unwrap();
+            assert_eq!(inputs(&mut stepper), FULL_STEP[expected]);
+        }
+
+        stepper.run(Direction::Reverse, 0);
+        for expected in [0, 3, 2] {
+            stepper.poll(0).unwrap();
+            assert_eq!(inputs(&mut stepper), FULL_STEP[expected]);
+        }
+        assert_eq!(stepper.position(), 2);
+    }
+
+    #[test]
+    fn test_step_table() {
+        let mut stepper = stepper();
+        stepper.table = &HALF_STEP;
+
+        stepper.step_backward().unwrap();
+        assert_eq!(inputs(&mut stepper), HALF_STEP[7]);
+        stepper.step_forward().unwrap();
+        stepper.step_forward().unwrap();
+        assert_eq!(inputs(&mut stepper), HALF_STEP[1]);
+        assert_eq!(stepper.position(), 1);
+        assert!(!stepper.is_running());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_empty_step_table() {
+        Stepper::with_step_table(L293x::new((), (), (), (), (), ()), &[]);
+    }
+
+    #[test]
+    fn test_move_by() {
+        let mut stepper = stepper();
+        stepper.move_by(-3, 1);
+        assert!(stepper.is_running());
+
+        let mut now = 0;
+        while stepper.is_running() {
+            stepper.poll(now).unwrap();
+            now += 1;
+        }
+        assert_eq!(stepper.position(), -3);
+        assert_eq!(now, 3);
+
+        stepper.move_by(0, 1);
+        assert!(!stepper.is_running());
+    }
+
+    #[test]
+    fn test_poll_error() {
+        let mut a3 = DigitalPin::new();
+        a3.fail();
+        let mut stepper = Stepper::new(L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            a3,
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        ));
+        stepper.run(Direction::Forward, 0);
+
+        assert!(matches!(
+            stepper.poll(0),
+            Err(PollError::PinError(OutputStateError::InputPinError(..)))
+        ));
+        assert_eq!(stepper.position(), 0);
+    }
+
+    #[test]
+    fn test_enable_error() {
+        let mut en34 = DigitalPin::new();
+        en34.fail();
+        let mut stepper = Stepper::new(L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            en34,
+        ));
+
+        assert!(matches!(
+            stepper.enable(),
+            Err(OutputStateError::EnablePinError(..))
+        ));
+        assert!(matches!(
+            stepper.disable(),
+            Err(OutputStateError::EnablePinError(..))
+        ));
+    }
+
+    #[test]
+    fn test_stall_detection() {
+        let mut stepper = stepper().with_stall_detection(|position| position % 3 == 0);
+        stepper.move_by(-5, 0);
+
+        let mut now = 0;
+        let error = loop {
+            match stepper.poll(now) {
+                Ok(_) => now += 1,
+                Err(error) => break error,
+            }
+        };
+        assert_eq!(error, PollError::Stalled { step: 2 });
+        assert_eq!(error.kind(), ErrorKind::Other);
+        assert!(!stepper.is_running());
+        assert_eq!(stepper.position(), -3);
+
+        // The step index restarts with each movement
+        stepper.run(Direction::Reverse, 0);
+        assert!(stepper.poll(0).unwrap());
+        assert!(stepper.poll(0).unwrap());
+        assert_eq!(stepper.poll(0), Err(PollError::Stalled { step: 2 }));
+    }
+}
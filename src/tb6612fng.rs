@@ -0,0 +1,597 @@
+use core::cell::RefCell;
+
+use embedded_hal::digital::OutputPin;
+use embedded_hal::pwm::SetDutyCycle;
+
+use crate::driver::FullHBridgeDriver;
+use crate::full_h::speed_to_duty;
+use crate::{BridgePair, Direction, HalfH, OutputStateError};
+
+/// A TB6612FNG Dual DC Motor Driver
+///
+/// The [TB6612FNG](https://www.sparkfun.com/datasheets/Robotics/TB6612FNG.pdf) chip controls
+/// each of its two H-bridges using two direction inputs (`AIN1` & `AIN2` or `BIN1` & `BIN2`) and a
+/// `PWMA` or `PWMB` input. Additionally, the whole chip can be put into a standby mode using its
+/// `STBY` pin.
+///
+/// The motors are accessed using the [`motor_a()`](Tb6612fng::motor_a) and
+/// [`motor_b()`](Tb6612fng::motor_b) methods, which return [Tb6612fngMotor] handles. These set
+/// the direction of the motor using the static direction inputs and its speed using the duty
+/// cycle of the `PWMx` pin, as the TB6612FNG chip expects. Thus, only the `PWMx` pins need to be
+/// PWM-capable. If the `PWMx` pins are tied high, [Vcc](crate::pins::Vcc) can be passed instead
+/// of a real pin.
+///
+/// The Half-H bridges of the outputs use the `PWMx` pins as their enable pins.
+///
+/// <div class="warning">
+/// The TB6612FNG chip brakes the motors, if the PWM input is low. Thus, disabling a single output
+/// using its Half-H bridge handle brakes the motor instead of letting it coast!
+/// </div>
+///
+/// The [`brake_a()`](Tb6612fng::brake_a) and [`brake_b()`](Tb6612fng::brake_b) methods brake the
+/// motors without a motor handle.
+///
+/// # Examples
+///
+/// ```
+/// use l293x::Tb6612fng;
+///
+/// // [...] create the input pins, the PWM pins and the standby pin
+/// let mut tb6612fng = Tb6612fng::new(ain1, ain2, pwma, bin1, bin2, pwmb, stby);
+///
+/// tb6612fng.wake()?;
+/// tb6612fng.motor_a().set_speed(i16::MAX / 2)?;
+/// tb6612fng.motor_b().reverse()?;
+/// tb6612fng.standby()?;
+/// ```
+#[derive(Debug)]
+pub struct Tb6612fng<AIN1, AIN2, PWMA, BIN1, BIN2, PWMB, STBY> {
+    a: BridgePair<AIN1, AIN2, PWMA>,
+    b: BridgePair<BIN1, BIN2, PWMB>,
+    standby: STBY,
+}
+
+impl<AIN1, AIN2, PWMA, BIN1, BIN2, PWMB, STBY> Tb6612fng<AIN1, AIN2, PWMA, BIN1, BIN2, PWMB, STBY> {
+    /// Create a new TB6612FNG chip driver.
+    ///
+    /// If the `STBY` pin of the chip is tied high, [Vcc](crate::pins::Vcc) can be passed as
+    /// `standby` pin.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use l293x::Tb6612fng;
+    /// let tb6612fng = Tb6612fng::new(ain1, ain2, pwma, bin1, bin2, pwmb, stby);
+    /// ```
+    #[inline]
+    pub fn new(
+        ain1: AIN1,
+        ain2: AIN2,
+        pwma: PWMA,
+        bin1: BIN1,
+        bin2: BIN2,
+        pwmb: PWMB,
+        standby: STBY,
+    ) -> Self {
+        Self {
+            a: BridgePair::new(ain1, ain2, pwma),
+            b: BridgePair::new(bin1, bin2, pwmb),
+            standby,
+        }
+    }
+
+    /// Release the driver and return the input pins, the PWM pins and the standby pin.
+    #[inline]
+    #[allow(clippy::type_complexity)]
+    pub fn release(self) -> (AIN1, AIN2, PWMA, BIN1, BIN2, PWMB, STBY) {
+        let (ain1, ain2, pwma) = self.a.release();
+        let (bin1, bin2, pwmb) = self.b.release();
+        (ain1, ain2, pwma, bin1, bin2, pwmb, self.standby)
+    }
+
+    /// Get the Half-H bridge of output `AO1`.
+    #[inline]
+    pub fn ao1(&self) -> HalfH<'_, AIN1, PWMA> {
+        self.a.first()
+    }
+
+    /// Get the Half-H bridge of output `AO2`.
+    #[inline]
+    pub fn ao2(&self) -> HalfH<'_, AIN2, PWMA> {
+        self.a.second()
+    }
+
+    /// Get the Half-H bridge of output `BO1`.
+    #[inline]
+    pub fn bo1(&self) -> HalfH<'_, BIN1, PWMB> {
+        self.b.first()
+    }
+
+    /// Get the Half-H bridge of output `BO2`.
+    #[inline]
+    pub fn bo2(&self) -> HalfH<'_, BIN2, PWMB> {
+        self.b.second()
+    }
+
+    /// Get the motor connected to the outputs `AO1` and `AO2`.
+    #[inline]
+    pub fn motor_a(&self) -> Tb6612fngMotor<'_, AIN1, AIN2, PWMA> {
+        Tb6612fngMotor::new(&self.a)
+    }
+
+    /// Get the motor connected to the outputs `BO1` and `BO2`.
+    #[inline]
+    pub fn motor_b(&self) -> Tb6612fngMotor<'_, BIN1, BIN2, PWMB> {
+        Tb6612fngMotor::new(&self.b)
+    }
+}
+
+impl<AIN1, AIN2, PWMA, BIN1, BIN2, PWMB, STBY> Tb6612fng<AIN1, AIN2, PWMA, BIN1, BIN2, PWMB, STBY>
+where
+    AIN1: OutputPin,
+    AIN2: OutputPin<Error = AIN1::Error>,
+{
+    /// Actively brake the motor `A`.
+    ///
+    /// This sets both inputs of the bridge high, which shorts the terminals of the motor.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the first input pin, which could not be set.
+    pub fn brake_a(&mut self) -> Result<(), AIN1::Error> {
+        let (ain1, ain2) = self.a.inputs_mut();
+        ain1.set_high()?;
+        ain2.set_high()
+    }
+}
+
+impl<AIN1, AIN2, PWMA, BIN1, BIN2, PWMB, STBY> Tb6612fng<AIN1, AIN2, PWMA, BIN1, BIN2, PWMB, STBY>
+where
+    BIN1: OutputPin,
+    BIN2: OutputPin<Error = BIN1::Error>,
+{
+    /// Actively brake the motor `B`.
+    ///
+    /// This sets both inputs of the bridge high, which shorts the terminals of the motor.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the first input pin, which could not be set.
+    pub fn brake_b(&mut self) -> Result<(), BIN1::Error> {
+        let (bin1, bin2) = self.b.inputs_mut();
+        bin1.set_high()?;
+        bin2.set_high()
+    }
+}
+
+impl<AIN1, AIN2, PWMA, BIN1, BIN2, PWMB, STBY> Tb6612fng<AIN1, AIN2, PWMA, BIN1, BIN2, PWMB, STBY>
+where
+    STBY: OutputPin,
+{
+    /// Put the chip into its standby mode.
+    ///
+    /// This sets the `STBY` pin low, which disables both bridges and lets the motors coast.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the standby pin.
+    #[inline]
+    pub fn standby(&mut self) -> Result<(), STBY::Error> {
+        self.standby.set_low()
+    }
+
+    /// Wake the chip up from its standby mode.
+    ///
+    /// This sets the `STBY` pin high.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the standby pin.
+    #[inline]
+    pub fn wake(&mut self) -> Result<(), STBY::Error> {
+        self.standby.set_high()
+    }
+}
+
+/// A motor connected to one of the H-bridges of a [TB6612FNG](Tb6612fng) chip
+///
+/// This is returned by the [`motor_a()`](Tb6612fng::motor_a) and
+/// [`motor_b()`](Tb6612fng::motor_b) methods of the chip. The TB6612FNG chip selects the direction
+/// of a motor using the static inputs `xIN1` & `xIN2` and drives it with the duty cycle of the
+/// `PWMx` input. While the `PWMx` input is low, the terminals of the motor are shorted. Thus, the
+/// handle keeps the `PWMx` input high to let the motor coast.
+///
+/// | Method                                   | `xIN1` | `xIN2` | `PWMx`     | Motor terminals |
+/// | ---------------------------------------- | ------ | ------ | ---------- | --------------- |
+/// | [`forward`](Tb6612fngMotor::forward)     | High   | Low    | High       | Driven          |
+/// | [`reverse`](Tb6612fngMotor::reverse)     | Low    | High   | High       | Driven          |
+/// | [`set_speed`](Tb6612fngMotor::set_speed) | Dir.   | Dir.   | Duty cycle | Driven          |
+/// | [`brake`](Tb6612fngMotor::brake)         | High   | High   | -          | Shorted         |
+/// | [`coast`](Tb6612fngMotor::coast)         | Low    | Low    | High       | Disconnected    |
+///
+/// # Examples
+///
+/// ```
+/// use l293x::Tb6612fng;
+///
+/// let tb6612fng = Tb6612fng::new(ain1, ain2, pwma, bin1, bin2, pwmb, stby);
+/// let mut motor = tb6612fng.motor_a();
+///
+/// motor.set_speed(i16::MAX / 2)?;
+/// motor.brake()?;
+/// motor.reverse()?;
+/// motor.coast()?;
+/// ```
+#[derive(Debug)]
+pub struct Tb6612fngMotor<'a, IN1, IN2, PWM> {
+    in1: &'a RefCell<IN1>,
+    in2: &'a RefCell<IN2>,
+    pwm: &'a RefCell<PWM>,
+    speed: i16,
+}
+
+impl<'a, IN1, IN2, PWM> Tb6612fngMotor<'a, IN1, IN2, PWM> {
+    /// Create a new motor handle driving the pins of the given bridge pair.
+    #[inline]
+    fn new(pair: &'a BridgePair<IN1, IN2, PWM>) -> Self {
+        let (in1, in2, pwm) = pair.pins();
+        Self {
+            in1,
+            in2,
+            pwm,
+            speed: 0,
+        }
+    }
+
+    /// Get the last commanded speed of the motor.
+    ///
+    /// Braking and coasting counts as a speed of `0`.
+    #[inline]
+    pub fn speed(&self) -> i16 {
+        self.speed
+    }
+}
+
+impl<'a, IN1, IN2, PWM> Tb6612fngMotor<'a, IN1, IN2, PWM>
+where
+    IN1: OutputPin,
+    IN2: OutputPin<Error = IN1::Error>,
+{
+    /// Actively brake the motor.
+    ///
+    /// This sets both direction inputs high, which shorts the terminals of the motor independent
+    /// of the `PWMx` input.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the first input pin, which could not be set.
+    pub fn brake(&mut self) -> Result<(), IN1::Error> {
+        self.in1.borrow_mut().set_high()?;
+        self.in2.borrow_mut().set_high()?;
+        self.speed = 0;
+        Ok(())
+    }
+
+    /// Set the direction inputs for the given `direction`.
+    ///
+    /// The input going low is set first, so that the motor coasts in between instead of being
+    /// driven in the wrong direction.
+    fn set_direction(&mut self, direction: Direction) -> Result<(), IN1::Error> {
+        match direction {
+            Direction::Forward => {
+                self.in2.borrow_mut().set_low()?;
+                self.in1.borrow_mut().set_high()
+            }
+            Direction::Reverse => {
+                self.in1.borrow_mut().set_low()?;
+                self.in2.borrow_mut().set_high()
+            }
+        }
+    }
+
+    /// Set both direction inputs low.
+    fn release_inputs(&mut self) -> Result<(), IN1::Error> {
+        self.in1.borrow_mut().set_low()?;
+        self.in2.borrow_mut().set_low()
+    }
+}
+
+impl<'a, IN1, IN2, PWM> Tb6612fngMotor<'a, IN1, IN2, PWM>
+where
+    IN1: OutputPin,
+    IN2: OutputPin<Error = IN1::Error>,
+    PWM: OutputPin,
+{
+    /// Drive the motor at full speed in forward direction.
+    ///
+    /// # Errors
+    ///
+    /// The error of the first pin, which could not be set, will be returned.
+    #[inline]
+    pub fn forward(&mut self) -> Result<(), OutputStateError<IN1::Error, PWM::Error>> {
+        self.drive(Direction::Forward)
+    }
+
+    /// Drive the motor at full speed in reverse direction.
+    ///
+    /// # Errors
+    ///
+    /// The error of the first pin, which could not be set, will be returned.
+    #[inline]
+    pub fn reverse(&mut self) -> Result<(), OutputStateError<IN1::Error, PWM::Error>> {
+        self.drive(Direction::Reverse)
+    }
+
+    /// Drive the motor at full speed in the given direction.
+    ///
+    /// This sets the direction inputs and the `PWMx` pin high.
+    ///
+    /// # Errors
+    ///
+    /// The error of the first pin, which could not be set, will be returned.
+    pub fn drive(
+        &mut self,
+        direction: Direction,
+    ) -> Result<(), OutputStateError<IN1::Error, PWM::Error>> {
+        self.set_direction(direction)
+            .map_err(OutputStateError::InputPinError)?;
+        self.pwm
+            .borrow_mut()
+            .set_high()
+            .map_err(OutputStateError::EnablePinError)?;
+        self.speed = match direction {
+            Direction::Forward => i16::MAX,
+            Direction::Reverse => -i16::MAX,
+        };
+        Ok(())
+    }
+
+    /// Let the motor coast.
+    ///
+    /// This sets both direction inputs low and the `PWMx` pin high, which disconnects the motor.
+    ///
+    /// # Errors
+    ///
+    /// The error of the first pin, which could not be set, will be returned.
+    pub fn coast(&mut self) -> Result<(), OutputStateError<IN1::Error, PWM::Error>> {
+        self.release_inputs()
+            .map_err(OutputStateError::InputPinError)?;
+        self.pwm
+            .borrow_mut()
+            .set_high()
+            .map_err(OutputStateError::EnablePinError)?;
+        self.speed = 0;
+        Ok(())
+    }
+}
+
+impl<'a, IN1, IN2, PWM> Tb6612fngMotor<'a, IN1, IN2, PWM>
+where
+    IN1: OutputPin,
+    IN2: OutputPin<Error = IN1::Error>,
+    PWM: SetDutyCycle,
+{
+    /// Set the speed of the motor.
+    ///
+    /// The sign of the `speed` selects the direction inputs: positive values drive the motor
+    /// forward, negative values in reverse. The magnitude defines the duty cycle of the `PWMx`
+    /// pin, where [`i16::MAX`] means fully on. A `speed` of `0` [brakes](Tb6612fngMotor::brake)
+    /// the motor.
+    ///
+    /// # Errors
+    ///
+    /// The error of the first pin, which could not be set, will be returned.
+    pub fn set_speed(
+        &mut self,
+        speed: i16,
+    ) -> Result<(), OutputStateError<IN1::Error, PWM::Error>> {
+        let direction = match speed.signum() {
+            0 => return self.brake().map_err(OutputStateError::InputPinError),
+            1 => Direction::Forward,
+            _ => Direction::Reverse,
+        };
+        self.set_direction(direction)
+            .map_err(OutputStateError::InputPinError)?;
+        let mut pwm = self.pwm.borrow_mut();
+        let duty = speed_to_duty(speed, pwm.max_duty_cycle());
+        pwm.set_duty_cycle(duty)
+            .map_err(OutputStateError::EnablePinError)?;
+        self.speed = speed;
+        Ok(())
+    }
+}
+
+impl<'a, IN1, IN2, PWM> FullHBridgeDriver for Tb6612fngMotor<'a, IN1, IN2, PWM>
+where
+    IN1: OutputPin,
+    IN2: OutputPin<Error = IN1::Error>,
+    PWM: SetDutyCycle,
+{
+    type Error = OutputStateError<IN1::Error, PWM::Error>;
+
+    /// The H-bridges of the chip are enabled, as long as the chip is not in its standby mode.
+    /// Thus, this does nothing.
+    #[inline]
+    fn enable(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Let the motor coast by setting both direction inputs low and the `PWMx` pin fully on.
+    fn disable(&mut self) -> Result<(), Self::Error> {
+        self.release_inputs()
+            .map_err(OutputStateError::InputPinError)?;
+        self.pwm
+            .borrow_mut()
+            .set_duty_cycle_fully_on()
+            .map_err(OutputStateError::EnablePinError)?;
+        self.speed = 0;
+        Ok(())
+    }
+
+    #[inline]
+    fn set_speed(&mut self, speed: i16) -> Result<(), Self::Error> {
+        Tb6612fngMotor::set_speed(self, speed)
+    }
+
+    #[inline]
+    fn speed(&self) -> i16 {
+        Tb6612fngMotor::speed(self)
+    }
+
+    /// Brake the motor by setting both direction inputs high.
+    #[inline]
+    fn brake(&mut self) -> Result<(), Self::Error> {
+        Tb6612fngMotor::brake(self).map_err(OutputStateError::InputPinError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pins::Vcc;
+    use crate::test_util::{DigitalPin, PwmPin};
+    use coverage_helper::test;
+    use embedded_hal::digital::StatefulOutputPin;
+
+    #[test]
+    fn test_motors() {
+        let mut tb6612fng = Tb6612fng::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            PwmPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            Vcc(),
+            DigitalPin::new(),
+        );
+
+        let mut motor = tb6612fng.motor_a();
+        motor.set_speed(i16::MAX / 2).unwrap();
+        assert_eq!(motor.speed(), i16::MAX / 2);
+        assert!(tb6612fng.a.inputs_mut().0.is_set_high().unwrap());
+        assert!(tb6612fng.a.inputs_mut().1.is_set_low().unwrap());
+        assert_eq!(
+            tb6612fng.a.pins().2.borrow().get_duty_cycle(),
+            speed_to_duty(i16::MAX / 2, u16::MAX)
+        );
+
+        let mut motor = tb6612fng.motor_a();
+        motor.set_speed(-i16::MAX).unwrap();
+        assert!(tb6612fng.a.inputs_mut().0.is_set_low().unwrap());
+        assert!(tb6612fng.a.inputs_mut().1.is_set_high().unwrap());
+        assert_eq!(tb6612fng.a.pins().2.borrow().get_duty_cycle(), u16::MAX);
+
+        let mut motor = tb6612fng.motor_a();
+        motor.set_speed(0).unwrap();
+        assert_eq!(motor.speed(), 0);
+        assert!(tb6612fng.a.inputs_mut().0.is_set_high().unwrap());
+        assert!(tb6612fng.a.inputs_mut().1.is_set_high().unwrap());
+
+        let mut motor = tb6612fng.motor_b();
+        motor.reverse().unwrap();
+        assert_eq!(motor.speed(), -i16::MAX);
+        assert!(tb6612fng.bo1().is_set_low().unwrap());
+        assert!(tb6612fng.bo2().is_set_high().unwrap());
+
+        tb6612fng.brake_b().unwrap();
+        assert!(tb6612fng.bo1().is_set_high().unwrap());
+        let mut motor = tb6612fng.motor_b();
+        motor.forward().unwrap();
+        motor.coast().unwrap();
+        assert_eq!(motor.speed(), 0);
+        assert!(tb6612fng.bo1().is_set_low().unwrap());
+        assert!(tb6612fng.bo2().is_set_low().unwrap());
+        tb6612fng.motor_b().brake().unwrap();
+        assert!(tb6612fng.bo2().is_set_high().unwrap());
+    }
+
+    #[test]
+    fn test_coast_keeps_pwm_high() {
+        let tb6612fng = Tb6612fng::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            (),
+            (),
+            (),
+            (),
+        );
+
+        let mut motor = tb6612fng.motor_a();
+        motor.brake().unwrap();
+        motor.coast().unwrap();
+        assert!(tb6612fng.ao1().is_enabled().unwrap());
+        assert!(tb6612fng.ao1().is_set_low().unwrap());
+        assert!(tb6612fng.ao2().is_set_low().unwrap());
+
+        tb6612fng.a.pins().2.borrow_mut().fail();
+        assert!(matches!(
+            tb6612fng.motor_a().forward(),
+            Err(OutputStateError::EnablePinError(_))
+        ));
+        tb6612fng.a.pins().0.borrow_mut().fail();
+        assert!(matches!(
+            tb6612fng.motor_a().coast(),
+            Err(OutputStateError::InputPinError(_))
+        ));
+    }
+
+    #[test]
+    fn test_driver() {
+        let tb6612fng = Tb6612fng::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            PwmPin::new(),
+            (),
+            (),
+            (),
+            (),
+        );
+        let mut motor = tb6612fng.motor_a();
+
+        FullHBridgeDriver::enable(&mut motor).unwrap();
+        FullHBridgeDriver::set_speed(&mut motor, i16::MAX / 2).unwrap();
+        assert_eq!(FullHBridgeDriver::speed(&motor), i16::MAX / 2);
+        FullHBridgeDriver::brake(&mut motor).unwrap();
+        assert_eq!(motor.speed(), 0);
+        assert!(tb6612fng.a.pins().0.borrow_mut().is_set_high().unwrap());
+        assert!(tb6612fng.a.pins().1.borrow_mut().is_set_high().unwrap());
+
+        FullHBridgeDriver::disable(&mut motor).unwrap();
+        assert!(tb6612fng.a.pins().0.borrow_mut().is_set_low().unwrap());
+        assert!(tb6612fng.a.pins().1.borrow_mut().is_set_low().unwrap());
+        assert_eq!(tb6612fng.a.pins().2.borrow().get_duty_cycle(), u16::MAX);
+
+        tb6612fng.a.pins().2.borrow_mut().fail();
+        assert!(matches!(
+            FullHBridgeDriver::disable(&mut motor),
+            Err(OutputStateError::EnablePinError(_))
+        ));
+        assert!(matches!(
+            FullHBridgeDriver::set_speed(&mut motor, 1),
+            Err(OutputStateError::EnablePinError(_))
+        ));
+    }
+
+    #[test]
+    fn test_standby() {
+        let mut tb6612fng = Tb6612fng::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            (),
+            (),
+            (),
+            DigitalPin::new(),
+        );
+
+        tb6612fng.wake().unwrap();
+        tb6612fng.brake_a().unwrap();
+        assert!(tb6612fng.a.inputs_mut().0.is_set_high().unwrap());
+        assert!(tb6612fng.a.inputs_mut().1.is_set_high().unwrap());
+
+        assert!(tb6612fng.standby.is_set_high().unwrap());
+        tb6612fng.standby().unwrap();
+        assert!(tb6612fng.standby.is_set_low().unwrap());
+    }
+}
@@ -0,0 +1,115 @@
+use crate::{resolve_output, L293xState, OutputLevel};
+
+/// The state of a [L293](crate::L293x) chip for debugging and monitoring
+///
+/// The telemetry is taken using the [`telemetry()`](crate::L293x::telemetry) method. It contains
+/// the [pin states and the last duty cycles](L293xState) commanded to the outputs and resolves
+/// the resulting [output levels](L293xTelemetry::output_levels). It is a plain value, which can
+/// be sent to a host, e.g. to feed a debugging dashboard over USB or UART. If the `serde` feature
+/// is enabled, it implements `serde::Serialize`, so any serde format (e.g. `postcard` or
+/// `serde-json-core`) can be used to encode it.
+///
+/// # Examples
+///
+/// ```
+/// let telemetry = l293x.telemetry()?;
+/// let len = postcard::to_slice(&telemetry, &mut buffer)?.len();
+/// uart.write_all(&buffer[..len])?;
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct L293xTelemetry {
+    /// The states of the input and enable pins and the last duty cycles of the inputs.
+    pub state: L293xState,
+}
+
+impl L293xTelemetry {
+    /// Get the electrical levels of the outputs 1 - 4 resulting from the pin states.
+    pub fn output_levels(&self) -> [OutputLevel; 4] {
+        let [a1, a2, a3, a4] = self.state.inputs;
+        [
+            resolve_output(a1, self.state.enable12),
+            resolve_output(a2, self.state.enable12),
+            resolve_output(a3, self.state.enable34),
+            resolve_output(a4, self.state.enable34),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coverage_helper::test;
+
+    #[test]
+    fn test_output_levels() {
+        let telemetry = L293xTelemetry {
+            state: L293xState {
+                inputs: [true, false, true, false],
+                enable12: true,
+                enable34: false,
+                duty_cycles: [None; 4],
+            },
+        };
+        assert_eq!(
+            telemetry.output_levels(),
+            [
+                OutputLevel::High,
+                OutputLevel::Low,
+                OutputLevel::HighZ,
+                OutputLevel::HighZ
+            ]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialize() {
+        use serde_test::{assert_ser_tokens, Token};
+
+        let telemetry = L293xTelemetry {
+            state: L293xState {
+                inputs: [true, false, false, false],
+                enable12: true,
+                enable34: false,
+                duty_cycles: [Some(1000), None, None, None],
+            },
+        };
+        assert_ser_tokens(
+            &telemetry,
+            &[
+                Token::Struct {
+                    name: "L293xTelemetry",
+                    len: 1,
+                },
+                Token::Str("state"),
+                Token::Struct {
+                    name: "L293xState",
+                    len: 4,
+                },
+                Token::Str("inputs"),
+                Token::Tuple { len: 4 },
+                Token::Bool(true),
+                Token::Bool(false),
+                Token::Bool(false),
+                Token::Bool(false),
+                Token::TupleEnd,
+                Token::Str("enable12"),
+                Token::Bool(true),
+                Token::Str("enable34"),
+                Token::Bool(false),
+                Token::Str("duty_cycles"),
+                Token::Tuple { len: 4 },
+                Token::Some,
+                Token::U16(1000),
+                Token::None,
+                Token::None,
+                Token::None,
+                Token::TupleEnd,
+                Token::StructEnd,
+                Token::StructEnd,
+            ],
+        );
+    }
+}
@@ -0,0 +1,750 @@
+//! # Test utilities
+//!
+//! This module is only available, if the `test-util` feature is enabled. It contains the mock
+//! implementations of the embedded-hal traits used by the tests of this crate. Crates building on
+//! the [L293x](crate::L293x) or [HalfH](crate::HalfH) drivers can use them to unit-test their
+//! logic on the host, without writing their own mocks.
+//!
+//! Every mock can be told to [fail](DigitalPin::fail), so that the error handling of the code
+//! under test can be checked as well. To check the sequence of pin writes, any pin can be wrapped
+//! into a [Recorder].
+//!
+//! # Examples
+//!
+//! ```
+//! use l293x::test_util::{DigitalPin, PwmPin};
+//! use l293x::L293x;
+//!
+//! let mut enable = DigitalPin::new();
+//! enable.fail();
+//! let l293x = L293x::new(PwmPin::new(), PwmPin::new(), (), (), enable, ());
+//! assert!(l293x.full_h12().set_speed(1000).is_err());
+//! ```
+use core::cell::Cell;
+
+use embedded_hal::{delay, digital, pwm};
+
+/// Mock of a digital pin
+///
+/// The pin implements the [OutputPin](digital::OutputPin),
+/// [StatefulOutputPin](digital::StatefulOutputPin) and [InputPin](digital::InputPin) traits. Its
+/// input level is the last state it was set to. Initially, the pin is low.
+#[derive(Debug, Default)]
+pub struct DigitalPin {
+    state: bool,
+    should_fail: bool,
+}
+
+impl DigitalPin {
+    /// Create a new pin in low state.
+    pub fn new() -> Self {
+        Self {
+            state: false,
+            should_fail: false,
+        }
+    }
+
+    /// Let all following operations of the pin fail with a [DigitalError].
+    pub fn fail(&mut self) {
+        self.should_fail = true
+    }
+}
+
+/// Error returned by a failing [DigitalPin]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct DigitalError();
+
+impl digital::Error for DigitalError {
+    #[inline]
+    fn kind(&self) -> digital::ErrorKind {
+        digital::ErrorKind::Other
+    }
+}
+
+impl digital::ErrorType for DigitalPin {
+    type Error = DigitalError;
+}
+
+impl digital::OutputPin for DigitalPin {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        match self.should_fail {
+            false => {
+                self.state = false;
+                Ok(())
+            }
+            true => Err(DigitalError()),
+        }
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        match self.should_fail {
+            false => {
+                self.state = true;
+                Ok(())
+            }
+            true => Err(DigitalError()),
+        }
+    }
+}
+
+impl digital::StatefulOutputPin for DigitalPin {
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        match self.should_fail {
+            false => Ok(self.state),
+            true => Err(DigitalError()),
+        }
+    }
+
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        match self.should_fail {
+            false => Ok(!self.state),
+            true => Err(DigitalError()),
+        }
+    }
+}
+
+impl digital::InputPin for DigitalPin {
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        match self.should_fail {
+            false => Ok(self.state),
+            true => Err(DigitalError()),
+        }
+    }
+
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        match self.should_fail {
+            false => Ok(!self.state),
+            true => Err(DigitalError()),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl DigitalPin {
+    fn wait(&self) -> Result<(), DigitalError> {
+        match self.should_fail {
+            false => Ok(()),
+            true => Err(DigitalError()),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl embedded_hal_async::digital::Wait for DigitalPin {
+    async fn wait_for_high(&mut self) -> Result<(), Self::Error> {
+        self.wait()
+    }
+
+    async fn wait_for_low(&mut self) -> Result<(), Self::Error> {
+        self.wait()
+    }
+
+    async fn wait_for_rising_edge(&mut self) -> Result<(), Self::Error> {
+        self.wait()
+    }
+
+    async fn wait_for_falling_edge(&mut self) -> Result<(), Self::Error> {
+        self.wait()
+    }
+
+    async fn wait_for_any_edge(&mut self) -> Result<(), Self::Error> {
+        self.wait()
+    }
+}
+
+/// Mock of a PWM pin
+///
+/// The pin implements the [SetDutyCycle](pwm::SetDutyCycle) trait with a maximum duty cycle of
+/// `u16::MAX` by default. Initially, the duty cycle is `0`.
+///
+/// Setting a duty cycle greater than the maximum duty cycle panics. Thus, using a pin with a
+/// small maximum duty cycle (e.g. of an 8-bit timer) catches code, which does not scale the duty
+/// cycles to the pin.
+#[derive(Debug)]
+pub struct PwmPin {
+    duty: u16,
+    max_duty: u16,
+    should_fail: bool,
+}
+
+impl PwmPin {
+    /// Create a new pin with a duty cycle of `0`.
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_max_duty_cycle(u16::MAX)
+    }
+
+    /// Create a new pin with the given maximum duty cycle, e.g. `255` for an 8-bit timer.
+    #[inline]
+    pub fn with_max_duty_cycle(max_duty: u16) -> Self {
+        Self {
+            duty: 0,
+            max_duty,
+            should_fail: false,
+        }
+    }
+
+    /// Get the last duty cycle set.
+    #[inline]
+    pub fn get_duty_cycle(&self) -> u16 {
+        self.duty
+    }
+
+    /// Let all following operations of the pin fail with a [PwmError].
+    pub fn fail(&mut self) {
+        self.should_fail = true;
+    }
+}
+
+/// Error returned by a failing [PwmPin]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct PwmError();
+
+impl pwm::Error for PwmError {
+    #[inline]
+    fn kind(&self) -> pwm::ErrorKind {
+        pwm::ErrorKind::Other
+    }
+}
+
+impl pwm::ErrorType for PwmPin {
+    type Error = PwmError;
+}
+
+impl Default for PwmPin {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl pwm::SetDutyCycle for PwmPin {
+    fn max_duty_cycle(&self) -> u16 {
+        self.max_duty
+    }
+
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+        assert!(duty <= self.max_duty, "duty cycle exceeds the maximum");
+        match self.should_fail {
+            true => Err(PwmError()),
+            false => {
+                self.duty = duty;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Mock of a delay
+///
+/// The delay does not block, but sums up the requested delays, so that the timing of the code
+/// under test can be checked instantly.
+#[derive(Debug, Default)]
+pub struct Delay {
+    elapsed_ns: u64,
+}
+
+impl Delay {
+    /// Create a new delay.
+    #[inline]
+    pub fn new() -> Self {
+        Self { elapsed_ns: 0 }
+    }
+
+    /// Get the sum of all delays in nanoseconds.
+    #[inline]
+    pub fn elapsed_ns(&self) -> u64 {
+        self.elapsed_ns
+    }
+}
+
+impl delay::DelayNs for Delay {
+    fn delay_ns(&mut self, ns: u32) {
+        self.elapsed_ns += u64::from(ns);
+    }
+}
+
+#[cfg(feature = "async")]
+impl embedded_hal_async::delay::DelayNs for Delay {
+    async fn delay_ns(&mut self, ns: u32) {
+        self.elapsed_ns += u64::from(ns);
+    }
+}
+
+/// Mock of a 128 bytes storage, which is erased (`0xff`) initially
+#[cfg(feature = "storage")]
+#[derive(Debug)]
+pub struct MemoryStorage {
+    bytes: [u8; 128],
+    should_fail: bool,
+}
+
+#[cfg(feature = "storage")]
+impl MemoryStorage {
+    /// Create a new, erased storage.
+    pub fn new() -> Self {
+        Self {
+            bytes: [0xff; 128],
+            should_fail: false,
+        }
+    }
+
+    /// Let all following reads and writes fail.
+    pub fn fail(&mut self) {
+        self.should_fail = true;
+    }
+}
+
+#[cfg(feature = "storage")]
+impl Default for MemoryStorage {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "storage")]
+impl embedded_storage::ReadStorage for MemoryStorage {
+    type Error = ();
+
+    fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        let offset = offset as usize;
+        match self.should_fail {
+            true => Err(()),
+            false => {
+                bytes.copy_from_slice(&self.bytes[offset..offset + bytes.len()]);
+                Ok(())
+            }
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        self.bytes.len()
+    }
+}
+
+#[cfg(feature = "storage")]
+impl embedded_storage::Storage for MemoryStorage {
+    fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+        let offset = offset as usize;
+        match self.should_fail {
+            true => Err(()),
+            false => {
+                self.bytes[offset..offset + bytes.len()].copy_from_slice(bytes);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Mock of a CAN frame
+#[cfg(feature = "can")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CanFrame {
+    id: embedded_can::Id,
+    remote: bool,
+    data: [u8; 8],
+    dlc: usize,
+}
+
+#[cfg(feature = "can")]
+impl embedded_can::Frame for CanFrame {
+    fn new(id: impl Into<embedded_can::Id>, data: &[u8]) -> Option<Self> {
+        let mut frame = Self::new_remote(id, data.len())?;
+        frame.remote = false;
+        frame.data[..data.len()].copy_from_slice(data);
+        Some(frame)
+    }
+
+    fn new_remote(id: impl Into<embedded_can::Id>, dlc: usize) -> Option<Self> {
+        match dlc <= 8 {
+            true => Some(Self {
+                id: id.into(),
+                remote: true,
+                data: [0; 8],
+                dlc,
+            }),
+            false => None,
+        }
+    }
+
+    fn is_extended(&self) -> bool {
+        matches!(self.id, embedded_can::Id::Extended(_))
+    }
+
+    fn is_remote_frame(&self) -> bool {
+        self.remote
+    }
+
+    fn id(&self) -> embedded_can::Id {
+        self.id
+    }
+
+    fn dlc(&self) -> usize {
+        self.dlc
+    }
+
+    fn data(&self) -> &[u8] {
+        match self.remote {
+            true => &[],
+            false => &self.data[..self.dlc],
+        }
+    }
+}
+
+/// Mock of a CAN bus receiving up to 8 queued frames
+///
+/// Transmitted frames are queued as well, so that they are received again.
+#[cfg(feature = "can")]
+#[derive(Debug)]
+pub struct CanBus {
+    frames: [Option<CanFrame>; 8],
+    received: usize,
+    should_fail: bool,
+}
+
+#[cfg(feature = "can")]
+impl CanBus {
+    /// Create a new bus without queued frames.
+    pub fn new() -> Self {
+        Self {
+            frames: Default::default(),
+            received: 0,
+            should_fail: false,
+        }
+    }
+
+    /// Queue a frame to be received.
+    ///
+    /// # Panics
+    ///
+    /// This function panics, if 8 frames have been queued already.
+    pub fn push(&mut self, frame: CanFrame) {
+        let free = self.frames.iter_mut().find(|f| f.is_none()).unwrap();
+        *free = Some(frame);
+    }
+
+    /// Let all following receives fail.
+    pub fn fail(&mut self) {
+        self.should_fail = true;
+    }
+}
+
+#[cfg(feature = "can")]
+impl Default for CanBus {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "can")]
+impl embedded_can::nb::Can for CanBus {
+    type Frame = CanFrame;
+    type Error = embedded_can::ErrorKind;
+
+    fn transmit(&mut self, frame: &Self::Frame) -> nb::Result<Option<Self::Frame>, Self::Error> {
+        self.push(frame.clone());
+        Ok(None)
+    }
+
+    fn receive(&mut self) -> nb::Result<Self::Frame, Self::Error> {
+        if self.should_fail {
+            return Err(nb::Error::Other(embedded_can::ErrorKind::Other));
+        }
+        let frame = self.frames.get_mut(self.received).and_then(Option::take);
+        match frame {
+            Some(frame) => {
+                self.received += 1;
+                Ok(frame)
+            }
+            None => Err(nb::Error::WouldBlock),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "async"))]
+extern crate std;
+
+#[cfg(all(test, feature = "async"))]
+struct NoopWaker;
+
+#[cfg(all(test, feature = "async"))]
+impl std::task::Wake for NoopWaker {
+    fn wake(self: std::sync::Arc<Self>) {}
+}
+
+#[cfg(all(test, feature = "async"))]
+pub fn block_on<F: core::future::Future>(future: F) -> F::Output {
+    let waker = std::sync::Arc::new(NoopWaker).into();
+    let mut context = core::task::Context::from_waker(&waker);
+    let mut future = std::boxed::Box::pin(future);
+    loop {
+        if let core::task::Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
+
+/// Logical clock shared by several [Recorder]s
+///
+/// The clock is advanced by every change recorded. Thus, the timestamps of the records define the
+/// order of the changes across all pins sharing the clock.
+#[derive(Debug, Default)]
+pub struct Clock(Cell<u32>);
+
+impl Clock {
+    /// Create a new clock starting at `0`.
+    #[inline]
+    pub fn new() -> Self {
+        Self(Cell::new(0))
+    }
+
+    /// Get the timestamp of the next change.
+    #[inline]
+    pub fn now(&self) -> u32 {
+        self.0.get()
+    }
+
+    fn tick(&self) -> u32 {
+        let now = self.0.get();
+        self.0.set(now + 1);
+        now
+    }
+}
+
+/// A change of a pin recorded by a [Recorder]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Event {
+    /// The pin was set low.
+    Low,
+    /// The pin was set high.
+    High,
+    /// The duty cycle of the pin was set.
+    Duty(u16),
+}
+
+/// A change of a pin together with its logical timestamp
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Record {
+    /// The timestamp of the change taken from the [Clock].
+    pub time: u32,
+    /// The change of the pin.
+    pub event: Event,
+}
+
+/// Pin wrapper recording the changes of the pin
+///
+/// Every successful call to [`set_low()`](digital::OutputPin::set_low),
+/// [`set_high()`](digital::OutputPin::set_high) or
+/// [`set_duty_cycle()`](pwm::SetDutyCycle::set_duty_cycle) is recorded, even if it does not change
+/// the state of the pin. Thus, host tests can assert on the exact sequence of pin writes done by
+/// e.g. stepper moves, ramps or transactions. Failed calls are not recorded. Up to `N` changes
+/// are recorded.
+///
+/// # Panics
+///
+/// Setting the pin panics, if `N` changes have been recorded already. Use
+/// [`clear()`](Recorder::clear) to discard the records.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_hal::digital::OutputPin;
+/// use l293x::test_util::{Clock, DigitalPin, Recorder};
+/// use l293x::L293x;
+///
+/// let clock = Clock::new();
+/// let l293x = L293x::new(
+///     Recorder::<_, 8>::new(DigitalPin::new(), &clock),
+///     Recorder::<_, 8>::new(DigitalPin::new(), &clock),
+///     (),
+///     (),
+///     Recorder::<_, 8>::new(DigitalPin::new(), &clock),
+///     (),
+/// );
+/// l293x.y1().enable()?;
+/// l293x.y1().set_high()?;
+/// let ((a1, _, _, _), (en12, _)) = l293x.release();
+/// // The output is enabled before the input is set
+/// assert!(en12.records()[0].time < a1.records()[0].time);
+/// ```
+#[derive(Debug)]
+pub struct Recorder<'a, P, const N: usize> {
+    pin: P,
+    clock: &'a Clock,
+    records: [Record; N],
+    len: usize,
+}
+
+impl<'a, P, const N: usize> Recorder<'a, P, N> {
+    /// Create a new recorder wrapping the `pin` and taking the timestamps from the `clock`.
+    #[inline]
+    pub fn new(pin: P, clock: &'a Clock) -> Self {
+        Self {
+            pin,
+            clock,
+            records: [Record {
+                time: 0,
+                event: Event::Low,
+            }; N],
+            len: 0,
+        }
+    }
+
+    /// Get the recorded changes in the order they occurred.
+    #[inline]
+    pub fn records(&self) -> &[Record] {
+        &self.records[..self.len]
+    }
+
+    /// Iterate over the recorded changes without their timestamps.
+    #[inline]
+    pub fn events(&self) -> impl Iterator<Item = Event> + '_ {
+        self.records().iter().map(|record| record.event)
+    }
+
+    /// Discard all recorded changes.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Release the recorder and return the pin.
+    #[inline]
+    pub fn release(self) -> P {
+        self.pin
+    }
+
+    fn record(&mut self, event: Event) {
+        let record = self
+            .records
+            .get_mut(self.len)
+            .expect("the recorder is full");
+        *record = Record {
+            time: self.clock.tick(),
+            event,
+        };
+        self.len += 1;
+    }
+}
+
+impl<P: digital::ErrorType, const N: usize> digital::ErrorType for Recorder<'_, P, N> {
+    type Error = P::Error;
+}
+
+impl<P: digital::OutputPin, const N: usize> digital::OutputPin for Recorder<'_, P, N> {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.pin.set_low()?;
+        self.record(Event::Low);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.pin.set_high()?;
+        self.record(Event::High);
+        Ok(())
+    }
+}
+
+impl<P: digital::StatefulOutputPin, const N: usize> digital::StatefulOutputPin
+    for Recorder<'_, P, N>
+{
+    #[inline]
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        self.pin.is_set_high()
+    }
+
+    #[inline]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        self.pin.is_set_low()
+    }
+}
+
+impl<P: pwm::ErrorType, const N: usize> pwm::ErrorType for Recorder<'_, P, N> {
+    type Error = P::Error;
+}
+
+impl<P: pwm::SetDutyCycle, const N: usize> pwm::SetDutyCycle for Recorder<'_, P, N> {
+    #[inline]
+    fn max_duty_cycle(&self) -> u16 {
+        self.pin.max_duty_cycle()
+    }
+
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+        self.pin.set_duty_cycle(duty)?;
+        self.record(Event::Duty(duty));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::L293x;
+    use coverage_helper::test;
+    use embedded_hal::digital::OutputPin;
+
+    #[test]
+    fn test_recorder() {
+        let clock = Clock::new();
+        let mut pin = Recorder::<_, 2>::new(DigitalPin::new(), &clock);
+        pin.set_high().unwrap();
+        pin.set_high().unwrap();
+        assert_eq!(
+            pin.records(),
+            [
+                Record {
+                    time: 0,
+                    event: Event::High
+                },
+                Record {
+                    time: 1,
+                    event: Event::High
+                }
+            ]
+        );
+        pin.clear();
+        pin.set_low().unwrap();
+        assert!(pin.events().eq([Event::Low]));
+        assert_eq!(clock.now(), 3);
+
+        let mut pin = pin.release();
+        pin.fail();
+        let mut pin = Recorder::<_, 2>::new(pin, &clock);
+        assert!(pin.set_high().is_err());
+        assert!(pin.records().is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_full() {
+        let clock = Clock::new();
+        let mut pin = Recorder::<_, 1>::new(DigitalPin::new(), &clock);
+        pin.set_high().unwrap();
+        pin.set_low().unwrap();
+    }
+
+    #[test]
+    fn test_transaction() {
+        let clock = Clock::new();
+        let pin = || Recorder::<_, 4>::new(DigitalPin::new(), &clock);
+        let mut l293x = L293x::new(pin(), pin(), pin(), pin(), pin(), pin());
+        l293x.enable_y1_and_y2().unwrap();
+        l293x.y1().set_high().unwrap();
+        l293x
+            .transaction(|txn| {
+                txn.set_y1_low();
+                txn.set_y2_high();
+            })
+            .unwrap();
+        let ((a1, a2, _, _), (en12, _)) = l293x.release();
+        assert!(en12.events().eq([Event::High, Event::Low, Event::High]));
+        assert!(a1.events().eq([Event::High, Event::Low]));
+        assert!(a2.events().eq([Event::High]));
+        // The pair is disabled, while its inputs change
+        assert!(en12.records()[1].time < a1.records()[1].time);
+        assert!(a1.records()[1].time < a2.records()[0].time);
+        assert!(a2.records()[0].time < en12.records()[2].time);
+    }
+}
@@ -0,0 +1,293 @@
+//! # Thermal derating
+//!
+//! The L293D has no thermal feedback pin. It silently disables its outputs, if the junction
+//! temperature exceeds the limit of its thermal shutdown. This module implements a
+//! [ThermalModel], which estimates the junction temperature of the chip from the commanded duty
+//! cycles, the configured load currents and the ambient temperature. This allows the application
+//! to reduce the load (i.e. derate the outputs), before the chip shuts down.
+//!
+//! The model is a steady-state estimate: Each output dissipates the power given by its load
+//! current and its voltage drop, for the portion of the time it is driven (i.e. its duty cycle).
+//! The sum of these powers heats the junction above the ambient temperature by the thermal
+//! resistance of the package. Because the thermal capacity of the chip is ignored, short bursts
+//! are overestimated, which makes the model err on the safe side.
+//!
+//! All temperatures are given in millidegrees Celsius and all calculations use integer arithmetic
+//! only.
+//!
+//! # Examples
+//!
+//! ```
+//! use l293x::Channel;
+//! use l293x::thermal::{ThermalConfig, ThermalModel};
+//!
+//! let mut model = ThermalModel::new(ThermalConfig {
+//!     load_current_ma: [500, 500, 0, 0],
+//!     ..ThermalConfig::L293D
+//! });
+//!
+//! // Clamp the duty cycle, so that the estimate stays below the limit
+//! let duty = model.clamp_duty(Channel::Y1, duty, pwm.max_duty_cycle());
+//! pwm.set_duty_cycle(duty)?;
+//!
+//! // Or only raise a warning
+//! if let Err(warning) = model.set_duty(Channel::Y2, duty, pwm.max_duty_cycle()) {
+//!     log::warn!("L293D too hot: {}m°C", warning.junction_temperature);
+//! }
+//! ```
+use crate::Channel;
+
+/// Configuration of a [ThermalModel]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ThermalConfig {
+    /// The ambient temperature in millidegrees Celsius.
+    pub ambient: i32,
+    /// The maximum allowed junction temperature in millidegrees Celsius.
+    pub limit: i32,
+    /// The thermal resistance between the junction and the ambient air in °C/W.
+    pub thermal_resistance: u32,
+    /// The voltage drop of a driven output in millivolts.
+    pub voltage_drop_mv: u32,
+    /// The load current of the outputs `y1` - `y4` in milliamperes.
+    pub load_current_ma: [u32; 4],
+}
+
+impl ThermalConfig {
+    /// The configuration of a L293D chip in a DIP-16 package without any load.
+    ///
+    /// The values are taken from the datasheet: A thermal resistance of 80°C/W, a voltage drop
+    /// of 1.4V per output and a maximum junction temperature of 150°C. The ambient temperature
+    /// is assumed to be 25°C. The load currents need to be configured by the application.
+    pub const L293D: ThermalConfig = ThermalConfig {
+        ambient: 25_000,
+        limit: 150_000,
+        thermal_resistance: 80,
+        voltage_drop_mv: 1400,
+        load_current_ma: [0; 4],
+    };
+}
+
+impl Default for ThermalConfig {
+    #[inline]
+    fn default() -> Self {
+        Self::L293D
+    }
+}
+
+/// Warning returned by [`ThermalModel::set_duty()`], if the estimated junction temperature
+/// exceeds the limit
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ThermalWarning {
+    /// The estimated junction temperature in millidegrees Celsius.
+    pub junction_temperature: i32,
+}
+
+/// Estimation of the junction temperature of a L293D chip
+///
+/// The model stores the duty cycles of all four outputs. They are updated using either
+/// [`set_duty()`](ThermalModel::set_duty), which raises a [ThermalWarning] if the estimate exceeds
+/// the limit, or [`clamp_duty()`](ThermalModel::clamp_duty), which reduces the duty cycle to keep
+/// the estimate within the limit. Outputs driven statically high or low should be recorded with
+/// their full duty cycle. For more information, please see the
+/// [module documentation](crate::thermal).
+#[derive(Debug, Clone)]
+pub struct ThermalModel {
+    config: ThermalConfig,
+    duties: [u32; 4],
+}
+
+impl ThermalModel {
+    /// Create a new thermal model with all outputs idle.
+    #[inline]
+    pub fn new(config: ThermalConfig) -> Self {
+        Self {
+            config,
+            duties: [0; 4],
+        }
+    }
+
+    /// Get the configuration of the model.
+    #[inline]
+    pub fn config(&self) -> &ThermalConfig {
+        &self.config
+    }
+
+    /// Update the ambient temperature in millidegrees Celsius, e.g. using a temperature sensor.
+    #[inline]
+    pub fn set_ambient(&mut self, ambient: i32) {
+        self.config.ambient = ambient;
+    }
+
+    /// Record the duty cycle of an output.
+    ///
+    /// The duty cycle is given relative to `max_duty`, which is usually the
+    /// [maximum duty cycle](embedded_hal::pwm::SetDutyCycle::max_duty_cycle) of the input pin.
+    ///
+    /// # Errors
+    ///
+    /// If the estimated junction temperature exceeds the limit afterwards, a [ThermalWarning] is
+    /// returned. The duty cycle is recorded nevertheless.
+    pub fn set_duty(
+        &mut self,
+        channel: Channel,
+        duty: u16,
+        max_duty: u16,
+    ) -> Result<(), ThermalWarning> {
+        self.duties[channel.index()] = normalize(duty, max_duty);
+        match self.is_over_limit() {
+            true => Err(ThermalWarning {
+                junction_temperature: self.junction_temperature(),
+            }),
+            false => Ok(()),
+        }
+    }
+
+    /// Clamp and record the duty cycle of an output.
+    ///
+    /// The duty cycle is reduced to the [maximum allowed duty cycle](ThermalModel::max_duty) of
+    /// the output, given the duty cycles of the other outputs. The clamped duty cycle is recorded
+    /// and returned, so that it can be applied to the input pin.
+    pub fn clamp_duty(&mut self, channel: Channel, duty: u16, max_duty: u16) -> u16 {
+        let duty = duty.min(self.max_duty(channel, max_duty));
+        self.duties[channel.index()] = normalize(duty, max_duty);
+        duty
+    }
+
+    /// Get the maximum duty cycle of an output, which keeps the estimate within the limit.
+    ///
+    /// The duty cycles of the other outputs are taken into account. If they already exceed the
+    /// limit, `0` is returned.
+    pub fn max_duty(&self, channel: Channel, max_duty: u16) -> u16 {
+        let index = channel.index();
+        let full_power = self.output_power(index, u32::from(u16::MAX));
+        if full_power == 0 {
+            return max_duty;
+        }
+        let others: u64 = (0..4)
+            .filter(|&i| i != index)
+            .map(|i| self.output_power(i, self.duties[i]))
+            .sum();
+        let allowed = self.allowed_power().saturating_sub(others);
+        let duty = u128::from(max_duty) * u128::from(allowed) / u128::from(full_power);
+        duty.min(u128::from(max_duty)) as u16
+    }
+
+    /// Get the estimated power dissipated by the chip in microwatts.
+    pub fn power_uw(&self) -> u64 {
+        (0..4).map(|i| self.output_power(i, self.duties[i])).sum()
+    }
+
+    /// Get the estimated junction temperature in millidegrees Celsius.
+    pub fn junction_temperature(&self) -> i32 {
+        let rise = self.power_uw() * u64::from(self.config.thermal_resistance) / 1000;
+        i64::from(self.config.ambient)
+            .saturating_add(rise.min(i64::MAX as u64) as i64)
+            .min(i64::from(i32::MAX)) as i32
+    }
+
+    /// Check whether the estimated junction temperature exceeds the limit.
+    #[inline]
+    pub fn is_over_limit(&self) -> bool {
+        self.junction_temperature() > self.config.limit
+    }
+
+    /// Get the power dissipated by an output at the given normalized duty cycle in microwatts.
+    fn output_power(&self, index: usize, duty: u32) -> u64 {
+        u64::from(self.config.load_current_ma[index])
+            * u64::from(self.config.voltage_drop_mv)
+            * u64::from(duty)
+            / u64::from(u16::MAX)
+    }
+
+    /// Get the power in microwatts, which heats the junction up to the limit.
+    fn allowed_power(&self) -> u64 {
+        let headroom = i64::from(self.config.limit) - i64::from(self.config.ambient);
+        match (headroom, self.config.thermal_resistance) {
+            (headroom, _) if headroom <= 0 => 0,
+            (_, 0) => u64::MAX,
+            (headroom, resistance) => headroom as u64 * 1000 / u64::from(resistance),
+        }
+    }
+}
+
+/// Scale a duty cycle relative to `max_duty` to the range of an [u16].
+fn normalize(duty: u16, max_duty: u16) -> u32 {
+    match max_duty {
+        0 => 0,
+        _ => u32::from(duty.min(max_duty)) * u32::from(u16::MAX) / u32::from(max_duty),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coverage_helper::test;
+
+    fn model() -> ThermalModel {
+        // 1A at 1.4V dissipate 1.4W, which heats the chip by 112°C
+        ThermalModel::new(ThermalConfig {
+            load_current_ma: [1000, 1000, 0, 0],
+            ..ThermalConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_estimate() {
+        let mut model = model();
+        assert_eq!(model.junction_temperature(), 25_000);
+
+        model.set_duty(Channel::Y1, 2, 5).unwrap();
+        assert_eq!(model.power_uw(), 560_000);
+        assert_eq!(model.junction_temperature(), 69_800);
+
+        // Outputs without a load do not heat the chip
+        model.set_duty(Channel::Y3, 5, 5).unwrap();
+        assert_eq!(model.junction_temperature(), 69_800);
+        assert!(!model.is_over_limit());
+    }
+
+    #[test]
+    fn test_warning() {
+        let mut model = model();
+        model.set_duty(Channel::Y1, 5, 5).unwrap();
+        assert_eq!(
+            model.set_duty(Channel::Y2, 3, 5),
+            Err(ThermalWarning {
+                junction_temperature: 204_200
+            })
+        );
+        assert!(model.is_over_limit());
+
+        model.set_ambient(-40_000);
+        assert!(!model.is_over_limit());
+        assert_eq!(model.config().ambient, -40_000);
+    }
+
+    #[test]
+    fn test_clamp() {
+        let mut model = model();
+        assert_eq!(model.clamp_duty(Channel::Y1, 1000, 1000), 1000);
+        // Only 0.1625W are left for the second output
+        assert_eq!(model.clamp_duty(Channel::Y2, 1000, 1000), 116);
+        assert!(!model.is_over_limit());
+
+        assert_eq!(model.max_duty(Channel::Y4, 1000), 1000);
+        assert_eq!(model.clamp_duty(Channel::Y1, 0, 0), 0);
+    }
+
+    #[test]
+    fn test_no_headroom() {
+        let mut config = ThermalConfig {
+            load_current_ma: [1000; 4],
+            ..ThermalConfig::L293D
+        };
+        config.ambient = config.limit;
+        assert_eq!(ThermalModel::new(config).max_duty(Channel::Y1, 1000), 0);
+
+        config.thermal_resistance = 0;
+        config.ambient = 0;
+        assert_eq!(ThermalModel::new(config).max_duty(Channel::Y1, 1000), 1000);
+    }
+}
@@ -0,0 +1,163 @@
+//! # Tones
+//!
+//! Piezo buzzers and small speakers are often wired to an output of the [L293](crate::L293x)
+//! chip. This module implements a [Buzzer], which plays tones of a given frequency by toggling
+//! any [OutputPin], e.g. a [HalfH](crate::HalfH) output, and timing the toggles using a
+//! [DelayNs]. Thus, simple beeps and melodies do not require any user-written bit-banging loops.
+//!
+//! The buzzer blocks, while a tone is played. The accuracy of the frequency depends on the
+//! accuracy of the delay and the time needed to toggle the pin.
+//!
+//! # Examples
+//!
+//! ```
+//! use l293x::tone::{Buzzer, Note};
+//! use l293x::AutoEnable;
+//!
+//! let mut buzzer = Buzzer::new(l293x.y1().with_auto_enable(AutoEnable::Always));
+//! // A short beep
+//! buzzer.tone(2_000, 100, &mut delay)?;
+//! // Two beeps with a pause in between
+//! buzzer.play(&[Note::new(1_000, 100), Note::rest(50), Note::new(1_000, 100)], &mut delay)?;
+//! ```
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::OutputPin;
+
+/// A note of a melody played by a [Buzzer]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Note {
+    /// The frequency of the tone in Hertz, or `0` for a rest.
+    pub frequency_hz: u32,
+    /// The duration of the note in milliseconds.
+    pub duration_ms: u32,
+}
+
+impl Note {
+    /// Create a new note with the given frequency and duration.
+    #[inline]
+    pub const fn new(frequency_hz: u32, duration_ms: u32) -> Self {
+        Self {
+            frequency_hz,
+            duration_ms,
+        }
+    }
+
+    /// Create a rest, i.e. a note without tone, with the given duration.
+    #[inline]
+    pub const fn rest(duration_ms: u32) -> Self {
+        Self::new(0, duration_ms)
+    }
+}
+
+/// A buzzer driven by a digital output pin
+///
+/// The pin is toggled with a duty cycle of 50% at the frequency of the tone. After each tone,
+/// the pin is set low, so that no current flows through the buzzer.
+#[derive(Debug)]
+pub struct Buzzer<P> {
+    pin: P,
+}
+
+impl<P> Buzzer<P> {
+    /// Create a new buzzer driven by the `pin`.
+    #[inline]
+    pub fn new(pin: P) -> Self {
+        Self { pin }
+    }
+
+    /// Release the buzzer and return the pin.
+    #[inline]
+    pub fn release(self) -> P {
+        self.pin
+    }
+}
+
+impl<P: OutputPin> Buzzer<P> {
+    /// Play a tone with the given frequency in Hertz for the given duration in milliseconds.
+    ///
+    /// The tone consists of the number of full periods fitting into the duration. A frequency of
+    /// `0` plays a rest, i.e. the pin is kept low for the duration.
+    ///
+    /// # Errors
+    ///
+    /// The error of the pin will be returned, if it could not be set. The tone is aborted in this
+    /// case.
+    pub fn tone<D: DelayNs>(
+        &mut self,
+        frequency_hz: u32,
+        duration_ms: u32,
+        delay: &mut D,
+    ) -> Result<(), P::Error> {
+        self.pin.set_low()?;
+        if frequency_hz == 0 {
+            delay.delay_ms(duration_ms);
+            return Ok(());
+        }
+        let half_period_ns = 500_000_000 / frequency_hz;
+        let periods = u64::from(duration_ms) * u64::from(frequency_hz) / 1_000;
+        for _ in 0..periods {
+            self.pin.set_high()?;
+            delay.delay_ns(half_period_ns);
+            self.pin.set_low()?;
+            delay.delay_ns(half_period_ns);
+        }
+        Ok(())
+    }
+
+    /// Play the given notes one after another.
+    ///
+    /// # Errors
+    ///
+    /// The error of the pin will be returned, if it could not be set. The melody is aborted in
+    /// this case.
+    pub fn play<D: DelayNs>(&mut self, notes: &[Note], delay: &mut D) -> Result<(), P::Error> {
+        notes
+            .iter()
+            .try_for_each(|note| self.tone(note.frequency_hz, note.duration_ms, delay))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{Clock, Delay, DigitalPin, Event, Recorder};
+    use crate::{AutoEnable, L293x};
+    use coverage_helper::test;
+    use embedded_hal::digital::StatefulOutputPin;
+
+    #[test]
+    fn test_tone() {
+        let clock = Clock::new();
+        let mut buzzer = Buzzer::new(Recorder::<_, 32>::new(DigitalPin::new(), &clock));
+        let mut delay = Delay::new();
+
+        buzzer.tone(1_000, 10, &mut delay).unwrap();
+        assert_eq!(delay.elapsed_ns(), 10_000_000);
+        let mut pin = buzzer.release();
+        assert_eq!(pin.events().filter(|e| *e == Event::High).count(), 10);
+        assert!(pin.is_set_low().unwrap());
+    }
+
+    #[test]
+    fn test_play() {
+        let l293x = L293x::new(DigitalPin::new(), (), (), (), DigitalPin::new(), ());
+        let mut buzzer = Buzzer::new(l293x.y1().with_auto_enable(AutoEnable::Always));
+        let mut delay = Delay::new();
+
+        buzzer
+            .play(&[Note::new(500, 20), Note::rest(30)], &mut delay)
+            .unwrap();
+        assert_eq!(delay.elapsed_ns(), 50_000_000);
+        assert!(l293x.y1().is_set_low().unwrap());
+    }
+
+    #[test]
+    fn test_error() {
+        let mut pin = DigitalPin::new();
+        pin.fail();
+        let mut buzzer = Buzzer::new(pin);
+        assert!(buzzer.tone(1_000, 10, &mut Delay::new()).is_err());
+    }
+}
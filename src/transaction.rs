@@ -0,0 +1,118 @@
+use embedded_hal::digital::PinState;
+
+/// Staged changes of the pins of a L293x chip
+///
+/// A transaction collects changes of the inputs and the enable pins of a chip, which are applied
+/// together by the [`transaction()`](crate::L293x::transaction) method of the chip. Changing the
+/// inputs of two outputs sharing an enable pin one after another lets the outputs pass through an
+/// intermediate combination for a short time, e.g. both outputs being high. Instead, the
+/// transaction disables the affected pairs first, sets all inputs and enables the pairs last.
+///
+/// # Examples
+///
+/// ```
+/// // Reverse a motor connected to the outputs 1 and 2 without an intermediate state
+/// l293x.transaction(|txn| {
+///     txn.set_y1_low();
+///     txn.set_y2_high();
+/// })?;
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct L293xTransaction {
+    pub(crate) inputs: [Option<bool>; 4],
+    pub(crate) enable12: Option<bool>,
+    pub(crate) enable34: Option<bool>,
+}
+
+macro_rules! transaction_input_impl {
+    ($output:ident, $index:literal) => {
+        paste::item! {
+            #[doc = "Stage setting the input of output " $output " high."]
+            #[inline]
+            pub fn [< set_ $output _high >](&mut self) -> &mut Self {
+                self.[< set_ $output _state >](PinState::High)
+            }
+
+            #[doc = "Stage setting the input of output " $output " low."]
+            #[inline]
+            pub fn [< set_ $output _low >](&mut self) -> &mut Self {
+                self.[< set_ $output _state >](PinState::Low)
+            }
+
+            #[doc = "Stage setting the input of output " $output " to the given `state`."]
+            #[inline]
+            pub fn [< set_ $output _state >](&mut self, state: PinState) -> &mut Self {
+                self.inputs[$index] = Some(state == PinState::High);
+                self
+            }
+        }
+    };
+}
+
+macro_rules! transaction_enable_impl {
+    ($pair:ident, $enable:ident) => {
+        paste::item! {
+            #[doc = "Stage enabling the outputs " $pair "."]
+            ///
+            /// Otherwise, the pair keeps its current enable state.
+            #[inline]
+            pub fn [< enable_ $pair >](&mut self) -> &mut Self {
+                self.$enable = Some(true);
+                self
+            }
+
+            #[doc = "Stage disabling the outputs " $pair "."]
+            ///
+            /// Otherwise, the pair keeps its current enable state.
+            #[inline]
+            pub fn [< disable_ $pair >](&mut self) -> &mut Self {
+                self.$enable = Some(false);
+                self
+            }
+        }
+    };
+}
+
+impl L293xTransaction {
+    /// Create a new, empty transaction.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check whether the transaction does not contain any changes.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    transaction_input_impl!(y1, 0);
+    transaction_input_impl!(y2, 1);
+    transaction_input_impl!(y3, 2);
+    transaction_input_impl!(y4, 3);
+    transaction_enable_impl!(y1_and_y2, enable12);
+    transaction_enable_impl!(y3_and_y4, enable34);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use coverage_helper::test;
+
+    #[test]
+    fn test_staging() {
+        let mut txn = L293xTransaction::new();
+        assert!(txn.is_empty());
+
+        txn.set_y1_high().set_y2_low().set_y3_state(PinState::High);
+        txn.set_y4_high().set_y4_low();
+        txn.enable_y1_and_y2().disable_y3_and_y4();
+        assert!(!txn.is_empty());
+        assert_eq!(
+            txn.inputs,
+            [Some(true), Some(false), Some(true), Some(false)]
+        );
+        assert_eq!((txn.enable12, txn.enable34), (Some(true), Some(false)));
+    }
+}
@@ -0,0 +1,231 @@
+//! # Typestate Half-H bridges
+//!
+//! By default, setting the state of a disabled [HalfH] bridge does not enable its output (see
+//! [AutoEnable](crate::AutoEnable)). Thus, the new state has no effect, until the output is
+//! enabled, while querying the state of a disabled output returns a
+//! [NotEnabled](crate::OutputStateError::NotEnabled) error at runtime. This module offers a
+//! typestate variant of the bridge instead: A [TypedHalfH]
+//! tracks whether its output is [Enabled] or [Disabled] in its type. The setters of the input are
+//! only available on an enabled bridge, which is returned by [`enable()`](TypedHalfH::enable).
+//! Thus, forgetting to enable the bridge is caught at compile time.
+//!
+//! Because an enabled bridge does not need to touch its enable pin, its setters only return the
+//! error of the input pin.
+//!
+//! <div class="warning">
+//! The typestate only tracks the changes done using the bridge itself. If the enable pin is shared
+//! with another output (like on the L293 chip), disabling the other output disables this output as
+//! well, regardless of its type.
+//! </div>
+//!
+//! # Examples
+//!
+//! ```
+//! use embedded_hal::digital::OutputPin;
+//! use l293x::L293x;
+//!
+//! let l293x = L293x::new(input1, input2, input3, input4, enable12, enable34);
+//! let y1 = l293x.y1().into_disabled()?;
+//!
+//! // y1.set_high()?; <- does not compile, the bridge is not enabled
+//! let mut y1 = y1.enable()?;
+//! y1.set_high()?;
+//! ```
+use core::marker::PhantomData;
+
+use embedded_hal::digital::{self, OutputPin, PinState, StatefulOutputPin};
+use embedded_hal::pwm::{self, SetDutyCycle};
+
+use crate::HalfH;
+
+/// Marker of an enabled [TypedHalfH] bridge
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Enabled;
+
+/// Marker of a disabled [TypedHalfH] bridge
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Disabled;
+
+/// Half-H bridge tracking whether its output is enabled in its type
+///
+/// The bridge is created from a [HalfH] bridge using the
+/// [`into_enabled()`](HalfH::into_enabled) or [`into_disabled()`](HalfH::into_disabled) method.
+/// For more information, please see the [module documentation](crate::typestate).
+#[derive(Debug)]
+pub struct TypedHalfH<'a, INPUT, ENABLE, STATE> {
+    bridge: HalfH<'a, INPUT, ENABLE>,
+    state: PhantomData<STATE>,
+}
+
+impl<'a, INPUT, ENABLE, STATE> TypedHalfH<'a, INPUT, ENABLE, STATE> {
+    fn new(bridge: HalfH<'a, INPUT, ENABLE>) -> Self {
+        Self {
+            bridge,
+            state: PhantomData,
+        }
+    }
+
+    /// Convert the bridge back into a [HalfH] bridge, whose state is checked at runtime.
+    #[inline]
+    pub fn into_inner(self) -> HalfH<'a, INPUT, ENABLE> {
+        self.bridge
+    }
+}
+
+impl<'a, INPUT, ENABLE> HalfH<'a, INPUT, ENABLE>
+where
+    ENABLE: OutputPin,
+{
+    /// Enable the output and convert the bridge into an [enabled](Enabled) [TypedHalfH] bridge.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the enable pin.
+    #[inline]
+    pub fn into_enabled(self) -> Result<TypedHalfH<'a, INPUT, ENABLE, Enabled>, ENABLE::Error> {
+        TypedHalfH::<_, _, Disabled>::new(self).enable()
+    }
+
+    /// Disable the output and convert the bridge into a [disabled](Disabled) [TypedHalfH]
+    /// bridge.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the enable pin.
+    #[inline]
+    pub fn into_disabled(self) -> Result<TypedHalfH<'a, INPUT, ENABLE, Disabled>, ENABLE::Error> {
+        TypedHalfH::<_, _, Enabled>::new(self).disable()
+    }
+}
+
+impl<'a, INPUT, ENABLE> TypedHalfH<'a, INPUT, ENABLE, Disabled>
+where
+    ENABLE: OutputPin,
+{
+    /// Enable the output.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the enable pin. The bridge is consumed in this
+    /// case. Use [`HalfH::into_disabled()`] to recover it.
+    pub fn enable(mut self) -> Result<TypedHalfH<'a, INPUT, ENABLE, Enabled>, ENABLE::Error> {
+        self.bridge.enable()?;
+        Ok(TypedHalfH::new(self.bridge))
+    }
+}
+
+impl<'a, INPUT, ENABLE> TypedHalfH<'a, INPUT, ENABLE, Enabled>
+where
+    ENABLE: OutputPin,
+{
+    /// Disable the output.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the enable pin. The bridge is consumed in this
+    /// case. Use [`HalfH::into_disabled()`] to recover it.
+    pub fn disable(mut self) -> Result<TypedHalfH<'a, INPUT, ENABLE, Disabled>, ENABLE::Error> {
+        self.bridge.disable()?;
+        Ok(TypedHalfH::new(self.bridge))
+    }
+}
+
+impl<INPUT, ENABLE> digital::ErrorType for TypedHalfH<'_, INPUT, ENABLE, Enabled>
+where
+    INPUT: digital::ErrorType,
+{
+    type Error = INPUT::Error;
+}
+
+impl<INPUT, ENABLE> OutputPin for TypedHalfH<'_, INPUT, ENABLE, Enabled>
+where
+    INPUT: OutputPin,
+{
+    #[inline]
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.bridge.set_input_state(PinState::Low)
+    }
+
+    #[inline]
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.bridge.set_input_state(PinState::High)
+    }
+}
+
+impl<INPUT, ENABLE> StatefulOutputPin for TypedHalfH<'_, INPUT, ENABLE, Enabled>
+where
+    INPUT: StatefulOutputPin,
+{
+    #[inline]
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        self.bridge.is_input_set_high()
+    }
+
+    #[inline]
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        self.bridge.is_input_set_high().map(|high| !high)
+    }
+}
+
+impl<INPUT, ENABLE> pwm::ErrorType for TypedHalfH<'_, INPUT, ENABLE, Enabled>
+where
+    INPUT: pwm::ErrorType,
+{
+    type Error = INPUT::Error;
+}
+
+impl<INPUT, ENABLE> SetDutyCycle for TypedHalfH<'_, INPUT, ENABLE, Enabled>
+where
+    INPUT: SetDutyCycle,
+{
+    #[inline]
+    fn max_duty_cycle(&self) -> u16 {
+        self.bridge.input().borrow().max_duty_cycle()
+    }
+
+    #[inline]
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+        self.bridge.set_input_duty_cycle(duty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{DigitalPin, PwmPin};
+    use crate::L293x;
+    use coverage_helper::test;
+
+    #[test]
+    fn test_enable_disable() {
+        let l293x = L293x::new(DigitalPin::new(), (), (), (), DigitalPin::new(), ());
+        let mut y1 = l293x.y1().into_enabled().unwrap();
+        y1.set_high().unwrap();
+        assert!(y1.is_set_high().unwrap());
+        y1.set_low().unwrap();
+        assert!(y1.is_set_low().unwrap());
+
+        let y1 = y1.disable().unwrap();
+        assert!(y1.into_inner().is_disabled().unwrap());
+        let y1 = l293x.y1().into_disabled().unwrap().enable().unwrap();
+        assert!(y1.into_inner().is_enabled().unwrap());
+    }
+
+    #[test]
+    fn test_duty_cycle() {
+        let l293x = L293x::new(PwmPin::new(), (), (), (), DigitalPin::new(), ());
+        let mut y1 = l293x.y1().into_enabled().unwrap();
+        assert_eq!(y1.max_duty_cycle(), u16::MAX);
+        y1.set_duty_cycle(1000).unwrap();
+        assert_eq!(l293x.release().0 .0.get_duty_cycle(), 1000);
+    }
+
+    #[test]
+    fn test_error() {
+        let mut enable = DigitalPin::new();
+        enable.fail();
+        let l293x = L293x::new(DigitalPin::new(), (), (), (), enable, ());
+        assert!(l293x.y1().into_enabled().is_err());
+        assert!(l293x.y1().into_disabled().is_err());
+    }
+}
@@ -0,0 +1,438 @@
+//! # User interface helpers
+//!
+//! This module contains helpers, which translate the inputs of simple hardware user interfaces
+//! (e.g. knobs or buttons) into motor commands, like speed setpoints or directions. These can then
+//! be passed to a motor driver, e.g. using the [`FullH::set_speed()`](crate::FullH::set_speed) or
+//! [`FullH::drive()`](crate::FullH::drive) methods.
+//!
+//! The helpers read their input pins, when they are updated. They are meant to be updated
+//! periodically, e.g. from the main loop or a timer interrupt.
+
+use embedded_hal::digital::InputPin;
+
+use crate::Direction;
+
+/// The quadrature states of a detented rotary encoder in clockwise order.
+const QUADRATURE: [u8; 4] = [0b00, 0b01, 0b11, 0b10];
+
+/// The number of quadrature transitions between two detents of the knob.
+const TRANSITIONS_PER_DETENT: i8 = 4;
+
+/// Speed setpoint controlled by a rotary-encoder knob
+///
+/// The knob decodes a detented quadrature encoder from its two channels (`A` and `B`) and an
+/// optional push button (`BTN`):
+///
+/// - Each detent in clockwise direction increases the setpoint by the step, each detent in
+///   counter-clockwise direction decreases it. The setpoint is limited to the range of `-max` to
+///   `max`.
+/// - If the knob is turned fast, the step is multiplied by the
+///   [acceleration](QuadratureKnob::set_acceleration) factor.
+/// - Pressing the button stops the motor, i.e. resets the setpoint to `0`.
+///
+/// Knobs without a push button are created using [`new()`](QuadratureKnob::new), knobs with a
+/// push button using [`with_button()`](QuadratureKnob::with_button).
+///
+/// # Examples
+///
+/// ```
+/// use l293x::ui::QuadratureKnob;
+///
+/// // [...] create the pins and the motor
+/// let mut knob = QuadratureKnob::with_button(encoder_a, encoder_b, button, 500, i16::MAX);
+/// knob.set_acceleration(20, 8);
+///
+/// loop {
+///     let speed = knob.update(now_ms())?;
+///     motor.set_speed(speed)?;
+/// }
+/// ```
+#[derive(Debug)]
+pub struct QuadratureKnob<A, B, BTN> {
+    a: A,
+    b: B,
+    button: Option<BTN>,
+    step: i16,
+    max: i16,
+    acceleration: Option<(u32, i16)>,
+    quadrature: Option<usize>,
+    transitions: i8,
+    last_detent: Option<u32>,
+    pressed: bool,
+    setpoint: i16,
+}
+
+impl<A, B> QuadratureKnob<A, B, A> {
+    /// Create a new knob without a push button.
+    ///
+    /// The setpoint starts at `0` and changes by `step` per detent, but never exceeds the range
+    /// of `-max` to `max`.
+    #[inline]
+    pub fn new(a: A, b: B, step: i16, max: i16) -> Self {
+        Self::from_parts(a, b, None, step, max)
+    }
+}
+
+impl<A, B, BTN> QuadratureKnob<A, B, BTN> {
+    /// Create a new knob with a push button.
+    ///
+    /// The setpoint starts at `0` and changes by `step` per detent, but never exceeds the range
+    /// of `-max` to `max`.
+    #[inline]
+    pub fn with_button(a: A, b: B, button: BTN, step: i16, max: i16) -> Self {
+        Self::from_parts(a, b, Some(button), step, max)
+    }
+
+    fn from_parts(a: A, b: B, button: Option<BTN>, step: i16, max: i16) -> Self {
+        Self {
+            a,
+            b,
+            button,
+            step,
+            max: max.max(0),
+            acceleration: None,
+            quadrature: None,
+            transitions: 0,
+            last_detent: None,
+            pressed: false,
+            setpoint: 0,
+        }
+    }
+
+    /// Accelerate the setpoint changes, if the knob is turned fast.
+    ///
+    /// If a detent follows the previous one within `window` (in the unit of the timestamps passed
+    /// to [`update()`](QuadratureKnob::update)), the setpoint changes by `step * factor`
+    /// instead of `step`. A `factor` of `1` disables the acceleration.
+    pub fn set_acceleration(&mut self, window: u32, factor: i16) {
+        self.acceleration = match factor > 1 {
+            true => Some((window, factor)),
+            false => None,
+        };
+    }
+
+    /// Get the current speed setpoint.
+    #[inline]
+    pub fn setpoint(&self) -> i16 {
+        self.setpoint
+    }
+
+    /// Set the speed setpoint, e.g. to restore a saved value.
+    ///
+    /// The setpoint is limited to the range of `-max` to `max`.
+    #[inline]
+    pub fn set_setpoint(&mut self, setpoint: i16) {
+        self.setpoint = setpoint.clamp(-self.max, self.max);
+    }
+
+    /// Release the knob and return its pins.
+    #[inline]
+    pub fn release(self) -> (A, B, Option<BTN>) {
+        (self.a, self.b, self.button)
+    }
+
+    /// Apply a single detent in the given direction (`1` or `-1`).
+    fn detent(&mut self, direction: i16, now: u32) {
+        let factor = match (self.acceleration, self.last_detent) {
+            (Some((window, factor)), Some(last)) if now.wrapping_sub(last) <= window => factor,
+            _ => 1,
+        };
+        self.last_detent = Some(now);
+        let change = self.step.saturating_mul(factor).saturating_mul(direction);
+        self.set_setpoint(self.setpoint.saturating_add(change));
+    }
+
+    /// Decode the given quadrature state and apply completed detents.
+    fn decode(&mut self, a: bool, b: bool, now: u32) {
+        let state = u8::from(a) << 1 | u8::from(b);
+        let index = QUADRATURE.iter().position(|s| *s == state).unwrap_or(0);
+        if let Some(previous) = self.quadrature {
+            // Invalid transitions (i.e. skipped states) are ignored
+            match (index + 4 - previous) % 4 {
+                1 => self.transitions += 1,
+                3 => self.transitions -= 1,
+                _ => {}
+            }
+        }
+        self.quadrature = Some(index);
+        if self.transitions >= TRANSITIONS_PER_DETENT {
+            self.transitions = 0;
+            self.detent(1, now);
+        } else if self.transitions <= -TRANSITIONS_PER_DETENT {
+            self.transitions = 0;
+            self.detent(-1, now);
+        }
+    }
+}
+
+impl<A, B, BTN> QuadratureKnob<A, B, BTN>
+where
+    A: InputPin,
+    B: InputPin<Error = A::Error>,
+    BTN: InputPin<Error = A::Error>,
+{
+    /// Read the encoder (and the button, if any) and update the speed setpoint.
+    ///
+    /// The button is expected to be active low, i.e. to pull its pin to ground when pressed.
+    /// Pressing it resets the setpoint to `0`. The timestamp `now` is only used for the
+    /// acceleration and may wrap around.
+    ///
+    /// # Errors
+    ///
+    /// The error of the first pin, which could not be read, will be returned.
+    pub fn update(&mut self, now: u32) -> Result<i16, A::Error> {
+        let (a, b) = (self.a.is_high()?, self.b.is_high()?);
+        let pressed = match &mut self.button {
+            Some(button) => button.is_low()?,
+            None => false,
+        };
+        self.decode(a, b, now);
+        if pressed && !self.pressed {
+            self.setpoint = 0;
+        }
+        self.pressed = pressed;
+        Ok(self.setpoint)
+    }
+}
+
+/// Debouncing of a single button
+#[derive(Debug, Default)]
+struct Debounce {
+    pressed: bool,
+    bouncing: u32,
+}
+
+impl Debounce {
+    /// Update the debounced state with the raw state, which was read after `dt`.
+    ///
+    /// The debounced state changes, once the raw state differed from it for at least `debounce`.
+    fn update(&mut self, raw: bool, dt: u32, debounce: u32) -> bool {
+        match raw == self.pressed {
+            true => self.bouncing = 0,
+            false => {
+                self.bouncing = self.bouncing.saturating_add(dt);
+                if self.bouncing >= debounce {
+                    self.pressed = raw;
+                    self.bouncing = 0;
+                }
+            }
+        }
+        self.pressed
+    }
+}
+
+/// Direction control using two buttons
+///
+/// This is the classic hand controller: The motor runs forward, as long as the forward button is
+/// pressed, and in reverse, as long as the reverse button is pressed. If none or both of the
+/// buttons are pressed, the motor stops.
+///
+/// Both buttons are debounced: A button only counts as pressed (or released), once its pin
+/// stayed in the new state for the configured debounce time. The buttons are expected to be
+/// active low, i.e. to pull their pins to ground when pressed.
+///
+/// # Examples
+///
+/// ```
+/// use l293x::ui::TwoButtonControl;
+///
+/// // [...] create the pins and the motor
+/// let mut control = TwoButtonControl::new(forward_button, reverse_button, 20);
+///
+/// loop {
+///     match control.update(1)? {
+///         Some(direction) => motor.drive(direction)?,
+///         None => motor.brake()?,
+///     }
+///     delay.delay_ms(1);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct TwoButtonControl<F, R> {
+    forward: F,
+    reverse: R,
+    debounce: u32,
+    forward_state: Debounce,
+    reverse_state: Debounce,
+}
+
+impl<F, R> TwoButtonControl<F, R> {
+    /// Create a new two-button control.
+    ///
+    /// The `debounce` time uses the same unit as the time passed to
+    /// [`update()`](TwoButtonControl::update), e.g. milliseconds.
+    #[inline]
+    pub fn new(forward: F, reverse: R, debounce: u32) -> Self {
+        Self {
+            forward,
+            reverse,
+            debounce,
+            forward_state: Debounce::default(),
+            reverse_state: Debounce::default(),
+        }
+    }
+
+    /// Get the current command.
+    ///
+    /// Returns the direction to drive the motor in, or `None`, if the motor should stop.
+    pub fn command(&self) -> Option<Direction> {
+        match (self.forward_state.pressed, self.reverse_state.pressed) {
+            (true, false) => Some(Direction::Forward),
+            (false, true) => Some(Direction::Reverse),
+            _ => None,
+        }
+    }
+
+    /// Release the control and return the pins of the forward and the reverse button.
+    #[inline]
+    pub fn release(self) -> (F, R) {
+        (self.forward, self.reverse)
+    }
+}
+
+impl<F, R> TwoButtonControl<F, R>
+where
+    F: InputPin,
+    R: InputPin<Error = F::Error>,
+{
+    /// Read the buttons and update the command.
+    ///
+    /// The time `dt` passed since the previous update is used for debouncing the buttons. The
+    /// current [command](TwoButtonControl::command) is returned.
+    ///
+    /// # Errors
+    ///
+    /// The error of the first pin, which could not be read, will be returned. The command is
+    /// not changed in this case.
+    pub fn update(&mut self, dt: u32) -> Result<Option<Direction>, F::Error> {
+        let (forward, reverse) = (self.forward.is_low()?, self.reverse.is_low()?);
+        self.forward_state.update(forward, dt, self.debounce);
+        self.reverse_state.update(reverse, dt, self.debounce);
+        Ok(self.command())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::DigitalPin;
+    use coverage_helper::test;
+    use embedded_hal::digital::OutputPin;
+
+    type MockKnob = QuadratureKnob<DigitalPin, DigitalPin, DigitalPin>;
+
+    fn knob() -> MockKnob {
+        let mut button = DigitalPin::new();
+        button.set_high().unwrap();
+        QuadratureKnob::with_button(DigitalPin::new(), DigitalPin::new(), button, 10, 25)
+    }
+
+    /// Turn the knob by one detent and return the final setpoint.
+    fn turn(knob: &mut MockKnob, clockwise: bool, now: u32) -> i16 {
+        let mut states = QUADRATURE;
+        if !clockwise {
+            states.reverse();
+            states.rotate_right(1);
+        }
+        let mut setpoint = 0;
+        for state in states[1..].iter().chain(&states[..1]) {
+            knob.a.set_state((state & 0b10 != 0).into()).unwrap();
+            knob.b.set_state((state & 0b01 != 0).into()).unwrap();
+            setpoint = knob.update(now).unwrap();
+        }
+        setpoint
+    }
+
+    #[test]
+    fn test_detents() {
+        let mut knob = knob();
+        knob.update(0).unwrap();
+
+        assert_eq!(turn(&mut knob, true, 0), 10);
+        assert_eq!(turn(&mut knob, true, 100), 20);
+        assert_eq!(turn(&mut knob, true, 200), 25);
+        assert_eq!(turn(&mut knob, false, 300), 15);
+        assert_eq!(knob.setpoint(), 15);
+    }
+
+    #[test]
+    fn test_push_to_stop() {
+        let mut knob = knob();
+        knob.update(0).unwrap();
+        turn(&mut knob, false, 0);
+        assert_eq!(knob.setpoint(), -10);
+
+        knob.button.as_mut().unwrap().set_low().unwrap();
+        assert_eq!(knob.update(10).unwrap(), 0);
+        // Holding the button does not prevent turning the knob
+        assert_eq!(turn(&mut knob, true, 20), 10);
+    }
+
+    #[test]
+    fn test_acceleration() {
+        let mut knob = knob();
+        knob.set_acceleration(5, 2);
+        knob.update(0).unwrap();
+
+        assert_eq!(turn(&mut knob, true, 0), 10);
+        assert_eq!(turn(&mut knob, true, 3), 25);
+        knob.set_setpoint(0);
+        assert_eq!(turn(&mut knob, false, 100), -10);
+    }
+
+    #[test]
+    fn test_without_button() {
+        let mut knob = QuadratureKnob::new(DigitalPin::new(), DigitalPin::new(), 1, 5);
+        knob.set_acceleration(5, 1);
+        knob.update(0).unwrap();
+
+        knob.a.fail();
+        assert!(knob.update(0).is_err());
+        let (_, _, button) = knob.release();
+        assert!(button.is_none());
+    }
+
+    fn buttons() -> TwoButtonControl<DigitalPin, DigitalPin> {
+        let mut forward = DigitalPin::new();
+        let mut reverse = DigitalPin::new();
+        forward.set_high().unwrap();
+        reverse.set_high().unwrap();
+        TwoButtonControl::new(forward, reverse, 20)
+    }
+
+    #[test]
+    fn test_two_buttons() {
+        let mut control = buttons();
+        assert_eq!(control.update(10).unwrap(), None);
+
+        control.forward.set_low().unwrap();
+        assert_eq!(control.update(10).unwrap(), None);
+        assert_eq!(control.update(10).unwrap(), Some(Direction::Forward));
+
+        control.reverse.set_low().unwrap();
+        control.update(20).unwrap();
+        assert_eq!(control.command(), None);
+
+        control.forward.set_high().unwrap();
+        control.update(20).unwrap();
+        assert_eq!(control.command(), Some(Direction::Reverse));
+    }
+
+    #[test]
+    fn test_two_buttons_bouncing() {
+        let mut control = buttons();
+
+        control.forward.set_low().unwrap();
+        assert_eq!(control.update(15).unwrap(), None);
+        control.forward.set_high().unwrap();
+        assert_eq!(control.update(1).unwrap(), None);
+        control.forward.set_low().unwrap();
+        assert_eq!(control.update(15).unwrap(), None);
+        assert_eq!(control.update(5).unwrap(), Some(Direction::Forward));
+
+        control.reverse.fail();
+        assert!(control.update(20).is_err());
+        let (mut forward, _) = control.release();
+        assert!(forward.is_low().unwrap());
+    }
+}
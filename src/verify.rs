@@ -0,0 +1,189 @@
+//! # Duty cycle verification
+//!
+//! In safety-relevant applications, it is not sufficient to only command a duty cycle. A dead
+//! timer channel or a broken trace between the microcontroller and the chip would go unnoticed.
+//! This module implements a [VerifiedPwm] pin, which measures the actual duty cycle on a feedback
+//! pin after each change and compares it to the commanded one.
+//!
+//! The measurement itself is hardware specific and thus, needs to be provided by the user as a
+//! capture callback, e.g. using the input capture unit of a timer.
+//!
+//! For statically driven outputs, the [L293x](crate::L293x) provides the
+//! [`verify_y1()`](crate::L293x::verify_y1) - [`verify_y4()`](crate::L293x::verify_y4) methods
+//! instead. These read the level of a feedback [InputPin](embedded_hal::digital::InputPin) wired to
+//! the output and return an [OutputMismatch](VerifyError::OutputMismatch) error, if it diverges
+//! from the commanded state.
+//!
+//! # Examples
+//!
+//! ```
+//! use l293x::L293x;
+//! use l293x::verify::VerifiedPwm;
+//!
+//! // [...] create the PWM pin and the input capture of the feedback pin
+//! let input1 = VerifiedPwm::new(pwm1, move || capture.measure_duty(), 100);
+//! let mut l293x = L293x::new(input1, (), (), (), enable12, ());
+//!
+//! // Returns a `DutyMismatch` error, if the measured duty cycle diverges
+//! l293x.set_y1_duty_cycle(1000)?;
+//! ```
+
+use core::convert::Infallible;
+use core::fmt::Debug;
+
+use embedded_hal::pwm::{Error, ErrorKind, ErrorType, SetDutyCycle};
+
+/// Error returned by a [VerifiedPwm] pin or the output verification of the [L293x](crate::L293x)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum VerifyError<E, F = Infallible> {
+    /// The wrapped PWM pin returned an error. The contained error may contain additional
+    /// information.
+    PinError(E),
+    /// The feedback pin of an output returned an error, while reading its level.
+    FeedbackPinError(F),
+    /// The measured duty cycle diverges from the commanded one by more than the tolerance.
+    DutyMismatch {
+        /// The commanded duty cycle.
+        commanded: u16,
+        /// The duty cycle measured by the capture callback.
+        measured: u16,
+    },
+    /// The level measured on the feedback pin of an output diverges from the commanded state.
+    OutputMismatch {
+        /// Whether the output was commanded high.
+        commanded: bool,
+        /// Whether the feedback pin measured a high level.
+        measured: bool,
+    },
+}
+
+impl<E: Error, F: Debug> Error for VerifyError<E, F> {
+    fn kind(&self) -> ErrorKind {
+        match self {
+            VerifyError::PinError(e) => e.kind(),
+            VerifyError::FeedbackPinError(_)
+            | VerifyError::DutyMismatch { .. }
+            | VerifyError::OutputMismatch { .. } => ErrorKind::Other,
+        }
+    }
+}
+
+/// A PWM pin, which verifies the commanded duty cycle using a measurement
+///
+/// Each time the duty cycle is set, the capture callback is called to measure the actual duty
+/// cycle on the feedback pin. The measured value must use the same scale as the
+/// [maximum duty cycle](SetDutyCycle::max_duty_cycle) of the wrapped pin. If it diverges from the
+/// commanded duty cycle by more than the given tolerance, a
+/// [DutyMismatch](VerifyError::DutyMismatch) error is returned.
+///
+/// Because this type implements the [SetDutyCycle] trait itself, it can be used as an input pin
+/// of the [L293x](crate::L293x) or any other driver of this crate.
+#[derive(Debug)]
+pub struct VerifiedPwm<P, F> {
+    pin: P,
+    capture: F,
+    tolerance: u16,
+}
+
+impl<P, F> VerifiedPwm<P, F>
+where
+    P: SetDutyCycle,
+    F: FnMut() -> u16,
+{
+    /// Create a new verified PWM pin.
+    ///
+    /// The `capture` callback measures the actual duty cycle, while the `tolerance` defines the
+    /// maximum allowed difference between the commanded and the measured duty cycle.
+    #[inline]
+    pub fn new(pin: P, capture: F, tolerance: u16) -> Self {
+        Self {
+            pin,
+            capture,
+            tolerance,
+        }
+    }
+
+    /// Release the verified pin and return the wrapped PWM pin and the capture callback.
+    #[inline]
+    pub fn release(self) -> (P, F) {
+        (self.pin, self.capture)
+    }
+}
+
+impl<P, F> ErrorType for VerifiedPwm<P, F>
+where
+    P: SetDutyCycle,
+{
+    type Error = VerifyError<P::Error>;
+}
+
+impl<P, F> SetDutyCycle for VerifiedPwm<P, F>
+where
+    P: SetDutyCycle,
+    F: FnMut() -> u16,
+{
+    #[inline]
+    fn max_duty_cycle(&self) -> u16 {
+        self.pin.max_duty_cycle()
+    }
+
+    fn set_duty_cycle(&mut self, duty: u16) -> Result<(), Self::Error> {
+        self.pin
+            .set_duty_cycle(duty)
+            .map_err(VerifyError::PinError)?;
+        let measured = (self.capture)();
+        match duty.abs_diff(measured) > self.tolerance {
+            true => Err(VerifyError::DutyMismatch {
+                commanded: duty,
+                measured,
+            }),
+            false => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{PwmError, PwmPin};
+    use coverage_helper::test;
+
+    #[test]
+    fn test_verified() {
+        let mut pin = VerifiedPwm::new(PwmPin::new(), || 1010, 10);
+
+        assert_eq!(pin.max_duty_cycle(), u16::MAX);
+        assert!(pin.set_duty_cycle(1000).is_ok());
+        assert!(pin.set_duty_cycle(1020).is_ok());
+        assert_eq!(pin.release().0.get_duty_cycle(), 1020);
+    }
+
+    #[test]
+    fn test_mismatch() {
+        // A dead timer channel, which stays low
+        let mut pin = VerifiedPwm::new(PwmPin::new(), || 0, 10);
+
+        assert!(pin.set_duty_cycle_fully_off().is_ok());
+        let error = pin.set_duty_cycle(1000).unwrap_err();
+        assert_eq!(
+            error,
+            VerifyError::DutyMismatch {
+                commanded: 1000,
+                measured: 0
+            }
+        );
+        assert_eq!(error.kind(), ErrorKind::Other);
+    }
+
+    #[test]
+    fn test_pin_error() {
+        let mut pwm = PwmPin::new();
+        pwm.fail();
+        let mut pin = VerifiedPwm::new(pwm, || 0, 10);
+
+        let error = pin.set_duty_cycle(0).unwrap_err();
+        assert_eq!(error, VerifyError::PinError(PwmError()));
+        assert_eq!(error.kind(), PwmError().kind());
+    }
+}
@@ -0,0 +1,168 @@
+use embedded_hal::digital::{ErrorType, StatefulOutputPin};
+
+use crate::{HalfH, L293x};
+
+/// Read-only view of a [L293](crate::L293x) chip
+///
+/// This view is returned by the [`view()`](L293x::view) method of the chip driver. It allows
+/// querying the states of the outputs and the enable pins, but it does **not** allow changing
+/// them. Because it only holds a shared reference to the chip, it is cheap to copy and can be
+/// handed to telemetry or logging code, separating the observation from the control of the chip.
+///
+/// # Note
+///
+/// Like the query methods of the chip itself, the view does not check the electrical levels of
+/// the pins, but uses the internal states of the pins instead.
+///
+/// # Examples
+///
+/// ```
+/// # use l293x::L293x;
+/// let l293x = L293x::new(input1, input2, input3, input4, enable12, enable34);
+/// let view = l293x.view();
+///
+/// // The view can be used to query the states of the chip
+/// let enabled = view.y1_and_y2_enabled()?;
+/// let high = view.is_y1_set_high()?;
+/// ```
+#[derive(Debug)]
+pub struct L293xView<'a, A1, A2, A3, A4, EN12, EN34> {
+    chip: &'a L293x<A1, A2, A3, A4, EN12, EN34>,
+}
+
+impl<'a, A1, A2, A3, A4, EN12, EN34> Clone for L293xView<'a, A1, A2, A3, A4, EN12, EN34> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, A1, A2, A3, A4, EN12, EN34> Copy for L293xView<'a, A1, A2, A3, A4, EN12, EN34> {}
+
+impl<A1, A2, A3, A4, EN12, EN34> L293x<A1, A2, A3, A4, EN12, EN34> {
+    /// Get a read-only view of the chip.
+    ///
+    /// For more information, see the documentation of the [L293xView].
+    #[inline]
+    pub fn view(&self) -> L293xView<'_, A1, A2, A3, A4, EN12, EN34> {
+        L293xView { chip: self }
+    }
+}
+
+impl<'a, A1, A2, A3, A4, EN12, EN34> L293xView<'a, A1, A2, A3, A4, EN12, EN34>
+where
+    EN12: StatefulOutputPin,
+{
+    /// Check whether the output channels 1 & 2 are enabled.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the common enable pin
+    /// of the Half-H-Bridges 1 & 2.
+    pub fn y1_and_y2_enabled(&self) -> Result<bool, EN12::Error> {
+        self.chip.y1().is_enabled()
+    }
+}
+
+impl<'a, A1, A2, A3, A4, EN12, EN34> L293xView<'a, A1, A2, A3, A4, EN12, EN34>
+where
+    EN34: StatefulOutputPin,
+{
+    /// Check whether the output channels 3 & 4 are enabled.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the common enable pin
+    /// of the Half-H-Bridges 3 & 4.
+    pub fn y3_and_y4_enabled(&self) -> Result<bool, EN34::Error> {
+        self.chip.y3().is_enabled()
+    }
+}
+
+macro_rules! view_output_impl {
+    ($output:ident, $type_:ty, $enable_ty:ty) => {
+        paste::item! {
+            impl<'a, A1, A2, A3, A4, EN12, EN34> L293xView<'a, A1, A2, A3, A4, EN12, EN34>
+            where
+                $type_: StatefulOutputPin,
+                $enable_ty: StatefulOutputPin,
+            {
+                #[doc = "Check if output " $output " is set high"]
+                ///
+                /// # Errors
+                ///
+                /// If the output is disabled, a
+                /// [NotEnabled](crate::OutputStateError::NotEnabled) error will be returned.
+                /// Errors of the pins are returned as described in the documentation of the
+                #[doc = "[L293x::is_" $output "_set_high()] method."]
+                pub fn [< is_ $output _set_high >](
+                    &self
+                ) -> Result<bool, <HalfH<'a, $type_, $enable_ty> as ErrorType>::Error> {
+                    self.chip.$output().is_set_high()
+                }
+
+                #[doc = "Check if output " $output " is set low"]
+                ///
+                /// # Errors
+                ///
+                /// If the output is disabled, a
+                /// [NotEnabled](crate::OutputStateError::NotEnabled) error will be returned.
+                /// Errors of the pins are returned as described in the documentation of the
+                #[doc = "[L293x::is_" $output "_set_low()] method."]
+                pub fn [< is_ $output _set_low >](
+                    &self
+                ) -> Result<bool, <HalfH<'a, $type_, $enable_ty> as ErrorType>::Error> {
+                    self.chip.$output().is_set_low()
+                }
+            }
+        }
+    };
+}
+view_output_impl!(y1, A1, EN12);
+view_output_impl!(y2, A2, EN12);
+view_output_impl!(y3, A3, EN34);
+view_output_impl!(y4, A4, EN34);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::DigitalPin;
+    use crate::OutputStateError;
+    use coverage_helper::test;
+    use embedded_hal::digital::OutputPin;
+
+    #[test]
+    fn test_view() {
+        let l293x = L293x::new(
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+            DigitalPin::new(),
+        );
+        let view = l293x.view();
+        let copy = view;
+
+        assert!(!view.y1_and_y2_enabled().unwrap());
+        assert!(!copy.y3_and_y4_enabled().unwrap());
+        assert!(matches!(
+            view.is_y1_set_high(),
+            Err(OutputStateError::NotEnabled)
+        ));
+
+        l293x.y1().enable().unwrap();
+        l293x.y4().enable().unwrap();
+        l293x.y1().set_high().unwrap();
+        l293x.y4().set_low().unwrap();
+        assert!(view.y1_and_y2_enabled().unwrap());
+        assert!(copy.y3_and_y4_enabled().unwrap());
+        assert!(view.is_y1_set_high().unwrap());
+        assert!(view.is_y2_set_low().unwrap());
+        assert!(!view.is_y2_set_high().unwrap());
+        assert!(!view.is_y3_set_high().unwrap());
+        assert!(copy.is_y4_set_low().unwrap());
+        assert!(!copy.is_y1_set_low().unwrap());
+        assert!(copy.is_y3_set_low().unwrap());
+        assert!(!copy.is_y4_set_high().unwrap());
+    }
+}